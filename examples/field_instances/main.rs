@@ -56,7 +56,7 @@ fn main() {
 fn setup(mut commands: Commands, asset_server: Res<AssetServer>) {
     commands.spawn(Camera2dBundle::default());
 
-    let ldtk_handle = asset_server.load("field_instances.ldtk");
+    let ldtk_handle = asset_server.load("field_instances.ldtk").into();
 
     commands.spawn(LdtkWorldBundle {
         ldtk_handle,
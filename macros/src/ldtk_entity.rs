@@ -6,6 +6,7 @@ static WORLDLY_ATTRIBUTE_NAME: &str = "worldly";
 static GRID_COORDS_ATTRIBUTE_NAME: &str = "grid_coords";
 static LDTK_ENTITY_ATTRIBUTE_NAME: &str = "ldtk_entity";
 static FROM_ENTITY_INSTANCE_ATTRIBUTE_NAME: &str = "from_entity_instance";
+static TRANSFORM_FROM_POINT_ATTRIBUTE_NAME: &str = "transform_from_point";
 static WITH_ATTRIBUTE_NAME: &str = "with";
 
 pub fn expand_ldtk_entity_derive(ast: syn::DeriveInput) -> proc_macro::TokenStream {
@@ -88,6 +89,17 @@ pub fn expand_ldtk_entity_derive(ast: syn::DeriveInput) -> proc_macro::TokenStre
             continue;
         }
 
+        let transform_from_point = field
+            .attrs
+            .iter()
+            .find(|a| *a.path.get_ident().as_ref().unwrap() == TRANSFORM_FROM_POINT_ATTRIBUTE_NAME);
+        if let Some(attribute) = transform_from_point {
+            field_constructions.push(expand_transform_from_point_attribute(
+                attribute, field_name, field_type,
+            ));
+            continue;
+        }
+
         let with = field
             .attrs
             .iter()
@@ -289,7 +301,7 @@ fn expand_worldly_attribute(
 fn expand_grid_coords_attribute(
     attribute: &syn::Attribute,
     field_name: &syn::Ident,
-    _: &syn::Type,
+    field_type: &syn::Type,
 ) -> proc_macro2::TokenStream {
     match attribute
         .parse_meta()
@@ -297,10 +309,20 @@ fn expand_grid_coords_attribute(
     {
         syn::Meta::Path(_) => {
             quote! {
-                #field_name: bevy_ecs_ldtk::prelude::GridCoords::from_entity_info(entity_instance, layer_instance),
+                #field_name: <#field_type>::from_entity_info(entity_instance, layer_instance),
             }
         }
-        _ => panic!("#[grid_coords] attribute should take the form #[grid_coords]"),
+        syn::Meta::List(syn::MetaList { nested, .. }) if nested.len() == 1 => {
+            match nested.first().unwrap() {
+                syn::NestedMeta::Meta(syn::Meta::Path(anchor)) => {
+                    quote! {
+                        #field_name: bevy_ecs_ldtk::prelude::GridCoords::from_entity_info_anchored(entity_instance, layer_instance, bevy_ecs_ldtk::prelude::GridCoordsAnchor::#anchor),
+                    }
+                }
+                _ => panic!("Expected GridCoordsAnchor variant as the only argument of #[grid_coords(...)]"),
+            }
+        }
+        _ => panic!("#[grid_coords] attribute should take the form #[grid_coords] or #[grid_coords(Anchor)]"),
     }
 }
 
@@ -342,6 +364,32 @@ fn expand_from_entity_instance_attribute(
     }
 }
 
+fn expand_transform_from_point_attribute(
+    attribute: &syn::Attribute,
+    field_name: &syn::Ident,
+    _: &syn::Type,
+) -> proc_macro2::TokenStream {
+    match attribute
+        .parse_meta()
+        .expect("Cannot parse #[transform_from_point...] attribute")
+    {
+        syn::Meta::List(syn::MetaList { nested, .. }) if nested.len() == 1 => {
+            match nested.first().unwrap() {
+                syn::NestedMeta::Lit(syn::Lit::Str(identifier)) => {
+                    let identifier = identifier.value();
+                    quote! {
+                        #field_name: bevy_ecs_ldtk::prelude::TransformOverride::from_point_field(entity_instance, layer_instance, #identifier),
+                    }
+                }
+                _ => panic!("Expected field identifier as the only argument of #[transform_from_point(...)]"),
+            }
+        }
+        _ => {
+            panic!("#[transform_from_point...] attribute should take the form #[transform_from_point(\"field_identifier\")]")
+        }
+    }
+}
+
 fn expand_with_attribute(
     attribute: &syn::Attribute,
     field_name: &syn::Ident,
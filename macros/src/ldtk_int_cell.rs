@@ -66,6 +66,7 @@ pub fn expand_ldtk_int_cell_derive(ast: syn::DeriveInput) -> proc_macro::TokenSt
             fn bundle_int_cell(
                 int_grid_cell: bevy_ecs_ldtk::prelude::IntGridCell,
                 layer_instance: &bevy_ecs_ldtk::prelude::LayerInstance,
+                layer_definition: &bevy_ecs_ldtk::ldtk::LayerDefinition,
             ) -> Self {
                 Self {
                     #(#field_constructions)*
@@ -88,7 +89,7 @@ fn expand_ldtk_int_cell_attribute(
     {
         syn::Meta::Path(_) => {
             quote! {
-                #field_name: <#field_type as bevy_ecs_ldtk::prelude::LdtkIntCell>::bundle_int_cell(int_grid_cell, layer_instance),
+                #field_name: <#field_type as bevy_ecs_ldtk::prelude::LdtkIntCell>::bundle_int_cell(int_grid_cell, layer_instance, layer_definition),
             }
         }
         _ => panic!("#[ldtk_int_cell] attribute should take the form #[ldtk_int_cell]"),
@@ -126,7 +126,10 @@ impl LdtkIntCellAppExt for App {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::{components::IntGridCell, ldtk::LayerInstance};
+    use crate::{
+        components::IntGridCell,
+        ldtk::{LayerDefinition, LayerInstance},
+    };
 
     #[derive(Default, Component, Debug)]
     struct ComponentA;
@@ -141,7 +144,11 @@ mod tests {
     }
 
     impl LdtkIntCell for LdtkIntCellBundle {
-        fn bundle_int_cell(_: IntGridCell, _: &LayerInstance) -> LdtkIntCellBundle {
+        fn bundle_int_cell(
+            _: IntGridCell,
+            _: &LayerInstance,
+            _: &LayerDefinition,
+        ) -> LdtkIntCellBundle {
             LdtkIntCellBundle::default()
         }
     }
@@ -0,0 +1,75 @@
+//! Provides [LayerDefinitionAppExt] for customizing layers by identifier as they spawn.
+use crate::{ldtk::LayerDefinition, systems};
+use bevy::{ecs::system::EntityCommands, prelude::*};
+use std::sync::Arc;
+
+/// Function called with a newly spawned layer entity and its [LayerDefinition].
+pub type LayerDefinitionCallback = Arc<dyn Fn(&mut EntityCommands, &LayerDefinition) + Send + Sync>;
+
+/// [Resource] storing the callbacks registered with [LayerDefinitionAppExt], by layer identifier.
+#[derive(Clone, Default, Resource)]
+pub struct LayerDefinitionCallbacks {
+    pub callbacks: Vec<(String, LayerDefinitionCallback)>,
+}
+
+/// Provides functions to customize layer entities as they spawn, based on their layer identifier.
+///
+/// Useful for teams that drive behavior with layer naming conventions, since callbacks receive the
+/// full [LayerDefinition] without re-parsing the project's definitions.
+///
+/// Callbacks are called in [PreUpdate], once for every layer entity with a newly added
+/// [LayerMetadata](crate::prelude::LayerMetadata) whose identifier matches.
+/// Layers split into several tilemaps have several layer entities, so the callback is called for
+/// each of them.
+///
+/// Not intended for custom implementations on your own types.
+pub trait LayerDefinitionAppExt {
+    /// Registers a callback for the layers with the given identifier.
+    /// ```no_run
+    /// use bevy::prelude::*;
+    /// use bevy_ecs_ldtk::{app::LayerDefinitionAppExt, prelude::*};
+    ///
+    /// #[derive(Component)]
+    /// struct Hazard;
+    ///
+    /// fn main() {
+    ///     App::new()
+    ///         .add_plugins((DefaultPlugins, LdtkPlugin))
+    ///         .add_layer_definition_callback("Lava", |layer, layer_definition| {
+    ///             if layer_definition.doc.as_deref() == Some("hazard") {
+    ///                 layer.insert(Hazard);
+    ///             }
+    ///         })
+    ///         // add other systems, plugins, resources...
+    ///         .run();
+    /// }
+    /// ```
+    fn add_layer_definition_callback(
+        &mut self,
+        layer_identifier: impl Into<String>,
+        callback: impl Fn(&mut EntityCommands, &LayerDefinition) + Send + Sync + 'static,
+    ) -> &mut Self;
+}
+
+impl LayerDefinitionAppExt for App {
+    fn add_layer_definition_callback(
+        &mut self,
+        layer_identifier: impl Into<String>,
+        callback: impl Fn(&mut EntityCommands, &LayerDefinition) + Send + Sync + 'static,
+    ) -> &mut Self {
+        if !self.world.contains_resource::<LayerDefinitionCallbacks>() {
+            self.init_resource::<LayerDefinitionCallbacks>()
+                .add_systems(
+                    PreUpdate,
+                    systems::call_layer_definition_callbacks.after(systems::process_ldtk_levels),
+                );
+        }
+
+        self.world
+            .resource_mut::<LayerDefinitionCallbacks>()
+            .callbacks
+            .push((layer_identifier.into(), Arc::new(callback)));
+
+        self
+    }
+}
@@ -1,5 +1,5 @@
 use crate::{
-    components::{EntityInstanceBundle, GridCoords, Worldly},
+    components::{EntityInstanceBundle, GridCoords, GridCoordsRect, Worldly},
     ldtk::{EntityInstance, LayerInstance, TilesetDefinition},
     utils,
 };
@@ -13,6 +13,7 @@ use std::{collections::HashMap, marker::PhantomData};
 /// [SpriteBundle]: bevy::prelude::SpriteBundle
 /// [SpriteSheetBundle]: bevy::prelude::SpriteSheetBundle
 /// [TextureAtlas]: bevy::prelude::TextureAtlas
+/// [TransformOverride]: crate::prelude::TransformOverride
 ///
 /// Provides a constructor which can be used for spawning entities from an LDtk file.
 ///
@@ -179,6 +180,26 @@ use std::{collections::HashMap, marker::PhantomData};
 /// }
 /// ```
 ///
+/// For entities larger than one cell, the reported cell can be chosen with a
+/// [GridCoordsAnchor](crate::prelude::GridCoordsAnchor), or every occupied cell can be stored in a
+/// [GridCoordsRect] instead.
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_ecs_ldtk::prelude::*;
+/// # #[derive(Component, Default)]
+/// # struct Boulder;
+/// #[derive(Bundle, LdtkEntity, Default)]
+/// pub struct BoulderBundle {
+///     boulder: Boulder,
+///     #[sprite_sheet_bundle]
+///     sprite_sheet_bundle: SpriteSheetBundle,
+///     #[grid_coords(BottomLeft)]
+///     grid_coords: GridCoords,
+///     #[grid_coords]
+///     occupied_cells: GridCoordsRect,
+/// }
+/// ```
+///
 /// ### `#[ldtk_entity]`
 /// Indicates that a component or bundle that implements [LdtkEntity] should be created with
 /// [LdtkEntity::bundle_entity], allowing for nested [LdtkEntity]s.
@@ -246,6 +267,27 @@ use std::{collections::HashMap, marker::PhantomData};
 /// }
 /// ```
 ///
+/// ### `#[transform_from_point(...)]`
+/// Indicates that a [TransformOverride] component should be created from the given `Point` field,
+/// so the entity is placed at that point instead of its own position.
+///
+/// This is useful for teleporters and camera targets whose destination is authored as a separate
+/// point field.
+/// The point is converted like the entity's own position, so layer offsets still apply.
+/// If the field is null, the entity keeps its own position.
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_ecs_ldtk::prelude::*;
+/// # #[derive(Component, Default)]
+/// # struct CameraTarget;
+/// #[derive(Bundle, LdtkEntity, Default)]
+/// pub struct CameraTargetBundle {
+///     camera_target: CameraTarget,
+///     #[transform_from_point("spawn_at")]
+///     transform_override: TransformOverride,
+/// }
+/// ```
+///
 /// ### `#[with(...)]`
 ///
 /// Indicates that this component or bundle should be initialized with the given
@@ -292,6 +334,8 @@ pub trait LdtkEntity {
     /// [SpatialBundle](bevy::prelude::SpatialBundle) to the entity **after** this bundle is
     /// inserted.
     /// So, any custom implementations of these components within this trait will be overwritten.
+    /// Use a [TransformOverride](crate::prelude::TransformOverride) to customize the
+    /// [Transform] instead.
     fn bundle_entity(
         entity_instance: &EntityInstance,
         layer_instance: &LayerInstance,
@@ -375,6 +419,19 @@ impl LdtkEntity for GridCoords {
     }
 }
 
+impl LdtkEntity for GridCoordsRect {
+    fn bundle_entity(
+        entity_instance: &EntityInstance,
+        layer_instance: &LayerInstance,
+        _: Option<&Handle<Image>>,
+        _: Option<&TilesetDefinition>,
+        _: &AssetServer,
+        _: &mut Assets<TextureAtlas>,
+    ) -> Self {
+        GridCoordsRect::from_entity_info(entity_instance, layer_instance)
+    }
+}
+
 #[derive(Copy, Clone, Eq, PartialEq, Debug, Default, Hash)]
 pub struct PhantomLdtkEntity<B: LdtkEntity + Bundle> {
     ldtk_entity: PhantomData<B>,
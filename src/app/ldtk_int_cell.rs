@@ -1,6 +1,6 @@
 use crate::{
     components::{IntGridCell, IntGridCellBundle},
-    ldtk::LayerInstance,
+    ldtk::{LayerDefinition, LayerInstance},
 };
 use bevy::{ecs::system::EntityCommands, prelude::*};
 use std::{collections::HashMap, marker::PhantomData};
@@ -168,11 +168,43 @@ pub trait LdtkIntCell {
     /// So, any custom implementations of these components within this trait will be overwritten.
     /// Furthermore, a [bevy_ecs_tilemap::tiles::TileBundle] will be inserted **before** this bundle, so
     /// be careful not to overwrite the components provided by that bundle.
-    fn bundle_int_cell(int_grid_cell: IntGridCell, layer_instance: &LayerInstance) -> Self;
+    ///
+    /// The `layer_definition` describes the cell's layer, including the identifier, color, and
+    /// group of each IntGrid value.
+    /// ```
+    /// # use bevy::prelude::*;
+    /// # use bevy_ecs_ldtk::{ldtk::LayerDefinition, prelude::*};
+    /// #[derive(Component)]
+    /// struct Terrain {
+    ///     name: Option<String>,
+    ///     color: Color,
+    /// }
+    ///
+    /// impl LdtkIntCell for Terrain {
+    ///     fn bundle_int_cell(
+    ///         int_grid_cell: IntGridCell,
+    ///         _: &LayerInstance,
+    ///         layer_definition: &LayerDefinition,
+    ///     ) -> Self {
+    ///         let value_definition =
+    ///             layer_definition.int_grid_value_definition(int_grid_cell.value);
+    ///
+    ///         Terrain {
+    ///             name: value_definition.and_then(|definition| definition.identifier.clone()),
+    ///             color: value_definition.map_or(Color::WHITE, |definition| definition.color),
+    ///         }
+    ///     }
+    /// }
+    /// ```
+    fn bundle_int_cell(
+        int_grid_cell: IntGridCell,
+        layer_instance: &LayerInstance,
+        layer_definition: &LayerDefinition,
+    ) -> Self;
 }
 
 impl LdtkIntCell for IntGridCellBundle {
-    fn bundle_int_cell(int_grid_cell: IntGridCell, _: &LayerInstance) -> Self {
+    fn bundle_int_cell(int_grid_cell: IntGridCell, _: &LayerInstance, _: &LayerDefinition) -> Self {
         IntGridCellBundle { int_grid_cell }
     }
 }
@@ -196,6 +228,7 @@ pub trait PhantomLdtkIntCellTrait {
         entity_commands: &'b mut EntityCommands<'w, 's, 'a>,
         int_grid_cell: IntGridCell,
         layer_instance: &LayerInstance,
+        layer_definition: &LayerDefinition,
     ) -> &'b mut EntityCommands<'w, 's, 'a>;
 }
 
@@ -205,8 +238,13 @@ impl<B: LdtkIntCell + Bundle> PhantomLdtkIntCellTrait for PhantomLdtkIntCell<B>
         entity_commands: &'b mut EntityCommands<'w, 's, 'a>,
         int_grid_cell: IntGridCell,
         layer_instance: &LayerInstance,
+        layer_definition: &LayerDefinition,
     ) -> &'b mut EntityCommands<'w, 's, 'a> {
-        entity_commands.insert(B::bundle_int_cell(int_grid_cell, layer_instance))
+        entity_commands.insert(B::bundle_int_cell(
+            int_grid_cell,
+            layer_instance,
+            layer_definition,
+        ))
     }
 }
 
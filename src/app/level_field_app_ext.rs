@@ -0,0 +1,120 @@
+//! Provides [LevelFieldAppExt] for reacting to the fields of the selected level.
+use crate::{components::LevelIid, ldtk::FieldValue, systems};
+use bevy::prelude::*;
+use std::sync::Arc;
+
+/// The value of a level field when its level becomes the selected level.
+#[derive(Clone, PartialEq, Debug)]
+pub struct LevelFieldChange<'a> {
+    /// Iid of the newly selected level.
+    pub level_iid: &'a LevelIid,
+    /// Value of the field in the newly selected level.
+    pub value: &'a FieldValue,
+    /// Value of the field in the previously selected level, if it had the field.
+    ///
+    /// Useful for crossfading between the two, like for music.
+    pub previous: Option<&'a FieldValue>,
+}
+
+/// Function called with a [LevelFieldChange] when a level becomes the selected level.
+pub type LevelFieldCallback = Arc<dyn Fn(&mut Commands, &LevelFieldChange) + Send + Sync>;
+
+/// [Resource] storing the callbacks registered with [LevelFieldAppExt], by field identifier.
+#[derive(Clone, Default, Resource)]
+pub struct LevelFieldCallbacks {
+    pub callbacks: Vec<(String, LevelFieldCallback)>,
+}
+
+/// Provides functions to react to the fields of levels as they become the
+/// [LevelSelection](crate::prelude::LevelSelection).
+///
+/// Callbacks are called once per selected level, as soon as the level is found in a loaded
+/// project, before it spawns.
+/// They are only called for fields the level has.
+///
+/// Not intended for custom implementations on your own types.
+pub trait LevelFieldAppExt {
+    /// Registers a callback for the field with the given identifier.
+    /// ```no_run
+    /// use bevy::prelude::*;
+    /// use bevy_ecs_ldtk::{app::LevelFieldAppExt, prelude::*};
+    ///
+    /// fn main() {
+    ///     App::new()
+    ///         .add_plugins((DefaultPlugins, LdtkPlugin))
+    ///         .add_level_field_callback("music", |_, change| {
+    ///             if change.previous != Some(change.value) {
+    ///                 info!("crossfade from {:?} to {:?}", change.previous, change.value);
+    ///             }
+    ///         })
+    ///         // add other systems, plugins, resources...
+    ///         .run();
+    /// }
+    /// ```
+    fn add_level_field_callback(
+        &mut self,
+        field_identifier: impl Into<String>,
+        callback: impl Fn(&mut Commands, &LevelFieldChange) + Send + Sync + 'static,
+    ) -> &mut Self;
+
+    /// Inserts the resource created from the field's value, or removes it if `from_value` returns
+    /// `None`.
+    /// ```no_run
+    /// use bevy::prelude::*;
+    /// use bevy_ecs_ldtk::{app::LevelFieldAppExt, prelude::*};
+    ///
+    /// #[derive(Resource)]
+    /// struct Weather(String);
+    ///
+    /// fn main() {
+    ///     App::new()
+    ///         .add_plugins((DefaultPlugins, LdtkPlugin))
+    ///         .insert_level_field_resource("weather", |value| match value {
+    ///             FieldValue::Enum(Some(weather)) => Some(Weather(weather.clone())),
+    ///             _ => None,
+    ///         })
+    ///         // add other systems, plugins, resources...
+    ///         .run();
+    /// }
+    /// ```
+    fn insert_level_field_resource<R: Resource>(
+        &mut self,
+        field_identifier: impl Into<String>,
+        from_value: fn(&FieldValue) -> Option<R>,
+    ) -> &mut Self;
+}
+
+impl LevelFieldAppExt for App {
+    fn add_level_field_callback(
+        &mut self,
+        field_identifier: impl Into<String>,
+        callback: impl Fn(&mut Commands, &LevelFieldChange) + Send + Sync + 'static,
+    ) -> &mut Self {
+        if !self.world.contains_resource::<LevelFieldCallbacks>() {
+            self.init_resource::<LevelFieldCallbacks>().add_systems(
+                crate::plugin::ProcessLdtkApi,
+                systems::call_level_field_callbacks.after(systems::apply_level_selection),
+            );
+        }
+
+        self.world
+            .resource_mut::<LevelFieldCallbacks>()
+            .callbacks
+            .push((field_identifier.into(), Arc::new(callback)));
+
+        self
+    }
+
+    fn insert_level_field_resource<R: Resource>(
+        &mut self,
+        field_identifier: impl Into<String>,
+        from_value: fn(&FieldValue) -> Option<R>,
+    ) -> &mut Self {
+        self.add_level_field_callback(field_identifier, move |commands, change| {
+            match from_value(change.value) {
+                Some(resource) => commands.insert_resource(resource),
+                None => commands.remove_resource::<R>(),
+            }
+        })
+    }
+}
@@ -2,10 +2,24 @@
 
 mod entity_app_ext;
 mod int_cell_app_ext;
+mod layer_definition_app_ext;
 mod ldtk_entity;
 mod ldtk_int_cell;
+mod level_field_app_ext;
+mod post_processing_app_ext;
+mod project_config_app_ext;
+#[cfg(feature = "render")]
+mod tilemap_material_app_ext;
+mod z_ordering_app_ext;
 
 pub use entity_app_ext::*;
 pub use int_cell_app_ext::*;
+pub use layer_definition_app_ext::*;
 pub use ldtk_entity::*;
 pub use ldtk_int_cell::*;
+pub use level_field_app_ext::*;
+pub use post_processing_app_ext::*;
+pub use project_config_app_ext::*;
+#[cfg(feature = "render")]
+pub use tilemap_material_app_ext::*;
+pub use z_ordering_app_ext::*;
@@ -0,0 +1,95 @@
+//! Provides [LevelPostProcessingAppExt] for feeding per-level post-processing parameters into
+//! materials.
+use crate::{components::LevelPostProcessing, systems};
+use bevy::{asset::Asset, prelude::*};
+
+/// Function that writes [LevelPostProcessing] parameters into a material.
+pub type LevelPostProcessingApplier<M> = fn(&LevelPostProcessing, &mut M);
+
+/// [Resource] storing the materials of type `M` that receive the active level's
+/// [LevelPostProcessing] parameters.
+///
+/// Populated by [LevelPostProcessingAppExt].
+#[derive(Clone, Debug, Resource)]
+pub struct LevelPostProcessingMaterials<M: Asset> {
+    pub materials: Vec<(Handle<M>, LevelPostProcessingApplier<M>)>,
+}
+
+impl<M: Asset> Default for LevelPostProcessingMaterials<M> {
+    fn default() -> Self {
+        LevelPostProcessingMaterials {
+            materials: Vec::new(),
+        }
+    }
+}
+
+/// Provides functions to feed per-level post-processing parameters into materials.
+///
+/// Whenever the active level's [LevelPostProcessing] changes, the registered function is called on
+/// the material, so that color grading authored in LDtk takes effect automatically.
+///
+/// Not intended for custom implementations on your own types.
+pub trait LevelPostProcessingAppExt {
+    /// Registers a material to receive the active level's [LevelPostProcessing] parameters.
+    /// ```no_run
+    /// use bevy::{prelude::*, reflect::{TypePath, TypeUuid}, render::render_resource::AsBindGroup, sprite::Material2d};
+    /// use bevy_ecs_ldtk::{app::LevelPostProcessingAppExt, prelude::*};
+    ///
+    /// #[derive(AsBindGroup, TypeUuid, TypePath, Debug, Clone, Default)]
+    /// #[uuid = "c39e1a2c-61a4-4f5b-9b49-0f3ba0d7a0f1"]
+    /// struct ColorGrading {
+    ///     #[uniform(0)]
+    ///     saturation: f32,
+    /// }
+    ///
+    /// impl Material2d for ColorGrading {}
+    ///
+    /// fn main() {
+    ///     let mut app = App::new();
+    ///     app.add_plugins((DefaultPlugins, LdtkPlugin));
+    ///
+    ///     let grading = app
+    ///         .world
+    ///         .resource_mut::<Assets<ColorGrading>>()
+    ///         .add(ColorGrading::default());
+    ///
+    ///     app.add_level_post_processing_material(grading, |post_processing, material| {
+    ///         material.saturation = post_processing.float("saturation").unwrap_or(1.);
+    ///     })
+    ///     // add other systems, plugins, resources...
+    ///     .run();
+    /// }
+    /// ```
+    fn add_level_post_processing_material<M: Asset>(
+        &mut self,
+        material: Handle<M>,
+        apply: LevelPostProcessingApplier<M>,
+    ) -> &mut Self;
+}
+
+impl LevelPostProcessingAppExt for App {
+    fn add_level_post_processing_material<M: Asset>(
+        &mut self,
+        material: Handle<M>,
+        apply: LevelPostProcessingApplier<M>,
+    ) -> &mut Self {
+        if !self
+            .world
+            .contains_resource::<LevelPostProcessingMaterials<M>>()
+        {
+            self.init_resource::<LevelPostProcessingMaterials<M>>()
+                .add_systems(
+                    PostUpdate,
+                    systems::apply_level_post_processing_materials::<M>
+                        .after(systems::apply_active_level_post_processing),
+                );
+        }
+
+        self.world
+            .resource_mut::<LevelPostProcessingMaterials<M>>()
+            .materials
+            .push((material, apply));
+
+        self
+    }
+}
@@ -0,0 +1,201 @@
+//! Provides [ProjectConfigAppExt] for reading global settings authored in LDtk into resources.
+use crate::{
+    ldtk::{ldtk_fields::LdtkFields, raw_level_accessor::RawLevelAccessor},
+    systems,
+};
+use bevy::prelude::*;
+use std::sync::Arc;
+
+/// Where the fields of a project's configuration are authored.
+///
+/// LDtk doesn't support custom fields on projects or worlds, so configuration is read from the
+/// fields of a conventionally named level or entity instead.
+#[derive(Clone, Eq, PartialEq, Debug, Hash)]
+pub enum ProjectConfigSource {
+    /// The first level with this identifier.
+    Level(String),
+    /// The first entity with this identifier, in any level.
+    ///
+    /// Entities are only found in levels stored in the project file, so this doesn't support
+    /// projects with external levels.
+    Entity(String),
+}
+
+impl Default for ProjectConfigSource {
+    /// A level named `"Config"`.
+    fn default() -> Self {
+        ProjectConfigSource::Level("Config".to_string())
+    }
+}
+
+impl ProjectConfigSource {
+    /// Finds the level or entity with the configuration fields in the given project.
+    pub fn find_fields<'a>(
+        &self,
+        project: &'a impl RawLevelAccessor,
+    ) -> Option<&'a dyn LdtkFields> {
+        match self {
+            ProjectConfigSource::Level(identifier) => project
+                .iter_raw_levels()
+                .find(|level| level.identifier == *identifier)
+                .map(|level| level as &dyn LdtkFields),
+            ProjectConfigSource::Entity(identifier) => project
+                .iter_raw_levels()
+                .flat_map(|level| level.layer_instances.iter().flatten())
+                .flat_map(|layer_instance| layer_instance.entity_instances.iter())
+                .find(|entity_instance| entity_instance.identifier == *identifier)
+                .map(|entity_instance| entity_instance as &dyn LdtkFields),
+        }
+    }
+}
+
+/// Function called with the fields of a project's configuration when the project loads.
+pub type ProjectConfigCallback = Arc<dyn Fn(&mut Commands, &dyn LdtkFields) + Send + Sync>;
+
+/// [Resource] storing the callbacks registered with [ProjectConfigAppExt], by source.
+#[derive(Clone, Default, Resource)]
+pub struct ProjectConfigCallbacks {
+    pub callbacks: Vec<(ProjectConfigSource, ProjectConfigCallback)>,
+}
+
+/// Provides functions to read the fields of a configuration level or entity into resources as
+/// projects load.
+///
+/// This lets global tuning values authored in LDtk flow into the game without parsing them in
+/// your own systems.
+/// Callbacks are called in [PreUpdate] whenever an [LdtkProject] is loaded or modified, before its
+/// levels spawn.
+/// They are only called for projects that have the source.
+///
+/// Not intended for custom implementations on your own types.
+///
+/// [LdtkProject]: crate::assets::LdtkProject
+pub trait ProjectConfigAppExt {
+    /// Registers a callback for the fields of the given source.
+    fn add_project_config_callback(
+        &mut self,
+        source: ProjectConfigSource,
+        callback: impl Fn(&mut Commands, &dyn LdtkFields) + Send + Sync + 'static,
+    ) -> &mut Self;
+
+    /// Inserts the resource created from the fields of the given source, or removes it if
+    /// `from_fields` returns `None`.
+    /// ```no_run
+    /// use bevy::prelude::*;
+    /// use bevy_ecs_ldtk::{app::ProjectConfigAppExt, ldtk::ldtk_fields::LdtkFields, prelude::*};
+    ///
+    /// #[derive(Resource)]
+    /// struct Tuning {
+    ///     gravity: f32,
+    ///     max_health: i32,
+    /// }
+    ///
+    /// fn main() {
+    ///     App::new()
+    ///         .add_plugins((DefaultPlugins, LdtkPlugin))
+    ///         .insert_project_config_resource(ProjectConfigSource::default(), |fields| {
+    ///             Some(Tuning {
+    ///                 gravity: *fields.get_float_field("gravity").ok()?,
+    ///                 max_health: *fields.get_int_field("max_health").ok()?,
+    ///             })
+    ///         })
+    ///         // add other systems, plugins, resources...
+    ///         .run();
+    /// }
+    /// ```
+    fn insert_project_config_resource<R: Resource>(
+        &mut self,
+        source: ProjectConfigSource,
+        from_fields: fn(&dyn LdtkFields) -> Option<R>,
+    ) -> &mut Self;
+}
+
+impl ProjectConfigAppExt for App {
+    fn add_project_config_callback(
+        &mut self,
+        source: ProjectConfigSource,
+        callback: impl Fn(&mut Commands, &dyn LdtkFields) + Send + Sync + 'static,
+    ) -> &mut Self {
+        if !self.world.contains_resource::<ProjectConfigCallbacks>() {
+            self.init_resource::<ProjectConfigCallbacks>().add_systems(
+                PreUpdate,
+                systems::call_project_config_callbacks.before(systems::process_ldtk_levels),
+            );
+        }
+
+        self.world
+            .resource_mut::<ProjectConfigCallbacks>()
+            .callbacks
+            .push((source, Arc::new(callback)));
+
+        self
+    }
+
+    fn insert_project_config_resource<R: Resource>(
+        &mut self,
+        source: ProjectConfigSource,
+        from_fields: fn(&dyn LdtkFields) -> Option<R>,
+    ) -> &mut Self {
+        self.add_project_config_callback(source, move |commands, fields| {
+            match from_fields(fields) {
+                Some(resource) => commands.insert_resource(resource),
+                None => commands.remove_resource::<R>(),
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ldtk::{
+        ldtk_fields::field_instance_from_value, EntityInstance, FieldValue, LayerInstance,
+        LdtkJson, Level,
+    };
+
+    #[test]
+    fn sources_find_fields_of_levels_and_entities() {
+        let project = LdtkJson {
+            levels: vec![
+                Level {
+                    identifier: "Town".to_string(),
+                    layer_instances: Some(vec![LayerInstance {
+                        entity_instances: vec![EntityInstance {
+                            identifier: "Settings".to_string(),
+                            field_instances: vec![field_instance_from_value(
+                                "max_health",
+                                FieldValue::Int(Some(5)),
+                            )],
+                            ..Default::default()
+                        }],
+                        ..Default::default()
+                    }]),
+                    ..Default::default()
+                },
+                Level {
+                    identifier: "Config".to_string(),
+                    field_instances: vec![field_instance_from_value(
+                        "gravity",
+                        FieldValue::Float(Some(9.8)),
+                    )],
+                    ..Default::default()
+                },
+            ],
+            ..Default::default()
+        };
+
+        let level_fields = ProjectConfigSource::default()
+            .find_fields(&project)
+            .expect("config level should be found");
+        assert_eq!(level_fields.get_float_field("gravity"), Ok(&9.8));
+
+        let entity_fields = ProjectConfigSource::Entity("Settings".to_string())
+            .find_fields(&project)
+            .expect("settings entity should be found");
+        assert_eq!(entity_fields.get_int_field("max_health"), Ok(&5));
+
+        assert!(ProjectConfigSource::Level("Settings".to_string())
+            .find_fields(&project)
+            .is_none());
+    }
+}
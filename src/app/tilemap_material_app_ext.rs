@@ -0,0 +1,200 @@
+//! Provides [LdtkTilemapMaterialAppExt] for rendering LDtk layers with custom tilemap materials.
+use crate::systems;
+use bevy::prelude::*;
+use bevy_ecs_tilemap::prelude::{MaterialTilemap, MaterialTilemapPlugin};
+use std::collections::HashMap;
+
+/// [Resource] mapping layer identifiers and tile enum tags to the custom [MaterialTilemap] of type
+/// `M` they should be rendered with.
+///
+/// Populated by [LdtkTilemapMaterialAppExt], but it can also be modified at runtime.
+/// Changes only apply to layers spawned afterwards.
+#[derive(Clone, Debug, Resource)]
+pub struct LdtkTilemapMaterials<M: MaterialTilemap> {
+    pub materials: HashMap<String, Handle<M>>,
+    /// Materials for tilemaps with a [MaterialEnumTag], which take priority over `materials`.
+    ///
+    /// [MaterialEnumTag]: crate::prelude::MaterialEnumTag
+    pub enum_tag_materials: HashMap<String, Handle<M>>,
+}
+
+impl<M: MaterialTilemap> Default for LdtkTilemapMaterials<M> {
+    fn default() -> Self {
+        LdtkTilemapMaterials {
+            materials: HashMap::new(),
+            enum_tag_materials: HashMap::new(),
+        }
+    }
+}
+
+/// Provides functions to register custom [MaterialTilemap]s to bevy's [App] for particular LDtk
+/// layer identifiers.
+///
+/// By default, IntGrid, AutoTile, and Tile layers are rendered with `bevy_ecs_tilemap`'s
+/// standard material.
+/// Registering a material for a layer identifier will render those layers with your own shader
+/// instead, enabling effects like palette swaps, foliage sway, or dissolves.
+///
+/// *Requires the "render" feature, which is enabled by default*
+///
+/// Not intended for custom implementations on your own types.
+pub trait LdtkTilemapMaterialAppExt {
+    /// Registers a [MaterialTilemap] to render layers with the given identifier.
+    ///
+    /// The [MaterialTilemapPlugin] for `M` is added automatically if it hasn't been already.
+    /// ```no_run
+    /// use bevy::{prelude::*, reflect::{TypePath, TypeUuid}, render::render_resource::AsBindGroup};
+    /// use bevy_ecs_ldtk::{app::LdtkTilemapMaterialAppExt, prelude::*};
+    /// use bevy_ecs_tilemap::prelude::MaterialTilemap;
+    ///
+    /// #[derive(AsBindGroup, TypeUuid, TypePath, Debug, Clone, Default)]
+    /// #[uuid = "31575692-a956-4762-98c2-5d457f4f6c32"]
+    /// struct WindMaterial {
+    ///     #[uniform(0)]
+    ///     strength: f32,
+    /// }
+    ///
+    /// impl MaterialTilemap for WindMaterial {}
+    ///
+    /// fn main() {
+    ///     App::new()
+    ///         .add_plugins((DefaultPlugins, LdtkPlugin))
+    ///         .register_ldtk_tilemap_material("Foliage", WindMaterial { strength: 2. })
+    ///         // add other systems, plugins, resources...
+    ///         .run();
+    /// }
+    /// ```
+    fn register_ldtk_tilemap_material<M: MaterialTilemap>(
+        &mut self,
+        layer_identifier: &str,
+        material: M,
+    ) -> &mut Self;
+
+    /// Similar to [LdtkTilemapMaterialAppExt::register_ldtk_tilemap_material], except it accepts
+    /// a handle to a material that has already been added to [`Assets<M>`].
+    ///
+    /// Multiple layers can share the same handle this way.
+    fn register_ldtk_tilemap_material_handle<M: MaterialTilemap>(
+        &mut self,
+        layer_identifier: &str,
+        material: Handle<M>,
+    ) -> &mut Self;
+
+    /// Registers a [MaterialTilemap] to render tiles with the given enum tag, like emissive
+    /// materials for lava or crystal tiles.
+    ///
+    /// These tiles are only spawned in their own tilemaps if the enum tag is also in
+    /// [LdtkSettings::material_enum_tags].
+    /// ```no_run
+    /// # use bevy::{prelude::*, reflect::{TypePath, TypeUuid}, render::render_resource::AsBindGroup};
+    /// # use bevy_ecs_ldtk::{app::LdtkTilemapMaterialAppExt, prelude::*};
+    /// # use bevy_ecs_tilemap::prelude::MaterialTilemap;
+    /// #[derive(AsBindGroup, TypeUuid, TypePath, Debug, Clone, Default)]
+    /// #[uuid = "0d2d3f9c-6a4e-4d0e-9a55-0b5f1b8e4a21"]
+    /// struct GlowMaterial {
+    ///     #[uniform(0)]
+    ///     intensity: f32,
+    /// }
+    ///
+    /// impl MaterialTilemap for GlowMaterial {}
+    ///
+    /// fn main() {
+    ///     App::new()
+    ///         .add_plugins((DefaultPlugins, LdtkPlugin))
+    ///         .insert_resource(LdtkSettings {
+    ///             material_enum_tags: ["Lava".to_string()].into(),
+    ///             ..default()
+    ///         })
+    ///         .register_ldtk_enum_tag_material("Lava", GlowMaterial { intensity: 4. })
+    ///         // add other systems, plugins, resources...
+    ///         .run();
+    /// }
+    /// ```
+    ///
+    /// [LdtkSettings::material_enum_tags]: crate::prelude::LdtkSettings::material_enum_tags
+    fn register_ldtk_enum_tag_material<M: MaterialTilemap>(
+        &mut self,
+        enum_tag: &str,
+        material: M,
+    ) -> &mut Self;
+
+    /// Similar to [LdtkTilemapMaterialAppExt::register_ldtk_enum_tag_material], except it accepts
+    /// a handle to a material that has already been added to [`Assets<M>`].
+    fn register_ldtk_enum_tag_material_handle<M: MaterialTilemap>(
+        &mut self,
+        enum_tag: &str,
+        material: Handle<M>,
+    ) -> &mut Self;
+}
+
+/// Adds the resource, plugin, and system needed to render tilemaps with materials of type `M`.
+fn init_ldtk_tilemap_material<M: MaterialTilemap>(app: &mut App) {
+    if !app.world.contains_resource::<LdtkTilemapMaterials<M>>() {
+        if !app.is_plugin_added::<MaterialTilemapPlugin<M>>() {
+            app.add_plugins(MaterialTilemapPlugin::<M>::default());
+        }
+
+        app.init_resource::<LdtkTilemapMaterials<M>>()
+            .add_systems(PostUpdate, systems::apply_ldtk_tilemap_materials::<M>);
+    }
+}
+
+impl LdtkTilemapMaterialAppExt for App {
+    fn register_ldtk_tilemap_material<M: MaterialTilemap>(
+        &mut self,
+        layer_identifier: &str,
+        material: M,
+    ) -> &mut Self {
+        if !self.is_plugin_added::<MaterialTilemapPlugin<M>>() {
+            self.add_plugins(MaterialTilemapPlugin::<M>::default());
+        }
+
+        let handle = self.world.resource_mut::<Assets<M>>().add(material);
+
+        self.register_ldtk_tilemap_material_handle(layer_identifier, handle)
+    }
+
+    fn register_ldtk_tilemap_material_handle<M: MaterialTilemap>(
+        &mut self,
+        layer_identifier: &str,
+        material: Handle<M>,
+    ) -> &mut Self {
+        init_ldtk_tilemap_material::<M>(self);
+
+        self.world
+            .resource_mut::<LdtkTilemapMaterials<M>>()
+            .materials
+            .insert(layer_identifier.to_string(), material);
+
+        self
+    }
+
+    fn register_ldtk_enum_tag_material<M: MaterialTilemap>(
+        &mut self,
+        enum_tag: &str,
+        material: M,
+    ) -> &mut Self {
+        if !self.is_plugin_added::<MaterialTilemapPlugin<M>>() {
+            self.add_plugins(MaterialTilemapPlugin::<M>::default());
+        }
+
+        let handle = self.world.resource_mut::<Assets<M>>().add(material);
+
+        self.register_ldtk_enum_tag_material_handle(enum_tag, handle)
+    }
+
+    fn register_ldtk_enum_tag_material_handle<M: MaterialTilemap>(
+        &mut self,
+        enum_tag: &str,
+        material: Handle<M>,
+    ) -> &mut Self {
+        init_ldtk_tilemap_material::<M>(self);
+
+        self.world
+            .resource_mut::<LdtkTilemapMaterials<M>>()
+            .enum_tag_materials
+            .insert(enum_tag.to_string(), material);
+
+        self
+    }
+}
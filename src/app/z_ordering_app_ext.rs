@@ -0,0 +1,158 @@
+//! Provides [ZOrderingAppExt] for replacing how the z values of layers and entities are assigned.
+use crate::ldtk::{EntityInstance, LayerInstance, Level};
+use bevy::prelude::*;
+use std::sync::Arc;
+
+/// Context given to [ZOrderingStrategy::layer_z] for every layer entity that spawns.
+#[derive(Copy, Clone, Debug)]
+pub struct LayerZContext<'a> {
+    pub level: &'a Level,
+    pub layer_instance: &'a LayerInstance,
+    /// Index of the layer among the spawned layers of the level, from the bottom.
+    pub layer_index: usize,
+    /// The z the plugin would assign to the layer, according to [ZSpacing] and [LayerPlacement].
+    ///
+    /// [ZSpacing]: crate::prelude::ZSpacing
+    /// [LayerPlacement]: crate::prelude::LayerPlacement
+    pub default_z: f32,
+}
+
+/// Context given to [ZOrderingStrategy::entity_z] for every LDtk entity that spawns.
+#[derive(Copy, Clone, Debug)]
+pub struct EntityZContext<'a> {
+    pub level: &'a Level,
+    pub layer_instance: &'a LayerInstance,
+    pub entity_instance: &'a EntityInstance,
+    /// The translation of the entity relative to its layer, before its z is decided.
+    pub translation: Vec3,
+    /// The z read from the entity's fields according to [EntityZIndex], if any.
+    ///
+    /// [EntityZIndex]: crate::prelude::EntityZIndex
+    pub z_index: Option<f32>,
+}
+
+/// Decides the z values of the layers and entities of spawning levels.
+///
+/// Both methods default to the z values assigned by [LdtkSettings], so implementations only need
+/// to override the ones they change.
+/// Register an implementation with [ZOrderingAppExt::set_z_ordering_strategy].
+///
+/// [LdtkSettings]: crate::prelude::LdtkSettings
+pub trait ZOrderingStrategy: Send + Sync + 'static {
+    /// Returns the z of a layer entity, relative to its level.
+    ///
+    /// Layers split into several tilemaps, like y-sorted rows or enum tag materials, call this
+    /// once for each of them, and keep their offsets from one another.
+    fn layer_z(&self, context: &LayerZContext) -> f32 {
+        context.default_z
+    }
+
+    /// Returns the z of an entity relative to its layer, or `None` to leave it at its default.
+    ///
+    /// Like an [EntityZIndex] value, returning a z turns off [YSort] for the entity.
+    ///
+    /// [EntityZIndex]: crate::prelude::EntityZIndex
+    /// [YSort]: crate::prelude::YSort
+    fn entity_z(&self, context: &EntityZContext) -> Option<f32> {
+        context.z_index
+    }
+}
+
+/// The [ZOrderingStrategy] used unless another is registered, which keeps every default z.
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Default)]
+pub struct DefaultZOrdering;
+
+impl ZOrderingStrategy for DefaultZOrdering {}
+
+/// [Resource] storing the [ZOrderingStrategy] registered with [ZOrderingAppExt].
+#[derive(Clone, Resource)]
+pub struct LdtkZOrdering(pub Arc<dyn ZOrderingStrategy>);
+
+impl Default for LdtkZOrdering {
+    fn default() -> Self {
+        LdtkZOrdering(Arc::new(DefaultZOrdering))
+    }
+}
+
+/// Provides a function to replace how z values are assigned to spawning layers and entities.
+///
+/// Useful for games whose depth rules don't fit [LdtkSettings], like isometric depth, or custom
+/// sprites interleaved between specific layers.
+/// The strategy only affects levels spawned after it's registered.
+///
+/// Not intended for custom implementations on your own types.
+///
+/// [LdtkSettings]: crate::prelude::LdtkSettings
+pub trait ZOrderingAppExt {
+    /// Registers the [ZOrderingStrategy] used for all levels, replacing the previous one.
+    /// ```no_run
+    /// use bevy::prelude::*;
+    /// use bevy_ecs_ldtk::{app::*, prelude::*};
+    ///
+    /// /// Entities further down the screen are drawn in front, above every layer.
+    /// struct IsometricDepth;
+    ///
+    /// impl ZOrderingStrategy for IsometricDepth {
+    ///     fn entity_z(&self, context: &EntityZContext) -> Option<f32> {
+    ///         let level_height = context.level.px_hei as f32;
+    ///         Some(100. + (level_height - context.translation.y) / level_height)
+    ///     }
+    /// }
+    ///
+    /// fn main() {
+    ///     App::new()
+    ///         .add_plugins((DefaultPlugins, LdtkPlugin))
+    ///         .set_z_ordering_strategy(IsometricDepth)
+    ///         // add other systems, plugins, resources...
+    ///         .run();
+    /// }
+    /// ```
+    fn set_z_ordering_strategy(&mut self, strategy: impl ZOrderingStrategy) -> &mut Self;
+}
+
+impl ZOrderingAppExt for App {
+    fn set_z_ordering_strategy(&mut self, strategy: impl ZOrderingStrategy) -> &mut Self {
+        self.insert_resource(LdtkZOrdering(Arc::new(strategy)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Interleaved;
+
+    impl ZOrderingStrategy for Interleaved {
+        fn layer_z(&self, context: &LayerZContext) -> f32 {
+            context.layer_index as f32 * 10.
+        }
+    }
+
+    #[test]
+    fn strategies_default_to_plugin_z_values() {
+        let level = Level::default();
+        let layer_instance = LayerInstance::default();
+        let entity_instance = EntityInstance::default();
+
+        let layer_context = LayerZContext {
+            level: &level,
+            layer_instance: &layer_instance,
+            layer_index: 2,
+            default_z: 3.,
+        };
+        let entity_context = EntityZContext {
+            level: &level,
+            layer_instance: &layer_instance,
+            entity_instance: &entity_instance,
+            translation: Vec3::new(8., 8., 0.),
+            z_index: Some(5.),
+        };
+
+        let default_ordering = LdtkZOrdering::default();
+        assert_eq!(default_ordering.0.layer_z(&layer_context), 3.);
+        assert_eq!(default_ordering.0.entity_z(&entity_context), Some(5.));
+
+        assert_eq!(Interleaved.layer_z(&layer_context), 20.);
+        assert_eq!(Interleaved.entity_z(&entity_context), Some(5.));
+    }
+}
@@ -0,0 +1,189 @@
+//! Loading progress for LDtk projects and their dependencies, for use with loading states.
+//!
+//! *Requires the "asset_loader" feature*
+//!
+//! Loading an [`LdtkProject`] also loads its tileset images, level background images, and
+//! external level files.
+//! Loading states like those of [`bevy_asset_loader`] only wait for the project file itself, and
+//! continue before these dependencies are ready.
+//!
+//! The [`LdtkLoadingProgressPlugin`] reports the progress of every loaded project's dependencies
+//! to [`iyes_progress`], so a loading state only continues once all of them are loaded.
+//! Its progress is combined with the progress of the loading state's own asset collections:
+//! ```ignore
+//! use bevy::prelude::*;
+//! use bevy_asset_loader::prelude::*;
+//! use bevy_ecs_ldtk::{asset_loader::LdtkLoadingProgressPlugin, prelude::*};
+//! use iyes_progress::ProgressPlugin;
+//!
+//! #[derive(Clone, Eq, PartialEq, Debug, Default, Hash, States)]
+//! enum GameState {
+//!     #[default]
+//!     Loading,
+//!     Playing,
+//! }
+//!
+//! #[derive(AssetCollection, Resource)]
+//! struct LevelAssets {
+//!     #[asset(path = "my_project.ldtk")]
+//!     project: Handle<LdtkProject>,
+//! }
+//!
+//! fn main() {
+//!     App::new()
+//!         .add_plugins((DefaultPlugins, LdtkPlugin))
+//!         .add_state::<GameState>()
+//!         .add_plugins((
+//!             ProgressPlugin::new(GameState::Loading).continue_to(GameState::Playing),
+//!             LdtkLoadingProgressPlugin::new(GameState::Loading),
+//!         ))
+//!         .add_loading_state(LoadingState::new(GameState::Loading))
+//!         .add_collection_to_loading_state::<_, LevelAssets>(GameState::Loading)
+//!         .run();
+//! }
+//! ```
+//!
+//! Level files loaded lazily with [`ExternalLevelLoading::Lazy`] aren't dependencies of the
+//! project, so they aren't tracked.
+//!
+//! [`bevy_asset_loader`]: https://docs.rs/bevy_asset_loader
+//! [`ExternalLevelLoading::Lazy`]: crate::assets::ExternalLevelLoading::Lazy
+
+use crate::assets::{LdtkProject, LdtkProjectData};
+use bevy::{
+    asset::{HandleId, LoadState},
+    prelude::*,
+};
+use iyes_progress::{Progress, ProgressSystem};
+
+#[cfg(feature = "external_levels")]
+use crate::assets::ExternalLevelLoading;
+
+/// Plugin that tracks the loading progress of LDtk project dependencies in the given state.
+///
+/// See the [module-level documentation](crate::asset_loader) for details.
+///
+/// Not added by [`LdtkPlugin`].
+///
+/// [`LdtkPlugin`]: crate::prelude::LdtkPlugin
+#[derive(Clone, Debug)]
+pub struct LdtkLoadingProgressPlugin<S: States> {
+    state: S,
+}
+
+impl<S: States> LdtkLoadingProgressPlugin<S> {
+    /// Creates a plugin that tracks progress while in the given loading state.
+    pub fn new(state: S) -> Self {
+        LdtkLoadingProgressPlugin { state }
+    }
+}
+
+impl<S: States> Plugin for LdtkLoadingProgressPlugin<S> {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            track_ldtk_project_progress
+                .track_progress()
+                .run_if(in_state(self.state.clone())),
+        );
+    }
+}
+
+/// Handles of the assets loaded along with the project.
+fn project_dependencies(project: &LdtkProject, include_external_levels: bool) -> Vec<HandleId> {
+    // Tilesets loaded on demand aren't loaded along with the project
+    let tilesets = project
+        .tileset_map()
+        .iter()
+        .filter(|(uid, _)| !project.on_demand_tilesets().contains_key(uid))
+        .map(|(_, handle)| handle.id());
+
+    let levels: Vec<HandleId> = match project.data() {
+        #[cfg(feature = "internal_levels")]
+        LdtkProjectData::Standalone(project) => project
+            .level_map()
+            .values()
+            .filter_map(|metadata| metadata.bg_image().as_ref())
+            .map(|handle| handle.id())
+            .collect(),
+        #[cfg(feature = "external_levels")]
+        LdtkProjectData::Parent(project) => project
+            .level_map()
+            .values()
+            .flat_map(|metadata| {
+                let external_level =
+                    include_external_levels.then(|| metadata.external_handle().id());
+
+                metadata
+                    .metadata()
+                    .bg_image()
+                    .as_ref()
+                    .map(|handle| handle.id())
+                    .into_iter()
+                    .chain(external_level)
+            })
+            .collect(),
+    };
+
+    #[cfg(not(feature = "external_levels"))]
+    let _ = include_external_levels;
+
+    tilesets.chain(levels).collect()
+}
+
+/// Counts finished loads towards the progress.
+///
+/// Failed loads count as finished, so a missing file can't hold up the loading state forever.
+/// The asset server reports those failures itself.
+fn load_progress(load_states: impl IntoIterator<Item = LoadState>) -> Progress {
+    load_states
+        .into_iter()
+        .fold(Progress::default(), |progress, load_state| Progress {
+            done: progress.done
+                + matches!(load_state, LoadState::Loaded | LoadState::Failed) as u32,
+            total: progress.total + 1,
+        })
+}
+
+/// Reports the loading progress of the dependencies of every loaded [`LdtkProject`].
+///
+/// Projects that are still loading are tracked by the loading state itself.
+pub fn track_ldtk_project_progress(
+    asset_server: Res<AssetServer>,
+    ldtk_project_assets: Res<Assets<LdtkProject>>,
+    #[cfg(feature = "external_levels")] external_level_loading: Option<Res<ExternalLevelLoading>>,
+) -> Progress {
+    #[cfg(feature = "external_levels")]
+    let include_external_levels = !matches!(
+        external_level_loading.as_deref(),
+        Some(ExternalLevelLoading::Lazy { .. })
+    );
+
+    #[cfg(not(feature = "external_levels"))]
+    let include_external_levels = false;
+
+    load_progress(
+        ldtk_project_assets
+            .iter()
+            .flat_map(|(_, project)| project_dependencies(project, include_external_levels))
+            .map(|handle_id| asset_server.get_load_state(handle_id)),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn failed_and_loaded_dependencies_are_done() {
+        let progress = load_progress([
+            LoadState::Loaded,
+            LoadState::Loading,
+            LoadState::Failed,
+            LoadState::NotLoaded,
+        ]);
+
+        assert_eq!(progress, Progress { done: 2, total: 4 });
+        assert_eq!(load_progress([]), Progress { done: 0, total: 0 });
+    }
+}
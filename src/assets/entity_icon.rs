@@ -0,0 +1,133 @@
+use crate::ldtk::{EntityDefinition, TilesetDefinition};
+use bevy::{prelude::*, reflect::Reflect};
+use std::collections::HashMap;
+
+/// The tile an entity definition is displayed with in the LDtk editor.
+///
+/// Useful for displaying the same icons in game UI, like shop menus or map legends.
+/// Get them with [`LdtkProject::entity_icon`].
+///
+/// [`LdtkProject::entity_icon`]: crate::assets::LdtkProject::entity_icon
+#[derive(Clone, Debug, PartialEq, Reflect)]
+pub struct EntityIcon {
+    /// Image of the tileset the icon is in.
+    pub tileset: Handle<Image>,
+    /// Area of the icon in the tileset image, in pixels.
+    ///
+    /// Can be used as a [`Sprite::rect`].
+    pub rect: Rect,
+    /// The [`TextureAtlas`] sub-asset of the tileset, if it has one.
+    pub texture_atlas: Option<Handle<TextureAtlas>>,
+    /// Index of the icon in the `texture_atlas`.
+    ///
+    /// `None` if the icon isn't exactly one tile of the tileset's grid, in which case the `rect`
+    /// should be used instead.
+    pub atlas_index: Option<usize>,
+}
+
+impl EntityIcon {
+    /// Creates the icon of the given entity definition.
+    ///
+    /// Uses the definition's UI tile if it has one, which overrides its tile in the editor's UI.
+    /// Returns `None` if the definition has no tile, or if its tileset isn't loaded.
+    pub fn from_definition(
+        entity_definition: &EntityDefinition,
+        tileset_definitions: &[TilesetDefinition],
+        tileset_map: &HashMap<i32, Handle<Image>>,
+        tileset_atlas_map: &HashMap<i32, Handle<TextureAtlas>>,
+    ) -> Option<EntityIcon> {
+        let tileset_rectangle = entity_definition
+            .ui_tile_rect
+            .as_ref()
+            .or(entity_definition.tile_rect.as_ref())?;
+
+        let tileset = tileset_map.get(&tileset_rectangle.tileset_uid)?.clone();
+
+        let min = Vec2::new(tileset_rectangle.x as f32, tileset_rectangle.y as f32);
+        let size = Vec2::new(tileset_rectangle.w as f32, tileset_rectangle.h as f32);
+
+        let atlas_index = tileset_definitions
+            .iter()
+            .find(|tileset_definition| tileset_definition.uid == tileset_rectangle.tileset_uid)
+            .and_then(|tileset_definition| tileset_definition.atlas_index(tileset_rectangle));
+
+        Some(EntityIcon {
+            tileset,
+            rect: Rect::from_corners(min, min + size),
+            texture_atlas: tileset_atlas_map
+                .get(&tileset_rectangle.tileset_uid)
+                .cloned(),
+            atlas_index,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ldtk::TilesetRectangle;
+
+    #[test]
+    fn icons_prefer_ui_tiles() {
+        let tileset_definitions = vec![TilesetDefinition {
+            uid: 7,
+            tile_grid_size: 16,
+            c_wid: 4,
+            c_hei: 4,
+            ..default()
+        }];
+        let tileset = Handle::<Image>::default();
+        let tileset_map = HashMap::from([(7, tileset.clone())]);
+
+        let tile_rect = TilesetRectangle {
+            tileset_uid: 7,
+            x: 16,
+            y: 0,
+            w: 16,
+            h: 16,
+        };
+
+        let mut entity_definition = EntityDefinition {
+            tile_rect: Some(tile_rect),
+            ..default()
+        };
+
+        let icon = EntityIcon::from_definition(
+            &entity_definition,
+            &tileset_definitions,
+            &tileset_map,
+            &HashMap::new(),
+        )
+        .unwrap();
+        assert_eq!(icon.tileset, tileset);
+        assert_eq!(icon.rect, Rect::new(16., 0., 32., 16.));
+        assert_eq!(icon.atlas_index, Some(1));
+        assert_eq!(icon.texture_atlas, None);
+
+        entity_definition.ui_tile_rect = Some(TilesetRectangle {
+            y: 16,
+            w: 32,
+            ..tile_rect
+        });
+
+        let icon = EntityIcon::from_definition(
+            &entity_definition,
+            &tileset_definitions,
+            &tileset_map,
+            &HashMap::new(),
+        )
+        .unwrap();
+        assert_eq!(icon.rect, Rect::new(16., 16., 48., 32.));
+        assert_eq!(icon.atlas_index, None);
+
+        assert_eq!(
+            EntityIcon::from_definition(
+                &EntityDefinition::default(),
+                &tileset_definitions,
+                &tileset_map,
+                &HashMap::new(),
+            ),
+            None
+        );
+    }
+}
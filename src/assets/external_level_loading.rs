@@ -0,0 +1,274 @@
+use crate::{
+    assets::{
+        ldtk_project::ldtk_path_to_asset_path, LdtkExternalLevel, LdtkProject, LdtkProjectData,
+        LdtkProjectHandle, LevelMetadataAccessor,
+    },
+    components::{LevelIid, LevelSet},
+    ldtk::raw_level_accessor::RawLevelAccessor,
+};
+use bevy::{
+    asset::{AssetPath, LoadState},
+    prelude::*,
+};
+use std::collections::HashMap;
+
+/// [`Resource`] that determines when the level files of external-levels projects are loaded.
+///
+/// The loader reads this when it is created, so insert it before adding the [`LdtkPlugin`].
+/// ```no_run
+/// # use bevy::prelude::*;
+/// # use bevy_ecs_ldtk::{assets::ExternalLevelLoading, prelude::*};
+/// App::new()
+///     .insert_resource(ExternalLevelLoading::Lazy {
+///         max_retries: 5,
+///         retry_delay: 0.5,
+///     })
+///     .add_plugins((DefaultPlugins, LdtkPlugin))
+///     .run();
+/// ```
+///
+/// On web builds, this defaults to [`ExternalLevelLoading::Lazy`], so a project's level files
+/// aren't all requested over the network at startup.
+///
+/// [`LdtkPlugin`]: crate::prelude::LdtkPlugin
+#[derive(Copy, Clone, PartialEq, Debug, Resource)]
+pub enum ExternalLevelLoading {
+    /// Level files are loaded along with the project.
+    Eager,
+    /// Level files are loaded when their level enters a [`LevelSet`], and retried with
+    /// exponential backoff if they fail to load.
+    ///
+    /// Progress is reported with [`ExternalLevelFetchEvent`]s and the [`ExternalLevelFetches`]
+    /// resource.
+    Lazy {
+        /// Number of times a failed level file is requested again before giving up.
+        max_retries: u32,
+        /// Seconds before the first retry, doubled with each following retry.
+        retry_delay: f32,
+    },
+}
+
+impl Default for ExternalLevelLoading {
+    fn default() -> Self {
+        if cfg!(target_arch = "wasm32") {
+            ExternalLevelLoading::Lazy {
+                max_retries: 3,
+                retry_delay: 1.,
+            }
+        } else {
+            ExternalLevelLoading::Eager
+        }
+    }
+}
+
+impl ExternalLevelLoading {
+    /// Seconds to wait before the given retry, starting from 1.
+    ///
+    /// Returns `None` if no more retries should be made.
+    pub fn retry_delay(&self, retry: u32) -> Option<f32> {
+        match self {
+            ExternalLevelLoading::Lazy {
+                max_retries,
+                retry_delay,
+            } if retry <= *max_retries => Some(retry_delay * 2f32.powi(retry as i32 - 1)),
+            _ => None,
+        }
+    }
+}
+
+/// Events fired while lazily loading level files with [`ExternalLevelLoading::Lazy`].
+#[derive(Clone, Eq, PartialEq, Debug, Hash, Event)]
+pub enum ExternalLevelFetchEvent {
+    /// The level file has been requested.
+    Requested(LevelIid),
+    /// The level file has loaded, and the level will spawn.
+    Loaded(LevelIid),
+    /// The level file failed to load, and will be requested again.
+    Retrying { level_iid: LevelIid, retry: u32 },
+    /// The level file failed to load after all retries.
+    Failed(LevelIid),
+}
+
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+enum FetchStatus {
+    Pending,
+    Loaded,
+    Failed,
+}
+
+#[derive(Clone, Debug)]
+struct Fetch {
+    path: AssetPath<'static>,
+    handle: Handle<LdtkExternalLevel>,
+    status: FetchStatus,
+    retries: u32,
+    retry_at: Option<f64>,
+}
+
+/// [`Resource`] tracking the level files requested with [`ExternalLevelLoading::Lazy`].
+///
+/// Requested level files stay loaded.
+#[derive(Clone, Debug, Default, Resource)]
+pub struct ExternalLevelFetches {
+    fetches: HashMap<LevelIid, Fetch>,
+}
+
+impl ExternalLevelFetches {
+    /// Number of requested level files that haven't loaded or failed yet.
+    pub fn pending(&self) -> usize {
+        self.count(FetchStatus::Pending)
+    }
+
+    /// Number of requested level files that failed after all retries.
+    pub fn failed(&self) -> usize {
+        self.count(FetchStatus::Failed)
+    }
+
+    /// Fraction of requested level files that have loaded, from 0 to 1.
+    ///
+    /// This is 1 if no level files have been requested.
+    pub fn progress(&self) -> f32 {
+        if self.fetches.is_empty() {
+            1.
+        } else {
+            self.count(FetchStatus::Loaded) as f32 / self.fetches.len() as f32
+        }
+    }
+
+    fn count(&self, status: FetchStatus) -> usize {
+        self.fetches
+            .values()
+            .filter(|fetch| fetch.status == status)
+            .count()
+    }
+}
+
+/// Requests the level files of levels in [`LevelSet`]s, and retries failed requests.
+///
+/// Only does anything with [`ExternalLevelLoading::Lazy`].
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn fetch_external_levels(
+    loading: Res<ExternalLevelLoading>,
+    asset_server: Res<AssetServer>,
+    time: Res<Time>,
+    ldtk_project_assets: Res<Assets<LdtkProject>>,
+    level_assets: Res<Assets<LdtkExternalLevel>>,
+    world_query: Query<(&LdtkProjectHandle, &LevelSet)>,
+    mut fetches: ResMut<ExternalLevelFetches>,
+    mut fetch_events: EventWriter<ExternalLevelFetchEvent>,
+) {
+    if *loading == ExternalLevelLoading::Eager {
+        return;
+    }
+
+    for (ldtk_handle, level_set) in world_query.iter() {
+        let (Some(project), Some(project_path)) = (
+            ldtk_project_assets.get(ldtk_handle),
+            asset_server.get_handle_path(&ldtk_handle.handle),
+        ) else {
+            continue;
+        };
+
+        let LdtkProjectData::Parent(project) = project.data() else {
+            continue;
+        };
+
+        for level_iid in level_set.iids.iter() {
+            if fetches.fetches.contains_key(level_iid) {
+                continue;
+            }
+
+            let (Some(level_metadata), Some(level)) = (
+                project.level_map().get(level_iid.get()),
+                project.get_raw_level_by_iid(level_iid.get()),
+            ) else {
+                continue;
+            };
+
+            if level_assets.contains(level_metadata.external_handle()) {
+                continue;
+            }
+
+            let Some(rel_path) = &level.external_rel_path else {
+                continue;
+            };
+
+            let path = ldtk_path_to_asset_path(project_path.path(), rel_path);
+
+            fetches.fetches.insert(
+                level_iid.clone(),
+                Fetch {
+                    handle: asset_server.load(path.clone()),
+                    path,
+                    status: FetchStatus::Pending,
+                    retries: 0,
+                    retry_at: None,
+                },
+            );
+            fetch_events.send(ExternalLevelFetchEvent::Requested(level_iid.clone()));
+        }
+    }
+
+    let now = time.elapsed_seconds_f64();
+
+    for (level_iid, fetch) in fetches.fetches.iter_mut() {
+        if fetch.status != FetchStatus::Pending {
+            continue;
+        }
+
+        if let Some(retry_at) = fetch.retry_at {
+            if now >= retry_at {
+                fetch.handle = asset_server.load(fetch.path.clone());
+                fetch.retry_at = None;
+            }
+
+            continue;
+        }
+
+        match asset_server.get_load_state(&fetch.handle) {
+            LoadState::Loaded => {
+                fetch.status = FetchStatus::Loaded;
+                fetch_events.send(ExternalLevelFetchEvent::Loaded(level_iid.clone()));
+            }
+            LoadState::Failed => {
+                fetch.retries += 1;
+
+                match loading.retry_delay(fetch.retries) {
+                    Some(delay) => {
+                        fetch.retry_at = Some(now + delay as f64);
+                        fetch_events.send(ExternalLevelFetchEvent::Retrying {
+                            level_iid: level_iid.clone(),
+                            retry: fetch.retries,
+                        });
+                    }
+                    None => {
+                        fetch.status = FetchStatus::Failed;
+                        fetch_events.send(ExternalLevelFetchEvent::Failed(level_iid.clone()));
+                    }
+                }
+            }
+            _ => (),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn retries_back_off_exponentially() {
+        let loading = ExternalLevelLoading::Lazy {
+            max_retries: 3,
+            retry_delay: 0.5,
+        };
+
+        assert_eq!(loading.retry_delay(1), Some(0.5));
+        assert_eq!(loading.retry_delay(2), Some(1.));
+        assert_eq!(loading.retry_delay(3), Some(2.));
+        assert_eq!(loading.retry_delay(4), None);
+        assert_eq!(ExternalLevelLoading::Eager.retry_delay(1), None);
+
+        assert_eq!(ExternalLevelFetches::default().progress(), 1.);
+    }
+}
@@ -1,6 +1,10 @@
+#[cfg(feature = "internal_levels")]
+use crate::assets::LdtkPatchLoader;
 #[cfg(feature = "external_levels")]
-use crate::assets::{ldtk_external_level::LdtkExternalLevelLoader, LdtkExternalLevel};
-use crate::assets::{ldtk_project::LdtkProjectLoader, LdtkProject};
+use crate::assets::{
+    ldtk_external_level::LdtkExternalLevelLoader, ExternalLevelLoading, LdtkExternalLevel,
+};
+use crate::assets::{ldtk_project::LdtkProjectLoader, LdtkLoaderSettingsMap, LdtkProject};
 use bevy::prelude::*;
 
 /// Plugin that registers LDtk-related assets.
@@ -9,9 +13,22 @@ pub struct LdtkAssetPlugin;
 
 impl Plugin for LdtkAssetPlugin {
     fn build(&self, app: &mut App) {
+        // The loaders read these resources when they are created
+        #[cfg(feature = "external_levels")]
+        {
+            app.init_resource::<ExternalLevelLoading>();
+        }
+
+        app.init_resource::<LdtkLoaderSettingsMap>();
+
         app.add_asset::<LdtkProject>()
             .init_asset_loader::<LdtkProjectLoader>();
 
+        #[cfg(feature = "internal_levels")]
+        {
+            app.init_asset_loader::<LdtkPatchLoader>();
+        }
+
         #[cfg(feature = "external_levels")]
         {
             app.add_asset::<LdtkExternalLevel>()
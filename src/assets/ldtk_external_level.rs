@@ -1,4 +1,7 @@
-use crate::ldtk::{loaded_level::LoadedLevel, Level};
+use crate::{
+    assets::LdtkLoaderSettingsMap,
+    ldtk::{loaded_level::LoadedLevel, Level},
+};
 use bevy::{
     asset::{AssetLoader, LoadContext, LoadedAsset},
     prelude::*,
@@ -11,9 +14,16 @@ use thiserror::Error;
 ///
 /// Loaded as a dependency of the [`LdtkProject`] asset.
 ///
+/// Like every asset, level files are read and parsed in a task on bevy's IO task pool, not on the
+/// main thread.
+/// Levels that stream in mid-gameplay, like neighbors loaded with
+/// [`ExternalLevelLoading::Lazy`], only reach the main thread once they've been parsed, so the
+/// level entity waits for the asset instead of blocking the frame.
+///
 /// Requires the `external_levels` feature to be enabled.
 ///
 /// [`LdtkProject`]: crate::assets::LdtkProject
+/// [`ExternalLevelLoading::Lazy`]: crate::assets::ExternalLevelLoading::Lazy
 #[derive(Clone, Debug, PartialEq, TypeUuid, Reflect)]
 #[uuid = "5448469b-2134-44f5-a86c-a7b829f70a0c"]
 pub struct LdtkExternalLevel {
@@ -47,8 +57,24 @@ pub enum LdtkExternalLevelLoaderError {
 }
 
 /// AssetLoader for [`LdtkExternalLevel`]
-#[derive(Default)]
-pub struct LdtkExternalLevelLoader;
+pub struct LdtkExternalLevelLoader {
+    /// Settings of the level files being loaded, by asset path.
+    ///
+    /// Populated by the [`LdtkProject`] loader from the settings of the parent project.
+    ///
+    /// [`LdtkProject`]: crate::assets::LdtkProject
+    settings: LdtkLoaderSettingsMap,
+}
+
+impl FromWorld for LdtkExternalLevelLoader {
+    fn from_world(world: &mut World) -> Self {
+        LdtkExternalLevelLoader {
+            settings: world
+                .get_resource_or_insert_with(LdtkLoaderSettingsMap::default)
+                .clone(),
+        }
+    }
+}
 
 impl AssetLoader for LdtkExternalLevelLoader {
     fn load<'a>(
@@ -57,12 +83,18 @@ impl AssetLoader for LdtkExternalLevelLoader {
         load_context: &'a mut LoadContext,
     ) -> BoxedFuture<'a, anyhow::Result<()>> {
         Box::pin(async move {
-            let data: Level = serde_json::from_slice(bytes)?;
+            let mut data: Level =
+                info_span!("load_ldtk_external_level", path = %load_context.path().display())
+                    .in_scope(|| serde_json::from_slice(bytes))?;
 
             if data.layer_instances.is_none() {
                 Err(LdtkExternalLevelLoaderError::NullLayers)?;
             }
 
+            self.settings
+                .get(load_context.path())
+                .filter_layers(&mut data);
+
             let ldtk_level = LdtkExternalLevel { data };
 
             let loaded_asset = LoadedAsset::new(ldtk_level);
@@ -131,6 +131,57 @@ impl LdtkJsonWithMetadata<InternalLevels> {
         self.find_raw_level_by_level_selection(level_selection)
             .map(expect_level_loaded)
     }
+
+    /// Mutable access to the raw level with the given iid, along with the project's definitions.
+    ///
+    /// Only public to the crate so that levels can't be unloaded, which would break the type
+    /// guarantees of the other accessors.
+    pub(crate) fn get_raw_level_by_iid_mut(
+        &mut self,
+        iid: &String,
+    ) -> Option<(&mut Level, &crate::ldtk::Definitions)> {
+        let indices = *self.level_map.get(iid)?.indices();
+
+        let LdtkJson {
+            levels,
+            worlds,
+            defs,
+            ..
+        } = &mut self.json_data;
+
+        let level = match indices.world {
+            Some(world) => worlds.get_mut(world)?.levels.get_mut(indices.level)?,
+            None => levels.get_mut(indices.level)?,
+        };
+
+        Some((level, defs))
+    }
+
+    /// Adds a loaded level to the project, in the given world or in the root of the project.
+    ///
+    /// Only public to the crate so that unloaded levels can't be added, which would break the type
+    /// guarantees of the other accessors.
+    pub(crate) fn push_loaded_level(
+        &mut self,
+        level: Level,
+        world: Option<usize>,
+    ) -> Option<LevelIndices> {
+        let levels = match world {
+            Some(world) => &mut self.json_data.worlds.get_mut(world)?.levels,
+            None => &mut self.json_data.levels,
+        };
+
+        let indices = LevelIndices {
+            world,
+            level: levels.len(),
+        };
+
+        self.level_map
+            .insert(level.iid.clone(), LevelMetadata::new(None, indices));
+        levels.push(level);
+
+        Some(indices)
+    }
 }
 
 #[cfg(feature = "external_levels")]
@@ -0,0 +1,181 @@
+use crate::ldtk::Level;
+use bevy::prelude::*;
+use std::{
+    collections::{HashMap, HashSet},
+    path::{Path, PathBuf},
+    sync::{Arc, RwLock},
+};
+
+/// Settings that change how a single LDtk project is loaded.
+///
+/// Configure them per asset path with [`LdtkLoaderSettingsMap`].
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct LdtkLoaderSettings {
+    /// Whether the project's background color can be used as the clear color.
+    ///
+    /// See [`SetClearColor`].
+    ///
+    /// [`SetClearColor`]: crate::prelude::SetClearColor
+    pub clear_color: bool,
+    /// Whether level background images are loaded.
+    pub backgrounds: bool,
+    /// Identifiers of layers that are removed from the project's levels when loading.
+    pub excluded_layers: HashSet<String>,
+    /// Replacement paths for tileset images, keyed by the path used in the project.
+    ///
+    /// Paths are relative to the project file, like in LDtk.
+    pub tileset_path_remaps: HashMap<String, String>,
+    /// Whether tileset images are only loaded while a spawned level uses them.
+    ///
+    /// By default, the project holds strong handles to all of its tilesets, keeping them in
+    /// memory for as long as the project is loaded.
+    /// When this is enabled, the project only holds weak handles, and each spawned level holds
+    /// the tilesets it uses in a [`LevelTilesets`] component instead.
+    /// Tilesets are then unloaded once no spawned level uses them.
+    ///
+    /// [`LevelTilesets`]: crate::prelude::LevelTilesets
+    pub tilesets_on_demand: bool,
+}
+
+impl Default for LdtkLoaderSettings {
+    fn default() -> Self {
+        LdtkLoaderSettings {
+            clear_color: true,
+            backgrounds: true,
+            excluded_layers: HashSet::new(),
+            tileset_path_remaps: HashMap::new(),
+            tilesets_on_demand: false,
+        }
+    }
+}
+
+impl LdtkLoaderSettings {
+    /// Returns the path of the given tileset image, after remapping.
+    pub fn tileset_path<'a>(&'a self, rel_path: &'a str) -> &'a str {
+        self.tileset_path_remaps
+            .get(rel_path)
+            .map(String::as_str)
+            .unwrap_or(rel_path)
+    }
+
+    /// Removes excluded layers from the level.
+    pub fn filter_layers(&self, level: &mut Level) {
+        if self.excluded_layers.is_empty() {
+            return;
+        }
+
+        for layer_instances in level.layer_instances.iter_mut() {
+            layer_instances.retain(|layer_instance| {
+                !self.excluded_layers.contains(&layer_instance.identifier)
+            });
+        }
+    }
+}
+
+/// [`Resource`] storing the [`LdtkLoaderSettings`] of LDtk files, by asset path.
+///
+/// Files without settings are loaded with the default settings.
+/// Settings must be set before the file starts loading to take effect:
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_ecs_ldtk::{assets::{LdtkLoaderSettings, LdtkLoaderSettingsMap}, prelude::*};
+/// fn load_minimap(
+///     mut commands: Commands,
+///     asset_server: Res<AssetServer>,
+///     loader_settings: Res<LdtkLoaderSettingsMap>,
+/// ) {
+///     loader_settings.set(
+///         "minimap.ldtk",
+///         LdtkLoaderSettings {
+///             backgrounds: false,
+///             excluded_layers: ["Decorations".to_string()].into(),
+///             ..default()
+///         },
+///     );
+///
+///     commands.spawn(LdtkWorldBundle {
+///         ldtk_handle: asset_server.load("minimap.ldtk").into(),
+///         ..default()
+///     });
+/// }
+/// ```
+///
+/// The settings of an external-levels project also apply to its level files.
+///
+/// The map is shared with the asset loaders, so it can be changed without mutable access.
+#[derive(Clone, Debug, Default, Resource)]
+pub struct LdtkLoaderSettingsMap {
+    settings: Arc<RwLock<HashMap<PathBuf, LdtkLoaderSettings>>>,
+}
+
+impl LdtkLoaderSettingsMap {
+    /// Sets the settings used for loading the file at the given asset path.
+    pub fn set(&self, path: impl Into<PathBuf>, settings: LdtkLoaderSettings) {
+        self.settings
+            .write()
+            .expect("LdtkLoaderSettingsMap lock should not be poisoned")
+            .insert(path.into(), settings);
+    }
+
+    /// Removes the settings of the file at the given asset path, returning them.
+    pub fn remove(&self, path: &Path) -> Option<LdtkLoaderSettings> {
+        self.settings
+            .write()
+            .expect("LdtkLoaderSettingsMap lock should not be poisoned")
+            .remove(path)
+    }
+
+    /// Returns the settings used for loading the file at the given asset path.
+    pub fn get(&self, path: &Path) -> LdtkLoaderSettings {
+        self.settings
+            .read()
+            .expect("LdtkLoaderSettingsMap lock should not be poisoned")
+            .get(path)
+            .cloned()
+            .unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ldtk::LayerInstance;
+
+    #[test]
+    fn settings_are_stored_per_path() {
+        let map = LdtkLoaderSettingsMap::default();
+        let settings = LdtkLoaderSettings {
+            excluded_layers: ["Decorations".to_string()].into(),
+            tileset_path_remaps: [("tiles.png".to_string(), "tiles_hd.png".to_string())].into(),
+            ..default()
+        };
+
+        map.set("minimap.ldtk", settings.clone());
+        assert_eq!(map.clone().get(Path::new("minimap.ldtk")), settings);
+        assert_eq!(
+            map.get(Path::new("world.ldtk")),
+            LdtkLoaderSettings::default()
+        );
+
+        assert_eq!(settings.tileset_path("tiles.png"), "tiles_hd.png");
+        assert_eq!(settings.tileset_path("items.png"), "items.png");
+
+        let mut level = Level {
+            layer_instances: Some(vec![
+                LayerInstance {
+                    identifier: "Decorations".to_string(),
+                    ..default()
+                },
+                LayerInstance {
+                    identifier: "Walls".to_string(),
+                    ..default()
+                },
+            ]),
+            ..default()
+        };
+        settings.filter_layers(&mut level);
+        assert_eq!(level.layer_instances.unwrap()[0].identifier, "Walls");
+
+        assert_eq!(map.remove(Path::new("minimap.ldtk")), Some(settings));
+    }
+}
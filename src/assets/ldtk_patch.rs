@@ -0,0 +1,315 @@
+use crate::{
+    assets::{ldtk_project::load_ldtk_project, LdtkLoaderSettingsMap},
+    ldtk::{FieldInstance, LdtkJson, Level},
+};
+use bevy::{
+    asset::{AssetLoader, LoadContext},
+    prelude::*,
+    utils::BoxedFuture,
+};
+use path_clean::PathClean;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+
+/// Errors that can occur when applying an [`LdtkPatch`].
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum LdtkPatchError {
+    /// Patches can only be applied to projects with internal levels.
+    #[error("patched project {0} uses external levels")]
+    ExternalLevels(String),
+    /// An [`EntityPatch`] refers to an entity that isn't in the project.
+    #[error("no entity with iid {0} to patch")]
+    EntityNotFound(String),
+}
+
+/// Overrides for a single LDtk entity in an [`LdtkPatch`].
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EntityPatch {
+    /// Iid of the entity to override.
+    pub iid: String,
+    /// New pixel coordinates of the entity, relative to its level.
+    #[serde(default)]
+    pub px: Option<IVec2>,
+    /// Field instances that replace the entity's field instances with the same identifier.
+    ///
+    /// Field instances are in LDtk's format.
+    #[serde(default)]
+    pub field_instances: Vec<FieldInstance>,
+}
+
+/// Contents of a `.ldtkpatch` file, which loads as a base [`LdtkProject`] with overrides.
+///
+/// Patches allow DLC, mods, and per-platform tweaks to change a project without forking the
+/// project file.
+/// Load them like any other project, with `asset_server.load("my_project.ldtkpatch")`.
+///
+/// The overrides are applied in the order of the fields:
+/// 1. The levels of each of the `projects`, which replace the levels with matching iids in the
+/// base project.
+/// Levels with new iids are added to the first world.
+/// 2. The `levels`, in the same manner.
+/// 3. The `entities`.
+///
+/// ```json
+/// {
+///     "base": "my_project.ldtk",
+///     "projects": ["dlc/extra_levels.ldtk"],
+///     "entities": [{ "iid": "a1b2c3d4-...", "px": [64, 32] }]
+/// }
+/// ```
+///
+/// Paths are relative to the patch file.
+/// Paths in the base project, like those of tilesets, are still resolved relative to the base
+/// project.
+/// Only projects with internal levels can be patched.
+///
+/// [`LdtkProject`]: crate::assets::LdtkProject
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LdtkPatch {
+    /// Path to the base project.
+    pub base: String,
+    /// Paths to LDtk projects whose levels override the levels of the base project.
+    #[serde(default)]
+    pub projects: Vec<String>,
+    /// Levels, in LDtk's format, that override the levels of the base project.
+    #[serde(default)]
+    pub levels: Vec<Level>,
+    /// Overrides for entities of the patched project, matched by iid in any level.
+    ///
+    /// Each can move its entity and replace its field instances.
+    /// Applying the patch fails if an entity isn't found.
+    #[serde(default)]
+    pub entities: Vec<EntityPatch>,
+}
+
+impl LdtkPatch {
+    /// Applies the `levels` and `entities` of this patch to the given project data.
+    ///
+    /// The levels of the `projects` need to be applied beforehand with [`override_levels`].
+    pub fn apply(&self, data: &mut LdtkJson) -> Result<(), LdtkPatchError> {
+        override_levels(data, self.levels.iter().cloned());
+
+        for entity_patch in &self.entities {
+            patch_entity(data, entity_patch)?;
+        }
+
+        Ok(())
+    }
+}
+
+fn levels_mut(data: &mut LdtkJson) -> impl Iterator<Item = &mut Level> {
+    data.levels.iter_mut().chain(
+        data.worlds
+            .iter_mut()
+            .flat_map(|world| world.levels.iter_mut()),
+    )
+}
+
+/// Replaces the levels of the project with the given levels, matching them by iid.
+///
+/// Levels that don't match any level of the project are added to its first world.
+pub fn override_levels(data: &mut LdtkJson, levels: impl IntoIterator<Item = Level>) {
+    for level in levels {
+        let existing = levels_mut(data).find(|existing| existing.iid == level.iid);
+
+        if let Some(existing) = existing {
+            *existing = level;
+        } else if let Some(world) = data.worlds.first_mut() {
+            world.levels.push(level);
+        } else {
+            data.levels.push(level);
+        }
+    }
+}
+
+/// Applies an [`EntityPatch`] to the matching entity of the project.
+pub fn patch_entity(data: &mut LdtkJson, entity_patch: &EntityPatch) -> Result<(), LdtkPatchError> {
+    for level in levels_mut(data) {
+        let (level_world_x, level_world_y) = (level.world_x, level.world_y);
+
+        for layer_instance in level.layer_instances.iter_mut().flatten() {
+            let grid_size = layer_instance.grid_size.max(1);
+
+            let Some(entity_instance) = layer_instance
+                .entity_instances
+                .iter_mut()
+                .find(|entity_instance| entity_instance.iid == entity_patch.iid)
+            else {
+                continue;
+            };
+
+            if let Some(px) = entity_patch.px {
+                entity_instance.px = px;
+                entity_instance.grid = px / grid_size;
+                entity_instance.world_x = level_world_x + px.x;
+                entity_instance.world_y = level_world_y + px.y;
+            }
+
+            for field_instance in &entity_patch.field_instances {
+                match entity_instance
+                    .field_instances
+                    .iter_mut()
+                    .find(|existing| existing.identifier == field_instance.identifier)
+                {
+                    Some(existing) => *existing = field_instance.clone(),
+                    None => entity_instance.field_instances.push(field_instance.clone()),
+                }
+            }
+
+            return Ok(());
+        }
+    }
+
+    Err(LdtkPatchError::EntityNotFound(entity_patch.iid.clone()))
+}
+
+fn patch_relative_path(patch_path: &Path, rel_path: &str) -> PathBuf {
+    patch_path
+        .parent()
+        .unwrap()
+        .join(Path::new(rel_path))
+        .clean()
+}
+
+/// AssetLoader for `.ldtkpatch` files, which load as [`LdtkProject`]s.
+///
+/// The patched project is loaded with the [`LdtkLoaderSettings`] set for the patch's path.
+///
+/// [`LdtkProject`]: crate::assets::LdtkProject
+/// [`LdtkLoaderSettings`]: crate::assets::LdtkLoaderSettings
+pub struct LdtkPatchLoader {
+    /// Settings of the patches being loaded, by asset path.
+    settings: LdtkLoaderSettingsMap,
+}
+
+impl FromWorld for LdtkPatchLoader {
+    fn from_world(world: &mut World) -> Self {
+        let settings = world
+            .get_resource_or_insert_with(LdtkLoaderSettingsMap::default)
+            .clone();
+
+        LdtkPatchLoader { settings }
+    }
+}
+
+impl AssetLoader for LdtkPatchLoader {
+    fn load<'a>(
+        &'a self,
+        bytes: &'a [u8],
+        load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, anyhow::Result<()>> {
+        Box::pin(async move {
+            let patch: LdtkPatch = serde_json::from_slice(bytes)?;
+
+            let base_path = patch_relative_path(load_context.path(), &patch.base);
+            let mut data: LdtkJson =
+                serde_json::from_slice(&load_context.read_asset_bytes(&base_path).await?)?;
+
+            if data.external_levels {
+                return Err(LdtkPatchError::ExternalLevels(patch.base.clone()).into());
+            }
+
+            for project in &patch.projects {
+                let project_path = patch_relative_path(load_context.path(), project);
+                let project_data: LdtkJson =
+                    serde_json::from_slice(&load_context.read_asset_bytes(&project_path).await?)?;
+
+                if project_data.external_levels {
+                    return Err(LdtkPatchError::ExternalLevels(project.clone()).into());
+                }
+
+                override_levels(
+                    &mut data,
+                    project_data
+                        .levels
+                        .into_iter()
+                        .chain(project_data.worlds.into_iter().flat_map(|w| w.levels)),
+                );
+            }
+
+            patch.apply(&mut data)?;
+
+            let settings = self.settings.get(load_context.path());
+
+            load_ldtk_project(data, &base_path, load_context, false, &settings)
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["ldtkpatch"]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ldtk::{EntityInstance, LayerInstance, World};
+
+    fn level(iid: &str, entity_iids: &[&str]) -> Level {
+        Level {
+            iid: iid.to_string(),
+            world_x: 100,
+            layer_instances: Some(vec![LayerInstance {
+                grid_size: 16,
+                entity_instances: entity_iids
+                    .iter()
+                    .map(|iid| EntityInstance {
+                        iid: iid.to_string(),
+                        ..Default::default()
+                    })
+                    .collect(),
+                ..Default::default()
+            }]),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn patches_override_levels_and_entities() {
+        let mut data = LdtkJson {
+            worlds: vec![World {
+                levels: vec![level("a", &["chest"]), level("b", &[])],
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+
+        let patch = LdtkPatch {
+            levels: vec![level("b", &["door"]), level("c", &[])],
+            entities: vec![EntityPatch {
+                iid: "chest".to_string(),
+                px: Some(IVec2::new(40, 8)),
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+
+        patch.apply(&mut data).unwrap();
+
+        let levels = &data.worlds[0].levels;
+        assert_eq!(levels.len(), 3);
+        assert_eq!(
+            levels[1].layer_instances.as_ref().unwrap()[0].entity_instances[0].iid,
+            "door"
+        );
+
+        let chest = &levels[0].layer_instances.as_ref().unwrap()[0].entity_instances[0];
+        assert_eq!(chest.px, IVec2::new(40, 8));
+        assert_eq!(chest.grid, IVec2::new(2, 0));
+        assert_eq!(chest.world_x, 140);
+
+        assert_eq!(
+            patch_entity(
+                &mut data,
+                &EntityPatch {
+                    iid: "missing".to_string(),
+                    ..Default::default()
+                }
+            ),
+            Err(LdtkPatchError::EntityNotFound("missing".to_string()))
+        );
+    }
+}
@@ -2,9 +2,10 @@ use std::path::Path;
 
 use crate::{
     assets::{
-        LdtkJsonWithMetadata, LdtkProjectData, LevelIndices, LevelMetadata, LevelMetadataAccessor,
+        EntityIcon, ExternalLevelAssets, LdtkJsonWithMetadata, LdtkLoaderSettings,
+        LdtkLoaderSettingsMap, LdtkProjectData, LevelIndices, LevelMetadata, LevelMetadataAccessor,
     },
-    ldtk::{raw_level_accessor::RawLevelAccessor, LdtkJson, Level},
+    ldtk::{loaded_level::LoadedLevel, raw_level_accessor::RawLevelAccessor, LdtkJson, Level},
 };
 use bevy::{
     asset::{AssetLoader, AssetPath, LoadContext, LoadedAsset},
@@ -22,9 +23,9 @@ use thiserror::Error;
 use crate::assets::InternalLevels;
 
 #[cfg(feature = "external_levels")]
-use crate::assets::{ExternalLevelMetadata, ExternalLevels};
+use crate::assets::{ExternalLevelLoading, ExternalLevelMetadata, ExternalLevels};
 
-fn ldtk_path_to_asset_path<'b>(ldtk_path: &Path, rel_path: &str) -> AssetPath<'b> {
+pub(crate) fn ldtk_path_to_asset_path<'b>(ldtk_path: &Path, rel_path: &str) -> AssetPath<'b> {
     ldtk_path
         .parent()
         .unwrap()
@@ -72,6 +73,9 @@ fn ldtk_path_to_asset_path<'b>(ldtk_path: &Path, rel_path: &str) -> AssetPath<'b
 /// You will also need the [`LdtkExternalLevel`] asset collection.
 /// With these, you can use these [`external_level` accessors].
 ///
+/// If you need to support both cases, [`LdtkProject::get_loaded_level_by_iid`] works for either,
+/// taking the [`LdtkExternalLevel`] asset collection as an optional argument.
+///
 /// [`LoadedLevel`]: crate::ldtk::loaded_level::LoadedLevel
 /// [`LdtkExternalLevel`]: crate::assets::LdtkExternalLevel
 /// [`loaded_level` accessors]: LdtkJsonWithMetadata#impl-LdtkJsonWithMetadata<InternalLevels>
@@ -85,13 +89,32 @@ pub struct LdtkProject {
     tileset_map: HashMap<i32, Handle<Image>>,
     /// Image used for rendering int grid colors.
     int_grid_image_handle: Option<Handle<Image>>,
+    /// Whether the project's background color can be used as the clear color.
+    ///
+    /// See [`LdtkLoaderSettings::clear_color`].
+    sets_clear_color: bool,
+    /// Asset paths of the tilesets that are loaded when levels using them spawn, by tileset uid.
+    ///
+    /// Handles in the `tileset_map` of these tilesets are weak.
+    /// See [`LdtkLoaderSettings::tilesets_on_demand`].
+    #[reflect(ignore)]
+    on_demand_tilesets: HashMap<i32, AssetPath<'static>>,
+    /// Map from tileset uids to the [`TextureAtlas`]es slicing them into their tiles.
+    ///
+    /// These are labeled sub-assets of the project.
+    /// See [`LdtkProject::tileset_atlas_label`].
+    tileset_atlas_map: HashMap<i32, Handle<TextureAtlas>>,
+    /// Map from entity identifiers to the icons of entity definitions with a tile.
+    ///
+    /// See [`LdtkProject::entity_icon`].
+    entity_icons: HashMap<String, EntityIcon>,
 }
 
 impl LdtkProject {
     /// Construct a new [`LdtkProject`].
     ///
-    /// Private to preserve type guarantees about loaded levels.
-    fn new(
+    /// Only public to the crate to preserve type guarantees about loaded levels.
+    pub(crate) fn new(
         data: LdtkProjectData,
         tileset_map: HashMap<i32, Handle<Image>>,
         int_grid_image_handle: Option<Handle<Image>>,
@@ -100,9 +123,68 @@ impl LdtkProject {
             data,
             tileset_map,
             int_grid_image_handle,
+            sets_clear_color: true,
+            on_demand_tilesets: HashMap::new(),
+            tileset_atlas_map: HashMap::new(),
+            entity_icons: HashMap::new(),
         }
     }
 
+    /// The label of the [`TextureAtlas`] sub-asset of the tileset with the given identifier.
+    ///
+    /// Every tileset with an image gets one when the project loads, slicing the image with the
+    /// tileset's tile size, padding, and spacing.
+    /// It can be loaded with a path like `"my_project.ldtk#tileset_atlas/Dungeon"`, or accessed
+    /// with [`LdtkProject::tileset_atlas`].
+    pub fn tileset_atlas_label(tileset_identifier: &str) -> String {
+        format!("tileset_atlas/{tileset_identifier}")
+    }
+
+    /// The [`TextureAtlas`] sub-asset of the tileset with the given identifier, if it has an image.
+    pub fn tileset_atlas(&self, tileset_identifier: &str) -> Option<&Handle<TextureAtlas>> {
+        self.json_data()
+            .defs
+            .tilesets
+            .iter()
+            .find(|tileset| tileset.identifier == tileset_identifier)
+            .and_then(|tileset| self.tileset_atlas_map.get(&tileset.uid))
+    }
+
+    /// The icon of the entity definition with the given identifier, if it has a tile.
+    ///
+    /// These are the tiles the LDtk editor displays entities with.
+    /// ```
+    /// # use bevy::prelude::*;
+    /// # use bevy_ecs_ldtk::prelude::*;
+    /// fn spawn_shop_item(
+    ///     mut commands: Commands,
+    ///     ldtk_projects: Res<Assets<LdtkProject>>,
+    ///     ldtk_project_query: Query<&LdtkProjectHandle>,
+    /// ) {
+    ///     let Some(ldtk_project) = ldtk_project_query
+    ///         .get_single()
+    ///         .ok()
+    ///         .and_then(|handle| ldtk_projects.get(handle))
+    ///     else {
+    ///         return;
+    ///     };
+    ///
+    ///     if let Some(icon) = ldtk_project.entity_icon("Chest") {
+    ///         commands.spawn(SpriteBundle {
+    ///             texture: icon.tileset.clone(),
+    ///             sprite: Sprite {
+    ///                 rect: Some(icon.rect),
+    ///                 ..default()
+    ///             },
+    ///             ..default()
+    ///         });
+    ///     }
+    /// }
+    /// ```
+    pub fn entity_icon(&self, entity_identifier: &str) -> Option<&EntityIcon> {
+        self.entity_icons.get(entity_identifier)
+    }
+
     /// Raw ldtk json data.
     pub fn json_data(&self) -> &LdtkJson {
         self.data.json_data()
@@ -133,6 +215,62 @@ impl LdtkProject {
     pub fn as_parent(&self) -> &LdtkJsonWithMetadata<ExternalLevels> {
         self.data.as_parent()
     }
+
+    /// Returns the loaded level with the given iid, for both internal- and external-level projects.
+    ///
+    /// The `external_level_assets` are only needed for projects with external levels.
+    /// See [`LdtkProjectData::get_loaded_level_by_iid`] for more details.
+    pub fn get_loaded_level_by_iid<'a>(
+        &'a self,
+        external_level_assets: Option<&'a ExternalLevelAssets>,
+        iid: &String,
+    ) -> Option<LoadedLevel<'a>> {
+        self.data
+            .get_loaded_level_by_iid(external_level_assets, iid)
+    }
+
+    /// Mutable access to the raw level with the given iid, along with the project's definitions.
+    ///
+    /// Returns [`None`] for projects with external levels.
+    #[cfg(feature = "internal_levels")]
+    pub(crate) fn get_raw_level_by_iid_mut(
+        &mut self,
+        iid: &String,
+    ) -> Option<(&mut Level, &crate::ldtk::Definitions)> {
+        self.data.as_standalone_mut()?.get_raw_level_by_iid_mut(iid)
+    }
+
+    /// Adds a level to the project, in the given world or in the root of the project.
+    ///
+    /// The level can then be spawned like any other level, for example with a [`LevelSet`].
+    /// Since this modifies the asset, worlds using this project respawn, like they do when the
+    /// asset is hot-reloaded.
+    /// Levels added this way never have a background image.
+    ///
+    /// See [`LevelBuilder`] for assembling levels in code.
+    ///
+    /// [`LevelSet`]: crate::prelude::LevelSet
+    /// [`LevelBuilder`]: crate::level_builder::LevelBuilder
+    #[cfg(feature = "internal_levels")]
+    pub fn add_level(
+        &mut self,
+        level: Level,
+        world: Option<usize>,
+    ) -> Result<LevelIndices, AddLevelError> {
+        if level.layer_instances.is_none() {
+            return Err(AddLevelError::NotLoaded);
+        }
+
+        if self.get_level_metadata_by_iid(&level.iid).is_some() {
+            return Err(AddLevelError::DuplicateIid(level.iid));
+        }
+
+        self.data
+            .as_standalone_mut()
+            .ok_or(AddLevelError::ExternalLevels)?
+            .push_loaded_level(level, world)
+            .ok_or(AddLevelError::WorldNotFound)
+    }
 }
 
 impl RawLevelAccessor for LdtkProject {
@@ -143,6 +281,10 @@ impl RawLevelAccessor for LdtkProject {
     fn root_levels(&self) -> &[Level] {
         self.data.root_levels()
     }
+
+    fn root_world_layout(&self) -> Option<crate::ldtk::WorldLayout> {
+        self.data.root_world_layout()
+    }
 }
 
 impl LevelMetadataAccessor for LdtkProject {
@@ -151,6 +293,19 @@ impl LevelMetadataAccessor for LdtkProject {
     }
 }
 
+/// Errors that can occur when adding a level to an [`LdtkProject`].
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum AddLevelError {
+    #[error("level has no layer instances")]
+    NotLoaded,
+    #[error("project already has a level with iid {0}")]
+    DuplicateIid(String),
+    #[error("levels can only be added to projects with internal levels")]
+    ExternalLevels,
+    #[error("project has no world at the given index")]
+    WorldNotFound,
+}
+
 /// Errors that can occur when loading an [`LdtkProject`] asset.
 #[allow(dead_code)]
 #[derive(Debug, Error)]
@@ -170,8 +325,37 @@ pub enum LdtkProjectLoaderError {
 }
 
 /// AssetLoader for [`LdtkProject`].
-#[derive(Default)]
-pub struct LdtkProjectLoader;
+pub struct LdtkProjectLoader {
+    /// Whether external level files are left out of the project's dependencies.
+    lazy_external_levels: bool,
+    /// Settings of the projects being loaded, by asset path.
+    settings: LdtkLoaderSettingsMap,
+}
+
+impl FromWorld for LdtkProjectLoader {
+    fn from_world(world: &mut World) -> Self {
+        #[cfg(feature = "external_levels")]
+        let lazy_external_levels = matches!(
+            world.get_resource::<ExternalLevelLoading>(),
+            Some(ExternalLevelLoading::Lazy { .. })
+        );
+
+        #[cfg(not(feature = "external_levels"))]
+        let lazy_external_levels = {
+            let _ = world;
+            false
+        };
+
+        let settings = world
+            .get_resource_or_insert_with(LdtkLoaderSettingsMap::default)
+            .clone();
+
+        LdtkProjectLoader {
+            lazy_external_levels,
+            settings,
+        }
+    }
+}
 
 struct LoadLevelMetadataResult<'a, L> {
     dependent_asset_paths: Vec<AssetPath<'a>>,
@@ -180,15 +364,18 @@ struct LoadLevelMetadataResult<'a, L> {
 
 fn load_level_metadata<'a>(
     load_context: &LoadContext,
+    project_path: &Path,
     level_indices: LevelIndices,
     level: &Level,
     expect_level_loaded: bool,
+    settings: &LdtkLoaderSettings,
 ) -> Result<LoadLevelMetadataResult<'a, LevelMetadata>, LdtkProjectLoaderError> {
     let (bg_image_path, bg_image) = level
         .bg_rel_path
         .as_ref()
+        .filter(|_| settings.backgrounds)
         .map(|rel_path| {
-            let asset_path = ldtk_path_to_asset_path(load_context.path(), rel_path);
+            let asset_path = ldtk_path_to_asset_path(project_path, rel_path);
 
             (
                 Some(asset_path.clone()),
@@ -212,16 +399,26 @@ fn load_level_metadata<'a>(
 #[cfg(feature = "external_levels")]
 fn load_external_level_metadata<'a>(
     load_context: &LoadContext,
+    project_path: &Path,
     level_indices: LevelIndices,
     level: &Level,
+    lazy: bool,
+    settings: &LdtkLoaderSettings,
 ) -> Result<LoadLevelMetadataResult<'a, ExternalLevelMetadata>, LdtkProjectLoaderError> {
     let LoadLevelMetadataResult {
         level_metadata,
         mut dependent_asset_paths,
-    } = load_level_metadata(load_context, level_indices, level, false)?;
+    } = load_level_metadata(
+        load_context,
+        project_path,
+        level_indices,
+        level,
+        false,
+        settings,
+    )?;
 
     let external_level_path = ldtk_path_to_asset_path(
-        load_context.path(),
+        project_path,
         level
             .external_rel_path
             .as_ref()
@@ -229,7 +426,11 @@ fn load_external_level_metadata<'a>(
     );
 
     let external_handle = load_context.get_handle(external_level_path.clone());
-    dependent_asset_paths.push(external_level_path);
+
+    // Lazily-loaded level files are requested once their level is selected
+    if !lazy {
+        dependent_asset_paths.push(external_level_path);
+    }
 
     Ok(LoadLevelMetadataResult {
         level_metadata: ExternalLevelMetadata::new(level_metadata, external_handle),
@@ -237,94 +438,199 @@ fn load_external_level_metadata<'a>(
     })
 }
 
-impl AssetLoader for LdtkProjectLoader {
-    fn load<'a>(
-        &'a self,
-        bytes: &'a [u8],
-        load_context: &'a mut LoadContext,
-    ) -> BoxedFuture<'a, anyhow::Result<()>> {
-        Box::pin(async move {
-            let data: LdtkJson = serde_json::from_slice(bytes)?;
-
-            let mut dependent_asset_paths = Vec::new();
-
-            let mut tileset_map: HashMap<i32, Handle<Image>> = HashMap::new();
-            for tileset in &data.defs.tilesets {
-                if let Some(tileset_path) = &tileset.rel_path {
-                    let asset_path = ldtk_path_to_asset_path(load_context.path(), tileset_path);
-
-                    dependent_asset_paths.push(asset_path.clone());
-                    tileset_map.insert(tileset.uid, load_context.get_handle(asset_path));
-                } else if tileset.embed_atlas.is_some() {
-                    warn!("Ignoring LDtk's Internal_Icons. They cannot be displayed due to their license.");
-                } else {
-                    let identifier = &tileset.identifier;
-                    warn!("{identifier} tileset cannot be loaded, it has a null relative path.");
-                }
+/// Builds an [`LdtkProject`] from parsed project data and sets it as the loaded asset.
+///
+/// Relative paths in the project are resolved from `project_path`.
+/// If `lazy_external_levels` is true, external level files aren't loaded as dependencies.
+pub(crate) fn load_ldtk_project(
+    mut data: LdtkJson,
+    project_path: &Path,
+    load_context: &mut LoadContext,
+    lazy_external_levels: bool,
+    settings: &LdtkLoaderSettings,
+) -> anyhow::Result<()> {
+    let _span = info_span!("load_ldtk_project", path = %project_path.display()).entered();
+
+    let mut dependent_asset_paths = Vec::new();
+
+    let mut tileset_map: HashMap<i32, Handle<Image>> = HashMap::new();
+    let mut on_demand_tilesets: HashMap<i32, AssetPath<'static>> = HashMap::new();
+    for tileset in &data.defs.tilesets {
+        if let Some(tileset_path) = &tileset.rel_path {
+            let asset_path: AssetPath<'static> =
+                ldtk_path_to_asset_path(project_path, settings.tileset_path(tileset_path));
+
+            if settings.tilesets_on_demand {
+                tileset_map.insert(tileset.uid, Handle::weak(asset_path.clone().into()));
+                on_demand_tilesets.insert(tileset.uid, asset_path);
+            } else {
+                dependent_asset_paths.push(asset_path.clone());
+                tileset_map.insert(tileset.uid, load_context.get_handle(asset_path));
             }
+        } else if tileset.embed_atlas.is_some() {
+            warn!("Ignoring LDtk's Internal_Icons. They cannot be displayed due to their license.");
+        } else {
+            let identifier = &tileset.identifier;
+            warn!("{identifier} tileset cannot be loaded, it has a null relative path.");
+        }
+    }
 
-            let int_grid_image_handle = data.defs.create_int_grid_image().map(|image| {
-                load_context.set_labeled_asset("int_grid_image", LoadedAsset::new(image))
-            });
+    let tileset_atlas_map: HashMap<i32, Handle<TextureAtlas>> = data
+        .defs
+        .tilesets
+        .iter()
+        .filter_map(|tileset| {
+            let texture = tileset_map.get(&tileset.uid)?.clone();
+            let texture_atlas = tileset.create_texture_atlas(texture);
+
+            Some((
+                tileset.uid,
+                load_context.set_labeled_asset(
+                    &LdtkProject::tileset_atlas_label(&tileset.identifier),
+                    LoadedAsset::new(texture_atlas),
+                ),
+            ))
+        })
+        .collect();
+
+    let entity_icons: HashMap<String, EntityIcon> = data
+        .defs
+        .entities
+        .iter()
+        .filter_map(|entity_definition| {
+            let entity_icon = EntityIcon::from_definition(
+                entity_definition,
+                &data.defs.tilesets,
+                &tileset_map,
+                &tileset_atlas_map,
+            )?;
+
+            Some((entity_definition.identifier.clone(), entity_icon))
+        })
+        .collect();
+
+    let int_grid_image_handle = info_span!("create_int_grid_image")
+        .in_scope(|| data.defs.create_int_grid_image())
+        .map(|image| load_context.set_labeled_asset("int_grid_image", LoadedAsset::new(image)));
+
+    // External levels are filtered by their own loader
+    for level in data.levels.iter_mut().chain(
+        data.worlds
+            .iter_mut()
+            .flat_map(|world| world.levels.iter_mut()),
+    ) {
+        settings.filter_layers(level);
+    }
 
-            let ldtk_project = if data.external_levels {
-                #[cfg(feature = "external_levels")]
-                {
-                    let mut level_map = HashMap::new();
+    let mut ldtk_project = if data.external_levels {
+        #[cfg(feature = "external_levels")]
+        {
+            let mut level_map = HashMap::new();
+
+            for (level_indices, level) in data.iter_raw_levels_with_indices() {
+                let LoadLevelMetadataResult {
+                    level_metadata,
+                    dependent_asset_paths: new_asset_paths,
+                } = load_external_level_metadata(
+                    load_context,
+                    project_path,
+                    level_indices,
+                    level,
+                    lazy_external_levels,
+                    settings,
+                )?;
+
+                level_map.insert(level.iid.clone(), level_metadata);
+                dependent_asset_paths.extend(new_asset_paths);
+            }
 
-                    for (level_indices, level) in data.iter_raw_levels_with_indices() {
-                        let LoadLevelMetadataResult {
-                            level_metadata,
-                            dependent_asset_paths: new_asset_paths,
-                        } = load_external_level_metadata(load_context, level_indices, level)?;
+            LdtkProject::new(
+                LdtkProjectData::Parent(LdtkJsonWithMetadata::new(data, level_map)),
+                tileset_map,
+                int_grid_image_handle,
+            )
+        }
 
-                        level_map.insert(level.iid.clone(), level_metadata);
-                        dependent_asset_paths.extend(new_asset_paths);
-                    }
+        #[cfg(not(feature = "external_levels"))]
+        {
+            let _ = lazy_external_levels;
+            Err(LdtkProjectLoaderError::ExternalLevelsDisabled)?
+        }
+    } else {
+        #[cfg(feature = "internal_levels")]
+        {
+            let mut level_map = HashMap::new();
+
+            for (level_indices, level) in data.iter_raw_levels_with_indices() {
+                let LoadLevelMetadataResult {
+                    level_metadata,
+                    dependent_asset_paths: new_asset_paths,
+                } = load_level_metadata(
+                    load_context,
+                    project_path,
+                    level_indices,
+                    level,
+                    true,
+                    settings,
+                )?;
+
+                level_map.insert(level.iid.clone(), level_metadata);
+                dependent_asset_paths.extend(new_asset_paths);
+            }
 
-                    LdtkProject::new(
-                        LdtkProjectData::Parent(LdtkJsonWithMetadata::new(data, level_map)),
-                        tileset_map,
-                        int_grid_image_handle,
-                    )
-                }
+            LdtkProject::new(
+                LdtkProjectData::Standalone(LdtkJsonWithMetadata::new(data, level_map)),
+                tileset_map,
+                int_grid_image_handle,
+            )
+        }
 
-                #[cfg(not(feature = "external_levels"))]
-                {
-                    Err(LdtkProjectLoaderError::ExternalLevelsDisabled)?
-                }
-            } else {
-                #[cfg(feature = "internal_levels")]
-                {
-                    let mut level_map = HashMap::new();
-
-                    for (level_indices, level) in data.iter_raw_levels_with_indices() {
-                        let LoadLevelMetadataResult {
-                            level_metadata,
-                            dependent_asset_paths: new_asset_paths,
-                        } = load_level_metadata(load_context, level_indices, level, true)?;
-
-                        level_map.insert(level.iid.clone(), level_metadata);
-                        dependent_asset_paths.extend(new_asset_paths);
-                    }
+        #[cfg(not(feature = "internal_levels"))]
+        {
+            Err(LdtkProjectLoaderError::InternalLevelsDisabled)?
+        }
+    };
 
-                    LdtkProject::new(
-                        LdtkProjectData::Standalone(LdtkJsonWithMetadata::new(data, level_map)),
-                        tileset_map,
-                        int_grid_image_handle,
-                    )
-                }
+    ldtk_project.sets_clear_color = settings.clear_color;
+    ldtk_project.on_demand_tilesets = on_demand_tilesets;
+    ldtk_project.tileset_atlas_map = tileset_atlas_map;
+    ldtk_project.entity_icons = entity_icons;
 
-                #[cfg(not(feature = "internal_levels"))]
-                {
-                    Err(LdtkProjectLoaderError::InternalLevelsDisabled)?
+    load_context
+        .set_default_asset(LoadedAsset::new(ldtk_project).with_dependencies(dependent_asset_paths));
+    Ok(())
+}
+
+impl AssetLoader for LdtkProjectLoader {
+    fn load<'a>(
+        &'a self,
+        bytes: &'a [u8],
+        load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, anyhow::Result<()>> {
+        Box::pin(async move {
+            let data: LdtkJson =
+                info_span!("parse_ldtk_json").in_scope(|| serde_json::from_slice(bytes))?;
+            let project_path = load_context.path().to_path_buf();
+            let settings = self.settings.get(&project_path);
+
+            // Level files are loaded separately, so they look up the project's settings by path
+            if data.external_levels && settings != LdtkLoaderSettings::default() {
+                for level in data.iter_raw_levels() {
+                    if let Some(rel_path) = &level.external_rel_path {
+                        let level_path = ldtk_path_to_asset_path(&project_path, rel_path);
+                        self.settings
+                            .set(level_path.path().to_path_buf(), settings.clone());
+                    }
                 }
-            };
+            }
 
-            load_context.set_default_asset(
-                LoadedAsset::new(ldtk_project).with_dependencies(dependent_asset_paths),
-            );
-            Ok(())
+            load_ldtk_project(
+                data,
+                &project_path,
+                load_context,
+                self.lazy_external_levels,
+                &settings,
+            )
         })
     }
 
@@ -367,6 +673,10 @@ mod tests {
                 data,
                 tileset_map,
                 int_grid_image_handle: Some(Handle::weak(HandleId::random::<Image>())),
+                sets_clear_color: true,
+                on_demand_tilesets: HashMap::new(),
+                tileset_atlas_map: HashMap::new(),
+                entity_icons: HashMap::new(),
             }
         }
     }
@@ -1,6 +1,6 @@
 use crate::{
     assets::{LdtkJsonWithMetadata, LevelMetadata, LevelMetadataAccessor},
-    ldtk::{LdtkJson, Level},
+    ldtk::{loaded_level::LoadedLevel, LdtkJson, Level},
     prelude::RawLevelAccessor,
 };
 use bevy::reflect::Reflect;
@@ -10,7 +10,24 @@ use derive_more::{From, TryInto};
 use crate::assets::InternalLevels;
 
 #[cfg(feature = "external_levels")]
-use crate::assets::ExternalLevels;
+use crate::assets::{ExternalLevels, LdtkExternalLevel};
+
+/// The [`LdtkExternalLevel`] asset collection, used to access the loaded levels of parent projects
+/// in [`LdtkProjectData::get_loaded_level_by_iid`].
+///
+/// Without the `external_levels` feature, this is the unit type, so code calling that method
+/// compiles regardless of which level features are enabled.
+///
+/// [`LdtkExternalLevel`]: crate::assets::LdtkExternalLevel
+#[cfg(feature = "external_levels")]
+pub type ExternalLevelAssets = bevy::asset::Assets<LdtkExternalLevel>;
+
+/// Placeholder for the `LdtkExternalLevel` asset collection, which only exists with the
+/// `external_levels` feature.
+///
+/// See [`LdtkProjectData::get_loaded_level_by_iid`].
+#[cfg(not(feature = "external_levels"))]
+pub type ExternalLevelAssets = ();
 
 /// LDtk json data and level metadata for both internal- and external-level projects.
 ///
@@ -78,6 +95,45 @@ impl LdtkProjectData {
     pub fn as_parent(&self) -> &LdtkJsonWithMetadata<ExternalLevels> {
         self.try_into().unwrap()
     }
+
+    /// Returns the loaded level with the given iid, for both standalone and parent projects.
+    ///
+    /// The `external_level_assets` are only used by parent projects, which return [`None`]
+    /// without them.
+    /// So, code that supports both project types can pass them along instead of matching on the
+    /// project data.
+    ///
+    /// These levels are [loaded], meaning that they are type-guaranteed to have complete data.
+    ///
+    /// [loaded]: crate::assets::LdtkProject#raw-vs-loaded-levels
+    #[cfg_attr(not(feature = "external_levels"), allow(unused_variables))]
+    pub fn get_loaded_level_by_iid<'a>(
+        &'a self,
+        external_level_assets: Option<&'a ExternalLevelAssets>,
+        iid: &String,
+    ) -> Option<LoadedLevel<'a>> {
+        match self {
+            #[cfg(feature = "internal_levels")]
+            LdtkProjectData::Standalone(project) => project.get_loaded_level_by_iid(iid),
+            #[cfg(feature = "external_levels")]
+            LdtkProjectData::Parent(project) => {
+                project.get_external_level_by_iid(external_level_assets?, iid)
+            }
+        }
+    }
+
+    /// Mutable version of [`LdtkProjectData::as_standalone`], returning [`None`] instead of
+    /// panicking.
+    #[cfg(feature = "internal_levels")]
+    pub(crate) fn as_standalone_mut(
+        &mut self,
+    ) -> Option<&mut LdtkJsonWithMetadata<InternalLevels>> {
+        match self {
+            LdtkProjectData::Standalone(project) => Some(project),
+            #[allow(unreachable_patterns)]
+            _ => None,
+        }
+    }
 }
 
 impl RawLevelAccessor for LdtkProjectData {
@@ -88,6 +144,10 @@ impl RawLevelAccessor for LdtkProjectData {
     fn root_levels(&self) -> &[Level] {
         self.json_data().root_levels()
     }
+
+    fn root_world_layout(&self) -> Option<crate::ldtk::WorldLayout> {
+        self.json_data().root_world_layout()
+    }
 }
 
 impl LevelMetadataAccessor for LdtkProjectData {
@@ -177,6 +237,23 @@ pub mod internal_level_tests {
         );
     }
 
+    #[test]
+    fn loaded_level_accessor_ignores_external_level_assets() {
+        let project: LdtkProjectData = InternalLevels.fake();
+
+        for level in &project.json_data().levels {
+            assert_eq!(
+                project.get_loaded_level_by_iid(None, &level.iid),
+                project.as_standalone().get_loaded_level_by_iid(&level.iid),
+            );
+        }
+
+        assert_eq!(
+            project.get_loaded_level_by_iid(None, &"This_level_doesnt_exist".to_string()),
+            None
+        );
+    }
+
     #[cfg(feature = "external_levels")]
     #[test]
     #[should_panic]
@@ -261,6 +338,15 @@ pub mod external_level_tests {
         );
     }
 
+    #[test]
+    fn loaded_level_accessor_requires_external_level_assets() {
+        let project: LdtkProjectData = ExternalLevels.fake();
+
+        for level in &project.json_data().levels {
+            assert_eq!(project.get_loaded_level_by_iid(None, &level.iid), None);
+        }
+    }
+
     #[cfg(feature = "internal_levels")]
     #[test]
     #[should_panic]
@@ -0,0 +1,58 @@
+use crate::assets::LdtkProject;
+use bevy::prelude::*;
+
+/// [`Component`] storing the handle of an LDtk project asset.
+///
+/// It is the component that marks an entity as an LDtk world, and is part of the
+/// [`LdtkWorldBundle`].
+/// Querying for this instead of a bare `Handle<LdtkProject>` keeps world queries
+/// self-documenting:
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_ecs_ldtk::prelude::*;
+/// fn count_levels(
+///     world_query: Query<&LdtkProjectHandle>,
+///     ldtk_project_assets: Res<Assets<LdtkProject>>,
+/// ) {
+///     for ldtk_project_handle in world_query.iter() {
+///         if let Some(ldtk_project) = ldtk_project_handle.get(&ldtk_project_assets) {
+///             info!("{} levels", ldtk_project.iter_raw_levels().count());
+///         }
+///     }
+/// }
+/// ```
+///
+/// [`Component`]: https://docs.rs/bevy/latest/bevy/ecs/component/trait.Component.html
+/// [`LdtkWorldBundle`]: crate::prelude::LdtkWorldBundle
+#[derive(Clone, Eq, PartialEq, Debug, Default, Hash, Deref, DerefMut, Component, Reflect)]
+#[reflect(Component)]
+pub struct LdtkProjectHandle {
+    pub handle: Handle<LdtkProject>,
+}
+
+impl LdtkProjectHandle {
+    /// Returns the project asset, if it has loaded.
+    pub fn get<'a>(&self, ldtk_project_assets: &'a Assets<LdtkProject>) -> Option<&'a LdtkProject> {
+        ldtk_project_assets.get(&self.handle)
+    }
+
+    /// Returns a mutable reference to the project asset, if it has loaded.
+    pub fn get_mut<'a>(
+        &self,
+        ldtk_project_assets: &'a mut Assets<LdtkProject>,
+    ) -> Option<&'a mut LdtkProject> {
+        ldtk_project_assets.get_mut(&self.handle)
+    }
+}
+
+impl From<Handle<LdtkProject>> for LdtkProjectHandle {
+    fn from(handle: Handle<LdtkProject>) -> Self {
+        LdtkProjectHandle { handle }
+    }
+}
+
+impl From<LdtkProjectHandle> for Handle<LdtkProject> {
+    fn from(ldtk_project_handle: LdtkProjectHandle) -> Self {
+        ldtk_project_handle.handle
+    }
+}
@@ -1,7 +1,7 @@
 use crate::{
-    assets::LevelMetadata,
-    ldtk::{raw_level_accessor::RawLevelAccessor, Level},
-    LevelSelection,
+    assets::{LevelIndices, LevelMetadata},
+    ldtk::{neighbor_direction::NeighborDirection, raw_level_accessor::RawLevelAccessor, Level},
+    LevelIid, LevelSelection,
 };
 
 /// Convenience methods for types that store levels and level metadata.
@@ -27,6 +27,21 @@ pub trait LevelMetadataAccessor: RawLevelAccessor {
             .and_then(|metadata| self.get_raw_level_at_indices(metadata.indices()))
     }
 
+    /// Find the level neighboring the level with the given iid in the given direction.
+    ///
+    /// Neighbors are read from the level's `__neighbours` list.
+    /// If several levels neighbor it in that direction, the first one is returned.
+    ///
+    /// Note: all levels are considered [raw](crate::assets::LdtkProject#raw-vs-loaded-levels).
+    #[allow(clippy::ptr_arg)]
+    fn neighbor_in_direction(&self, iid: &String, direction: NeighborDirection) -> Option<&Level> {
+        self.get_raw_level_by_iid(iid)?
+            .neighbours
+            .iter()
+            .filter(|neighbour| neighbour.direction() == Some(direction))
+            .find_map(|neighbour| self.get_raw_level_by_iid(&neighbour.level_iid))
+    }
+
     /// Find the level matching the given [`LevelSelection`].
     ///
     /// This lookup is constant for [`LevelSelection::Iid`] and [`LevelSelection::Indices`] variants.
@@ -48,6 +63,39 @@ pub trait LevelMetadataAccessor: RawLevelAccessor {
                 .find(|Level { uid, .. }| uid == selected_uid),
         }
     }
+
+    /// Find the [`LevelIndices`] of the level matching the given [`LevelSelection`].
+    ///
+    /// Useful for converting iid-based selections to index-based ones, e.g. for level-select menus.
+    fn find_level_indices_by_level_selection(
+        &self,
+        level_selection: &LevelSelection,
+    ) -> Option<LevelIndices> {
+        match level_selection {
+            LevelSelection::Iid(iid) => self
+                .get_level_metadata_by_iid(iid.get())
+                .map(|metadata| *metadata.indices()),
+            LevelSelection::Indices(indices) => {
+                self.get_raw_level_at_indices(indices).map(|_| *indices)
+            }
+            _ => self
+                .iter_raw_levels_with_indices()
+                .find(|(indices, level)| level_selection.is_match(indices, level))
+                .map(|(indices, _)| indices),
+        }
+    }
+
+    /// Find the [`LevelIid`] of the level matching the given [`LevelSelection`].
+    ///
+    /// Useful for converting index-based selections to iid-based ones, which stay valid when
+    /// levels are reordered in LDtk.
+    fn find_level_iid_by_level_selection(
+        &self,
+        level_selection: &LevelSelection,
+    ) -> Option<LevelIid> {
+        self.find_raw_level_by_level_selection(level_selection)
+            .map(|level| LevelIid::new(level.iid.clone()))
+    }
 }
 
 #[cfg(test)]
@@ -79,6 +127,10 @@ pub mod tests {
         fn root_levels(&self) -> &[Level] {
             self.data.root_levels()
         }
+
+        fn root_world_layout(&self) -> Option<crate::ldtk::WorldLayout> {
+            self.data.root_world_layout()
+        }
     }
 
     impl LevelMetadataAccessor for BasicLevelMetadataAccessor {
@@ -157,6 +209,32 @@ pub mod tests {
         );
     }
 
+    #[test]
+    fn neighbor_lookup_uses_direction_codes() {
+        let mut accessor = BasicLevelMetadataAccessor::sample_with_root_levels();
+
+        let west_iid = accessor.data.levels[0].iid.clone();
+        let east_iid = accessor.data.levels[1].iid.clone();
+        accessor.data.levels[0].neighbours = vec![crate::ldtk::NeighbourLevel {
+            dir: "e".to_string(),
+            level_iid: east_iid.clone(),
+            ..Default::default()
+        }];
+
+        assert_eq!(
+            accessor.neighbor_in_direction(&west_iid, NeighborDirection::East),
+            Some(&accessor.data.levels[1])
+        );
+        assert_eq!(
+            accessor.neighbor_in_direction(&west_iid, NeighborDirection::West),
+            None
+        );
+        assert_eq!(
+            accessor.neighbor_in_direction(&east_iid, NeighborDirection::West),
+            None
+        );
+    }
+
     #[test]
     fn find_by_level_selection_returns_expected_root_levels() {
         let accessor = BasicLevelMetadataAccessor::sample_with_root_levels();
@@ -262,4 +340,42 @@ pub mod tests {
             None,
         );
     }
+
+    #[test]
+    fn level_selections_convert_between_iids_and_indices() {
+        let accessor = BasicLevelMetadataAccessor::sample_with_world_levels();
+
+        for (world_index, world) in accessor.data.worlds.iter().enumerate() {
+            for (level_index, expected_level) in world.levels.iter().enumerate() {
+                let indices = LevelIndices::in_world(world_index, level_index);
+                let iid = LevelIid::new(expected_level.iid.clone());
+
+                assert_eq!(
+                    accessor.find_level_iid_by_level_selection(&LevelSelection::Indices(indices)),
+                    Some(iid.clone())
+                );
+                assert_eq!(
+                    accessor.find_level_indices_by_level_selection(&LevelSelection::Iid(iid)),
+                    Some(indices)
+                );
+                assert_eq!(
+                    accessor.find_level_indices_by_level_selection(&LevelSelection::Uid(
+                        expected_level.uid
+                    )),
+                    Some(indices)
+                );
+            }
+        }
+
+        assert_eq!(
+            accessor.find_level_indices_by_level_selection(&LevelSelection::indices(0, 99)),
+            None
+        );
+        assert_eq!(
+            accessor.find_level_iid_by_level_selection(&LevelSelection::Identifier(
+                "Back_Rooms".to_string()
+            )),
+            None
+        );
+    }
 }
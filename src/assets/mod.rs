@@ -1,5 +1,8 @@
 //! Assets and related items for loading LDtk files.
 
+mod entity_icon;
+pub use entity_icon::EntityIcon;
+
 mod ldtk_asset_plugin;
 pub use ldtk_asset_plugin::LdtkAssetPlugin;
 
@@ -26,14 +29,39 @@ mod ldtk_external_level;
 #[cfg(feature = "external_levels")]
 pub use ldtk_external_level::LdtkExternalLevel;
 
+#[cfg(feature = "external_levels")]
+mod external_level_loading;
+
+#[cfg(feature = "external_levels")]
+pub(crate) use external_level_loading::fetch_external_levels;
+
+#[cfg(feature = "external_levels")]
+pub use external_level_loading::{
+    ExternalLevelFetchEvent, ExternalLevelFetches, ExternalLevelLoading,
+};
+
 mod ldtk_json_with_metadata;
 pub use ldtk_json_with_metadata::LdtkJsonWithMetadata;
 
 mod ldtk_project_data;
-pub use ldtk_project_data::LdtkProjectData;
+pub use ldtk_project_data::{ExternalLevelAssets, LdtkProjectData};
+
+#[cfg(feature = "internal_levels")]
+mod ldtk_patch;
+
+#[cfg(feature = "internal_levels")]
+pub use ldtk_patch::{
+    override_levels, patch_entity, EntityPatch, LdtkPatch, LdtkPatchError, LdtkPatchLoader,
+};
 
 mod ldtk_project;
-pub use ldtk_project::LdtkProject;
+pub use ldtk_project::{AddLevelError, LdtkProject};
+
+mod ldtk_project_handle;
+pub use ldtk_project_handle::LdtkProjectHandle;
+
+mod ldtk_loader_settings;
+pub use ldtk_loader_settings::{LdtkLoaderSettings, LdtkLoaderSettingsMap};
 
 mod level_indices;
 pub use level_indices::LevelIndices;
@@ -0,0 +1,242 @@
+//! Ambient audio emitters generated from LDtk entities.
+//!
+//! *Requires the "bevy_audio" feature*
+//!
+//! When enabled, the plugin adds an [`AudioBundle`] to every LDtk entity tagged with
+//! [`LdtkAudioSettings::tag`], playing the sound in the entity's sound field.
+//! Its volume and whether it loops are read from other fields of the entity, falling back to the
+//! defaults in [`LdtkAudioSettings`].
+//!
+//! The entity's size determines the range of the sound.
+//! It plays at full volume at the entity's center, and fades out towards its edges, based on the
+//! distance to the closest [`LdtkAudioListener`].
+//! This way, ambient sounds like waterfalls or machinery can be placed and sized in the editor:
+//! ```
+//! # use bevy::prelude::*;
+//! # use bevy_ecs_ldtk::audio::LdtkAudioListener;
+//! fn setup_camera(mut commands: Commands) {
+//!     commands.spawn((Camera2dBundle::default(), LdtkAudioListener));
+//! }
+//! ```
+//! Emitters are silent while there are no listeners.
+//!
+//! [`AudioBundle`]: https://docs.rs/bevy/latest/bevy/audio/type.AudioBundle.html
+
+use crate::ldtk::{ldtk_fields::LdtkFields, EntityInstance};
+use bevy::{
+    audio::{AudioSinkPlayback, PlaybackMode, Volume},
+    prelude::*,
+};
+
+/// Settings for generating audio emitters, found in [`LdtkSettings`].
+///
+/// [`LdtkSettings`]: crate::prelude::LdtkSettings
+#[derive(Clone, PartialEq, Debug)]
+pub struct LdtkAudioSettings {
+    /// Entity tag that marks LDtk entities as audio emitters.
+    pub tag: String,
+    /// Identifier of the `String` or `FilePath` field containing the asset path of the sound.
+    pub sound_field: String,
+    /// Identifier of the `Float` field used for the volume.
+    ///
+    /// Falls back to `default_volume`.
+    pub volume_field: String,
+    /// Identifier of the `Bool` field that determines if the sound loops.
+    ///
+    /// Falls back to `default_looping`.
+    pub loop_field: String,
+    pub default_volume: f32,
+    pub default_looping: bool,
+}
+
+impl Default for LdtkAudioSettings {
+    fn default() -> Self {
+        LdtkAudioSettings {
+            tag: "audio".to_string(),
+            sound_field: "sound".to_string(),
+            volume_field: "volume".to_string(),
+            loop_field: "loop".to_string(),
+            default_volume: 1.,
+            default_looping: true,
+        }
+    }
+}
+
+/// [`Component`] added to audio emitters generated from LDtk entities.
+///
+/// [`Component`]: https://docs.rs/bevy/latest/bevy/ecs/component/trait.Component.html
+#[derive(Copy, Clone, PartialEq, Debug, Default, Component, Reflect)]
+#[reflect(Component)]
+pub struct LdtkAudioEmitter {
+    /// Volume of the sound at the emitter's center.
+    pub volume: f32,
+    /// Distance from the emitter's center at which the sound becomes silent.
+    pub range: f32,
+}
+
+impl LdtkAudioEmitter {
+    /// Volume of the sound heard at the given distance from the emitter's center.
+    pub fn volume_at(&self, distance: f32) -> f32 {
+        if self.range <= 0. {
+            return 0.;
+        }
+
+        self.volume * (1. - distance / self.range).clamp(0., 1.)
+    }
+}
+
+/// [`Component`] marking the entities that hear [`LdtkAudioEmitter`]s, like the camera or player.
+///
+/// [`Component`]: https://docs.rs/bevy/latest/bevy/ecs/component/trait.Component.html
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Default, Hash, Component, Reflect)]
+#[reflect(Component)]
+pub struct LdtkAudioListener;
+
+fn audio_bundle_with_sound_loader(
+    entity_instance: &EntityInstance,
+    settings: &LdtkAudioSettings,
+    load_sound: impl FnOnce(&str) -> Handle<AudioSource>,
+) -> Option<(AudioBundle, LdtkAudioEmitter)> {
+    if !entity_instance.tags.contains(&settings.tag) {
+        return None;
+    }
+
+    let path = entity_instance
+        .get_string_field(&settings.sound_field)
+        .or_else(|_| entity_instance.get_file_path_field(&settings.sound_field))
+        .ok()?;
+
+    let volume = entity_instance
+        .get_float_field(&settings.volume_field)
+        .copied()
+        .unwrap_or(settings.default_volume);
+
+    let looping = entity_instance
+        .get_bool_field(&settings.loop_field)
+        .copied()
+        .unwrap_or(settings.default_looping);
+
+    let emitter = LdtkAudioEmitter {
+        volume,
+        range: entity_instance.width.max(entity_instance.height) as f32 / 2.,
+    };
+
+    Some((
+        AudioBundle {
+            source: load_sound(path),
+            settings: PlaybackSettings {
+                mode: if looping {
+                    PlaybackMode::Loop
+                } else {
+                    PlaybackMode::Once
+                },
+                // Silent until the first attenuation pass
+                volume: Volume::new_relative(0.),
+                ..default()
+            },
+        },
+        emitter,
+    ))
+}
+
+/// Creates an [`AudioBundle`] and [`LdtkAudioEmitter`] from an entity instance, if it has the
+/// configured audio tag and a non-null sound field.
+///
+/// Sounds are loaded with the given [`AssetServer`].
+///
+/// [`AudioBundle`]: https://docs.rs/bevy/latest/bevy/audio/type.AudioBundle.html
+/// [`AssetServer`]: https://docs.rs/bevy/latest/bevy/asset/struct.AssetServer.html
+pub fn audio_bundle_from_entity_info(
+    entity_instance: &EntityInstance,
+    settings: &LdtkAudioSettings,
+    asset_server: &AssetServer,
+) -> Option<(AudioBundle, LdtkAudioEmitter)> {
+    audio_bundle_with_sound_loader(entity_instance, settings, |path| asset_server.load(path))
+}
+
+/// Sets the volume of [`LdtkAudioEmitter`]s according to their distance to the closest
+/// [`LdtkAudioListener`].
+pub fn attenuate_audio_emitters(
+    emitter_query: Query<(&LdtkAudioEmitter, &GlobalTransform, &AudioSink)>,
+    listener_query: Query<&GlobalTransform, With<LdtkAudioListener>>,
+) {
+    for (emitter, emitter_transform, sink) in emitter_query.iter() {
+        let position = emitter_transform.translation().truncate();
+
+        let volume = listener_query
+            .iter()
+            .map(|listener| emitter.volume_at(position.distance(listener.translation().truncate())))
+            .fold(0., f32::max);
+
+        sink.set_volume(volume);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ldtk::{ldtk_fields::field_instance_from_value, FieldValue};
+
+    use super::*;
+
+    #[test]
+    fn audio_emitters_read_fields_with_fallbacks() {
+        let settings = LdtkAudioSettings::default();
+
+        let untagged = EntityInstance {
+            field_instances: vec![field_instance_from_value(
+                "sound",
+                FieldValue::FilePath(Some("sounds/river.ogg".to_string())),
+            )],
+            ..Default::default()
+        };
+        assert!(audio_bundle_with_sound_loader(&untagged, &settings, |_| unreachable!()).is_none());
+
+        let mut waterfall = EntityInstance {
+            tags: vec!["audio".to_string()],
+            width: 64,
+            height: 32,
+            ..untagged
+        };
+
+        let mut loaded_path = None;
+        let (bundle, emitter) = audio_bundle_with_sound_loader(&waterfall, &settings, |path| {
+            loaded_path = Some(path.to_string());
+            Handle::default()
+        })
+        .unwrap();
+
+        assert_eq!(loaded_path.as_deref(), Some("sounds/river.ogg"));
+        assert!(matches!(bundle.settings.mode, PlaybackMode::Loop));
+        assert_eq!(
+            emitter,
+            LdtkAudioEmitter {
+                volume: 1.,
+                range: 32.
+            }
+        );
+
+        waterfall.field_instances.extend([
+            field_instance_from_value("volume", FieldValue::Float(Some(0.5))),
+            field_instance_from_value("loop", FieldValue::Bool(false)),
+        ]);
+
+        let (bundle, emitter) =
+            audio_bundle_with_sound_loader(&waterfall, &settings, |_| Handle::default()).unwrap();
+
+        assert!(matches!(bundle.settings.mode, PlaybackMode::Once));
+        assert_eq!(emitter.volume, 0.5);
+    }
+
+    #[test]
+    fn emitter_volume_fades_out_over_range() {
+        let emitter = LdtkAudioEmitter {
+            volume: 0.8,
+            range: 10.,
+        };
+
+        assert_eq!(emitter.volume_at(0.), 0.8);
+        assert_eq!(emitter.volume_at(5.), 0.4);
+        assert_eq!(emitter.volume_at(20.), 0.);
+        assert_eq!(LdtkAudioEmitter::default().volume_at(0.), 0.);
+    }
+}
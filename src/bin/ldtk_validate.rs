@@ -0,0 +1,65 @@
+//! Validates LDtk projects with the same code paths `bevy_ecs_ldtk` uses to load them.
+//!
+//! Usage: `ldtk-validate <project.ldtk>... [--registry <registry dump>]`
+//!
+//! Exits with a nonzero status if any problems are found.
+//! See `bevy_ecs_ldtk::validation` for details.
+
+use bevy_ecs_ldtk::validation::{validate_project_file, RegistryDump};
+use std::{fs, path::PathBuf, process::ExitCode};
+
+const USAGE: &str = "usage: ldtk-validate <project.ldtk>... [--registry <registry dump>]";
+
+fn main() -> ExitCode {
+    let mut projects = Vec::new();
+    let mut registry = None;
+
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--registry" => {
+                let Some(path) = args.next() else {
+                    eprintln!("{USAGE}");
+                    return ExitCode::from(2);
+                };
+
+                match fs::read_to_string(&path) {
+                    Ok(dump) => registry = Some(RegistryDump::parse(&dump)),
+                    Err(e) => {
+                        eprintln!("unable to read registry dump {path}: {e}");
+                        return ExitCode::from(2);
+                    }
+                }
+            }
+            "-h" | "--help" => {
+                println!("{USAGE}");
+                return ExitCode::SUCCESS;
+            }
+            _ => projects.push(PathBuf::from(arg)),
+        }
+    }
+
+    if projects.is_empty() {
+        eprintln!("{USAGE}");
+        return ExitCode::from(2);
+    }
+
+    let mut issue_count = 0;
+
+    for project in projects {
+        let issues = validate_project_file(&project, registry.as_ref());
+
+        for issue in &issues {
+            println!("{}: {issue}", project.display());
+        }
+
+        issue_count += issues.len();
+    }
+
+    if issue_count == 0 {
+        ExitCode::SUCCESS
+    } else {
+        eprintln!("found {issue_count} problem(s)");
+        ExitCode::FAILURE
+    }
+}
@@ -0,0 +1,325 @@
+//! Optional plugin for keeping a camera inside the active level.
+//!
+//! Add [`LdtkCameraPlugin`] to your app and insert [`LdtkCameraConstraint`] on your camera.
+//! Move the camera however you like, for example by following the player, and the plugin will
+//! clamp it to the bounds of the active level afterwards.
+//! ```no_run
+//! # use bevy::prelude::*;
+//! # use bevy_ecs_ldtk::{camera::{LdtkCameraConstraint, LdtkCameraPlugin}, prelude::*};
+//! fn main() {
+//!     App::new()
+//!         .add_plugins((DefaultPlugins, LdtkPlugin, LdtkCameraPlugin))
+//!         .add_systems(Startup, setup)
+//!         .run();
+//! }
+//!
+//! fn setup(mut commands: Commands) {
+//!     commands.spawn((Camera2dBundle::default(), LdtkCameraConstraint::default()));
+//! }
+//! ```
+
+use crate::{
+    assets::{LdtkProject, LdtkProjectHandle, LevelMetadataAccessor},
+    components::LevelIid,
+    preview::LevelPreview,
+    resources::LevelSelection,
+};
+use bevy::{prelude::*, transform::TransformSystem};
+
+/// How a constrained camera handles levels that are smaller than its viewport.
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Default, Reflect)]
+pub enum SmallLevelFit {
+    /// Centers the level in the viewport, leaving the rest of the viewport empty.
+    #[default]
+    Letterbox,
+    /// Zooms the camera in until the level fills the viewport.
+    ZoomToFit,
+}
+
+/// [`Component`] that keeps an orthographic camera inside the bounds of the active level.
+///
+/// The active level is the one matching the [`LevelSelection`].
+/// If there is no [`LevelSelection`], it is the spawned level containing the camera.
+///
+/// When the active level changes, the camera pans smoothly into the bounds of the new level.
+///
+/// *Requires [`LdtkCameraPlugin`]*
+///
+/// [`Component`]: https://docs.rs/bevy/latest/bevy/ecs/component/trait.Component.html
+#[derive(Clone, PartialEq, Debug, Component, Reflect)]
+#[reflect(Component)]
+pub struct LdtkCameraConstraint {
+    pub small_level_fit: SmallLevelFit,
+    /// Scale of the camera's projection when no zooming is needed to fit the level.
+    pub zoom: f32,
+    /// Rate of the pan between levels, as the fraction of the remaining distance covered per
+    /// second on an exponential curve.
+    ///
+    /// Set to [`f32::INFINITY`] to snap to new levels instantly.
+    pub transition_speed: f32,
+    active_level: Option<LevelIid>,
+    transitioning: bool,
+    last_translation: Vec2,
+}
+
+impl Default for LdtkCameraConstraint {
+    fn default() -> Self {
+        LdtkCameraConstraint {
+            small_level_fit: SmallLevelFit::default(),
+            zoom: 1.,
+            transition_speed: 8.,
+            active_level: None,
+            transitioning: false,
+            last_translation: Vec2::ZERO,
+        }
+    }
+}
+
+impl LdtkCameraConstraint {
+    /// The level the camera is currently constrained to.
+    pub fn active_level(&self) -> Option<&LevelIid> {
+        self.active_level.as_ref()
+    }
+
+    /// Returns true while the camera is panning from one level to another.
+    pub fn is_transitioning(&self) -> bool {
+        self.transitioning
+    }
+}
+
+/// Plugin that adds the systems for [`LdtkCameraConstraint`].
+///
+/// Not added by [`LdtkPlugin`].
+///
+/// [`LdtkPlugin`]: crate::prelude::LdtkPlugin
+#[derive(Copy, Clone, Debug, Default)]
+pub struct LdtkCameraPlugin;
+
+impl Plugin for LdtkCameraPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            PostUpdate,
+            constrain_cameras.before(TransformSystem::TransformPropagate),
+        )
+        .register_type::<LdtkCameraConstraint>();
+    }
+}
+
+/// Moves a camera's center so that a viewport of the given size stays inside the bounds.
+///
+/// Along axes where the viewport is larger than the bounds, the viewport is centered instead.
+pub fn constrain_translation(target: Vec2, viewport_size: Vec2, bounds: Rect) -> Vec2 {
+    let half_viewport = viewport_size / 2.;
+    let center = bounds.center();
+
+    let constrain_axis = |target: f32, half_viewport: f32, min: f32, max: f32, center: f32| {
+        if half_viewport * 2. >= max - min {
+            center
+        } else {
+            target.clamp(min + half_viewport, max - half_viewport)
+        }
+    };
+
+    Vec2::new(
+        constrain_axis(
+            target.x,
+            half_viewport.x,
+            bounds.min.x,
+            bounds.max.x,
+            center.x,
+        ),
+        constrain_axis(
+            target.y,
+            half_viewport.y,
+            bounds.min.y,
+            bounds.max.y,
+            center.y,
+        ),
+    )
+}
+
+/// Calculates the projection scale of a constrained camera.
+///
+/// `unit_viewport_size` is the size of the viewport in world units at a projection scale of 1.
+pub fn constrained_scale(
+    zoom: f32,
+    small_level_fit: SmallLevelFit,
+    unit_viewport_size: Vec2,
+    level_size: Vec2,
+) -> f32 {
+    match small_level_fit {
+        SmallLevelFit::Letterbox => zoom,
+        SmallLevelFit::ZoomToFit => {
+            let fit = level_size / unit_viewport_size;
+            zoom.min(fit.x.min(fit.y))
+        }
+    }
+}
+
+/// Clamps cameras with [`LdtkCameraConstraint`] inside their active level.
+#[allow(clippy::type_complexity)]
+pub fn constrain_cameras(
+    time: Res<Time>,
+    level_selection: Option<Res<LevelSelection>>,
+    ldtk_project_assets: Res<Assets<LdtkProject>>,
+    ldtk_query: Query<&LdtkProjectHandle, Without<LevelPreview>>,
+    level_query: Query<(&LevelIid, &GlobalTransform, &Parent)>,
+    mut camera_query: Query<(
+        &mut LdtkCameraConstraint,
+        &mut Transform,
+        &mut OrthographicProjection,
+    )>,
+) {
+    let levels: Vec<(&LevelIid, Rect, bool)> = level_query
+        .iter()
+        .filter_map(|(level_iid, level_transform, parent)| {
+            let ldtk_project = ldtk_project_assets.get(ldtk_query.get(parent.get()).ok()?)?;
+
+            let level_metadata = ldtk_project.get_level_metadata_by_iid(level_iid.get())?;
+            let level = ldtk_project.get_raw_level_by_iid(level_iid.get())?;
+
+            let min = level_transform.translation().truncate();
+            let bounds = Rect::from_corners(
+                min,
+                min + Vec2::new(level.px_wid as f32, level.px_hei as f32),
+            );
+
+            let selected = level_selection
+                .as_ref()
+                .map(|level_selection| level_selection.is_match(level_metadata.indices(), level))
+                .unwrap_or(false);
+
+            Some((level_iid, bounds, selected))
+        })
+        .collect();
+
+    for (mut constraint, mut transform, mut projection) in camera_query.iter_mut() {
+        let target = transform.translation.truncate();
+
+        let active_level = if level_selection.is_some() {
+            levels.iter().find(|(_, _, selected)| *selected)
+        } else {
+            levels
+                .iter()
+                .find(|(level_iid, _, _)| Some(*level_iid) == constraint.active_level.as_ref())
+                .filter(|(_, bounds, _)| bounds.contains(target))
+                .or_else(|| levels.iter().find(|(_, bounds, _)| bounds.contains(target)))
+        };
+
+        let Some((level_iid, bounds, _)) = active_level else {
+            continue;
+        };
+
+        if constraint.active_level.as_ref() != Some(*level_iid) {
+            constraint.transitioning = constraint.active_level.is_some();
+            constraint.active_level = Some((*level_iid).clone());
+        }
+
+        if projection.scale <= 0. {
+            continue;
+        }
+
+        let unit_viewport_size = projection.area.size() / projection.scale;
+
+        if unit_viewport_size.cmple(Vec2::ZERO).any() {
+            continue;
+        }
+
+        let scale = constrained_scale(
+            constraint.zoom,
+            constraint.small_level_fit,
+            unit_viewport_size,
+            bounds.size(),
+        );
+
+        if projection.scale != scale {
+            projection.scale = scale;
+        }
+
+        let constrained = constrain_translation(target, unit_viewport_size * scale, *bounds);
+
+        let translation = if constraint.transitioning {
+            let t = 1. - (-constraint.transition_speed * time.delta_seconds()).exp();
+            let panned = constraint.last_translation.lerp(constrained, t);
+
+            if panned.distance(constrained) < 0.5 {
+                constraint.transitioning = false;
+                constrained
+            } else {
+                panned
+            }
+        } else {
+            constrained
+        };
+
+        constraint.last_translation = translation;
+
+        if transform.translation.truncate() != translation {
+            transform.translation = translation.extend(transform.translation.z);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn translation_is_clamped_inside_bounds() {
+        let bounds = Rect::new(0., 0., 400., 200.);
+        let viewport = Vec2::new(100., 50.);
+
+        assert_eq!(
+            constrain_translation(Vec2::new(200., 100.), viewport, bounds),
+            Vec2::new(200., 100.)
+        );
+        assert_eq!(
+            constrain_translation(Vec2::new(-30., 500.), viewport, bounds),
+            Vec2::new(50., 175.)
+        );
+    }
+
+    #[test]
+    fn small_levels_are_centered() {
+        let bounds = Rect::new(0., 0., 80., 200.);
+        let viewport = Vec2::new(100., 50.);
+
+        assert_eq!(
+            constrain_translation(Vec2::new(0., 0.), viewport, bounds),
+            Vec2::new(40., 25.)
+        );
+    }
+
+    #[test]
+    fn zoom_to_fit_only_zooms_in() {
+        let viewport = Vec2::new(320., 180.);
+
+        assert_eq!(
+            constrained_scale(
+                1.,
+                SmallLevelFit::ZoomToFit,
+                viewport,
+                Vec2::new(160., 180.)
+            ),
+            0.5
+        );
+        assert_eq!(
+            constrained_scale(
+                1.,
+                SmallLevelFit::ZoomToFit,
+                viewport,
+                Vec2::new(640., 360.)
+            ),
+            1.
+        );
+        assert_eq!(
+            constrained_scale(
+                1.,
+                SmallLevelFit::Letterbox,
+                viewport,
+                Vec2::new(160., 180.)
+            ),
+            1.
+        );
+    }
+}
@@ -0,0 +1,379 @@
+//! Snapshots of spawned levels that can be restored later, like for death and retry loops.
+//!
+//! [`LdtkCheckpoint::capture`] records the state of a spawned level that this crate tracks:
+//! - The values of its [`IntGridCell`]s.
+//! - Which of its LDtk entities are alive, by [`EntityIid`].
+//! - The values of the components listed in [`CheckpointComponents`] on those entities.
+//!
+//! [`LdtkCheckpoint::restore`] respawns the level, then brings it back to the recorded state.
+//! Only the level is respawned, the project is not reloaded.
+//! This requires the [`LdtkCheckpointPlugin`].
+//! ```no_run
+//! # use bevy::prelude::*;
+//! # use bevy_ecs_ldtk::{checkpoint::*, prelude::*};
+//! # #[derive(Default, Component, Reflect)]
+//! # #[reflect(Component)]
+//! # struct Health(i32);
+//! fn main() {
+//!     App::new()
+//!         .add_plugins((DefaultPlugins, LdtkPlugin, LdtkCheckpointPlugin))
+//!         .register_type::<Health>()
+//!         .insert_resource(CheckpointComponents::default().with::<Health>())
+//!         .run();
+//! }
+//!
+//! #[derive(Resource)]
+//! struct LastCheckpoint(LdtkCheckpoint);
+//!
+//! fn save_checkpoint(world: &mut World, level_entity: Entity) {
+//!     if let Some(checkpoint) = LdtkCheckpoint::capture(world, level_entity) {
+//!         world.insert_resource(LastCheckpoint(checkpoint));
+//!     }
+//! }
+//!
+//! fn retry(world: &mut World) {
+//!     if let Some(last_checkpoint) = world.remove_resource::<LastCheckpoint>() {
+//!         last_checkpoint.0.restore(world);
+//!         world.insert_resource(last_checkpoint);
+//!     }
+//! }
+//! ```
+//!
+//! IntGrid cells whose value was 0 when the level spawned don't exist as entities, so setting
+//! them later can't be captured.
+//!
+//! [`IntGridCell`]: crate::prelude::IntGridCell
+//! [`EntityIid`]: crate::prelude::EntityIid
+
+use crate::{
+    components::{EntityIid, GridCoords, IntGridCell, LayerMetadata, LevelIid, Respawn},
+    resources::LevelEvent,
+};
+use bevy::{
+    ecs::{event::ManualEventReader, reflect::ReflectComponent},
+    prelude::*,
+};
+use std::{
+    any::TypeId,
+    collections::{HashMap, HashSet},
+};
+
+/// [`Resource`] listing the components captured by [`LdtkCheckpoint`]s.
+///
+/// The components must be registered in the [`AppTypeRegistry`] and reflect [`Component`].
+///
+/// [`Resource`]: https://docs.rs/bevy/latest/bevy/ecs/system/trait.Resource.html
+/// [`AppTypeRegistry`]: https://docs.rs/bevy/latest/bevy/ecs/reflect/struct.AppTypeRegistry.html
+/// [`Component`]: https://docs.rs/bevy/latest/bevy/ecs/component/trait.Component.html
+#[derive(Clone, Eq, PartialEq, Debug, Default, Resource)]
+pub struct CheckpointComponents {
+    type_ids: HashSet<TypeId>,
+}
+
+impl CheckpointComponents {
+    /// Captures components of type `C` on LDtk entities.
+    pub fn add<C: Component>(&mut self) -> &mut Self {
+        self.type_ids.insert(TypeId::of::<C>());
+        self
+    }
+
+    /// Builder-style version of [`CheckpointComponents::add`].
+    pub fn with<C: Component>(mut self) -> Self {
+        self.add::<C>();
+        self
+    }
+}
+
+/// A snapshot of a spawned level.
+///
+/// See the [module-level documentation](crate::checkpoint) for more details.
+#[derive(Debug)]
+pub struct LdtkCheckpoint {
+    level_iid: LevelIid,
+    /// Layer iids and coordinates mapped to IntGrid values.
+    int_grid_cells: HashMap<(String, GridCoords), i32>,
+    alive_entities: HashSet<EntityIid>,
+    components: HashMap<EntityIid, Vec<Box<dyn Reflect>>>,
+}
+
+impl Clone for LdtkCheckpoint {
+    fn clone(&self) -> Self {
+        LdtkCheckpoint {
+            level_iid: self.level_iid.clone(),
+            int_grid_cells: self.int_grid_cells.clone(),
+            alive_entities: self.alive_entities.clone(),
+            components: self
+                .components
+                .iter()
+                .map(|(entity_iid, components)| {
+                    (
+                        entity_iid.clone(),
+                        components
+                            .iter()
+                            .map(|component| component.clone_value())
+                            .collect(),
+                    )
+                })
+                .collect(),
+        }
+    }
+}
+
+impl LdtkCheckpoint {
+    /// Captures the state of the given level entity.
+    ///
+    /// Returns `None` if the entity isn't a level.
+    pub fn capture(world: &World, level_entity: Entity) -> Option<LdtkCheckpoint> {
+        let level_iid = world.get::<LevelIid>(level_entity)?.clone();
+
+        let mut checkpoint = LdtkCheckpoint {
+            level_iid,
+            int_grid_cells: HashMap::new(),
+            alive_entities: HashSet::new(),
+            components: HashMap::new(),
+        };
+
+        let type_registry = world.get_resource::<AppTypeRegistry>().map(|r| r.read());
+        let reflect_components: Vec<ReflectComponent> =
+            match (world.get_resource::<CheckpointComponents>(), &type_registry) {
+                (Some(checkpoint_components), Some(type_registry)) => checkpoint_components
+                    .type_ids
+                    .iter()
+                    .filter_map(|type_id| {
+                        type_registry
+                            .get_type_data::<ReflectComponent>(*type_id)
+                            .cloned()
+                    })
+                    .collect(),
+                _ => Vec::new(),
+            };
+
+        for entity in descendants(world, level_entity) {
+            let entity_ref = world.entity(entity);
+
+            if let Some(key) = int_grid_cell_key(world, entity) {
+                if let Some(cell) = entity_ref.get::<IntGridCell>() {
+                    checkpoint.int_grid_cells.insert(key, cell.value);
+                }
+            }
+
+            if let Some(entity_iid) = entity_ref.get::<EntityIid>() {
+                checkpoint.alive_entities.insert(entity_iid.clone());
+
+                let components: Vec<Box<dyn Reflect>> = reflect_components
+                    .iter()
+                    .filter_map(|reflect_component| reflect_component.reflect(entity_ref))
+                    .map(|component| component.clone_value())
+                    .collect();
+
+                if !components.is_empty() {
+                    checkpoint.components.insert(entity_iid.clone(), components);
+                }
+            }
+        }
+
+        Some(checkpoint)
+    }
+
+    /// Returns the iid of the captured level.
+    pub fn level_iid(&self) -> &LevelIid {
+        &self.level_iid
+    }
+
+    /// Respawns the captured level and restores it to this checkpoint once it has spawned.
+    ///
+    /// Does nothing if the level isn't spawned.
+    pub fn restore(&self, world: &mut World) {
+        let Some(level_entity) = find_level(world, &self.level_iid) else {
+            return;
+        };
+
+        world.entity_mut(level_entity).insert(Respawn);
+        world
+            .get_resource_or_insert_with(PendingCheckpoints::default)
+            .0
+            .insert(self.level_iid.clone(), self.clone());
+    }
+
+    /// Brings the spawned level back to this checkpoint.
+    fn apply(&self, world: &mut World) {
+        let Some(level_entity) = find_level(world, &self.level_iid) else {
+            return;
+        };
+
+        let type_registry = world.get_resource::<AppTypeRegistry>().cloned();
+        let type_registry = type_registry.as_ref().map(|r| r.read());
+
+        for entity in descendants(world, level_entity) {
+            if let Some(key) = int_grid_cell_key(world, entity) {
+                match self.int_grid_cells.get(&key) {
+                    Some(value) => {
+                        if let Some(mut cell) = world.get_mut::<IntGridCell>(entity) {
+                            cell.value = *value;
+                        }
+                    }
+                    None => {
+                        world.entity_mut(entity).despawn_recursive();
+                        continue;
+                    }
+                }
+            }
+
+            let Some(entity_iid) = world.get::<EntityIid>(entity).cloned() else {
+                continue;
+            };
+
+            if !self.alive_entities.contains(&entity_iid) {
+                world.entity_mut(entity).despawn_recursive();
+                continue;
+            }
+
+            let (Some(components), Some(type_registry)) =
+                (self.components.get(&entity_iid), &type_registry)
+            else {
+                continue;
+            };
+
+            for component in components {
+                if let Some(reflect_component) = type_registry
+                    .get_with_name(component.type_name())
+                    .and_then(|registration| registration.data::<ReflectComponent>())
+                {
+                    reflect_component
+                        .apply_or_insert(&mut world.entity_mut(entity), component.as_ref());
+                }
+            }
+        }
+    }
+}
+
+/// Plugin that finishes restoring [`LdtkCheckpoint`]s once their levels respawn.
+///
+/// Not added by [`LdtkPlugin`].
+///
+/// [`LdtkPlugin`]: crate::prelude::LdtkPlugin
+#[derive(Copy, Clone, Debug, Default)]
+pub struct LdtkCheckpointPlugin;
+
+impl Plugin for LdtkCheckpointPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<CheckpointComponents>()
+            .init_resource::<PendingCheckpoints>()
+            .add_systems(PostUpdate, apply_pending_checkpoints);
+    }
+}
+
+/// Checkpoints waiting for their levels to respawn.
+#[derive(Default, Resource)]
+struct PendingCheckpoints(HashMap<LevelIid, LdtkCheckpoint>);
+
+/// Applies pending [`LdtkCheckpoint`]s to levels that finished spawning.
+pub fn apply_pending_checkpoints(
+    world: &mut World,
+    mut level_events: Local<ManualEventReader<LevelEvent>>,
+) {
+    let transformed_levels: Vec<LevelIid> = level_events
+        .iter(world.resource::<Events<LevelEvent>>())
+        .filter_map(|level_event| match level_event {
+            LevelEvent::Transformed(level_iid) => Some(level_iid.clone()),
+            _ => None,
+        })
+        .collect();
+
+    for level_iid in transformed_levels {
+        let Some(checkpoint) = world
+            .get_resource_mut::<PendingCheckpoints>()
+            .and_then(|mut pending| pending.0.remove(&level_iid))
+        else {
+            continue;
+        };
+
+        checkpoint.apply(world);
+    }
+}
+
+fn find_level(world: &World, level_iid: &LevelIid) -> Option<Entity> {
+    world
+        .iter_entities()
+        .find(|entity_ref| entity_ref.get::<LevelIid>() == Some(level_iid))
+        .map(|entity_ref| entity_ref.id())
+}
+
+/// Returns all descendants of the given entity, parents before children.
+fn descendants(world: &World, entity: Entity) -> Vec<Entity> {
+    let mut entities = Vec::new();
+    let mut index = 0;
+
+    if let Some(children) = world.get::<Children>(entity) {
+        entities.extend(children.iter().copied());
+    }
+
+    while let Some(entity) = entities.get(index).copied() {
+        if let Some(children) = world.get::<Children>(entity) {
+            entities.extend(children.iter().copied());
+        }
+
+        index += 1;
+    }
+
+    entities
+}
+
+/// Returns the layer iid and coordinates of an [`IntGridCell`] entity.
+fn int_grid_cell_key(world: &World, entity: Entity) -> Option<(String, GridCoords)> {
+    let entity_ref = world.get_entity(entity)?;
+    entity_ref.get::<IntGridCell>()?;
+
+    let grid_coords = *entity_ref.get::<GridCoords>()?;
+    let layer_metadata = world.get::<LayerMetadata>(entity_ref.get::<Parent>()?.get())?;
+
+    Some((layer_metadata.iid.clone(), grid_coords))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn checkpoints_restore_cells_entities_and_components() {
+        let mut world = World::new();
+        world.init_resource::<AppTypeRegistry>();
+        world
+            .resource::<AppTypeRegistry>()
+            .write()
+            .register::<Transform>();
+        world.insert_resource(CheckpointComponents::default().with::<Transform>());
+
+        let level = world.spawn(LevelIid::new("level")).id();
+        let layer = world
+            .spawn(LayerMetadata {
+                iid: "layer".to_string(),
+                ..default()
+            })
+            .id();
+        let cell = world
+            .spawn((IntGridCell { value: 1 }, GridCoords::new(0, 0)))
+            .id();
+        let player = world
+            .spawn((EntityIid::new("player"), Transform::from_xyz(1., 2., 0.)))
+            .id();
+        world.entity_mut(level).push_children(&[layer]);
+        world.entity_mut(layer).push_children(&[cell, player]);
+
+        let checkpoint = LdtkCheckpoint::capture(&world, level).unwrap();
+
+        world.get_mut::<IntGridCell>(cell).unwrap().value = 2;
+        world.get_mut::<Transform>(player).unwrap().translation.x = 10.;
+        let coin = world.spawn(EntityIid::new("coin")).id();
+        world.entity_mut(layer).push_children(&[coin]);
+
+        checkpoint.apply(&mut world);
+
+        assert_eq!(world.get::<IntGridCell>(cell).unwrap().value, 1);
+        assert_eq!(
+            world.get::<Transform>(player).unwrap().translation,
+            Vec3::new(1., 2., 0.)
+        );
+        assert!(world.get_entity(coin).is_none());
+    }
+}
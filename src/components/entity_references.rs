@@ -0,0 +1,140 @@
+use bevy::{
+    ecs::{
+        entity::{EntityMapper, MapEntities},
+        reflect::ReflectMapEntities,
+    },
+    prelude::*,
+};
+
+use crate::{
+    components::EntityIid,
+    ldtk::{EntityInstance, FieldValue},
+};
+
+/// [`Component`] storing the iids of all LDtk entities referenced by this entity's `EntityRef`
+/// and `Array<EntityRef>` fields.
+///
+/// Automatically inserted on any spawned LDtk entity that has at least one entity reference.
+/// The plugin uses this component to maintain [`ReferencedBy`] components on the referenced
+/// entities.
+///
+/// [`Component`]: https://docs.rs/bevy/latest/bevy/ecs/component/trait.Component.html
+#[derive(Clone, Debug, Default, Eq, PartialEq, Component, Reflect)]
+#[reflect(Component, Default, Debug)]
+pub struct EntityReferences {
+    pub iids: Vec<EntityIid>,
+}
+
+impl EntityReferences {
+    /// Collects the iids referenced by all of the entity instance's `EntityRef` and
+    /// `Array<EntityRef>` fields, in field order.
+    ///
+    /// Null references are skipped.
+    pub fn from_entity_info(entity_instance: &EntityInstance) -> EntityReferences {
+        let iids = entity_instance
+            .field_instances
+            .iter()
+            .flat_map(|field_instance| match &field_instance.value {
+                FieldValue::EntityRef(reference) => reference.iter().collect::<Vec<_>>(),
+                FieldValue::EntityRefs(references) => references.iter().flatten().collect(),
+                _ => Vec::new(),
+            })
+            .map(|reference| EntityIid::new(reference.entity_iid.clone()))
+            .collect();
+
+        EntityReferences { iids }
+    }
+}
+
+/// [`Component`] listing the spawned LDtk entities whose entity references point at this entity.
+///
+/// This is the backlink counterpart to [`EntityReferences`].
+/// For example, a switch can use it to find every door that references it.
+///
+/// The plugin keeps this component in sync as levels spawn and despawn.
+/// Referencing entities appear in the order of their [`Entity`] ids, and the component is removed
+/// once nothing references this entity anymore.
+///
+/// [`Component`]: https://docs.rs/bevy/latest/bevy/ecs/component/trait.Component.html
+#[derive(Clone, Debug, Default, Deref, Eq, PartialEq, Component, Reflect)]
+#[reflect(Component, MapEntities, Default, Debug)]
+pub struct ReferencedBy(pub Vec<Entity>);
+
+impl MapEntities for ReferencedBy {
+    fn map_entities(&mut self, entity_mapper: &mut EntityMapper) {
+        for entity in self.0.iter_mut() {
+            *entity = entity_mapper.get_or_reserve(*entity);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ldtk::{
+        ldtk_fields::field_instance_from_value, FieldInstance, ReferenceToAnEntityInstance,
+    };
+
+    use super::*;
+
+    fn entity_ref_field(value: FieldValue) -> FieldInstance {
+        field_instance_from_value("Ref", value)
+    }
+
+    fn reference(entity_iid: &str) -> ReferenceToAnEntityInstance {
+        ReferenceToAnEntityInstance {
+            entity_iid: entity_iid.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn entity_references_collects_single_and_plural_fields() {
+        let entity_instance = EntityInstance {
+            field_instances: vec![
+                entity_ref_field(FieldValue::EntityRef(Some(reference("a")))),
+                entity_ref_field(FieldValue::EntityRef(None)),
+                entity_ref_field(FieldValue::Int(Some(1))),
+                entity_ref_field(FieldValue::EntityRefs(vec![
+                    Some(reference("b")),
+                    None,
+                    Some(reference("c")),
+                ])),
+            ],
+            ..Default::default()
+        };
+
+        assert_eq!(
+            EntityReferences::from_entity_info(&entity_instance),
+            EntityReferences {
+                iids: vec![
+                    EntityIid::new("a"),
+                    EntityIid::new("b"),
+                    EntityIid::new("c")
+                ]
+            }
+        );
+    }
+
+    #[test]
+    fn entity_references_is_empty_without_reference_fields() {
+        let entity_instance = EntityInstance::default();
+
+        assert!(EntityReferences::from_entity_info(&entity_instance)
+            .iids
+            .is_empty());
+    }
+
+    #[test]
+    fn referenced_by_maps_its_entities() {
+        let mut world = World::new();
+        let mut entity_map =
+            bevy::utils::HashMap::from([(Entity::from_raw(0), Entity::from_raw(5))]);
+        let mut referenced_by = ReferencedBy(vec![Entity::from_raw(0)]);
+
+        EntityMapper::world_scope(&mut entity_map, &mut world, |_, entity_mapper| {
+            referenced_by.map_entities(entity_mapper);
+        });
+
+        assert_eq!(referenced_by.0, vec![Entity::from_raw(5)]);
+    }
+}
@@ -0,0 +1,80 @@
+use bevy::prelude::*;
+
+use crate::ldtk::EntityInstance;
+
+/// [`Component`] storing the tags of an LDtk entity's definition, like `"enemy"` or `"flying"`.
+///
+/// Automatically inserted on any spawned LDtk entity whose definition has at least one tag.
+///
+/// Bevy queries can't filter on component values, so the intended usage is to query for
+/// `&EntityTags` and filter with one of the helper methods:
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_ecs_ldtk::prelude::*;
+/// fn flying_enemies(query: Query<(Entity, &EntityTags)>) {
+///     for (entity, _) in query.iter().filter(|(_, tags)| tags.contains_all(["enemy", "flying"])) {
+///         // ...
+///     }
+/// }
+/// ```
+///
+/// [`Component`]: https://docs.rs/bevy/latest/bevy/ecs/component/trait.Component.html
+#[derive(Clone, Debug, Default, Deref, Eq, PartialEq, Component, Reflect)]
+#[reflect(Component, Default, Debug)]
+pub struct EntityTags(Vec<String>);
+
+impl EntityTags {
+    /// Creates a new [`EntityTags`] from a collection of tags.
+    pub fn new<T: Into<String>>(tags: impl IntoIterator<Item = T>) -> Self {
+        EntityTags(tags.into_iter().map(Into::into).collect())
+    }
+
+    /// Creates an [`EntityTags`] from the entity information available to the
+    /// [`LdtkEntity::bundle_entity`] method.
+    ///
+    /// [`LdtkEntity::bundle_entity`]: crate::app::LdtkEntity::bundle_entity
+    pub fn from_entity_info(entity_instance: &EntityInstance) -> Self {
+        EntityTags::new(entity_instance.tags.iter().cloned())
+    }
+
+    /// Returns true if the given tag is present.
+    pub fn contains(&self, tag: &str) -> bool {
+        self.0.iter().any(|t| t == tag)
+    }
+
+    /// Returns true if at least one of the given tags is present.
+    pub fn contains_any<'a>(&self, tags: impl IntoIterator<Item = &'a str>) -> bool {
+        tags.into_iter().any(|tag| self.contains(tag))
+    }
+
+    /// Returns true if all of the given tags are present.
+    pub fn contains_all<'a>(&self, tags: impl IntoIterator<Item = &'a str>) -> bool {
+        tags.into_iter().all(|tag| self.contains(tag))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn entity_tags_matches_contained_tags() {
+        let entity_instance = EntityInstance {
+            tags: vec!["enemy".to_string(), "flying".to_string()],
+            ..Default::default()
+        };
+
+        let entity_tags = EntityTags::from_entity_info(&entity_instance);
+
+        assert_eq!(entity_tags, EntityTags::new(["enemy", "flying"]));
+
+        assert!(entity_tags.contains("enemy"));
+        assert!(!entity_tags.contains("boss"));
+
+        assert!(entity_tags.contains_any(["boss", "flying"]));
+        assert!(!entity_tags.contains_any(["boss", "swimming"]));
+
+        assert!(entity_tags.contains_all(["flying", "enemy"]));
+        assert!(!entity_tags.contains_all(["flying", "boss"]));
+    }
+}
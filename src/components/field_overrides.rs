@@ -0,0 +1,180 @@
+use bevy::prelude::*;
+use std::collections::HashMap;
+
+use crate::ldtk::{FieldInstance, FieldValue, Level};
+
+/// [`Component`] that overrides level and entity field values when levels spawn.
+///
+/// Insert it on an [`LdtkWorldBundle`] entity next to its [`LevelSet`].
+/// Overrides are keyed by the iid of the level or entity, and the identifier of the field.
+/// They are applied to the level data before any bundles are constructed, so
+/// [`LdtkEntity`] implementations and [`LevelEvent`]s see the overridden values.
+///
+/// This lets you spawn the same level with different parameters without duplicating it in the
+/// editor:
+/// ```
+/// use bevy::prelude::*;
+/// use bevy_ecs_ldtk::{ldtk::FieldValue, prelude::*};
+///
+/// fn spawn_arena(mut commands: Commands, asset_server: Res<AssetServer>) {
+///     let arena_iid = "a2f4d3e0-6d10-11ee-b2b5-6b2dc1f0e6a1";
+///
+///     commands.spawn((
+///         LdtkWorldBundle {
+///             ldtk_handle: asset_server.load("arena.ldtk").into(),
+///             level_set: LevelSet::from_iids([arena_iid]),
+///             ..Default::default()
+///         },
+///         FieldOverrides::default().with(arena_iid, "wave_count", FieldValue::Int(Some(5))),
+///     ));
+/// }
+/// ```
+///
+/// Only fields that exist on the level or entity are overridden, and the value should have the
+/// same type as the field.
+/// Changing this component doesn't affect levels that are already spawned, insert [`Respawn`] on
+/// the world entity to apply the new overrides.
+///
+/// [`Component`]: https://docs.rs/bevy/latest/bevy/ecs/component/trait.Component.html
+/// [`LdtkWorldBundle`]: crate::prelude::LdtkWorldBundle
+/// [`LevelSet`]: crate::prelude::LevelSet
+/// [`LdtkEntity`]: crate::prelude::LdtkEntity
+/// [`LevelEvent`]: crate::prelude::LevelEvent
+/// [`Respawn`]: crate::prelude::Respawn
+#[derive(Clone, PartialEq, Debug, Default, Component)]
+pub struct FieldOverrides {
+    overrides: HashMap<String, HashMap<String, FieldValue>>,
+}
+
+impl FieldOverrides {
+    /// Overrides the field with the given identifier on the level or entity with the given iid.
+    pub fn set(&mut self, iid: impl Into<String>, field: impl Into<String>, value: FieldValue) {
+        self.overrides
+            .entry(iid.into())
+            .or_default()
+            .insert(field.into(), value);
+    }
+
+    /// Builder-style version of [`FieldOverrides::set`].
+    pub fn with(
+        mut self,
+        iid: impl Into<String>,
+        field: impl Into<String>,
+        value: FieldValue,
+    ) -> Self {
+        self.set(iid, field, value);
+        self
+    }
+
+    /// Removes the override of the given field, returning its value.
+    pub fn remove(&mut self, iid: &str, field: &str) -> Option<FieldValue> {
+        let fields = self.overrides.get_mut(iid)?;
+        let value = fields.remove(field);
+
+        if fields.is_empty() {
+            self.overrides.remove(iid);
+        }
+
+        value
+    }
+
+    /// Returns the override of the given field, if any.
+    pub fn get(&self, iid: &str, field: &str) -> Option<&FieldValue> {
+        self.overrides.get(iid)?.get(field)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.overrides.is_empty()
+    }
+
+    /// Returns a copy of the level with the overrides applied, or `None` if none of them apply to
+    /// the level.
+    pub fn apply_to_level(&self, level: &Level) -> Option<Level> {
+        let entity_iids = level
+            .layer_instances
+            .iter()
+            .flatten()
+            .flat_map(|layer_instance| layer_instance.entity_instances.iter())
+            .map(|entity_instance| &entity_instance.iid);
+
+        if !std::iter::once(&level.iid)
+            .chain(entity_iids)
+            .any(|iid| self.overrides.contains_key(iid))
+        {
+            return None;
+        }
+
+        let mut level = level.clone();
+
+        self.apply_to_fields(&level.iid, &mut level.field_instances);
+
+        for layer_instance in level.layer_instances.iter_mut().flatten() {
+            for entity_instance in layer_instance.entity_instances.iter_mut() {
+                self.apply_to_fields(&entity_instance.iid, &mut entity_instance.field_instances);
+            }
+        }
+
+        Some(level)
+    }
+
+    fn apply_to_fields(&self, iid: &str, field_instances: &mut [FieldInstance]) {
+        let Some(fields) = self.overrides.get(iid) else {
+            return;
+        };
+
+        for field_instance in field_instances {
+            if let Some(value) = fields.get(&field_instance.identifier) {
+                field_instance.value = value.clone();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ldtk::{ldtk_fields::field_instance_from_value, EntityInstance, LayerInstance};
+
+    fn int_field(identifier: &str, value: i32) -> FieldInstance {
+        field_instance_from_value(identifier, FieldValue::Int(Some(value)))
+    }
+
+    #[test]
+    fn overrides_replace_matching_field_values() {
+        let level = Level {
+            iid: "arena".to_string(),
+            field_instances: vec![int_field("wave_count", 3)],
+            layer_instances: Some(vec![LayerInstance {
+                entity_instances: vec![EntityInstance {
+                    iid: "spawner".to_string(),
+                    field_instances: vec![int_field("rate", 1), int_field("limit", 10)],
+                    ..Default::default()
+                }],
+                ..Default::default()
+            }]),
+            ..Default::default()
+        };
+
+        let mut field_overrides = FieldOverrides::default()
+            .with("arena", "wave_count", FieldValue::Int(Some(5)))
+            .with("spawner", "rate", FieldValue::Int(Some(4)))
+            .with("spawner", "missing", FieldValue::Int(Some(0)));
+
+        let overridden = field_overrides.apply_to_level(&level).unwrap();
+        assert_eq!(
+            overridden.field_instances[0].value,
+            FieldValue::Int(Some(5))
+        );
+
+        let spawner = &overridden.layer_instances.unwrap()[0].entity_instances[0];
+        assert_eq!(spawner.field_instances.len(), 2);
+        assert_eq!(spawner.field_instances[0].value, FieldValue::Int(Some(4)));
+        assert_eq!(spawner.field_instances[1].value, FieldValue::Int(Some(10)));
+
+        field_overrides.remove("arena", "wave_count");
+        field_overrides.remove("spawner", "rate");
+        field_overrides.remove("spawner", "missing");
+        assert!(field_overrides.is_empty());
+        assert_eq!(field_overrides.apply_to_level(&level), None);
+    }
+}
@@ -0,0 +1,182 @@
+use super::GridCoords;
+use crate::ldtk::{EntityInstance, LayerInstance};
+use bevy::prelude::*;
+
+/// Which cell of an entity larger than one cell is reported by
+/// [GridCoords::from_entity_info_anchored].
+///
+/// Can be chosen with the `#[grid_coords(...)]` attribute of `#[derive(LdtkEntity)]`.
+/// See [LdtkEntity#grid_coords] for more info.
+///
+/// [LdtkEntity#grid_coords]: crate::app::LdtkEntity#grid_coords
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Default, Hash, Reflect)]
+pub enum GridCoordsAnchor {
+    /// The cell containing the entity's pivot, like [GridCoords::from_entity_info].
+    #[default]
+    Pivot,
+    /// The cell in the center of the entity, rounding down and left for even sizes.
+    Center,
+    BottomLeft,
+    BottomRight,
+    TopLeft,
+    TopRight,
+}
+
+/// [Component] storing every cell occupied by an entity, for entities larger than one cell.
+///
+/// Both corners are inclusive, so a 2x2 entity has a `max` one cell up and right of its `min`.
+///
+/// Can be added to an [LdtkEntity] bundle with the `#[grid_coords]` attribute.
+/// See [LdtkEntity#grid_coords] for attribute macro usage.
+///
+/// [LdtkEntity]: crate::app::LdtkEntity
+/// [LdtkEntity#grid_coords]: crate::app::LdtkEntity#grid_coords
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Default, Hash, Component, Reflect)]
+#[reflect(Component)]
+pub struct GridCoordsRect {
+    /// The bottom left cell.
+    pub min: GridCoords,
+    /// The top right cell.
+    pub max: GridCoords,
+}
+
+impl GridCoordsRect {
+    /// Creates a [GridCoordsRect] from the entity information available to the
+    /// [LdtkEntity::bundle_entity] method.
+    ///
+    /// Cells the entity only partially covers are included.
+    ///
+    /// [LdtkEntity::bundle_entity]: crate::app::LdtkEntity::bundle_entity
+    pub fn from_entity_info(
+        entity_instance: &EntityInstance,
+        layer_instance: &LayerInstance,
+    ) -> GridCoordsRect {
+        let grid_size = layer_instance.grid_size;
+        let size = IVec2::new(entity_instance.width, entity_instance.height);
+
+        // In LDtk pixel space, where y points down
+        let top_left =
+            entity_instance.px - (entity_instance.pivot * size.as_vec2()).round().as_ivec2();
+        let bottom_right = top_left + size.max(IVec2::ONE) - IVec2::ONE;
+
+        let first_cell = IVec2::new(
+            top_left.x.div_euclid(grid_size),
+            top_left.y.div_euclid(grid_size),
+        );
+        let last_cell = IVec2::new(
+            bottom_right.x.div_euclid(grid_size),
+            bottom_right.y.div_euclid(grid_size),
+        );
+
+        let flip_y = |ldtk_y: i32| layer_instance.c_hei - 1 - ldtk_y;
+
+        GridCoordsRect {
+            min: GridCoords::new(first_cell.x, flip_y(last_cell.y)),
+            max: GridCoords::new(last_cell.x, flip_y(first_cell.y)),
+        }
+    }
+
+    /// The number of cells the rect spans on each axis.
+    pub fn size(&self) -> IVec2 {
+        IVec2::from(self.max) - IVec2::from(self.min) + IVec2::ONE
+    }
+
+    /// Returns true if the given cell is inside the rect.
+    pub fn contains(&self, grid_coords: GridCoords) -> bool {
+        (self.min.x..=self.max.x).contains(&grid_coords.x)
+            && (self.min.y..=self.max.y).contains(&grid_coords.y)
+    }
+
+    /// Iterates over every cell in the rect, row by row from the bottom.
+    pub fn iter(&self) -> impl Iterator<Item = GridCoords> {
+        let GridCoordsRect { min, max } = *self;
+        (min.y..=max.y).flat_map(move |y| (min.x..=max.x).map(move |x| GridCoords::new(x, y)))
+    }
+
+    /// Returns the cell of the rect at the given anchor.
+    ///
+    /// [GridCoordsAnchor::Pivot] isn't known by the rect, so the `pivot` cell is returned for it.
+    pub fn anchor(&self, anchor: GridCoordsAnchor, pivot: GridCoords) -> GridCoords {
+        let GridCoordsRect { min, max } = *self;
+
+        match anchor {
+            GridCoordsAnchor::Pivot => pivot,
+            GridCoordsAnchor::Center => {
+                GridCoords::new((min.x + max.x).div_euclid(2), (min.y + max.y).div_euclid(2))
+            }
+            GridCoordsAnchor::BottomLeft => min,
+            GridCoordsAnchor::BottomRight => GridCoords::new(max.x, min.y),
+            GridCoordsAnchor::TopLeft => GridCoords::new(min.x, max.y),
+            GridCoordsAnchor::TopRight => max,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rects_cover_multi_cell_entities() {
+        let layer_instance = LayerInstance {
+            grid_size: 16,
+            c_hei: 10,
+            ..Default::default()
+        };
+
+        // A 2x2 entity with a bottom center pivot, standing on the line between two cells
+        let entity_instance = EntityInstance {
+            px: IVec2::new(32, 64),
+            grid: IVec2::new(2, 4),
+            pivot: Vec2::new(0.5, 1.),
+            width: 32,
+            height: 32,
+            ..Default::default()
+        };
+
+        let rect = GridCoordsRect::from_entity_info(&entity_instance, &layer_instance);
+        assert_eq!(
+            rect,
+            GridCoordsRect {
+                min: GridCoords::new(1, 6),
+                max: GridCoords::new(2, 7),
+            }
+        );
+        assert_eq!(rect.size(), IVec2::new(2, 2));
+        assert_eq!(rect.iter().count(), 4);
+        assert!(rect.contains(GridCoords::new(2, 6)));
+        assert!(!rect.contains(GridCoords::new(2, 5)));
+
+        assert_eq!(
+            GridCoords::from_entity_info_anchored(
+                &entity_instance,
+                &layer_instance,
+                GridCoordsAnchor::TopLeft
+            ),
+            GridCoords::new(1, 7)
+        );
+        assert_eq!(
+            GridCoords::from_entity_info_anchored(
+                &entity_instance,
+                &layer_instance,
+                GridCoordsAnchor::Pivot
+            ),
+            GridCoords::new(2, 5)
+        );
+
+        let single_cell = EntityInstance {
+            px: IVec2::new(8, 8),
+            pivot: Vec2::splat(0.5),
+            width: 16,
+            height: 16,
+            ..Default::default()
+        };
+        assert_eq!(
+            GridCoordsRect::from_entity_info(&single_cell, &layer_instance),
+            GridCoordsRect {
+                min: GridCoords::new(0, 9),
+                max: GridCoords::new(0, 9),
+            }
+        );
+    }
+}
@@ -0,0 +1,111 @@
+use bevy::prelude::*;
+use std::collections::HashMap;
+
+use super::GridCoords;
+use crate::utils::int_grid_index_to_grid_coords;
+
+/// [Component] on IntGrid layers storing the nonzero int grid values of the layer, by position.
+///
+/// Only inserted when [LdtkSettings::int_grid_cell_storage] is [IntGridCellStorage::Sparse].
+/// In that case, this is the only place the values of most cells can be found, since only cells
+/// with an [LdtkIntCell] registration get [IntGridCell] entities.
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_ecs_ldtk::prelude::*;
+/// fn is_wall(layers: Query<(&LayerMetadata, &IntGridCells)>, grid_coords: GridCoords) -> bool {
+///     layers
+///         .iter()
+///         .filter(|(layer_metadata, _)| layer_metadata.identifier == "Walls")
+///         .any(|(_, cells)| cells.value_at(grid_coords) == Some(1))
+/// }
+/// ```
+///
+/// [LdtkSettings::int_grid_cell_storage]: crate::prelude::LdtkSettings::int_grid_cell_storage
+/// [IntGridCellStorage::Sparse]: crate::prelude::IntGridCellStorage::Sparse
+/// [LdtkIntCell]: crate::prelude::LdtkIntCell
+/// [IntGridCell]: crate::prelude::IntGridCell
+#[derive(Clone, Eq, PartialEq, Debug, Default, Component, Reflect)]
+#[reflect(Component)]
+pub struct IntGridCells {
+    values: HashMap<GridCoords, i32>,
+    size: IVec2,
+}
+
+impl IntGridCells {
+    /// Constructs an [IntGridCells] from the `int_grid_csv` of a layer, skipping zeros.
+    pub fn from_int_grid_csv(int_grid_csv: &[i32], layer_width: i32, layer_height: i32) -> Self {
+        let values = int_grid_csv
+            .iter()
+            .enumerate()
+            .filter(|(_, value)| **value != 0)
+            .filter_map(|(i, value)| {
+                let grid_coords =
+                    int_grid_index_to_grid_coords(i, layer_width as u32, layer_height as u32)?;
+                Some((grid_coords, *value))
+            })
+            .collect();
+
+        IntGridCells {
+            values,
+            size: IVec2::new(layer_width, layer_height),
+        }
+    }
+
+    /// The width and height of the layer, in cells.
+    pub fn size(&self) -> IVec2 {
+        self.size
+    }
+
+    /// Returns the int grid value at the given position, or [None] if it is zero.
+    pub fn value_at(&self, grid_coords: GridCoords) -> Option<i32> {
+        self.values.get(&grid_coords).copied()
+    }
+
+    /// Sets the int grid value at the given position, removing it if the value is zero.
+    pub fn set(&mut self, grid_coords: GridCoords, value: i32) {
+        if value == 0 {
+            self.values.remove(&grid_coords);
+        } else {
+            self.values.insert(grid_coords, value);
+        }
+    }
+
+    /// Iterates over the positions and values of all nonzero cells, in no particular order.
+    pub fn iter(&self) -> impl Iterator<Item = (GridCoords, i32)> + '_ {
+        self.values
+            .iter()
+            .map(|(grid_coords, value)| (*grid_coords, *value))
+    }
+
+    /// Returns the number of nonzero cells.
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    /// Returns true if all cells are zero.
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cells_are_read_from_int_grid_csv() {
+        let mut cells = IntGridCells::from_int_grid_csv(&[0, 1, 2, 0], 2, 2);
+
+        assert_eq!(cells.len(), 2);
+        assert_eq!(cells.value_at(GridCoords::new(1, 1)), Some(1));
+        assert_eq!(cells.value_at(GridCoords::new(0, 0)), Some(2));
+        assert_eq!(cells.value_at(GridCoords::new(0, 1)), None);
+
+        cells.set(GridCoords::new(1, 1), 0);
+        cells.set(GridCoords::new(0, 1), 3);
+
+        assert_eq!(cells.value_at(GridCoords::new(1, 1)), None);
+        assert_eq!(cells.value_at(GridCoords::new(0, 1)), Some(3));
+        assert_eq!(cells.len(), 2);
+    }
+}
@@ -0,0 +1,116 @@
+use bevy::{
+    prelude::*,
+    render::render_resource::{Extent3d, TextureDimension, TextureFormat},
+    tasks::{futures_lite::future, AsyncComputeTaskPool, Task},
+};
+
+/// [Component] on IntGrid layers holding a data texture of the layer's int grid values.
+///
+/// Only inserted when [LdtkSettings::int_grid_textures] is [IntGridTextures::Enabled].
+/// The texture has one texel per cell, in the [TextureFormat::R32Sint] format, so each texel
+/// stores the value of its cell exactly, with 0 for empty cells.
+/// Like LDtk's `int_grid_csv`, the first row of texels is the top row of the layer.
+///
+/// This lets shaders sample the structure of a level directly, for effects like fog of war, flow
+/// fields, or heat maps.
+/// Since the format is an integer format, shaders should read it with `textureLoad` rather than
+/// sampling it with a filter.
+///
+/// The texture is created on the [AsyncComputeTaskPool] so large layers don't stall the frame
+/// they spawn in, and the component is inserted once it's ready, usually a frame later.
+///
+/// [LdtkSettings::int_grid_textures]: crate::prelude::LdtkSettings::int_grid_textures
+/// [IntGridTextures::Enabled]: crate::prelude::IntGridTextures::Enabled
+#[derive(Clone, Eq, PartialEq, Debug, Default, Component, Reflect)]
+#[reflect(Component)]
+pub struct IntGridTexture {
+    pub image: Handle<Image>,
+}
+
+impl IntGridTexture {
+    /// Creates the data texture for the `int_grid_csv` of a layer.
+    pub fn image_from_int_grid_csv(
+        int_grid_csv: &[i32],
+        layer_width: i32,
+        layer_height: i32,
+    ) -> Image {
+        let texel_count = (layer_width.max(0) * layer_height.max(0)) as usize;
+
+        let data = int_grid_csv
+            .iter()
+            .copied()
+            .chain(std::iter::repeat(0))
+            .take(texel_count)
+            .flat_map(i32::to_le_bytes)
+            .collect();
+
+        Image::new(
+            Extent3d {
+                width: layer_width.max(0) as u32,
+                height: layer_height.max(0) as u32,
+                depth_or_array_layers: 1,
+            },
+            TextureDimension::D2,
+            data,
+            TextureFormat::R32Sint,
+        )
+    }
+}
+
+/// [Component] on IntGrid layers whose [IntGridTexture] is still being created.
+#[derive(Debug, Component)]
+pub(crate) struct IntGridTextureTask(Task<Image>);
+
+impl IntGridTextureTask {
+    /// Starts creating the data texture for the `int_grid_csv` of a layer.
+    pub(crate) fn spawn(int_grid_csv: Vec<i32>, layer_width: i32, layer_height: i32) -> Self {
+        IntGridTextureTask(AsyncComputeTaskPool::get().spawn(async move {
+            IntGridTexture::image_from_int_grid_csv(&int_grid_csv, layer_width, layer_height)
+        }))
+    }
+
+    /// Returns the texture if the task has finished.
+    pub(crate) fn poll(&mut self) -> Option<Image> {
+        future::block_on(future::poll_once(&mut self.0))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::tasks::TaskPool;
+
+    #[test]
+    fn texels_store_int_grid_values() {
+        let image = IntGridTexture::image_from_int_grid_csv(&[0, 1, 2, -3, 4, 5], 3, 2);
+
+        assert_eq!(image.texture_descriptor.size.width, 3);
+        assert_eq!(image.texture_descriptor.size.height, 2);
+        assert_eq!(image.texture_descriptor.format, TextureFormat::R32Sint);
+
+        let values: Vec<i32> = image
+            .data
+            .chunks_exact(4)
+            .map(|texel| i32::from_le_bytes(texel.try_into().unwrap()))
+            .collect();
+        assert_eq!(values, vec![0, 1, 2, -3, 4, 5]);
+    }
+
+    #[test]
+    fn textures_are_created_in_tasks() {
+        AsyncComputeTaskPool::init(TaskPool::default);
+
+        let mut task = IntGridTextureTask::spawn(vec![7, 0], 2, 1);
+        let image = loop {
+            if let Some(image) = task.poll() {
+                break image;
+            }
+            std::thread::yield_now();
+        };
+
+        assert_eq!(
+            image.data,
+            [7i32.to_le_bytes(), 0i32.to_le_bytes()].concat()
+        );
+    }
+}
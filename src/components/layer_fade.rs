@@ -0,0 +1,120 @@
+use bevy::prelude::*;
+
+/// [Component] on a layer entity that fades the layer's tiles and sprites in or out.
+///
+/// The plugin multiplies the alpha of every tile and sprite in the layer by the fade's
+/// [`LayerFade::opacity`], on top of the layer's own opacity from LDtk.
+/// The component stays on the layer once the fade finishes, so the layer keeps its final opacity.
+/// Insert a new fade, or [`LayerFade::retarget`] the current one, to fade it again.
+///
+/// Useful for reveal effects, like hiding a building's "Roof" layer when the player walks in.
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_ecs_ldtk::prelude::*;
+/// fn hide_roof(
+///     mut commands: Commands,
+///     level_query: LdtkLevelQuery,
+///     level_selection: Res<LevelSelection>,
+/// ) {
+///     if let LevelSelection::Iid(level_iid) = level_selection.as_ref() {
+///         for roof in level_query.layers_in_level(level_iid, "Roof") {
+///             commands.entity(roof).insert(LayerFade::fade_out(0.5));
+///         }
+///     }
+/// }
+/// ```
+#[derive(Copy, Clone, PartialEq, Debug, Component, Reflect)]
+#[reflect(Component, Default, Debug)]
+pub struct LayerFade {
+    /// Opacity at the start of the fade, from 0 to 1.
+    pub from: f32,
+    /// Opacity at the end of the fade, from 0 to 1.
+    pub to: f32,
+    /// Seconds the fade takes.
+    pub duration: f32,
+    /// Seconds since the fade started.
+    pub elapsed: f32,
+}
+
+impl Default for LayerFade {
+    fn default() -> Self {
+        LayerFade {
+            from: 1.,
+            to: 1.,
+            duration: 0.,
+            elapsed: 0.,
+        }
+    }
+}
+
+impl LayerFade {
+    /// Creates a fade between the given opacities that hasn't started yet.
+    pub fn new(from: f32, to: f32, duration: f32) -> LayerFade {
+        LayerFade {
+            from,
+            to,
+            duration,
+            elapsed: 0.,
+        }
+    }
+
+    /// Creates a fade from fully opaque to fully transparent.
+    pub fn fade_out(duration: f32) -> LayerFade {
+        LayerFade::new(1., 0., duration)
+    }
+
+    /// Creates a fade from fully transparent to fully opaque.
+    pub fn fade_in(duration: f32) -> LayerFade {
+        LayerFade::new(0., 1., duration)
+    }
+
+    /// The current opacity of the layer, from 0 to 1.
+    pub fn opacity(&self) -> f32 {
+        if self.is_finished() {
+            self.to
+        } else {
+            self.from + (self.to - self.from) * (self.elapsed / self.duration)
+        }
+    }
+
+    /// Returns true if the fade has reached its target opacity.
+    pub fn is_finished(&self) -> bool {
+        self.elapsed >= self.duration
+    }
+
+    /// Restarts the fade from the current opacity towards a new one.
+    ///
+    /// Lets a fade reverse smoothly when it's interrupted, like when the player leaves a building
+    /// before its roof has faded out.
+    pub fn retarget(&mut self, to: f32, duration: f32) {
+        *self = LayerFade::new(self.opacity(), to, duration);
+    }
+}
+
+/// Alpha of a tile or sprite before its layer started fading.
+#[derive(Copy, Clone, PartialEq, Debug, Component)]
+pub(crate) struct FadeBaseAlpha(pub f32);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fades_interpolate_and_retarget_from_current_opacity() {
+        let mut fade = LayerFade::fade_out(2.);
+        assert_eq!(fade.opacity(), 1.);
+
+        fade.elapsed = 0.5;
+        assert_eq!(fade.opacity(), 0.75);
+        assert!(!fade.is_finished());
+
+        fade.retarget(1., 1.);
+        assert_eq!(fade, LayerFade::new(0.75, 1., 1.));
+
+        fade.elapsed = 3.;
+        assert!(fade.is_finished());
+        assert_eq!(fade.opacity(), 1.);
+
+        assert_eq!(LayerFade::fade_in(0.).opacity(), 1.);
+    }
+}
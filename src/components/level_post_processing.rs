@@ -0,0 +1,125 @@
+use bevy::prelude::*;
+use std::collections::{HashMap, HashSet};
+
+use crate::ldtk::{FieldValue, Level};
+
+/// [`Component`] and [`Resource`] storing per-level post-processing parameters, read from the
+/// level's custom fields.
+///
+/// The fields to read are designated by [`LdtkSettings::post_processing_fields`].
+/// Color fields are stored in `colors`, while Float and Int fields are stored in `floats`.
+/// Null fields are skipped.
+///
+/// As a [`Component`], it is inserted on every spawned level entity.
+/// As a [`Resource`], it always holds the parameters of the active level, which is the one
+/// matching the [`LevelSelection`], or the most recently spawned level if there is no
+/// [`LevelSelection`].
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_ecs_ldtk::prelude::*;
+/// fn color_grade(post_processing: Res<LevelPostProcessing>, mut clear_color: ResMut<ClearColor>) {
+///     if post_processing.is_changed() {
+///         if let Some(ambient) = post_processing.color("ambient_color") {
+///             clear_color.0 = ambient;
+///         }
+///     }
+/// }
+/// ```
+///
+/// To feed these parameters into a material's uniforms, see
+/// [`LevelPostProcessingAppExt`].
+///
+/// [`Component`]: https://docs.rs/bevy/latest/bevy/ecs/component/trait.Component.html
+/// [`Resource`]: https://docs.rs/bevy/latest/bevy/ecs/system/trait.Resource.html
+/// [`LdtkSettings::post_processing_fields`]: crate::prelude::LdtkSettings::post_processing_fields
+/// [`LevelSelection`]: crate::prelude::LevelSelection
+/// [`LevelPostProcessingAppExt`]: crate::app::LevelPostProcessingAppExt
+#[derive(Clone, PartialEq, Debug, Default, Component, Resource, Reflect)]
+#[reflect(Component, Default, Debug)]
+pub struct LevelPostProcessing {
+    pub colors: HashMap<String, Color>,
+    pub floats: HashMap<String, f32>,
+}
+
+impl LevelPostProcessing {
+    /// Creates a [`LevelPostProcessing`] from the designated fields of a level.
+    pub fn from_level(level: &Level, fields: &HashSet<String>) -> LevelPostProcessing {
+        let mut post_processing = LevelPostProcessing::default();
+
+        for field_instance in level
+            .field_instances
+            .iter()
+            .filter(|field_instance| fields.contains(&field_instance.identifier))
+        {
+            let identifier = field_instance.identifier.clone();
+
+            match field_instance.value {
+                FieldValue::Color(color) => {
+                    post_processing.colors.insert(identifier, color);
+                }
+                FieldValue::Float(Some(float)) => {
+                    post_processing.floats.insert(identifier, float);
+                }
+                FieldValue::Int(Some(int)) => {
+                    post_processing.floats.insert(identifier, int as f32);
+                }
+                _ => (),
+            }
+        }
+
+        post_processing
+    }
+
+    /// Returns the value of the given color parameter, if present.
+    pub fn color(&self, identifier: &str) -> Option<Color> {
+        self.colors.get(identifier).copied()
+    }
+
+    /// Returns the value of the given numeric parameter, if present.
+    pub fn float(&self, identifier: &str) -> Option<f32> {
+        self.floats.get(identifier).copied()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ldtk::ldtk_fields::field_instance_from_value;
+
+    use super::*;
+
+    #[test]
+    fn only_designated_fields_are_read() {
+        let level = Level {
+            field_instances: vec![
+                field_instance_from_value("ambient_color", FieldValue::Color(Color::BLUE)),
+                field_instance_from_value("saturation", FieldValue::Float(Some(0.5))),
+                field_instance_from_value("exposure", FieldValue::Int(Some(2))),
+                field_instance_from_value("contrast", FieldValue::Float(None)),
+                field_instance_from_value("music", FieldValue::String(Some("calm".to_string()))),
+                field_instance_from_value("fog_color", FieldValue::Color(Color::GRAY)),
+            ],
+            ..Default::default()
+        };
+
+        let fields = [
+            "ambient_color",
+            "saturation",
+            "exposure",
+            "contrast",
+            "music",
+        ]
+        .into_iter()
+        .map(String::from)
+        .collect();
+
+        let post_processing = LevelPostProcessing::from_level(&level, &fields);
+
+        assert_eq!(post_processing.color("ambient_color"), Some(Color::BLUE));
+        assert_eq!(post_processing.color("fog_color"), None);
+        assert_eq!(post_processing.float("saturation"), Some(0.5));
+        assert_eq!(post_processing.float("exposure"), Some(2.));
+        assert_eq!(post_processing.float("contrast"), None);
+        assert_eq!(post_processing.colors.len(), 1);
+        assert_eq!(post_processing.floats.len(), 2);
+    }
+}
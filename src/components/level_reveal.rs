@@ -0,0 +1,103 @@
+use bevy::prelude::*;
+use bevy_ecs_tilemap::tiles::TilePos;
+
+/// How a [`LevelReveal`] animates the contents of a level.
+#[derive(Copy, Clone, PartialEq, Debug, Default, Reflect)]
+pub enum LevelRevealStyle {
+    /// Every sprite and tile fades in at once.
+    #[default]
+    Fade,
+    /// Tiles fade in one after another, starting from the bottom-left corner of each tilemap.
+    ///
+    /// Sprites fade in immediately, like with [`LevelRevealStyle::Fade`].
+    TileStagger {
+        /// Seconds between the start of a tile's fade and the start of its neighbors'.
+        tile_delay: f32,
+    },
+    /// The built-in reveal system ignores the level.
+    ///
+    /// Animate the reveal in your own system, and remove the [`LevelReveal`] when it's done.
+    Custom,
+}
+
+/// [`Component`] on a level entity that animates the level in after it spawns.
+///
+/// If [`LdtkSettings::level_reveal`] is set, it is inserted on every newly spawned level.
+/// The plugin fades in the level's sprites and tiles from transparent to their original alpha, then
+/// removes this component.
+/// To take over the animation, use [`LevelRevealStyle::Custom`].
+///
+/// [`Component`]: https://docs.rs/bevy/latest/bevy/ecs/component/trait.Component.html
+/// [`LdtkSettings::level_reveal`]: crate::prelude::LdtkSettings::level_reveal
+#[derive(Clone, PartialEq, Debug, Default, Component, Reflect)]
+#[reflect(Component, Default, Debug)]
+pub struct LevelReveal {
+    pub style: LevelRevealStyle,
+    /// Seconds it takes a single sprite or tile to fade in.
+    pub duration: f32,
+    /// Seconds since the reveal started.
+    pub elapsed: f32,
+}
+
+impl LevelReveal {
+    /// Creates a [`LevelReveal`] that hasn't started yet.
+    pub fn new(style: LevelRevealStyle, duration: f32) -> LevelReveal {
+        LevelReveal {
+            style,
+            duration,
+            elapsed: 0.,
+        }
+    }
+
+    /// Returns how far along the fade is for a sprite or tile, from 0 to 1.
+    ///
+    /// Tiles should provide their [`TilePos`].
+    pub fn progress(&self, tile_pos: Option<&TilePos>) -> f32 {
+        let delay = match (self.style, tile_pos) {
+            (LevelRevealStyle::TileStagger { tile_delay }, Some(tile_pos)) => {
+                (tile_pos.x + tile_pos.y) as f32 * tile_delay
+            }
+            _ => 0.,
+        };
+
+        if self.duration <= 0. {
+            if self.elapsed >= delay {
+                1.
+            } else {
+                0.
+            }
+        } else {
+            ((self.elapsed - delay) / self.duration).clamp(0., 1.)
+        }
+    }
+}
+
+/// Alpha of a sprite or tile before its level started revealing.
+#[derive(Copy, Clone, PartialEq, Debug, Component)]
+pub(crate) struct RevealBaseAlpha(pub f32);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stagger_delays_tiles_by_position() {
+        let mut reveal = LevelReveal::new(LevelRevealStyle::TileStagger { tile_delay: 0.1 }, 1.);
+        reveal.elapsed = 0.5;
+
+        assert_eq!(reveal.progress(None), 0.5);
+        assert_eq!(reveal.progress(Some(&TilePos::new(0, 0))), 0.5);
+        assert!((reveal.progress(Some(&TilePos::new(1, 2))) - 0.2).abs() < 1e-6);
+        assert_eq!(reveal.progress(Some(&TilePos::new(5, 5))), 0.);
+
+        reveal.elapsed = 3.;
+        assert_eq!(reveal.progress(Some(&TilePos::new(5, 5))), 1.);
+    }
+
+    #[test]
+    fn instant_reveals_are_complete() {
+        let reveal = LevelReveal::new(LevelRevealStyle::Fade, 0.);
+
+        assert_eq!(reveal.progress(None), 1.);
+    }
+}
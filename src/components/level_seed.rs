@@ -0,0 +1,68 @@
+use bevy::prelude::*;
+
+/// [`Component`] storing a seed for randomness within a level, inserted on every spawned level.
+///
+/// The seed is derived from the project and level iids, so it's the same every time the level
+/// spawns, on every platform.
+/// Systems that randomize things in a level can seed their RNG with it, rather than coordinating
+/// their own seeding scheme.
+/// Inserting a [`LevelRunSeed`] mixes it into the seeds of levels spawned afterwards, so that
+/// levels vary between runs while staying reproducible for a given run seed.
+///
+/// [`Component`]: https://docs.rs/bevy/latest/bevy/ecs/component/trait.Component.html
+/// [`LevelRunSeed`]: crate::prelude::LevelRunSeed
+#[derive(Copy, Clone, Debug, Default, Hash, Eq, PartialEq, Ord, PartialOrd, Component, Reflect)]
+#[reflect(Component, Default, Debug)]
+pub struct LevelSeed(pub u64);
+
+const FNV_PRIME: u64 = 0x100000001b3;
+
+impl LevelSeed {
+    /// Derives the seed of a level from its iids.
+    ///
+    /// Uses FNV-1a, like [`StableEntityId`](super::StableEntityId).
+    pub fn new(project_iid: &str, level_iid: &str) -> Self {
+        let mut hash: u64 = 0xcbf29ce484222325;
+
+        // Separate the iids so that moving characters between them changes the seed
+        for byte in [project_iid, level_iid]
+            .into_iter()
+            .flat_map(|iid| iid.bytes().chain([0xff]))
+        {
+            hash ^= byte as u64;
+            hash = hash.wrapping_mul(FNV_PRIME);
+        }
+
+        LevelSeed(hash)
+    }
+
+    /// Mixes a run seed into the level seed.
+    pub fn with_run_seed(self, run_seed: u64) -> Self {
+        let mut hash = self.0;
+
+        for byte in run_seed.to_le_bytes() {
+            hash ^= byte as u64;
+            hash = hash.wrapping_mul(FNV_PRIME);
+        }
+
+        LevelSeed(hash)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn seeds_are_stable_and_distinct() {
+        let seed = LevelSeed::new("project", "level");
+
+        assert_eq!(seed, LevelSeed::new("project", "level"));
+        assert_ne!(seed, LevelSeed::new("projectl", "evel"));
+        assert_ne!(seed, LevelSeed::new("project", "other"));
+
+        assert_eq!(seed.with_run_seed(7), seed.with_run_seed(7));
+        assert_ne!(seed.with_run_seed(7), seed.with_run_seed(8));
+        assert_ne!(seed.with_run_seed(0), seed);
+    }
+}
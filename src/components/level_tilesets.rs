@@ -0,0 +1,93 @@
+use bevy::{asset::AssetPath, prelude::*};
+use std::collections::{BTreeSet, HashMap};
+
+use crate::ldtk::loaded_level::LoadedLevel;
+
+/// [Component] on level entities holding the tileset images used by the level.
+///
+/// Only inserted for projects loaded with [LdtkLoaderSettings::tilesets_on_demand].
+/// These projects only hold weak handles to their tilesets, so tileset images stay loaded while a
+/// spawned level holds them here, and are unloaded once no spawned level uses them.
+///
+/// [LdtkLoaderSettings::tilesets_on_demand]: crate::assets::LdtkLoaderSettings::tilesets_on_demand
+#[derive(Clone, Eq, PartialEq, Debug, Default, Component)]
+pub struct LevelTilesets {
+    handles: Vec<Handle<Image>>,
+}
+
+impl LevelTilesets {
+    /// Loads the on-demand tilesets used by the level.
+    pub(crate) fn load(
+        level: &LoadedLevel,
+        tileset_paths: &HashMap<i32, AssetPath<'static>>,
+        asset_server: &AssetServer,
+    ) -> LevelTilesets {
+        let handles = used_tileset_uids(level)
+            .into_iter()
+            .filter_map(|tileset_uid| tileset_paths.get(&tileset_uid))
+            .map(|asset_path| asset_server.load(asset_path.clone()))
+            .collect();
+
+        LevelTilesets { handles }
+    }
+
+    /// Strong handles to the tileset images used by the level.
+    pub fn handles(&self) -> &[Handle<Image>] {
+        &self.handles
+    }
+}
+
+/// Returns the uids of the tilesets used by the level's layers and entities.
+pub(crate) fn used_tileset_uids(level: &LoadedLevel) -> BTreeSet<i32> {
+    let mut tileset_uids = BTreeSet::new();
+
+    for layer_instance in level.layer_instances() {
+        tileset_uids.extend(layer_instance.tileset_def_uid);
+
+        tileset_uids.extend(
+            layer_instance
+                .entity_instances
+                .iter()
+                .filter_map(|entity_instance| entity_instance.tile.as_ref())
+                .map(|tile| tile.tileset_uid),
+        );
+    }
+
+    tileset_uids
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ldtk::{EntityInstance, LayerInstance, Level, TilesetRectangle};
+
+    #[test]
+    fn tilesets_of_layers_and_entities_are_used() {
+        let level = Level {
+            layer_instances: Some(vec![
+                LayerInstance {
+                    tileset_def_uid: Some(3),
+                    ..Default::default()
+                },
+                LayerInstance {
+                    entity_instances: vec![
+                        EntityInstance {
+                            tile: Some(TilesetRectangle {
+                                tileset_uid: 7,
+                                ..Default::default()
+                            }),
+                            ..Default::default()
+                        },
+                        EntityInstance::default(),
+                    ],
+                    ..Default::default()
+                },
+            ]),
+            ..Default::default()
+        };
+
+        let loaded_level = LoadedLevel::try_from(&level).unwrap();
+
+        assert_eq!(used_tileset_uids(&loaded_level), BTreeSet::from([3, 7]));
+    }
+}
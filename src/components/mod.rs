@@ -2,16 +2,78 @@
 mod entity_iid;
 pub use entity_iid::EntityIid;
 
+mod entity_references;
+pub use entity_references::{EntityReferences, ReferencedBy};
+
+mod entity_tags;
+pub use entity_tags::EntityTags;
+
+mod field_overrides;
+pub use field_overrides::FieldOverrides;
+
+mod grid_coords_rect;
+pub use grid_coords_rect::{GridCoordsAnchor, GridCoordsRect};
+
+mod int_grid_cells;
+pub use int_grid_cells::IntGridCells;
+
+mod int_grid_texture;
+pub use int_grid_texture::IntGridTexture;
+pub(crate) use int_grid_texture::IntGridTextureTask;
+
+mod layer_fade;
+pub(crate) use layer_fade::FadeBaseAlpha;
+pub use layer_fade::LayerFade;
+
 mod level_iid;
 pub use level_iid::LevelIid;
 
+mod level_tilesets;
+pub(crate) use level_tilesets::used_tileset_uids;
+pub use level_tilesets::LevelTilesets;
+
+mod level_post_processing;
+pub use level_post_processing::LevelPostProcessing;
+
+mod level_reveal;
+pub(crate) use level_reveal::RevealBaseAlpha;
+pub use level_reveal::{LevelReveal, LevelRevealStyle};
+
 mod level_set;
 pub use level_set::LevelSet;
 
+mod level_seed;
+pub use level_seed::LevelSeed;
+
+mod owning_level;
+pub use owning_level::OwningLevel;
+
+mod parallax;
+pub use parallax::{LayerParallax, LdtkParallaxCamera};
+
+mod repeating_background;
+pub use repeating_background::{BackgroundTile, RepeatingBackground};
+
+mod stable_entity_id;
+pub use stable_entity_id::StableEntityId;
+
+mod tile_animation;
+pub use tile_animation::{TileAnimation, TileAnimationFrame};
+
+mod tile_data_table;
+pub use tile_data_table::TileDataTable;
+
+mod transform_override;
+pub(crate) use transform_override::apply_transform_override;
+pub use transform_override::TransformOverride;
+
+mod y_sort;
+pub use y_sort::YSort;
+
 pub use crate::ldtk::EntityInstance;
 use crate::{
-    ldtk::{LayerInstance, Type},
-    prelude::LdtkProject,
+    assets::LdtkProjectHandle,
+    ldtk::{LayerDefinition, LayerInstance, Type},
     utils::ldtk_grid_coords_to_grid_coords,
 };
 use bevy::prelude::*;
@@ -28,9 +90,14 @@ use bevy_ecs_tilemap::tiles::{TileBundle, TilePos};
 
 /// [Component] added to any `IntGrid` tile by default.
 ///
+/// With [IntGridCellStorage::Sparse], only cells with an [LdtkIntCell] registration get this
+/// component, and the values of the rest are stored in an [IntGridCells] component on their layer.
+///
 /// When loading levels, you can flesh out `IntGrid` entities in your own system by querying for
 /// `Added<IntGridCell>`.
 /// Or, you can hook into the entity's spawning process using [LdtkIntCell].
+///
+/// [IntGridCellStorage::Sparse]: crate::prelude::IntGridCellStorage::Sparse
 #[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Debug, Default, Hash, Component, Reflect)]
 #[reflect(Component)]
 pub struct IntGridCell {
@@ -52,6 +119,9 @@ pub struct IntGridCell {
 ///
 /// Implements [LdtkEntity], and can be added to an [LdtkEntity] bundle with the `#[worldly]` field
 /// attribute. See [LdtkEntity#worldly] for more details.
+/// It's also inserted on entities tagged with the [WorldlyTag] in LDtk, `"global"` by default.
+///
+/// [WorldlyTag]: crate::prelude::WorldlyTag
 #[derive(Clone, Eq, PartialEq, Debug, Default, Hash, Component, Reflect)]
 #[reflect(Component)]
 pub struct Worldly {
@@ -187,12 +257,31 @@ impl GridCoords {
     ) -> GridCoords {
         ldtk_grid_coords_to_grid_coords(entity_instance.grid, layer_instance.c_hei)
     }
+
+    /// Creates a [GridCoords] of the cell at the given anchor of an entity, which may be larger
+    /// than one cell.
+    ///
+    /// Used for the `#[grid_coords(...)]` attribute macro for `#[derive(LdtkEntity)]`.
+    /// See [LdtkEntity#grid_coords] for more info.
+    pub fn from_entity_info_anchored(
+        entity_instance: &EntityInstance,
+        layer_instance: &LayerInstance,
+        anchor: GridCoordsAnchor,
+    ) -> GridCoords {
+        GridCoordsRect::from_entity_info(entity_instance, layer_instance).anchor(
+            anchor,
+            GridCoords::from_entity_info(entity_instance, layer_instance),
+        )
+    }
 }
 
 /// [Component] for storing user-defined custom data for a paticular tile in an LDtk tileset
 /// definition.
 ///
-/// Automatically inserted on any tiles with metadata.
+/// Automatically inserted on any tiles with metadata, unless
+/// [LdtkSettings::tile_metadata_storage] stores it in a [TileDataTable] instead.
+///
+/// [LdtkSettings::tile_metadata_storage]: crate::prelude::LdtkSettings::tile_metadata_storage
 #[derive(Clone, Eq, PartialEq, Debug, Default, Hash, Component, Reflect)]
 #[reflect(Component)]
 pub struct TileMetadata {
@@ -202,7 +291,10 @@ pub struct TileMetadata {
 /// [Component] for storing user-defined, enum-based tags for a particular tile in an LDtk tileset
 /// definition.
 ///
-/// Automatically inserted on any tiles with enum tags.
+/// Automatically inserted on any tiles with enum tags, unless
+/// [LdtkSettings::tile_metadata_storage] stores them in a [TileDataTable] instead.
+///
+/// [LdtkSettings::tile_metadata_storage]: crate::prelude::LdtkSettings::tile_metadata_storage
 #[derive(Clone, Eq, PartialEq, Debug, Default, Hash, Component, Reflect)]
 #[reflect(Component)]
 pub struct TileEnumTags {
@@ -210,11 +302,47 @@ pub struct TileEnumTags {
     pub source_enum_uid: Option<i32>,
 }
 
+/// [Component] on tilemaps that were split off from their layer because their tiles have the
+/// given enum tag.
+///
+/// Only inserted for enum tags in [LdtkSettings::material_enum_tags] or
+/// [LdtkSettings::enum_tag_z_offsets].
+/// These tilemaps have the same [LayerMetadata] as the rest of their layer.
+///
+/// [LdtkSettings::material_enum_tags]: crate::prelude::LdtkSettings::material_enum_tags
+/// [LdtkSettings::enum_tag_z_offsets]: crate::prelude::LdtkSettings::enum_tag_z_offsets
+#[derive(Clone, Eq, PartialEq, Debug, Default, Hash, Component, Reflect)]
+#[reflect(Component)]
+pub struct MaterialEnumTag(pub String);
+
+/// [Component] on LDtk entities that spawned with flags recorded in [PersistentEntityState].
+///
+/// The flags are sorted.
+///
+/// [PersistentEntityState]: crate::prelude::PersistentEntityState
+#[derive(Clone, Eq, PartialEq, Debug, Default, Hash, Component, Reflect)]
+#[reflect(Component)]
+pub struct EntityStateFlags(pub Vec<String>);
+
+impl EntityStateFlags {
+    /// Returns whether the entity spawned with the given flag.
+    pub fn contains(&self, flag: &str) -> bool {
+        self.0.iter().any(|f| f == flag)
+    }
+}
+
 /// [Component] for storing some LDtk layer information on layer entities.
 ///
 /// Based on [LayerInstance], but without the fields with tile and entity information.
 ///
 /// Automatically inserted for IntGrid, AutoTile, and Tile layers.
+///
+/// These layer entities also have the layer's [TileStorage], for looking up tile entities by
+/// position.
+/// See [LayerTiles] for looking up tiles in layers that were spawned as several tilemaps.
+///
+/// [TileStorage]: bevy_ecs_tilemap::tiles::TileStorage
+/// [LayerTiles]: crate::layer_tiles::LayerTiles
 #[derive(Clone, PartialEq, Debug, Default, Component, Reflect)]
 #[reflect(Component)]
 pub struct LayerMetadata {
@@ -277,6 +405,24 @@ pub struct LayerMetadata {
 
     /// Layer instance visibility
     pub visible: bool,
+
+    /// User defined documentation of the layer definition, if any.
+    pub doc: Option<String>,
+
+    /// User defined UI color of the layer definition, if any.
+    pub ui_color: Option<Color>,
+}
+
+impl LayerMetadata {
+    /// Adds the user-defined data of the layer's [LayerDefinition].
+    ///
+    /// The `From<&LayerInstance>` implementation leaves them empty, since layer instances don't
+    /// store them.
+    pub fn with_definition(mut self, layer_definition: &LayerDefinition) -> Self {
+        self.doc = layer_definition.doc.clone();
+        self.ui_color = layer_definition.ui_color;
+        self
+    }
 }
 
 impl From<&LayerInstance> for LayerMetadata {
@@ -301,22 +447,67 @@ impl From<&LayerInstance> for LayerMetadata {
             px_offset_y: instance.px_offset_y,
             seed: instance.seed,
             visible: instance.visible,
+            ..Default::default()
         }
     }
 }
 
 /// [Component] that indicates that an LDtk level or world should respawn.
 ///
-/// Inserting this component on an entity with either [`Handle<LdtkProject>`] or [`LevelIid`]
+/// Inserting this component on an entity with either [`LdtkProjectHandle`] or [`LevelIid`]
 /// components will cause it to respawn.
 /// This can be used to implement a simple level-restart feature.
 /// Internally, this is used to support the entire level spawning process
 ///
-/// [`Handle<LdtkProject>`]: crate::assets::LdtkProject
+/// Descendants of a respawning level can be kept with [`PreserveOnRespawn`].
+///
+/// [`LdtkProjectHandle`]: crate::assets::LdtkProjectHandle
 #[derive(Copy, Clone, Eq, PartialEq, Debug, Default, Hash, Component, Reflect)]
 #[reflect(Component)]
 pub struct Respawn;
 
+/// [Component] that keeps an entity spawned when the level it's a descendant of respawns.
+///
+/// Normally, a [Respawn] on a level despawns all of its descendants before the level is spawned
+/// again.
+/// Descendants with this component are kept instead, along with their own descendants, and become
+/// direct children of the level without moving.
+/// This is useful for entities your own systems spawn under a level that should outlive a
+/// restart, like particles or corpses.
+///
+/// LDtk entities are spawned again when their level respawns, so marking them keeps a duplicate.
+/// Worlds despawn their levels entirely when they respawn, so this has no effect there.
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Default, Hash, Component, Reflect)]
+#[reflect(Component)]
+pub struct PreserveOnRespawn;
+
+/// [Component] marking the points that levels waiting to spawn are prioritized by.
+///
+/// When [SpawnBudget::LevelsPerFrame] spreads level spawning over several frames, the levels
+/// nearest to any entity with this component spawn first.
+/// Without any, the positions of active cameras are used instead.
+///
+/// [SpawnBudget::LevelsPerFrame]: crate::prelude::SpawnBudget::LevelsPerFrame
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Default, Hash, Component, Reflect)]
+#[reflect(Component)]
+pub struct SpawnFocus;
+
+/// [Component] marking levels whose spawning was put off by the [SpawnBudget] to a later frame.
+///
+/// [SpawnBudget]: crate::prelude::SpawnBudget
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Default, Hash, Component)]
+pub(crate) struct DeferredLevelSpawn;
+
+/// [Component] marking the placeholder visual of an LDtk entity.
+///
+/// Spawned as a child of LDtk entities when [EntityEditorVisuals::Placeholder] is enabled.
+/// Placeholders are despawned automatically if the LDtk entity's bundle provides its own visuals.
+///
+/// [EntityEditorVisuals::Placeholder]: crate::prelude::EntityEditorVisuals::Placeholder
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Default, Hash, Component, Reflect)]
+#[reflect(Component)]
+pub struct EditorVisualPlaceholder;
+
 #[derive(Copy, Clone, Debug, Default, Bundle)]
 pub(crate) struct TileGridBundle {
     pub tile_bundle: TileBundle,
@@ -351,7 +542,7 @@ pub(crate) struct EntityInstanceBundle {
 /// However, this behavior can be changed by marking them with the [`Worldly`] component.
 #[derive(Clone, Default, Bundle)]
 pub struct LdtkWorldBundle {
-    pub ldtk_handle: Handle<LdtkProject>,
+    pub ldtk_handle: LdtkProjectHandle,
     pub level_set: LevelSet,
     pub transform: Transform,
     pub global_transform: GlobalTransform,
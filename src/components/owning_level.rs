@@ -0,0 +1,44 @@
+use bevy::prelude::*;
+
+use crate::components::LevelIid;
+
+/// [Component] on every layer, tile, and LDtk entity spawned by the plugin, storing the
+/// [LevelIid] of the level that spawned it.
+///
+/// Lets systems filter by level with a component match, rather than walking up the hierarchy to
+/// the level entity.
+/// This is handy for per-room logic when several levels are spawned at once, like in GridVania
+/// worlds that load level neighbors.
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_ecs_ldtk::prelude::*;
+/// fn count_room_enemies(
+///     enemies: Query<&OwningLevel, With<EntityIid>>,
+///     level_selection: Res<LevelSelection>,
+/// ) {
+///     let LevelSelection::Iid(current_level) = level_selection.as_ref() else {
+///         return;
+///     };
+///
+///     let count = enemies
+///         .iter()
+///         .filter(|owning_level| owning_level.level_iid() == current_level)
+///         .count();
+///     info!("{count} enemies in this room");
+/// }
+/// ```
+///
+/// [Worldly] entities don't belong to their level anymore, so this component is removed from
+/// them when they're adopted by their world.
+///
+/// [Worldly]: crate::prelude::Worldly
+#[derive(Clone, Eq, PartialEq, Debug, Default, Hash, Component, Reflect)]
+#[reflect(Component, Default, Debug)]
+pub struct OwningLevel(pub LevelIid);
+
+impl OwningLevel {
+    /// The [LevelIid] of the level that spawned this entity.
+    pub fn level_iid(&self) -> &LevelIid {
+        &self.0
+    }
+}
@@ -0,0 +1,152 @@
+use bevy::prelude::*;
+
+use crate::ldtk::LayerDefinition;
+
+/// [`Component`] marking the camera that drives LDtk layer parallax.
+///
+/// Layers with a nonzero parallax factor in LDtk will be offset (and optionally scaled) relative
+/// to this camera's position, mimicking the parallax preview of the editor.
+/// If no camera has this component, layers keep their original transforms.
+///
+/// The camera's [`Transform`] is used rather than its [`GlobalTransform`], so that parallax
+/// doesn't lag behind camera movement by a frame.
+/// So, this component is intended for cameras that don't have a parent.
+///
+/// [`Component`]: https://docs.rs/bevy/latest/bevy/ecs/component/trait.Component.html
+/// [`Transform`]: https://docs.rs/bevy/latest/bevy/prelude/struct.Transform.html
+/// [`GlobalTransform`]: https://docs.rs/bevy/latest/bevy/prelude/struct.GlobalTransform.html
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Default, Hash, Component, Reflect)]
+#[reflect(Component)]
+pub struct LdtkParallaxCamera;
+
+/// [`Component`] storing the parallax settings of a layer entity.
+///
+/// Automatically inserted on layers whose definition has a nonzero parallax factor.
+///
+/// [`Component`]: https://docs.rs/bevy/latest/bevy/ecs/component/trait.Component.html
+#[derive(Copy, Clone, PartialEq, Debug, Default, Component, Reflect)]
+#[reflect(Component)]
+pub struct LayerParallax {
+    /// Parallax factors of the layer, from -1 to 1.
+    ///
+    /// Positive factors make the layer appear further away, negative factors make it appear
+    /// closer.
+    pub factor: Vec2,
+    /// Whether or not the layer is also scaled according to its parallax factor.
+    pub scaling: bool,
+    /// Center of the level relative to the level entity.
+    ///
+    /// Parallax offsets are zero when the camera is centered on the level, like in LDtk.
+    pub level_center: Vec2,
+    /// Translation of the layer relative to its level, without parallax applied.
+    pub base_translation: Vec3,
+}
+
+impl LayerParallax {
+    /// Creates a [`LayerParallax`] from the parallax settings of a layer definition.
+    ///
+    /// Returns [`None`] if the layer has no parallax factor.
+    pub fn from_layer_definition(
+        layer_definition: &LayerDefinition,
+        level_size: Vec2,
+        base_translation: Vec3,
+    ) -> Option<LayerParallax> {
+        let factor = Vec2::new(
+            layer_definition.parallax_factor_x,
+            layer_definition.parallax_factor_y,
+        );
+
+        if factor == Vec2::ZERO {
+            return None;
+        }
+
+        Some(LayerParallax {
+            factor,
+            scaling: layer_definition.parallax_scaling,
+            level_center: level_size / 2.,
+            base_translation,
+        })
+    }
+
+    /// Calculates the transform of the layer relative to its level, given the camera's position
+    /// relative to the level.
+    pub fn layer_transform(&self, camera_position: Vec2) -> Transform {
+        let camera_offset = camera_position - self.level_center;
+
+        let scale = if self.scaling {
+            Vec2::ONE - self.factor
+        } else {
+            Vec2::ONE
+        };
+
+        // Scale around the level center rather than the layer origin
+        let base = self.base_translation.truncate();
+        let scaled = self.level_center + (base - self.level_center) * scale;
+
+        let translation = scaled + camera_offset * self.factor;
+
+        Transform::from_translation(translation.extend(self.base_translation.z))
+            .with_scale(scale.extend(1.))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn layer_definition(factor: Vec2, scaling: bool) -> LayerDefinition {
+        LayerDefinition {
+            parallax_factor_x: factor.x,
+            parallax_factor_y: factor.y,
+            parallax_scaling: scaling,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn layers_without_parallax_are_ignored() {
+        assert_eq!(
+            LayerParallax::from_layer_definition(
+                &layer_definition(Vec2::ZERO, true),
+                Vec2::splat(100.),
+                Vec3::ZERO
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn parallax_offset_is_relative_to_level_center() {
+        let parallax = LayerParallax::from_layer_definition(
+            &layer_definition(Vec2::new(0.5, 0.25), false),
+            Vec2::new(200., 100.),
+            Vec3::new(0., 0., 3.),
+        )
+        .unwrap();
+
+        assert_eq!(
+            parallax.layer_transform(Vec2::new(100., 50.)),
+            Transform::from_xyz(0., 0., 3.)
+        );
+
+        assert_eq!(
+            parallax.layer_transform(Vec2::new(140., 90.)),
+            Transform::from_xyz(20., 10., 3.)
+        );
+    }
+
+    #[test]
+    fn parallax_scaling_scales_around_level_center() {
+        let parallax = LayerParallax::from_layer_definition(
+            &layer_definition(Vec2::splat(0.5), true),
+            Vec2::new(200., 100.),
+            Vec3::ZERO,
+        )
+        .unwrap();
+
+        assert_eq!(
+            parallax.layer_transform(Vec2::new(100., 50.)),
+            Transform::from_xyz(50., 25., 0.).with_scale(Vec3::new(0.5, 0.5, 1.))
+        );
+    }
+}
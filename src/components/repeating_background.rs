@@ -0,0 +1,110 @@
+use bevy::prelude::*;
+
+use crate::resources::BackgroundRepeat;
+
+/// [`Component`] on level background image entities that repeat to fill the view of the
+/// [`LdtkParallaxCamera`].
+///
+/// Inserted when [`BackgroundImageSettings::repeat`] isn't [`BackgroundRepeat::None`].
+/// The repetitions are spawned as children of this entity, with a [`BackgroundTile`] component.
+///
+/// [`Component`]: https://docs.rs/bevy/latest/bevy/ecs/component/trait.Component.html
+/// [`LdtkParallaxCamera`]: crate::prelude::LdtkParallaxCamera
+/// [`BackgroundImageSettings::repeat`]: crate::prelude::BackgroundImageSettings::repeat
+#[derive(Clone, PartialEq, Debug, Component)]
+pub struct RepeatingBackground {
+    pub repeat: BackgroundRepeat,
+    /// Center of the image where it is placed in LDtk, relative to this entity.
+    pub origin: Vec2,
+    /// Size of a single repetition of the image, after scaling.
+    pub size: Vec2,
+    pub(crate) texture_atlas: Handle<TextureAtlas>,
+    pub(crate) scale: Vec2,
+}
+
+impl RepeatingBackground {
+    /// Returns the centers of the repetitions needed to cover the given area, relative to this
+    /// entity.
+    pub fn tile_centers(&self, view: Rect) -> Vec<Vec2> {
+        let axes = self.repeat.axes();
+
+        let xs = repetitions(axes.x, self.origin.x, self.size.x, view.min.x, view.max.x);
+        let ys = repetitions(axes.y, self.origin.y, self.size.y, view.min.y, view.max.y);
+
+        ys.iter()
+            .flat_map(|y| xs.iter().map(move |x| Vec2::new(*x, *y)))
+            .collect()
+    }
+
+    /// Creates the bundle of a single repetition of the image, centered at the given position.
+    pub(crate) fn tile_bundle(&self, center: Vec2) -> (SpriteSheetBundle, BackgroundTile) {
+        (
+            SpriteSheetBundle {
+                texture_atlas: self.texture_atlas.clone(),
+                transform: Transform::from_translation(center.extend(0.))
+                    .with_scale(self.scale.extend(1.)),
+                ..default()
+            },
+            BackgroundTile,
+        )
+    }
+}
+
+/// Returns the centers of the repetitions covering `min..max` along one axis.
+fn repetitions(repeat: bool, origin: f32, size: f32, min: f32, max: f32) -> Vec<f32> {
+    if !repeat || size <= 0. || min > max {
+        return vec![origin];
+    }
+
+    let first = ((min - origin) / size + 0.5).floor() as i32;
+    let last = ((max - origin) / size + 0.5).floor() as i32;
+
+    (first..=last).map(|n| origin + n as f32 * size).collect()
+}
+
+/// [`Component`] marking a single repetition of a [`RepeatingBackground`].
+///
+/// [`Component`]: https://docs.rs/bevy/latest/bevy/ecs/component/trait.Component.html
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Default, Hash, Component)]
+pub struct BackgroundTile;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn repeating_background(repeat: BackgroundRepeat) -> RepeatingBackground {
+        RepeatingBackground {
+            repeat,
+            origin: Vec2::new(50., 50.),
+            size: Vec2::new(100., 100.),
+            texture_atlas: Handle::default(),
+            scale: Vec2::ONE,
+        }
+    }
+
+    #[test]
+    fn repetitions_cover_the_view() {
+        let view = Rect::new(-60., 40., 190., 60.);
+
+        assert_eq!(
+            repeating_background(BackgroundRepeat::X).tile_centers(view),
+            vec![
+                Vec2::new(-50., 50.),
+                Vec2::new(50., 50.),
+                Vec2::new(150., 50.),
+            ]
+        );
+
+        assert_eq!(
+            repeating_background(BackgroundRepeat::None).tile_centers(view),
+            vec![Vec2::new(50., 50.)]
+        );
+
+        assert_eq!(
+            repeating_background(BackgroundRepeat::XY)
+                .tile_centers(Rect::new(-10., -10., 90., 90.))
+                .len(),
+            4
+        );
+    }
+}
@@ -0,0 +1,47 @@
+use bevy::prelude::*;
+
+/// [`Component`] storing a 64-bit id derived from an LDtk entity's project, level and entity iids.
+///
+/// Only inserted when [`DeterministicSpawning::Enabled`] is used.
+/// The id is the same on every client and across runs, so rollback and replay systems can use it
+/// to match entities that were spawned separately.
+///
+/// [`Component`]: https://docs.rs/bevy/latest/bevy/ecs/component/trait.Component.html
+/// [`DeterministicSpawning::Enabled`]: crate::prelude::DeterministicSpawning::Enabled
+#[derive(Copy, Clone, Debug, Default, Hash, Eq, PartialEq, Ord, PartialOrd, Component, Reflect)]
+#[reflect(Component, Default, Debug)]
+pub struct StableEntityId(pub u64);
+
+impl StableEntityId {
+    /// Derives the id of an entity from its iids.
+    ///
+    /// Uses FNV-1a, so the id doesn't depend on the platform or the Rust version.
+    pub fn new(project_iid: &str, level_iid: &str, entity_iid: &str) -> Self {
+        let mut hash: u64 = 0xcbf29ce484222325;
+
+        // Separate the iids so that moving characters between them changes the id
+        for byte in [project_iid, level_iid, entity_iid]
+            .into_iter()
+            .flat_map(|iid| iid.bytes().chain([0xff]))
+        {
+            hash ^= byte as u64;
+            hash = hash.wrapping_mul(0x100000001b3);
+        }
+
+        StableEntityId(hash)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ids_are_stable_and_distinct() {
+        let id = StableEntityId::new("project", "level", "entity");
+
+        assert_eq!(id, StableEntityId(0xaa2b0b3bdab5edf0));
+        assert_ne!(id, StableEntityId::new("project", "levele", "ntity"));
+        assert_ne!(id, StableEntityId::new("other", "level", "entity"));
+    }
+}
@@ -0,0 +1,224 @@
+use bevy::prelude::*;
+use bevy_ecs_tilemap::tiles::AnimatedTile;
+use serde::Deserialize;
+
+/// A single frame of a [`TileAnimation`].
+#[derive(Copy, Clone, PartialEq, Debug, Default, Reflect)]
+pub struct TileAnimationFrame {
+    /// Tile id in the tileset to display for this frame.
+    pub tile_id: u32,
+    /// How long this frame is displayed, in seconds.
+    pub duration: f32,
+}
+
+/// [`Component`] describing a tile animation authored in the tileset's custom data.
+///
+/// The plugin reads the custom data of every tile in a tileset definition, and recognizes the
+/// following convention:
+/// ```json
+/// { "animation": { "frames": [12, 13, 14, 15], "durations": 0.15 } }
+/// ```
+/// `frames` lists the tile ids of the animation in order.
+/// `durations` is either a single duration in seconds used for every frame, or a list with one
+/// duration per frame.
+///
+/// Animations whose frames are consecutive tile ids with a uniform duration are spawned with
+/// `bevy_ecs_tilemap`'s [`AnimatedTile`], which is animated on the GPU.
+/// Every other animation is spawned with this component, and animated by the plugin.
+///
+/// [`Component`]: https://docs.rs/bevy/latest/bevy/ecs/component/trait.Component.html
+#[derive(Clone, PartialEq, Debug, Default, Component, Reflect)]
+#[reflect(Component)]
+pub struct TileAnimation {
+    pub frames: Vec<TileAnimationFrame>,
+    /// Index of the frame currently displayed.
+    pub current_frame: usize,
+    /// Time spent on the current frame, in seconds.
+    pub elapsed: f32,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum FrameDurations {
+    Uniform(f32),
+    PerFrame(Vec<f32>),
+}
+
+#[derive(Deserialize)]
+struct TileAnimationData {
+    frames: Vec<u32>,
+    durations: FrameDurations,
+}
+
+#[derive(Deserialize)]
+struct TileCustomData {
+    animation: TileAnimationData,
+}
+
+impl TileAnimation {
+    /// Parses a tile's custom data according to the [`TileAnimation`] convention.
+    ///
+    /// Returns [`None`] if the data doesn't describe an animation, or if the number of durations
+    /// doesn't match the number of frames.
+    pub fn from_custom_data(data: &str) -> Option<TileAnimation> {
+        let TileCustomData {
+            animation: TileAnimationData { frames, durations },
+        } = serde_json::from_str(data).ok()?;
+
+        if frames.is_empty() {
+            return None;
+        }
+
+        let durations = match durations {
+            FrameDurations::Uniform(duration) => vec![duration; frames.len()],
+            FrameDurations::PerFrame(durations) if durations.len() == frames.len() => durations,
+            FrameDurations::PerFrame(_) => return None,
+        };
+
+        let frames = frames
+            .into_iter()
+            .zip(durations)
+            .map(|(tile_id, duration)| TileAnimationFrame { tile_id, duration })
+            .collect();
+
+        Some(TileAnimation {
+            frames,
+            ..default()
+        })
+    }
+
+    /// Converts this animation to a `bevy_ecs_tilemap` [`AnimatedTile`], if possible.
+    ///
+    /// This is only possible if the frames are consecutive tile ids with a uniform, positive
+    /// duration.
+    pub fn as_animated_tile(&self) -> Option<AnimatedTile> {
+        let first = self.frames.first()?;
+
+        let consecutive_and_uniform = self.frames.iter().enumerate().all(|(i, frame)| {
+            frame.tile_id == first.tile_id + i as u32 && frame.duration == first.duration
+        });
+
+        if consecutive_and_uniform && first.duration > 0. {
+            Some(AnimatedTile {
+                start: first.tile_id,
+                end: first.tile_id + self.frames.len() as u32,
+                speed: 1. / first.duration,
+            })
+        } else {
+            None
+        }
+    }
+
+    /// Advances the animation by `delta` seconds, returning the tile id that should be displayed.
+    pub fn tick(&mut self, delta: f32) -> Option<u32> {
+        if self.frames.is_empty() {
+            return None;
+        }
+
+        self.elapsed += delta;
+
+        // Guard against zero-duration frames looping forever
+        for _ in 0..self.frames.len() {
+            let duration = self.frames[self.current_frame].duration;
+            if self.elapsed < duration {
+                break;
+            }
+
+            self.elapsed -= duration;
+            self.current_frame = (self.current_frame + 1) % self.frames.len();
+        }
+
+        Some(self.frames[self.current_frame].tile_id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_uniform_and_per_frame_durations() {
+        let uniform =
+            TileAnimation::from_custom_data(r#"{"animation":{"frames":[4,5],"durations":0.5}}"#)
+                .unwrap();
+
+        assert_eq!(
+            uniform.frames,
+            vec![
+                TileAnimationFrame {
+                    tile_id: 4,
+                    duration: 0.5
+                },
+                TileAnimationFrame {
+                    tile_id: 5,
+                    duration: 0.5
+                },
+            ]
+        );
+
+        let per_frame = TileAnimation::from_custom_data(
+            r#"{"animation":{"frames":[9,2],"durations":[0.1,0.3]}}"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            per_frame.frames,
+            vec![
+                TileAnimationFrame {
+                    tile_id: 9,
+                    duration: 0.1
+                },
+                TileAnimationFrame {
+                    tile_id: 2,
+                    duration: 0.3
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn rejects_data_without_animation() {
+        assert_eq!(TileAnimation::from_custom_data("not json"), None);
+        assert_eq!(TileAnimation::from_custom_data(r#"{"loot":3}"#), None);
+        assert_eq!(
+            TileAnimation::from_custom_data(r#"{"animation":{"frames":[],"durations":1}}"#),
+            None
+        );
+        assert_eq!(
+            TileAnimation::from_custom_data(r#"{"animation":{"frames":[1,2],"durations":[0.1]}}"#),
+            None
+        );
+    }
+
+    #[test]
+    fn consecutive_uniform_animations_become_animated_tiles() {
+        let consecutive =
+            TileAnimation::from_custom_data(r#"{"animation":{"frames":[3,4,5],"durations":0.25}}"#)
+                .unwrap();
+
+        let animated_tile = consecutive.as_animated_tile().unwrap();
+        assert_eq!(animated_tile.start, 3);
+        assert_eq!(animated_tile.end, 6);
+        assert_eq!(animated_tile.speed, 4.);
+
+        let out_of_order =
+            TileAnimation::from_custom_data(r#"{"animation":{"frames":[3,5,4],"durations":0.25}}"#)
+                .unwrap();
+
+        assert!(out_of_order.as_animated_tile().is_none());
+    }
+
+    #[test]
+    fn tick_advances_through_frames() {
+        let mut animation = TileAnimation::from_custom_data(
+            r#"{"animation":{"frames":[7,8,9],"durations":[1,2,1]}}"#,
+        )
+        .unwrap();
+
+        assert_eq!(animation.tick(0.5), Some(7));
+        assert_eq!(animation.tick(0.5), Some(8));
+        assert_eq!(animation.tick(1.5), Some(8));
+        assert_eq!(animation.tick(0.5), Some(9));
+        assert_eq!(animation.tick(1.), Some(7));
+    }
+}
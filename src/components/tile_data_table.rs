@@ -0,0 +1,28 @@
+use bevy::prelude::*;
+use std::collections::HashMap;
+
+use super::{GridCoords, TileEnumTags, TileMetadata};
+
+/// [Component] on tilemaps storing the [TileMetadata] and [TileEnumTags] of their tiles, by
+/// position.
+///
+/// Only inserted when [LdtkSettings::tile_metadata_storage] is
+/// [TileMetadataStorage::LayerTables], instead of inserting those components on every tile.
+/// Use [LayerTileData] to look up tile data regardless of how it's stored.
+///
+/// [LdtkSettings::tile_metadata_storage]: crate::prelude::LdtkSettings::tile_metadata_storage
+/// [TileMetadataStorage::LayerTables]: crate::prelude::TileMetadataStorage::LayerTables
+/// [LayerTileData]: crate::prelude::LayerTileData
+#[derive(Clone, Eq, PartialEq, Debug, Default, Component, Reflect)]
+#[reflect(Component)]
+pub struct TileDataTable {
+    pub metadata: HashMap<GridCoords, TileMetadata>,
+    pub enum_tags: HashMap<GridCoords, TileEnumTags>,
+}
+
+impl TileDataTable {
+    /// Returns true if the table has no data for any tile.
+    pub fn is_empty(&self) -> bool {
+        self.metadata.is_empty() && self.enum_tags.is_empty()
+    }
+}
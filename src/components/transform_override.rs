@@ -0,0 +1,125 @@
+use bevy::prelude::*;
+
+use crate::{
+    ldtk::{ldtk_fields::LdtkFields, EntityInstance, FieldValue, LayerInstance},
+    utils::ldtk_grid_coords_to_translation,
+};
+
+/// [Component] that replaces the [Transform] the plugin calculates for an LDtk entity from its
+/// position.
+///
+/// The plugin inserts a [SpatialBundle] on LDtk entities after their [LdtkEntity] bundle, so a
+/// [Transform] in the bundle itself is overwritten.
+/// Adding this component to the bundle instead replaces the calculated [Transform] with
+/// `transform`, then the component is removed.
+/// The z of the calculated [Transform] is kept, so [EntityZIndex] still applies.
+///
+/// Can be added to an [LdtkEntity] bundle with the `#[transform_from_point("field")]` attribute,
+/// which places the entity at a `Point` field rather than its own position.
+/// See [LdtkEntity#transform_from_point] for attribute macro usage.
+///
+/// [LdtkEntity]: crate::prelude::LdtkEntity
+/// [LdtkEntity#transform_from_point]: crate::prelude::LdtkEntity#transform_from_point
+/// [EntityZIndex]: crate::prelude::EntityZIndex
+#[derive(Copy, Clone, PartialEq, Debug, Default, Component, Reflect)]
+#[reflect(Component)]
+pub struct TransformOverride {
+    /// The [Transform] of the entity relative to its layer, or [None] to keep the calculated one.
+    pub transform: Option<Transform>,
+}
+
+impl TransformOverride {
+    /// Places the entity in the center of the cell of the given `Point` field.
+    ///
+    /// Points are stored in the grid of the entity's layer, so the resulting translation is
+    /// relative to the layer, like the entity's own.
+    /// Layer entities are translated by the layer's offsets, so the entity's [GlobalTransform]
+    /// includes them as well.
+    ///
+    /// If the field doesn't exist, isn't a `Point`, or is null, the calculated [Transform] is kept.
+    pub fn from_point_field(
+        entity_instance: &EntityInstance,
+        layer_instance: &LayerInstance,
+        identifier: &str,
+    ) -> TransformOverride {
+        let transform = match entity_instance.get_field(identifier) {
+            Ok(FieldValue::Point(Some(point))) => Some(Transform::from_translation(
+                ldtk_grid_coords_to_translation(
+                    *point,
+                    layer_instance.c_hei,
+                    IVec2::splat(layer_instance.grid_size),
+                )
+                .extend(0.),
+            )),
+            _ => None,
+        };
+
+        TransformOverride { transform }
+    }
+}
+
+/// Applies and removes the [TransformOverride] of an entity, if it has one.
+pub(crate) fn apply_transform_override(entity: Entity, world: &mut World) {
+    let Some(mut entity_mut) = world.get_entity_mut(entity) else {
+        return;
+    };
+
+    let Some(TransformOverride {
+        transform: Some(transform_override),
+    }) = entity_mut.take::<TransformOverride>()
+    else {
+        return;
+    };
+
+    if let Some(mut transform) = entity_mut.get_mut::<Transform>() {
+        let z = transform.translation.z;
+        *transform = transform_override;
+        transform.translation.z = z;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ldtk::ldtk_fields::field_instance_from_value;
+
+    #[test]
+    fn overrides_replace_transforms_from_point_fields() {
+        let entity_instance = EntityInstance {
+            field_instances: vec![field_instance_from_value(
+                "spawn_at",
+                FieldValue::Point(Some(IVec2::new(2, 0))),
+            )],
+            ..Default::default()
+        };
+        let layer_instance = LayerInstance {
+            c_hei: 4,
+            grid_size: 16,
+            ..Default::default()
+        };
+
+        let transform_override =
+            TransformOverride::from_point_field(&entity_instance, &layer_instance, "spawn_at");
+        assert_eq!(
+            transform_override.transform,
+            Some(Transform::from_xyz(40., 56., 0.))
+        );
+        assert_eq!(
+            TransformOverride::from_point_field(&entity_instance, &layer_instance, "missing"),
+            TransformOverride::default()
+        );
+
+        let mut world = World::new();
+        let entity = world
+            .spawn((Transform::from_xyz(8., 8., 3.), transform_override))
+            .id();
+
+        apply_transform_override(entity, &mut world);
+
+        assert_eq!(
+            world.get::<Transform>(entity),
+            Some(&Transform::from_xyz(40., 56., 3.))
+        );
+        assert!(world.get::<TransformOverride>(entity).is_none());
+    }
+}
@@ -0,0 +1,47 @@
+use bevy::prelude::*;
+
+/// [Component] that derives the z of an LDtk entity from its y coordinate, also used as the
+/// [LdtkSettings] option that y-sorts a layer's contents.
+///
+/// Useful for top-down games, where things lower on the screen should be drawn in front.
+/// The resulting z is `offset - y * scale`, with y relative to the level, and is shared by every
+/// y-sorted layer of the level.
+/// So, entities and split tile rows of layers with the same offset and scale interleave with each
+/// other, and the offset decides where that band sits between the other layers.
+/// Keep the scale small enough that the band doesn't reach layers that should stay below or above
+/// it.
+///
+/// On Entity layers, this is inserted on every LDtk entity, and their z is kept up to date as they
+/// move.
+///
+/// [LdtkSettings]: crate::prelude::LdtkSettings
+#[derive(Copy, Clone, PartialEq, Debug, Component, Reflect)]
+#[reflect(Component, Default, Debug)]
+pub struct YSort {
+    /// How much z changes per pixel of y.
+    pub scale: f32,
+    /// Z added to every y-sorted item.
+    pub offset: f32,
+    /// On Tile and AutoLayer layers, spawns each row of tiles as its own tilemap so that rows are
+    /// y-sorted as well.
+    ///
+    /// This is useful for tall tiles like trees, but increases the number of tilemaps.
+    pub split_tiles: bool,
+}
+
+impl Default for YSort {
+    fn default() -> Self {
+        YSort {
+            scale: 0.0001,
+            offset: 0.,
+            split_tiles: false,
+        }
+    }
+}
+
+impl YSort {
+    /// Calculates the z for a given y coordinate, relative to the level.
+    pub fn z(&self, y: f32) -> f32 {
+        self.offset - y * self.scale
+    }
+}
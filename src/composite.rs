@@ -0,0 +1,377 @@
+//! Functions for compositing a level's tile layers into a single [`Image`].
+//!
+//! The resulting image has one pixel per LDtk pixel, and can be used as a minimap or world-map
+//! texture that matches the spawned level.
+//! Images can be generated on demand with [`compose_level`], or automatically for every level as
+//! it spawns by inserting the [`LevelComposites`] resource.
+//! ```no_run
+//! # use bevy::prelude::*;
+//! # use bevy_ecs_ldtk::composite::LevelComposites;
+//! App::new().init_resource::<LevelComposites>();
+//! ```
+//!
+//! Only IntGrid, AutoTile, and Tile layers are drawn, along with the level's background color.
+//! Entities and background images are not included.
+//!
+//! [`Image`]: https://docs.rs/bevy/latest/bevy/render/texture/struct.Image.html
+
+use crate::{
+    assets::LdtkProject,
+    ldtk::{loaded_level::LoadedLevel, LayerInstance, TileInstance, Type},
+};
+use bevy::{
+    prelude::*,
+    render::render_resource::{Extent3d, TextureDimension, TextureFormat},
+};
+use std::collections::{HashMap, HashSet};
+use thiserror::Error;
+
+/// Options for compositing a level with [`compose_level`].
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct CompositeOptions {
+    /// Identifiers of the layers to draw.
+    ///
+    /// If [`None`], all IntGrid, AutoTile, and Tile layers are drawn.
+    pub layers: Option<HashSet<String>>,
+    /// Whether or not the level's background color is drawn.
+    pub background: bool,
+    /// Whether or not IntGrid layers without a tileset are drawn using their value colors.
+    pub int_grid_colors: bool,
+}
+
+impl Default for CompositeOptions {
+    fn default() -> Self {
+        CompositeOptions {
+            layers: None,
+            background: true,
+            int_grid_colors: true,
+        }
+    }
+}
+
+/// Errors that can occur when compositing a level.
+#[derive(Debug, Error)]
+pub enum CompositeError {
+    /// A tileset used by the level hasn't finished loading into the image assets store.
+    #[error("tileset {0} not loaded into the image assets store")]
+    TilesetNotLoaded(i32),
+    /// A tileset image has a texture format other than 8-bit RGBA.
+    #[error("tileset {0} has unsupported texture format {1:?}, expected 8-bit RGBA")]
+    UnsupportedFormat(i32, TextureFormat),
+}
+
+/// [`Resource`] that, when present, makes the plugin composite every level as it spawns.
+///
+/// Levels are composited according to `options`, and the resulting images are stored in `images`
+/// by level iid.
+/// Respawning a level replaces its image.
+///
+/// [`Resource`]: https://docs.rs/bevy/latest/bevy/ecs/system/trait.Resource.html
+#[derive(Clone, Debug, Default, Resource)]
+pub struct LevelComposites {
+    pub options: CompositeOptions,
+    pub images: HashMap<String, Handle<Image>>,
+}
+
+/// RGBA8 pixel buffer that tiles are drawn onto.
+struct Canvas {
+    width: i32,
+    height: i32,
+    data: Vec<u8>,
+}
+
+impl Canvas {
+    fn new(width: i32, height: i32) -> Canvas {
+        Canvas {
+            width,
+            height,
+            data: vec![0; (width.max(0) * height.max(0) * 4) as usize],
+        }
+    }
+
+    /// Alpha-blends a pixel onto the canvas, ignoring pixels outside of it.
+    fn blend(&mut self, x: i32, y: i32, src: [u8; 4], opacity: f32) {
+        if x < 0 || y < 0 || x >= self.width || y >= self.height {
+            return;
+        }
+
+        let i = ((y * self.width + x) * 4) as usize;
+        let dst = &mut self.data[i..i + 4];
+
+        let src_a = src[3] as f32 / 255. * opacity;
+        let dst_a = dst[3] as f32 / 255.;
+        let out_a = src_a + dst_a * (1. - src_a);
+
+        if out_a <= 0. {
+            return;
+        }
+
+        for c in 0..3 {
+            let blended = (src[c] as f32 * src_a + dst[c] as f32 * dst_a * (1. - src_a)) / out_a;
+            dst[c] = blended.round() as u8;
+        }
+        dst[3] = (out_a * 255.).round() as u8;
+    }
+
+    fn fill_rect(&mut self, min: IVec2, size: IVec2, color: [u8; 4], opacity: f32) {
+        for y in min.y..min.y + size.y {
+            for x in min.x..min.x + size.x {
+                self.blend(x, y, color, opacity);
+            }
+        }
+    }
+
+    /// Draws a tile from a tileset, respecting its flip bits and alpha.
+    fn draw_tile(
+        &mut self,
+        tileset: &Canvas,
+        tile: &TileInstance,
+        tile_size: i32,
+        offset: IVec2,
+        opacity: f32,
+    ) {
+        let flip_x = tile.f & 1 != 0;
+        let flip_y = tile.f & 2 != 0;
+
+        for dy in 0..tile_size {
+            for dx in 0..tile_size {
+                let sx = tile.src.x + if flip_x { tile_size - 1 - dx } else { dx };
+                let sy = tile.src.y + if flip_y { tile_size - 1 - dy } else { dy };
+
+                if let Some(pixel) = tileset.pixel(sx, sy) {
+                    let destination = tile.px + offset + IVec2::new(dx, dy);
+                    self.blend(destination.x, destination.y, pixel, opacity * tile.a);
+                }
+            }
+        }
+    }
+
+    fn pixel(&self, x: i32, y: i32) -> Option<[u8; 4]> {
+        if x < 0 || y < 0 || x >= self.width || y >= self.height {
+            return None;
+        }
+
+        let i = ((y * self.width + x) * 4) as usize;
+        Some([
+            self.data[i],
+            self.data[i + 1],
+            self.data[i + 2],
+            self.data[i + 3],
+        ])
+    }
+}
+
+fn tileset_canvas(
+    tileset_uid: i32,
+    ldtk_project: &LdtkProject,
+    images: &Assets<Image>,
+) -> Result<Canvas, CompositeError> {
+    let image = ldtk_project
+        .tileset_map()
+        .get(&tileset_uid)
+        .and_then(|handle| images.get(handle))
+        .ok_or(CompositeError::TilesetNotLoaded(tileset_uid))?;
+
+    match image.texture_descriptor.format {
+        TextureFormat::Rgba8UnormSrgb | TextureFormat::Rgba8Unorm => Ok(Canvas {
+            width: image.texture_descriptor.size.width as i32,
+            height: image.texture_descriptor.size.height as i32,
+            data: image.data.clone(),
+        }),
+        format => Err(CompositeError::UnsupportedFormat(tileset_uid, format)),
+    }
+}
+
+fn draw_layer(
+    canvas: &mut Canvas,
+    layer_instance: &LayerInstance,
+    ldtk_project: &LdtkProject,
+    images: &Assets<Image>,
+    tilesets: &mut HashMap<i32, Canvas>,
+    options: &CompositeOptions,
+) -> Result<(), CompositeError> {
+    let offset = IVec2::new(
+        layer_instance.px_total_offset_x,
+        layer_instance.px_total_offset_y,
+    );
+
+    match layer_instance.tileset_def_uid {
+        Some(tileset_uid) => {
+            if !tilesets.contains_key(&tileset_uid) {
+                let tileset = tileset_canvas(tileset_uid, ldtk_project, images)?;
+                tilesets.insert(tileset_uid, tileset);
+            }
+            let tileset = &tilesets[&tileset_uid];
+
+            let tile_size = ldtk_project
+                .json_data()
+                .defs
+                .tilesets
+                .iter()
+                .find(|definition| definition.uid == tileset_uid)
+                .map(|definition| definition.tile_grid_size)
+                .unwrap_or(layer_instance.grid_size);
+
+            // Same order as the spawned tilemaps: auto-layer tiles are drawn above grid tiles
+            for tile in layer_instance
+                .grid_tiles
+                .iter()
+                .chain(layer_instance.auto_layer_tiles.iter())
+            {
+                canvas.draw_tile(tileset, tile, tile_size, offset, layer_instance.opacity);
+            }
+        }
+        None if layer_instance.layer_instance_type == Type::IntGrid && options.int_grid_colors => {
+            let Some(layer_definition) = ldtk_project
+                .json_data()
+                .defs
+                .layers
+                .iter()
+                .find(|definition| definition.uid == layer_instance.layer_def_uid)
+            else {
+                return Ok(());
+            };
+
+            for (i, value) in layer_instance.int_grid_csv.iter().enumerate() {
+                let Some(value_definition) = layer_definition
+                    .int_grid_values
+                    .iter()
+                    .find(|definition| definition.value == *value)
+                else {
+                    continue;
+                };
+
+                let cell = IVec2::new(
+                    i as i32 % layer_instance.c_wid,
+                    i as i32 / layer_instance.c_wid,
+                );
+
+                canvas.fill_rect(
+                    cell * layer_instance.grid_size + offset,
+                    IVec2::splat(layer_instance.grid_size),
+                    value_definition.color.as_rgba_u8(),
+                    layer_instance.opacity,
+                );
+            }
+        }
+        None => (),
+    }
+
+    Ok(())
+}
+
+/// Composites a level's tile layers into a new [`Image`], with one pixel per LDtk pixel.
+///
+/// The tilesets used by the level must be loaded into `images`, and must use an 8-bit RGBA
+/// texture format, which is the case for PNG tilesets.
+///
+/// [`Image`]: https://docs.rs/bevy/latest/bevy/render/texture/struct.Image.html
+pub fn compose_level(
+    level: &LoadedLevel,
+    ldtk_project: &LdtkProject,
+    images: &Assets<Image>,
+    options: &CompositeOptions,
+) -> Result<Image, CompositeError> {
+    let mut canvas = Canvas::new(*level.px_wid(), *level.px_hei());
+
+    if options.background {
+        canvas.fill_rect(
+            IVec2::ZERO,
+            IVec2::new(canvas.width, canvas.height),
+            level.bg_color().as_rgba_u8(),
+            1.,
+        );
+    }
+
+    let mut tilesets = HashMap::new();
+
+    // LDtk lists layers from top to bottom
+    for layer_instance in level
+        .layer_instances()
+        .iter()
+        .rev()
+        .filter(|layer| layer.layer_instance_type != Type::Entities)
+        .filter(|layer| match &options.layers {
+            Some(layers) => layers.contains(&layer.identifier),
+            None => true,
+        })
+    {
+        draw_layer(
+            &mut canvas,
+            layer_instance,
+            ldtk_project,
+            images,
+            &mut tilesets,
+            options,
+        )?;
+    }
+
+    Ok(Image::new(
+        Extent3d {
+            width: canvas.width as u32,
+            height: canvas.height as u32,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        canvas.data,
+        TextureFormat::Rgba8UnormSrgb,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn blending_respects_opacity() {
+        let mut canvas = Canvas::new(2, 1);
+
+        canvas.blend(0, 0, [255, 0, 0, 255], 1.);
+        canvas.blend(0, 0, [0, 0, 255, 255], 0.5);
+        assert_eq!(canvas.pixel(0, 0), Some([128, 0, 128, 255]));
+
+        canvas.blend(1, 0, [0, 255, 0, 255], 0.);
+        assert_eq!(canvas.pixel(1, 0), Some([0, 0, 0, 0]));
+
+        // out of bounds pixels are ignored
+        canvas.blend(2, 0, [0, 255, 0, 255], 1.);
+        assert_eq!(canvas.pixel(2, 0), None);
+    }
+
+    #[test]
+    fn tiles_are_drawn_with_flips_and_offsets() {
+        // 2x2 tileset with a single 2x2 tile
+        let tileset = Canvas {
+            width: 2,
+            height: 2,
+            data: [
+                [1, 0, 0, 255],
+                [2, 0, 0, 255],
+                [3, 0, 0, 255],
+                [4, 0, 0, 255],
+            ]
+            .concat(),
+        };
+
+        let mut canvas = Canvas::new(3, 3);
+
+        canvas.draw_tile(
+            &tileset,
+            &TileInstance {
+                a: 1.,
+                f: 3,
+                px: IVec2::new(0, 0),
+                ..Default::default()
+            },
+            2,
+            IVec2::new(1, 1),
+            1.,
+        );
+
+        assert_eq!(canvas.pixel(0, 0), Some([0, 0, 0, 0]));
+        assert_eq!(canvas.pixel(1, 1), Some([4, 0, 0, 255]));
+        assert_eq!(canvas.pixel(2, 1), Some([3, 0, 0, 255]));
+        assert_eq!(canvas.pixel(1, 2), Some([2, 0, 0, 255]));
+        assert_eq!(canvas.pixel(2, 2), Some([1, 0, 0, 255]));
+    }
+}
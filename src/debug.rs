@@ -0,0 +1,366 @@
+//! Debug overlay for spawned levels.
+//!
+//! *Requires the "debug" feature*
+//!
+//! The [`LdtkDebugPlugin`] draws the following with [`Gizmos`]:
+//! - The borders of every spawned level.
+//! - The grid of every layer.
+//! - The bounds and pivot of every LDtk entity.
+//! - Links between spawned levels and their spawned neighbors.
+//!
+//! It also labels every level with its identifier and the identifiers of its layers, using world
+//! space text.
+//!
+//! Every part of the overlay can be toggled in the [`LdtkDebugSettings`] resource:
+//! ```
+//! # use bevy::prelude::*;
+//! # use bevy_ecs_ldtk::debug::LdtkDebugSettings;
+//! fn toggle_debug_overlay(input: Res<Input<KeyCode>>, mut settings: ResMut<LdtkDebugSettings>) {
+//!     if input.just_pressed(KeyCode::F3) {
+//!         settings.enabled = !settings.enabled;
+//!     }
+//! }
+//! ```
+//!
+//! [`Gizmos`]: https://docs.rs/bevy/latest/bevy/gizmos/gizmos/struct.Gizmos.html
+
+use crate::{
+    assets::{LdtkProject, LdtkProjectHandle, LevelMetadataAccessor},
+    components::{LayerMetadata, LevelIid},
+    ldtk::{EntityInstance, Level},
+    resources::{LevelDuplicates, LevelEvent},
+};
+use bevy::{prelude::*, sprite::Anchor, transform::TransformSystem};
+use std::collections::HashMap;
+
+/// [`Resource`] that toggles the parts of the debug overlay drawn by the [`LdtkDebugPlugin`].
+#[derive(Clone, PartialEq, Debug, Resource)]
+pub struct LdtkDebugSettings {
+    /// Toggles the entire overlay.
+    pub enabled: bool,
+    pub level_bounds: bool,
+    pub layer_grids: bool,
+    pub entity_bounds: bool,
+    pub neighbor_links: bool,
+    /// Toggles the level and layer identifier labels.
+    pub labels: bool,
+    pub level_color: Color,
+    pub grid_color: Color,
+    pub entity_color: Color,
+    pub label_font_size: f32,
+}
+
+impl Default for LdtkDebugSettings {
+    fn default() -> Self {
+        LdtkDebugSettings {
+            enabled: true,
+            level_bounds: true,
+            layer_grids: true,
+            entity_bounds: true,
+            neighbor_links: true,
+            labels: true,
+            level_color: Color::YELLOW,
+            grid_color: Color::rgba(1., 1., 1., 0.15),
+            entity_color: Color::FUCHSIA,
+            label_font_size: 16.,
+        }
+    }
+}
+
+/// [`Component`] marking the identifier label spawned for a level by the [`LdtkDebugPlugin`].
+///
+/// [`Component`]: https://docs.rs/bevy/latest/bevy/ecs/component/trait.Component.html
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Default, Hash, Component, Reflect)]
+#[reflect(Component)]
+pub struct LdtkDebugLabel;
+
+/// Plugin that draws a debug overlay over spawned levels.
+///
+/// See the [module-level documentation](crate::debug) for details.
+///
+/// Not added by [`LdtkPlugin`].
+///
+/// [`LdtkPlugin`]: crate::prelude::LdtkPlugin
+#[derive(Copy, Clone, Debug, Default)]
+pub struct LdtkDebugPlugin;
+
+impl Plugin for LdtkDebugPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<LdtkDebugSettings>()
+            .register_type::<LdtkDebugLabel>()
+            .add_systems(
+                PostUpdate,
+                (
+                    spawn_debug_labels,
+                    toggle_debug_labels,
+                    draw_debug_overlay.after(TransformSystem::TransformPropagate),
+                ),
+            );
+    }
+}
+
+/// Finds the raw data of a spawned level.
+fn find_level<'a>(
+    level_iid: &LevelIid,
+    level_parent: &Parent,
+    ldtk_query: &Query<&LdtkProjectHandle>,
+    ldtk_project_assets: &'a Assets<LdtkProject>,
+    level_duplicates: &'a LevelDuplicates,
+) -> Option<&'a Level> {
+    if let Some(duplicate) = level_duplicates.get(level_iid) {
+        return Some(&duplicate.level);
+    }
+
+    ldtk_project_assets
+        .get(ldtk_query.get(level_parent.get()).ok()?)?
+        .get_raw_level_by_iid(level_iid.get())
+}
+
+/// Returns the bounds and the pivot point of an LDtk entity centered at the given point.
+fn entity_bounds(center: Vec2, entity_instance: &EntityInstance) -> (Rect, Vec2) {
+    let size = IVec2::new(entity_instance.width, entity_instance.height).as_vec2();
+    let bounds = Rect::from_center_size(center, size);
+    let pivot = Vec2::new(
+        bounds.min.x + size.x * entity_instance.pivot.x,
+        bounds.max.y - size.y * entity_instance.pivot.y,
+    );
+
+    (bounds, pivot)
+}
+
+fn label_text(level_identifier: &str, layer_identifiers: &[&str]) -> String {
+    layer_identifiers
+        .iter()
+        .fold(level_identifier.to_string(), |text, layer_identifier| {
+            format!("{text}\n  {layer_identifier}")
+        })
+}
+
+#[allow(clippy::too_many_arguments)]
+fn spawn_debug_labels(
+    mut commands: Commands,
+    mut level_events: EventReader<LevelEvent>,
+    settings: Res<LdtkDebugSettings>,
+    level_query: Query<(Entity, &LevelIid, &Parent, Option<&Children>)>,
+    layer_query: Query<&LayerMetadata>,
+    ldtk_query: Query<&LdtkProjectHandle>,
+    ldtk_project_assets: Res<Assets<LdtkProject>>,
+    level_duplicates: Res<LevelDuplicates>,
+) {
+    for level_event in level_events.iter() {
+        let LevelEvent::Transformed(level_iid) = level_event else {
+            continue;
+        };
+
+        let Some((level_entity, _, level_parent, children)) =
+            level_query.iter().find(|(_, iid, ..)| *iid == level_iid)
+        else {
+            continue;
+        };
+
+        let Some(level) = find_level(
+            level_iid,
+            level_parent,
+            &ldtk_query,
+            &ldtk_project_assets,
+            &level_duplicates,
+        ) else {
+            continue;
+        };
+
+        let layer_identifiers: Vec<_> = layer_query
+            .iter_many(children.into_iter().flatten())
+            .map(|layer_metadata| layer_metadata.identifier.as_str())
+            .collect();
+
+        let visibility = if settings.enabled && settings.labels {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        };
+
+        commands.entity(level_entity).with_children(|parent| {
+            parent
+                .spawn(Text2dBundle {
+                    text: Text::from_section(
+                        label_text(&level.identifier, &layer_identifiers),
+                        TextStyle {
+                            font_size: settings.label_font_size,
+                            color: settings.level_color,
+                            ..default()
+                        },
+                    ),
+                    text_anchor: Anchor::BottomLeft,
+                    // Place the label just above the top left corner of the level, in front of it
+                    transform: Transform::from_xyz(0., level.px_hei as f32 + 2., 1000.),
+                    visibility,
+                    ..default()
+                })
+                .insert(LdtkDebugLabel)
+                .insert(Name::new("LDtk Debug Label"));
+        });
+    }
+}
+
+fn toggle_debug_labels(
+    settings: Res<LdtkDebugSettings>,
+    mut label_query: Query<&mut Visibility, With<LdtkDebugLabel>>,
+) {
+    if !settings.is_changed() {
+        return;
+    }
+
+    for mut visibility in label_query.iter_mut() {
+        *visibility = if settings.enabled && settings.labels {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        };
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn draw_debug_overlay(
+    mut gizmos: Gizmos,
+    settings: Res<LdtkDebugSettings>,
+    level_query: Query<(&LevelIid, &Parent, &GlobalTransform, Option<&Children>)>,
+    layer_query: Query<(&LayerMetadata, Option<&Children>)>,
+    entity_query: Query<(&EntityInstance, &GlobalTransform)>,
+    ldtk_query: Query<&LdtkProjectHandle>,
+    ldtk_project_assets: Res<Assets<LdtkProject>>,
+    level_duplicates: Res<LevelDuplicates>,
+) {
+    if !settings.enabled {
+        return;
+    }
+
+    let mut level_centers = HashMap::new();
+    let mut neighbor_links = Vec::new();
+
+    for (level_iid, level_parent, level_transform, children) in level_query.iter() {
+        let Some(level) = find_level(
+            level_iid,
+            level_parent,
+            &ldtk_query,
+            &ldtk_project_assets,
+            &level_duplicates,
+        ) else {
+            continue;
+        };
+
+        let to_world = |local: Vec2| level_transform.transform_point(local.extend(0.)).truncate();
+        let draw_rect = |gizmos: &mut Gizmos, rect: Rect, color: Color| {
+            let corners = [
+                rect.min,
+                Vec2::new(rect.max.x, rect.min.y),
+                rect.max,
+                Vec2::new(rect.min.x, rect.max.y),
+            ]
+            .map(to_world);
+
+            gizmos.linestrip_2d(corners.into_iter().chain([corners[0]]), color);
+        };
+
+        let level_size = IVec2::new(level.px_wid, level.px_hei).as_vec2();
+
+        if settings.level_bounds {
+            draw_rect(
+                &mut gizmos,
+                Rect::from_corners(Vec2::ZERO, level_size),
+                settings.level_color,
+            );
+        }
+
+        level_centers.insert(level_iid.clone(), to_world(level_size / 2.));
+        neighbor_links.extend(level.neighbours.iter().map(|neighbour| {
+            (
+                level_iid.clone(),
+                LevelIid::new(neighbour.level_iid.clone()),
+            )
+        }));
+
+        for (layer_metadata, layer_children) in
+            layer_query.iter_many(children.into_iter().flatten())
+        {
+            if settings.layer_grids && layer_metadata.grid_size > 0 {
+                let grid_size = layer_metadata.grid_size as f32;
+                let top_left = Vec2::new(
+                    layer_metadata.px_total_offset_x as f32,
+                    level_size.y - layer_metadata.px_total_offset_y as f32,
+                );
+                let size =
+                    IVec2::new(layer_metadata.c_wid, layer_metadata.c_hei).as_vec2() * grid_size;
+
+                for x in 0..=layer_metadata.c_wid {
+                    let x = top_left.x + x as f32 * grid_size;
+                    gizmos.line_2d(
+                        to_world(Vec2::new(x, top_left.y)),
+                        to_world(Vec2::new(x, top_left.y - size.y)),
+                        settings.grid_color,
+                    );
+                }
+
+                for y in 0..=layer_metadata.c_hei {
+                    let y = top_left.y - y as f32 * grid_size;
+                    gizmos.line_2d(
+                        to_world(Vec2::new(top_left.x, y)),
+                        to_world(Vec2::new(top_left.x + size.x, y)),
+                        settings.grid_color,
+                    );
+                }
+            }
+
+            if settings.entity_bounds {
+                let to_local = level_transform.affine().inverse();
+
+                for (entity_instance, entity_transform) in
+                    entity_query.iter_many(layer_children.into_iter().flatten())
+                {
+                    let center = to_local
+                        .transform_point3(entity_transform.translation())
+                        .truncate();
+                    let (bounds, pivot) = entity_bounds(center, entity_instance);
+
+                    draw_rect(&mut gizmos, bounds, settings.entity_color);
+                    gizmos.circle_2d(to_world(pivot), 2., settings.entity_color);
+                }
+            }
+        }
+    }
+
+    if settings.neighbor_links {
+        for (level_iid, neighbor_iid) in neighbor_links {
+            if let (Some(from), Some(to)) = (
+                level_centers.get(&level_iid),
+                level_centers.get(&neighbor_iid),
+            ) {
+                gizmos.line_2d(*from, *to, settings.level_color);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn entity_bounds_and_pivot_match_ldtk() {
+        let entity_instance = EntityInstance {
+            width: 16,
+            height: 32,
+            pivot: Vec2::new(0.5, 1.),
+            ..Default::default()
+        };
+
+        let (bounds, pivot) = entity_bounds(Vec2::new(8., 16.), &entity_instance);
+        assert_eq!(bounds.min, Vec2::ZERO);
+        assert_eq!(bounds.max, Vec2::new(16., 32.));
+        assert_eq!(pivot, Vec2::new(8., 0.));
+
+        assert_eq!(
+            label_text("Level_0", &["Entities", "Walls"]),
+            "Level_0\n  Entities\n  Walls"
+        );
+    }
+}
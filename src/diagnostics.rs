@@ -0,0 +1,296 @@
+//! Diagnostics for the cost of spawning levels, for tracking performance in big projects.
+//!
+//! The [`LdtkDiagnosticsPlugin`] measures every level that spawns and feeds the results to Bevy's
+//! diagnostics, so they show up in standard tooling like the `LogDiagnosticsPlugin`:
+//! - [`LdtkDiagnosticsPlugin::LEVEL_SPAWN_TIME`]
+//! - [`LdtkDiagnosticsPlugin::LEVEL_TILE_COUNT`]
+//! - [`LdtkDiagnosticsPlugin::LEVEL_ENTITY_COUNT`]
+//! - [`LdtkDiagnosticsPlugin::LEVEL_ASSET_MEMORY`]
+//!
+//! The measurements of each spawned level are also available in the [`LdtkLevelDiagnostics`]
+//! resource.
+//! ```no_run
+//! use bevy::{diagnostic::LogDiagnosticsPlugin, prelude::*};
+//! use bevy_ecs_ldtk::{diagnostics::LdtkDiagnosticsPlugin, prelude::*};
+//!
+//! fn main() {
+//!     App::new()
+//!         .add_plugins((
+//!             DefaultPlugins,
+//!             LdtkPlugin,
+//!             LdtkDiagnosticsPlugin,
+//!             LogDiagnosticsPlugin::default(),
+//!         ))
+//!         .run();
+//! }
+//! ```
+
+use crate::{
+    components::LevelIid, ldtk::EntityInstance, resources::LevelEvent, systems::process_ldtk_levels,
+};
+use bevy::{
+    diagnostic::{Diagnostic, DiagnosticId, Diagnostics, RegisterDiagnostic},
+    ecs::query::Has,
+    prelude::*,
+    utils::{Duration, Instant},
+};
+use bevy_ecs_tilemap::{map::TilemapTexture, tiles::TilePos};
+use std::collections::{HashMap, HashSet};
+
+/// Measurements of a single spawned level.
+#[derive(Copy, Clone, PartialEq, Debug, Default)]
+pub struct LevelDiagnostics {
+    /// Time spent in the level spawning system.
+    ///
+    /// If several levels spawn in the same update, the time is split evenly between them.
+    pub spawn_time: Duration,
+    /// Number of tiles in the level's tilemaps.
+    pub tile_count: usize,
+    /// Number of LDtk entities in the level.
+    pub entity_count: usize,
+    /// Estimated size, in bytes, of the images used by the level's tilemaps.
+    pub asset_memory: usize,
+}
+
+/// [`Resource`] storing the [`LevelDiagnostics`] of every spawned level.
+///
+/// Entries are removed when their level despawns.
+#[derive(Clone, PartialEq, Debug, Default, Resource)]
+pub struct LdtkLevelDiagnostics {
+    levels: HashMap<LevelIid, LevelDiagnostics>,
+}
+
+impl LdtkLevelDiagnostics {
+    /// Returns the measurements of the level with the given iid.
+    pub fn get(&self, level_iid: &LevelIid) -> Option<&LevelDiagnostics> {
+        self.levels.get(level_iid)
+    }
+
+    /// Iterates over the measurements of all spawned levels.
+    pub fn iter(&self) -> impl Iterator<Item = (&LevelIid, &LevelDiagnostics)> {
+        self.levels.iter()
+    }
+}
+
+/// [`Resource`] storing when the level spawning system started in this update.
+#[derive(Clone, Debug, Default, Resource)]
+struct SpawnTimer {
+    start: Option<Instant>,
+    pending: HashMap<LevelIid, Duration>,
+}
+
+/// Plugin that measures spawned levels and reports them as Bevy diagnostics.
+///
+/// See the [module-level documentation](crate::diagnostics) for details.
+///
+/// Not added by [`LdtkPlugin`].
+///
+/// [`LdtkPlugin`]: crate::prelude::LdtkPlugin
+#[derive(Copy, Clone, Debug, Default)]
+pub struct LdtkDiagnosticsPlugin;
+
+impl LdtkDiagnosticsPlugin {
+    /// Time it took to spawn a level, in milliseconds.
+    pub const LEVEL_SPAWN_TIME: DiagnosticId =
+        DiagnosticId::from_u128(0x6f1c0e7e_84b2_4b5e_9a42_0d0a3b6f6a01);
+    /// Number of tiles in a spawned level.
+    pub const LEVEL_TILE_COUNT: DiagnosticId =
+        DiagnosticId::from_u128(0x6f1c0e7e_84b2_4b5e_9a42_0d0a3b6f6a02);
+    /// Number of LDtk entities in a spawned level.
+    pub const LEVEL_ENTITY_COUNT: DiagnosticId =
+        DiagnosticId::from_u128(0x6f1c0e7e_84b2_4b5e_9a42_0d0a3b6f6a03);
+    /// Estimated size of the images used by a spawned level, in kibibytes.
+    pub const LEVEL_ASSET_MEMORY: DiagnosticId =
+        DiagnosticId::from_u128(0x6f1c0e7e_84b2_4b5e_9a42_0d0a3b6f6a04);
+}
+
+impl Plugin for LdtkDiagnosticsPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_diagnostic(
+            Diagnostic::new(Self::LEVEL_SPAWN_TIME, "ldtk_level_spawn_time", 20).with_suffix("ms"),
+        )
+        .register_diagnostic(Diagnostic::new(
+            Self::LEVEL_TILE_COUNT,
+            "ldtk_level_tile_count",
+            20,
+        ))
+        .register_diagnostic(Diagnostic::new(
+            Self::LEVEL_ENTITY_COUNT,
+            "ldtk_level_entity_count",
+            20,
+        ))
+        .register_diagnostic(
+            Diagnostic::new(Self::LEVEL_ASSET_MEMORY, "ldtk_level_asset_memory", 20)
+                .with_suffix("KiB"),
+        )
+        .init_resource::<LdtkLevelDiagnostics>()
+        .init_resource::<SpawnTimer>()
+        .add_systems(
+            PreUpdate,
+            (
+                start_spawn_timer.before(process_ldtk_levels),
+                stop_spawn_timer.after(process_ldtk_levels),
+            ),
+        )
+        .add_systems(Update, measure_spawned_levels);
+    }
+}
+
+fn start_spawn_timer(mut spawn_timer: ResMut<SpawnTimer>) {
+    spawn_timer.start = Some(Instant::now());
+}
+
+fn stop_spawn_timer(
+    mut spawn_timer: ResMut<SpawnTimer>,
+    mut level_events: EventReader<LevelEvent>,
+) {
+    let Some(start) = spawn_timer.start.take() else {
+        return;
+    };
+
+    let spawned: Vec<_> = level_events
+        .iter()
+        .filter_map(|level_event| match level_event {
+            LevelEvent::Spawned(level_iid) => Some(level_iid.clone()),
+            _ => None,
+        })
+        .collect();
+
+    if spawned.is_empty() {
+        return;
+    }
+
+    let spawn_time = start.elapsed() / spawned.len() as u32;
+
+    spawn_timer
+        .pending
+        .extend(spawned.into_iter().map(|level_iid| (level_iid, spawn_time)));
+}
+
+#[allow(clippy::too_many_arguments)]
+fn measure_spawned_levels(
+    mut diagnostics: Diagnostics,
+    mut level_diagnostics: ResMut<LdtkLevelDiagnostics>,
+    mut spawn_timer: ResMut<SpawnTimer>,
+    mut level_events: EventReader<LevelEvent>,
+    level_query: Query<(Entity, &LevelIid)>,
+    children_query: Query<&Children>,
+    contents_query: Query<(Has<TilePos>, Has<EntityInstance>, Option<&TilemapTexture>)>,
+    images: Res<Assets<Image>>,
+) {
+    for level_event in level_events.iter() {
+        match level_event {
+            LevelEvent::Spawned(level_iid) => {
+                let Some((level_entity, _)) = level_query.iter().find(|(_, iid)| *iid == level_iid)
+                else {
+                    continue;
+                };
+
+                let mut measurements = LevelDiagnostics {
+                    spawn_time: spawn_timer.pending.remove(level_iid).unwrap_or_default(),
+                    ..default()
+                };
+                let mut textures = HashSet::new();
+
+                for descendant in children_query.iter_descendants(level_entity) {
+                    let Ok((is_tile, is_entity, texture)) = contents_query.get(descendant) else {
+                        continue;
+                    };
+
+                    measurements.tile_count += is_tile as usize;
+                    measurements.entity_count += is_entity as usize;
+
+                    if let Some(TilemapTexture::Single(handle)) = texture {
+                        textures.insert(handle);
+                    }
+                }
+
+                measurements.asset_memory = textures
+                    .into_iter()
+                    .filter_map(|handle| images.get(handle))
+                    .map(|image| image.data.len())
+                    .sum();
+
+                diagnostics.add_measurement(LdtkDiagnosticsPlugin::LEVEL_SPAWN_TIME, || {
+                    measurements.spawn_time.as_secs_f64() * 1000.
+                });
+                diagnostics.add_measurement(LdtkDiagnosticsPlugin::LEVEL_TILE_COUNT, || {
+                    measurements.tile_count as f64
+                });
+                diagnostics.add_measurement(LdtkDiagnosticsPlugin::LEVEL_ENTITY_COUNT, || {
+                    measurements.entity_count as f64
+                });
+                diagnostics.add_measurement(LdtkDiagnosticsPlugin::LEVEL_ASSET_MEMORY, || {
+                    measurements.asset_memory as f64 / 1024.
+                });
+
+                level_diagnostics
+                    .levels
+                    .insert(level_iid.clone(), measurements);
+            }
+            LevelEvent::Despawned(level_iid) => {
+                level_diagnostics.levels.remove(level_iid);
+            }
+            _ => (),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::diagnostic::DiagnosticsStore;
+
+    #[test]
+    fn spawned_levels_are_measured() {
+        let mut app = App::new();
+        app.add_event::<LevelEvent>()
+            .init_resource::<Assets<Image>>()
+            .add_plugins(LdtkDiagnosticsPlugin);
+
+        let level_iid = LevelIid::new("level");
+
+        app.world.spawn(level_iid.clone()).with_children(|level| {
+            level
+                .spawn(SpatialBundle::default())
+                .with_children(|layer| {
+                    layer.spawn(TilePos::new(0, 0));
+                    layer.spawn(TilePos::new(1, 0));
+                });
+            level
+                .spawn(SpatialBundle::default())
+                .with_children(|layer| {
+                    layer.spawn(EntityInstance::default());
+                });
+        });
+
+        app.world.send_event(LevelEvent::Spawned(level_iid.clone()));
+        app.update();
+
+        let measurements = *app
+            .world
+            .resource::<LdtkLevelDiagnostics>()
+            .get(&level_iid)
+            .unwrap();
+        assert_eq!(measurements.tile_count, 2);
+        assert_eq!(measurements.entity_count, 1);
+        assert_eq!(measurements.asset_memory, 0);
+
+        let diagnostics = app.world.resource::<DiagnosticsStore>();
+        assert_eq!(
+            diagnostics
+                .get(LdtkDiagnosticsPlugin::LEVEL_TILE_COUNT)
+                .and_then(|diagnostic| diagnostic.value()),
+            Some(2.)
+        );
+
+        app.world
+            .send_event(LevelEvent::Despawned(level_iid.clone()));
+        app.update();
+
+        assert_eq!(
+            app.world.resource::<LdtkLevelDiagnostics>().get(&level_iid),
+            None
+        );
+    }
+}
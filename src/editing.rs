@@ -0,0 +1,551 @@
+//! Runtime editing of spawned levels, for building in-game level editors.
+//!
+//! *Requires the "internal_levels" feature*
+//!
+//! [`LdtkLevelEditor`] edits the level data of the [`LdtkProject`] asset in place.
+//! Like hot-reloading, modifying the asset respawns its worlds, so edits are reflected in the ECS
+//! through the usual spawning pipeline.
+//! The asset on disk is left untouched, but the edited project can be written back to a `.ldtk`
+//! file with [`export_ldtk_json`].
+//!
+//! Editing only changes the data of the edited layer.
+//! In particular, AutoLayer tiles are not regenerated when IntGrid values change.
+
+use crate::{
+    assets::{LdtkProject, LdtkProjectHandle},
+    components::{GridCoords, LevelIid},
+    ldtk::{Definitions, EntityInstance, LayerInstance, Level, TileInstance, Type},
+    utils::grid_coords_to_ldtk_grid_coords,
+};
+use bevy::{ecs::system::SystemParam, prelude::*};
+use thiserror::Error;
+
+/// Errors that can occur when editing a level.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum LevelEditError {
+    #[error("entity is not a spawned level of an internal-levels project")]
+    LevelNotFound,
+    #[error("level has no layer with identifier {0}")]
+    LayerNotFound(String),
+    #[error(
+        "layer {identifier} is a {actual:?} layer, but this edit requires a {expected:?} layer"
+    )]
+    WrongLayerType {
+        identifier: String,
+        expected: Type,
+        actual: Type,
+    },
+    #[error("level has no entity with iid {0}")]
+    EntityNotFound(String),
+    #[error("{0:?} is out of the layer's bounds")]
+    OutOfBounds(GridCoords),
+    #[error("layer {0} has no tileset")]
+    NoTileset(String),
+    #[error("project has no entity definition with identifier {0}")]
+    UnknownEntity(String),
+    #[error("offset {offset} is not aligned to the grid of layer {identifier}")]
+    MisalignedOffset { identifier: String, offset: IVec2 },
+    #[error("tileset {tileset} has no tile with id {tile_id}")]
+    InvalidTileId { tileset: String, tile_id: i32 },
+}
+
+fn layer_mut<'a>(
+    level: &'a mut Level,
+    layer_identifier: &str,
+    expected: Type,
+) -> Result<&'a mut LayerInstance, LevelEditError> {
+    let layer_instance = level
+        .layer_instances
+        .iter_mut()
+        .flatten()
+        .find(|layer_instance| layer_instance.identifier == layer_identifier)
+        .ok_or_else(|| LevelEditError::LayerNotFound(layer_identifier.to_string()))?;
+
+    if layer_instance.layer_instance_type != expected {
+        return Err(LevelEditError::WrongLayerType {
+            identifier: layer_identifier.to_string(),
+            expected,
+            actual: layer_instance.layer_instance_type,
+        });
+    }
+
+    Ok(layer_instance)
+}
+
+fn ldtk_grid_coords_in_layer(
+    layer_instance: &LayerInstance,
+    grid_coords: GridCoords,
+) -> Result<IVec2, LevelEditError> {
+    let ldtk_grid_coords = grid_coords_to_ldtk_grid_coords(grid_coords, layer_instance.c_hei);
+
+    if ldtk_grid_coords.cmpge(IVec2::ZERO).all()
+        && ldtk_grid_coords
+            .cmplt(IVec2::new(layer_instance.c_wid, layer_instance.c_hei))
+            .all()
+    {
+        Ok(ldtk_grid_coords)
+    } else {
+        Err(LevelEditError::OutOfBounds(grid_coords))
+    }
+}
+
+/// Adds an entity instance to the Entities layer with the given identifier.
+///
+/// The instance's `grid`, `world_x`, and `world_y` are recalculated from its `px`.
+pub fn add_entity_instance(
+    level: &mut Level,
+    layer_identifier: &str,
+    mut entity_instance: EntityInstance,
+) -> Result<(), LevelEditError> {
+    let (level_world_x, level_world_y) = (level.world_x, level.world_y);
+    let layer_instance = layer_mut(level, layer_identifier, Type::Entities)?;
+
+    entity_instance.grid = entity_instance.px / layer_instance.grid_size;
+    entity_instance.world_x = level_world_x + entity_instance.px.x;
+    entity_instance.world_y = level_world_y + entity_instance.px.y;
+
+    layer_instance.entity_instances.push(entity_instance);
+
+    Ok(())
+}
+
+/// Moves the entity instance with the given iid to the given LDtk pixel coordinates.
+pub fn move_entity_instance(
+    level: &mut Level,
+    entity_iid: &str,
+    px: IVec2,
+) -> Result<(), LevelEditError> {
+    let (level_world_x, level_world_y) = (level.world_x, level.world_y);
+
+    let (grid_size, entity_instance) = level
+        .layer_instances
+        .iter_mut()
+        .flatten()
+        .find_map(|layer_instance| {
+            let grid_size = layer_instance.grid_size;
+            layer_instance
+                .entity_instances
+                .iter_mut()
+                .find(|entity_instance| entity_instance.iid == entity_iid)
+                .map(|entity_instance| (grid_size, entity_instance))
+        })
+        .ok_or_else(|| LevelEditError::EntityNotFound(entity_iid.to_string()))?;
+
+    entity_instance.px = px;
+    entity_instance.grid = px / grid_size;
+    entity_instance.world_x = level_world_x + px.x;
+    entity_instance.world_y = level_world_y + px.y;
+
+    Ok(())
+}
+
+/// Removes the entity instance with the given iid, returning it.
+pub fn remove_entity_instance(
+    level: &mut Level,
+    entity_iid: &str,
+) -> Result<EntityInstance, LevelEditError> {
+    level
+        .layer_instances
+        .iter_mut()
+        .flatten()
+        .find_map(|layer_instance| {
+            let index = layer_instance
+                .entity_instances
+                .iter()
+                .position(|entity_instance| entity_instance.iid == entity_iid)?;
+
+            Some(layer_instance.entity_instances.remove(index))
+        })
+        .ok_or_else(|| LevelEditError::EntityNotFound(entity_iid.to_string()))
+}
+
+/// Sets the tile of the Tiles layer with the given identifier at the given [GridCoords].
+///
+/// Replaces any tiles already in that cell.
+/// If `tile_id` is [None], the cell is cleared instead.
+/// The layer is left untouched if `tile_id` isn't a tile of the layer's tileset.
+pub fn set_tile(
+    level: &mut Level,
+    defs: &Definitions,
+    layer_identifier: &str,
+    grid_coords: GridCoords,
+    tile_id: Option<i32>,
+) -> Result<(), LevelEditError> {
+    let layer_instance = layer_mut(level, layer_identifier, Type::Tiles)?;
+    let px = ldtk_grid_coords_in_layer(layer_instance, grid_coords)? * layer_instance.grid_size;
+
+    let tile = match tile_id {
+        Some(tile_id) => {
+            let tileset_definition = layer_instance
+                .tileset_def_uid
+                .and_then(|uid| defs.tilesets.iter().find(|tileset| tileset.uid == uid))
+                .ok_or_else(|| LevelEditError::NoTileset(layer_identifier.to_string()))?;
+
+            if !(0..tileset_definition.c_wid * tileset_definition.c_hei).contains(&tile_id) {
+                return Err(LevelEditError::InvalidTileId {
+                    tileset: tileset_definition.identifier.clone(),
+                    tile_id,
+                });
+            }
+
+            let tileset_coords = IVec2::new(
+                tile_id % tileset_definition.c_wid,
+                tile_id / tileset_definition.c_wid,
+            );
+
+            Some(TileInstance {
+                a: 1.,
+                d: vec![
+                    px.y / layer_instance.grid_size * layer_instance.c_wid
+                        + px.x / layer_instance.grid_size,
+                ],
+                f: 0,
+                px,
+                src: tileset_coords
+                    * (tileset_definition.tile_grid_size + tileset_definition.spacing)
+                    + IVec2::splat(tileset_definition.padding),
+                t: tile_id,
+            })
+        }
+        None => None,
+    };
+
+    layer_instance.grid_tiles.retain(|tile| tile.px != px);
+    layer_instance.grid_tiles.extend(tile);
+
+    Ok(())
+}
+
+/// Sets the value of the IntGrid layer with the given identifier at the given [GridCoords].
+pub fn set_int_grid_value(
+    level: &mut Level,
+    layer_identifier: &str,
+    grid_coords: GridCoords,
+    value: i32,
+) -> Result<(), LevelEditError> {
+    let layer_instance = layer_mut(level, layer_identifier, Type::IntGrid)?;
+    let ldtk_grid_coords = ldtk_grid_coords_in_layer(layer_instance, grid_coords)?;
+
+    let index = (ldtk_grid_coords.y * layer_instance.c_wid + ldtk_grid_coords.x) as usize;
+
+    match layer_instance.int_grid_csv.get_mut(index) {
+        Some(cell) => {
+            *cell = value;
+            Ok(())
+        }
+        None => Err(LevelEditError::OutOfBounds(grid_coords)),
+    }
+}
+
+/// Serializes the project's LDtk json data, including any runtime edits, so it can be written
+/// back to a `.ldtk` file.
+pub fn export_ldtk_json(ldtk_project: &LdtkProject) -> serde_json::Result<String> {
+    serde_json::to_string_pretty(ldtk_project.json_data())
+}
+
+/// [`SystemParam`] for editing spawned levels at runtime.
+///
+/// Every method takes the level entity to edit.
+/// See the [module-level documentation](crate::editing) for more details.
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_ecs_ldtk::{editing::LdtkLevelEditor, prelude::*};
+/// fn paint_wall(mut editor: LdtkLevelEditor, levels: Query<Entity, With<LevelIid>>) {
+///     for level in levels.iter() {
+///         if let Err(e) = editor.set_int_grid_value(level, "Walls", GridCoords::new(3, 4), 1) {
+///             warn!("{}", e);
+///         }
+///     }
+/// }
+/// ```
+///
+/// [`SystemParam`]: https://docs.rs/bevy/latest/bevy/ecs/system/trait.SystemParam.html
+#[derive(SystemParam)]
+pub struct LdtkLevelEditor<'w, 's> {
+    ldtk_project_assets: ResMut<'w, Assets<LdtkProject>>,
+    ldtk_query: Query<'w, 's, &'static LdtkProjectHandle>,
+    level_query: Query<'w, 's, (&'static LevelIid, &'static Parent)>,
+}
+
+impl<'w, 's> LdtkLevelEditor<'w, 's> {
+    fn edit<R>(
+        &mut self,
+        level_entity: Entity,
+        edit: impl FnOnce(&mut Level, &Definitions) -> Result<R, LevelEditError>,
+    ) -> Result<R, LevelEditError> {
+        let (level_iid, parent) = self
+            .level_query
+            .get(level_entity)
+            .map_err(|_| LevelEditError::LevelNotFound)?;
+
+        let handle = self
+            .ldtk_query
+            .get(parent.get())
+            .map_err(|_| LevelEditError::LevelNotFound)?;
+
+        let ldtk_project = self
+            .ldtk_project_assets
+            .get_mut(handle)
+            .ok_or(LevelEditError::LevelNotFound)?;
+
+        let (level, defs) = ldtk_project
+            .get_raw_level_by_iid_mut(level_iid.get())
+            .ok_or(LevelEditError::LevelNotFound)?;
+
+        edit(level, defs)
+    }
+
+    /// Adds an entity instance to the Entities layer with the given identifier.
+    ///
+    /// See [add_entity_instance].
+    pub fn add_entity(
+        &mut self,
+        level_entity: Entity,
+        layer_identifier: &str,
+        entity_instance: EntityInstance,
+    ) -> Result<(), LevelEditError> {
+        self.edit(level_entity, |level, _| {
+            add_entity_instance(level, layer_identifier, entity_instance)
+        })
+    }
+
+    /// Moves the entity instance with the given iid to the given LDtk pixel coordinates.
+    pub fn move_entity(
+        &mut self,
+        level_entity: Entity,
+        entity_iid: &str,
+        px: IVec2,
+    ) -> Result<(), LevelEditError> {
+        self.edit(level_entity, |level, _| {
+            move_entity_instance(level, entity_iid, px)
+        })
+    }
+
+    /// Removes the entity instance with the given iid, returning it.
+    pub fn remove_entity(
+        &mut self,
+        level_entity: Entity,
+        entity_iid: &str,
+    ) -> Result<EntityInstance, LevelEditError> {
+        self.edit(level_entity, |level, _| {
+            remove_entity_instance(level, entity_iid)
+        })
+    }
+
+    /// Sets or clears the tile of the Tiles layer with the given identifier at the given
+    /// [GridCoords].
+    ///
+    /// See [set_tile].
+    pub fn set_tile(
+        &mut self,
+        level_entity: Entity,
+        layer_identifier: &str,
+        grid_coords: GridCoords,
+        tile_id: Option<i32>,
+    ) -> Result<(), LevelEditError> {
+        self.edit(level_entity, |level, defs| {
+            set_tile(level, defs, layer_identifier, grid_coords, tile_id)
+        })
+    }
+
+    /// Sets the value of the IntGrid layer with the given identifier at the given [GridCoords].
+    pub fn set_int_grid_value(
+        &mut self,
+        level_entity: Entity,
+        layer_identifier: &str,
+        grid_coords: GridCoords,
+        value: i32,
+    ) -> Result<(), LevelEditError> {
+        self.edit(level_entity, |level, _| {
+            set_int_grid_value(level, layer_identifier, grid_coords, value)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ldtk::TilesetDefinition;
+
+    use super::*;
+
+    fn sample_level() -> Level {
+        Level {
+            world_x: 100,
+            world_y: 200,
+            layer_instances: Some(vec![
+                LayerInstance {
+                    identifier: "Entities".to_string(),
+                    layer_instance_type: Type::Entities,
+                    grid_size: 16,
+                    c_wid: 4,
+                    c_hei: 4,
+                    entity_instances: vec![EntityInstance {
+                        iid: "chest".to_string(),
+                        ..Default::default()
+                    }],
+                    ..Default::default()
+                },
+                LayerInstance {
+                    identifier: "Walls".to_string(),
+                    layer_instance_type: Type::IntGrid,
+                    grid_size: 16,
+                    c_wid: 4,
+                    c_hei: 4,
+                    int_grid_csv: vec![0; 16],
+                    ..Default::default()
+                },
+                LayerInstance {
+                    identifier: "Tiles".to_string(),
+                    layer_instance_type: Type::Tiles,
+                    grid_size: 16,
+                    c_wid: 4,
+                    c_hei: 4,
+                    tileset_def_uid: Some(1),
+                    ..Default::default()
+                },
+            ]),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn entities_can_be_added_moved_and_removed() {
+        let mut level = sample_level();
+
+        add_entity_instance(
+            &mut level,
+            "Entities",
+            EntityInstance {
+                iid: "coin".to_string(),
+                px: IVec2::new(32, 16),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        move_entity_instance(&mut level, "chest", IVec2::new(48, 0)).unwrap();
+
+        let entities = &level.layer_instances.as_ref().unwrap()[0].entity_instances;
+        assert_eq!(entities[1].grid, IVec2::new(2, 1));
+        assert_eq!((entities[1].world_x, entities[1].world_y), (132, 216));
+        assert_eq!(entities[0].grid, IVec2::new(3, 0));
+
+        assert_eq!(
+            remove_entity_instance(&mut level, "chest").unwrap().iid,
+            "chest"
+        );
+        assert_eq!(
+            remove_entity_instance(&mut level, "chest"),
+            Err(LevelEditError::EntityNotFound("chest".to_string()))
+        );
+        assert_eq!(
+            add_entity_instance(&mut level, "Walls", EntityInstance::default()),
+            Err(LevelEditError::WrongLayerType {
+                identifier: "Walls".to_string(),
+                expected: Type::Entities,
+                actual: Type::IntGrid,
+            })
+        );
+    }
+
+    #[test]
+    fn cells_are_set_by_grid_coords() {
+        let mut level = sample_level();
+        let defs = Definitions {
+            tilesets: vec![TilesetDefinition {
+                uid: 1,
+                c_wid: 8,
+                c_hei: 2,
+                tile_grid_size: 16,
+                spacing: 1,
+                padding: 2,
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+
+        set_int_grid_value(&mut level, "Walls", GridCoords::new(1, 3), 2).unwrap();
+        assert_eq!(
+            level.layer_instances.as_ref().unwrap()[1].int_grid_csv[1],
+            2
+        );
+
+        set_tile(&mut level, &defs, "Tiles", GridCoords::new(0, 0), Some(9)).unwrap();
+        set_tile(&mut level, &defs, "Tiles", GridCoords::new(0, 0), Some(10)).unwrap();
+
+        let tiles = &level.layer_instances.as_ref().unwrap()[2].grid_tiles;
+        assert_eq!(tiles.len(), 1);
+        assert_eq!(tiles[0].px, IVec2::new(0, 48));
+        assert_eq!(tiles[0].src, IVec2::new(36, 19));
+
+        set_tile(&mut level, &defs, "Tiles", GridCoords::new(0, 0), None).unwrap();
+        assert!(level.layer_instances.as_ref().unwrap()[2]
+            .grid_tiles
+            .is_empty());
+
+        assert_eq!(
+            set_int_grid_value(&mut level, "Walls", GridCoords::new(4, 0), 1),
+            Err(LevelEditError::OutOfBounds(GridCoords::new(4, 0)))
+        );
+    }
+
+    #[test]
+    fn tile_ids_outside_the_tileset_are_rejected() {
+        let mut level = sample_level();
+        let defs = Definitions {
+            tilesets: vec![TilesetDefinition {
+                identifier: "Terrain".to_string(),
+                uid: 1,
+                c_wid: 8,
+                c_hei: 2,
+                tile_grid_size: 16,
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+
+        set_tile(&mut level, &defs, "Tiles", GridCoords::new(0, 0), Some(15)).unwrap();
+
+        for tile_id in [-1, 16] {
+            assert_eq!(
+                set_tile(
+                    &mut level,
+                    &defs,
+                    "Tiles",
+                    GridCoords::new(0, 0),
+                    Some(tile_id)
+                ),
+                Err(LevelEditError::InvalidTileId {
+                    tileset: "Terrain".to_string(),
+                    tile_id,
+                })
+            );
+        }
+
+        // The tile that was already there is kept
+        let tiles = &level.layer_instances.as_ref().unwrap()[2].grid_tiles;
+        assert_eq!(tiles.len(), 1);
+        assert_eq!(tiles[0].t, 15);
+    }
+
+    #[test]
+    fn tilesets_without_tiles_reject_every_tile_id() {
+        let mut level = sample_level();
+        let defs = Definitions {
+            tilesets: vec![TilesetDefinition {
+                identifier: "Missing".to_string(),
+                uid: 1,
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+
+        assert_eq!(
+            set_tile(&mut level, &defs, "Tiles", GridCoords::new(0, 0), Some(0)),
+            Err(LevelEditError::InvalidTileId {
+                tileset: "Missing".to_string(),
+                tile_id: 0,
+            })
+        );
+    }
+}
@@ -0,0 +1,316 @@
+//! Hides LDtk entities far outside the view, for levels with thousands of decorative entities.
+//!
+//! Hidden entities aren't extracted for rendering, but every visible entity still is, so levels
+//! with huge entity layers pay for all of them every frame, even if only a few are on screen.
+//! [`EntityCulling`] in [`LdtkSettings::entity_culling`] hides the LDtk entities outside the view
+//! of every active orthographic camera, like [`LevelCulling`] does for whole levels.
+//! ```
+//! # use bevy::prelude::*;
+//! # use bevy_ecs_ldtk::{entity_culling::*, prelude::*};
+//! fn main() {
+//!     App::new()
+//!         .insert_resource(LdtkSettings {
+//!             entity_culling: EntityCulling::Enabled {
+//!                 margin: 64.,
+//!                 cell_size: 256.,
+//!             },
+//!             ..default()
+//!         })
+//!         // add other systems, plugins, resources...
+//!         ;
+//! }
+//! ```
+//!
+//! Entities are found with an [`LdtkEntitySpatialIndex`] of their positions, so only the entities
+//! near the edges of the view are updated as the camera moves.
+//!
+//! [`LdtkSettings::entity_culling`]: crate::prelude::LdtkSettings::entity_culling
+//! [`LevelCulling`]: crate::prelude::LevelCulling
+
+use crate::{components::EntityIid, resources::LdtkSettings};
+use bevy::prelude::*;
+use std::collections::{HashMap, HashSet};
+
+/// Option in [`LdtkSettings`] that determines whether LDtk entities outside of the view are
+/// hidden.
+///
+/// When enabled, the [`Visibility`] of each LDtk entity is set to [`Visibility::Hidden`] while its
+/// position is outside the view of every active orthographic camera, and restored once it's back
+/// in view.
+/// Entities are only hidden, not despawned, so their systems keep running.
+///
+/// Entities are culled by their position, not their size, so the margin should be at least half
+/// the size of the largest entity to avoid popping at the edges of the view.
+///
+/// [`LdtkSettings`]: crate::prelude::LdtkSettings
+#[derive(Copy, Clone, PartialEq, Debug, Default)]
+pub enum EntityCulling {
+    #[default]
+    Disabled,
+    Enabled {
+        /// Distance, in world units, that views are extended by before checking for overlap.
+        margin: f32,
+        /// Size, in world units, of the cells of the [`LdtkEntitySpatialIndex`].
+        ///
+        /// Entities are shown and hidden a cell at a time, so smaller cells cull more precisely,
+        /// while larger cells are cheaper to update.
+        cell_size: f32,
+    },
+}
+
+/// [`Component`] on LDtk entities hidden by [`EntityCulling`], storing the [`Visibility`] they're
+/// restored to once they're back in view.
+///
+/// [`Component`]: https://docs.rs/bevy/latest/bevy/ecs/component/trait.Component.html
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Default, Component, Reflect)]
+#[reflect(Component)]
+pub struct EntityCulled(pub Visibility);
+
+/// [`Resource`] storing the world positions of LDtk entities in a grid of cells, maintained while
+/// [`EntityCulling`] is enabled.
+///
+/// [`Resource`]: https://docs.rs/bevy/latest/bevy/ecs/system/trait.Resource.html
+#[derive(Clone, PartialEq, Debug, Default, Resource)]
+pub struct LdtkEntitySpatialIndex {
+    cell_size: f32,
+    cells: HashMap<IVec2, HashSet<Entity>>,
+    entity_cells: HashMap<Entity, IVec2>,
+}
+
+impl LdtkEntitySpatialIndex {
+    /// Creates an empty index with the given cell size, in world units.
+    pub fn new(cell_size: f32) -> LdtkEntitySpatialIndex {
+        LdtkEntitySpatialIndex {
+            cell_size,
+            ..default()
+        }
+    }
+
+    /// The size of the cells of the index, in world units.
+    pub fn cell_size(&self) -> f32 {
+        self.cell_size
+    }
+
+    /// The cell containing the given world position.
+    pub fn cell_of(&self, position: Vec2) -> IVec2 {
+        (position / self.cell_size).floor().as_ivec2()
+    }
+
+    /// Moves the entity to the cell containing the given world position, returning the cell.
+    pub fn insert(&mut self, entity: Entity, position: Vec2) -> IVec2 {
+        let cell = self.cell_of(position);
+
+        if let Some(previous_cell) = self.entity_cells.insert(entity, cell) {
+            if previous_cell == cell {
+                return cell;
+            }
+
+            self.remove_from_cell(entity, previous_cell);
+        }
+
+        self.cells.entry(cell).or_default().insert(entity);
+        cell
+    }
+
+    /// Removes the entity from the index.
+    pub fn remove(&mut self, entity: Entity) {
+        if let Some(cell) = self.entity_cells.remove(&entity) {
+            self.remove_from_cell(entity, cell);
+        }
+    }
+
+    fn remove_from_cell(&mut self, entity: Entity, cell: IVec2) {
+        if let Some(entities) = self.cells.get_mut(&cell) {
+            entities.remove(&entity);
+
+            if entities.is_empty() {
+                self.cells.remove(&cell);
+            }
+        }
+    }
+
+    /// The entities in the given cell.
+    pub fn entities_in_cell(&self, cell: IVec2) -> impl Iterator<Item = Entity> + '_ {
+        self.cells.get(&cell).into_iter().flatten().copied()
+    }
+
+    /// The cells overlapping the given world space rect that contain any entities.
+    pub fn occupied_cells_in_rect(&self, rect: Rect) -> impl Iterator<Item = IVec2> + '_ {
+        let min = self.cell_of(rect.min);
+        let max = self.cell_of(rect.max);
+
+        (min.y..=max.y)
+            .flat_map(move |y| (min.x..=max.x).map(move |x| IVec2::new(x, y)))
+            .filter(|cell| self.cells.contains_key(cell))
+    }
+
+    /// The entities in the cells overlapping the given world space rect.
+    ///
+    /// Entities near the edges of the rect may be slightly outside of it.
+    pub fn entities_in_rect(&self, rect: Rect) -> impl Iterator<Item = Entity> + '_ {
+        self.occupied_cells_in_rect(rect)
+            .flat_map(|cell| self.entities_in_cell(cell))
+    }
+}
+
+/// Hides LDtk entities that are outside the view of every active orthographic camera, according to
+/// [`EntityCulling`].
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
+pub fn cull_entities(
+    mut commands: Commands,
+    ldtk_settings: Res<LdtkSettings>,
+    mut spatial_index: ResMut<LdtkEntitySpatialIndex>,
+    mut visible_cells: Local<HashSet<IVec2>>,
+    camera_query: Query<(&Camera, &GlobalTransform, &OrthographicProjection)>,
+    moved_query: Query<(Entity, &GlobalTransform), (With<EntityIid>, Changed<GlobalTransform>)>,
+    entity_query: Query<(Entity, &GlobalTransform), With<EntityIid>>,
+    mut removed_entities: RemovedComponents<EntityIid>,
+    mut visibility_query: Query<(&mut Visibility, Option<&EntityCulled>)>,
+    culled_query: Query<Entity, With<EntityCulled>>,
+) {
+    let EntityCulling::Enabled { margin, cell_size } = ldtk_settings.entity_culling else {
+        if !spatial_index.entity_cells.is_empty() {
+            for entity in culled_query.iter() {
+                if let Ok((mut visibility, Some(culled))) = visibility_query.get_mut(entity) {
+                    *visibility = culled.0;
+                    commands.entity(entity).remove::<EntityCulled>();
+                }
+            }
+
+            *spatial_index = LdtkEntitySpatialIndex::default();
+            visible_cells.clear();
+        }
+
+        return;
+    };
+
+    // Every entity is indexed again if the cell size changes
+    let moved: Vec<(Entity, Vec2)> = if spatial_index.cell_size != cell_size {
+        *spatial_index = LdtkEntitySpatialIndex::new(cell_size);
+        visible_cells.clear();
+
+        entity_query
+            .iter()
+            .map(|(entity, transform)| (entity, transform.translation().truncate()))
+            .collect()
+    } else {
+        moved_query
+            .iter()
+            .map(|(entity, transform)| (entity, transform.translation().truncate()))
+            .collect()
+    };
+
+    for entity in removed_entities.iter() {
+        spatial_index.remove(entity);
+    }
+
+    let moved_cells: Vec<(Entity, IVec2)> = moved
+        .into_iter()
+        .map(|(entity, position)| (entity, spatial_index.insert(entity, position)))
+        .collect();
+
+    let views: Vec<Rect> = camera_query
+        .iter()
+        .filter(|(camera, _, _)| camera.is_active)
+        .map(|(_, camera_transform, projection)| {
+            let center = camera_transform.translation().truncate();
+            Rect {
+                min: center + projection.area.min - margin,
+                max: center + projection.area.max + margin,
+            }
+        })
+        .collect();
+
+    if views.is_empty() {
+        return;
+    }
+
+    let new_visible_cells: HashSet<IVec2> = views
+        .iter()
+        .flat_map(|view| spatial_index.occupied_cells_in_rect(*view))
+        .collect();
+
+    // Only entities in cells entering or leaving the view, or that moved, need to be updated
+    let mut in_view: HashMap<Entity, bool> = HashMap::new();
+
+    for cell in visible_cells.difference(&new_visible_cells) {
+        in_view.extend(
+            spatial_index
+                .entities_in_cell(*cell)
+                .map(|entity| (entity, false)),
+        );
+    }
+
+    for cell in new_visible_cells.difference(&visible_cells) {
+        in_view.extend(
+            spatial_index
+                .entities_in_cell(*cell)
+                .map(|entity| (entity, true)),
+        );
+    }
+
+    for (entity, cell) in moved_cells {
+        in_view.insert(entity, new_visible_cells.contains(&cell));
+    }
+
+    for (entity, in_view) in in_view {
+        let Ok((mut visibility, culled)) = visibility_query.get_mut(entity) else {
+            continue;
+        };
+
+        match (in_view, culled) {
+            (true, Some(culled)) => {
+                *visibility = culled.0;
+                commands.entity(entity).remove::<EntityCulled>();
+            }
+            (false, None) if *visibility != Visibility::Hidden => {
+                commands.entity(entity).insert(EntityCulled(*visibility));
+                *visibility = Visibility::Hidden;
+            }
+            _ => (),
+        }
+    }
+
+    *visible_cells = new_visible_cells;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn spatial_index_moves_entities_between_cells() {
+        let mut spatial_index = LdtkEntitySpatialIndex::new(16.);
+        let a = Entity::from_raw(0);
+        let b = Entity::from_raw(1);
+
+        assert_eq!(spatial_index.insert(a, Vec2::new(8., 8.)), IVec2::new(0, 0));
+        assert_eq!(
+            spatial_index.insert(b, Vec2::new(-8., 40.)),
+            IVec2::new(-1, 2)
+        );
+
+        let view = Rect::new(0., 0., 20., 20.);
+        assert_eq!(
+            spatial_index.entities_in_rect(view).collect::<Vec<_>>(),
+            vec![a]
+        );
+
+        spatial_index.insert(a, Vec2::new(100., 100.));
+        assert_eq!(spatial_index.entities_in_rect(view).count(), 0);
+        assert_eq!(
+            spatial_index
+                .entities_in_cell(IVec2::new(6, 6))
+                .collect::<Vec<_>>(),
+            vec![a]
+        );
+
+        spatial_index.remove(b);
+        assert_eq!(
+            spatial_index
+                .entities_in_rect(Rect::new(-100., -100., 100., 100.))
+                .collect::<Vec<_>>(),
+            vec![a]
+        );
+    }
+}
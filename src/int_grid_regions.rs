@@ -0,0 +1,312 @@
+//! Flood fill, connected region labeling, and region adjacency over IntGrid layers.
+//!
+//! These work on anything implementing [`IntGridValues`], like the [`IntGridCells`] of spawned
+//! layers, or the raw [`LayerInstance`]s of a project.
+//! Cells are connected to the 4 cells sharing an edge with them.
+//! ```
+//! # use bevy::prelude::*;
+//! # use bevy_ecs_ldtk::{int_grid_regions::*, prelude::*};
+//! const WATER: i32 = 3;
+//! const WALL: i32 = 1;
+//!
+//! /// Pools of water that are fully surrounded by walls.
+//! fn enclosed_pools(layers: Query<&IntGridCells>) {
+//!     for cells in layers.iter() {
+//!         let regions = IntGridRegions::label(cells, |value| value == WATER || value == WALL);
+//!
+//!         let enclosed = (0..regions.len())
+//!             .filter(|region| regions.value(*region) == WATER)
+//!             .filter(|region| !regions.touches_edge(*region))
+//!             .filter(|region| {
+//!                 regions
+//!                     .adjacent_regions(*region)
+//!                     .all(|neighbor| regions.value(neighbor) == WALL)
+//!             })
+//!             .count();
+//!
+//!         info!("{enclosed} enclosed pools");
+//!     }
+//! }
+//! ```
+
+use crate::{
+    components::{GridCoords, IntGridCells},
+    ldtk::LayerInstance,
+};
+use bevy::prelude::*;
+use std::collections::{BTreeSet, HashMap, HashSet};
+
+/// The offsets of the cells sharing an edge with a cell.
+const NEIGHBOR_OFFSETS: [IVec2; 4] = [IVec2::X, IVec2::NEG_X, IVec2::Y, IVec2::NEG_Y];
+
+/// Read access to the values of an IntGrid layer, in [GridCoords].
+pub trait IntGridValues {
+    /// The width and height of the layer, in cells.
+    fn size(&self) -> IVec2;
+
+    /// The value of the cell, or 0 if it's empty or outside of the layer.
+    fn int_grid_value(&self, grid_coords: GridCoords) -> i32;
+
+    /// Returns true if the cell is inside the layer.
+    fn contains(&self, grid_coords: GridCoords) -> bool {
+        let size = self.size();
+        (0..size.x).contains(&grid_coords.x) && (0..size.y).contains(&grid_coords.y)
+    }
+}
+
+impl IntGridValues for IntGridCells {
+    fn size(&self) -> IVec2 {
+        IntGridCells::size(self)
+    }
+
+    fn int_grid_value(&self, grid_coords: GridCoords) -> i32 {
+        self.value_at(grid_coords).unwrap_or(0)
+    }
+}
+
+impl IntGridValues for LayerInstance {
+    fn size(&self) -> IVec2 {
+        IVec2::new(self.c_wid, self.c_hei)
+    }
+
+    fn int_grid_value(&self, grid_coords: GridCoords) -> i32 {
+        if !self.contains(grid_coords) {
+            return 0;
+        }
+
+        // The csv is stored row by row from the top
+        let index = (self.c_hei - 1 - grid_coords.y) * self.c_wid + grid_coords.x;
+        self.int_grid_csv.get(index as usize).copied().unwrap_or(0)
+    }
+}
+
+fn neighbors(grid_coords: GridCoords) -> impl Iterator<Item = GridCoords> {
+    NEIGHBOR_OFFSETS
+        .into_iter()
+        .map(move |offset| (IVec2::from(grid_coords) + offset).into())
+}
+
+/// Returns every cell connected to `start` through cells whose values match.
+///
+/// Returns an empty set if `start` itself doesn't match or is outside of the layer.
+/// Matching 0 fills empty cells, like water filling a room.
+pub fn flood_fill(
+    int_grid: &impl IntGridValues,
+    start: GridCoords,
+    matches: impl Fn(i32) -> bool,
+) -> HashSet<GridCoords> {
+    let is_open = |grid_coords: GridCoords| {
+        int_grid.contains(grid_coords) && matches(int_grid.int_grid_value(grid_coords))
+    };
+
+    let mut filled = HashSet::new();
+    if !is_open(start) {
+        return filled;
+    }
+
+    let mut to_visit = vec![start];
+    filled.insert(start);
+
+    while let Some(grid_coords) = to_visit.pop() {
+        for neighbor in neighbors(grid_coords) {
+            if is_open(neighbor) && filled.insert(neighbor) {
+                to_visit.push(neighbor);
+            }
+        }
+    }
+
+    filled
+}
+
+/// Connected regions of equal values in an IntGrid layer.
+///
+/// Regions are numbered from 0, in the order they're found scanning rows from the bottom left.
+#[derive(Clone, Eq, PartialEq, Debug, Default)]
+pub struct IntGridRegions {
+    labels: HashMap<GridCoords, usize>,
+    regions: Vec<IntGridRegion>,
+}
+
+#[derive(Clone, Eq, PartialEq, Debug, Default)]
+struct IntGridRegion {
+    value: i32,
+    cells: Vec<GridCoords>,
+    touches_edge: bool,
+    adjacent: BTreeSet<usize>,
+}
+
+impl IntGridRegions {
+    /// Labels the connected regions of cells with matching values.
+    ///
+    /// Neighboring cells only belong to the same region if they have the same value.
+    /// Cells whose values don't match aren't part of any region.
+    pub fn label(int_grid: &impl IntGridValues, matches: impl Fn(i32) -> bool) -> IntGridRegions {
+        let size = int_grid.size();
+        let mut labeled = IntGridRegions::default();
+
+        for y in 0..size.y {
+            for x in 0..size.x {
+                let start = GridCoords::new(x, y);
+                let value = int_grid.int_grid_value(start);
+
+                if labeled.labels.contains_key(&start) || !matches(value) {
+                    continue;
+                }
+
+                let region = labeled.regions.len();
+                let cells = flood_fill(int_grid, start, |other| other == value);
+
+                labeled
+                    .labels
+                    .extend(cells.iter().map(|grid_coords| (*grid_coords, region)));
+                labeled.regions.push(IntGridRegion {
+                    value,
+                    touches_edge: cells.iter().any(|grid_coords| {
+                        neighbors(*grid_coords).any(|neighbor| !int_grid.contains(neighbor))
+                    }),
+                    cells: cells.into_iter().collect(),
+                    adjacent: BTreeSet::new(),
+                });
+            }
+        }
+
+        // Regions of different values touching each other
+        for region in 0..labeled.regions.len() {
+            let adjacent: BTreeSet<usize> = labeled.regions[region]
+                .cells
+                .iter()
+                .flat_map(|grid_coords| neighbors(*grid_coords))
+                .filter_map(|neighbor| labeled.labels.get(&neighbor).copied())
+                .filter(|other| *other != region)
+                .collect();
+
+            labeled.regions[region].adjacent = adjacent;
+        }
+
+        labeled
+    }
+
+    /// The number of regions.
+    pub fn len(&self) -> usize {
+        self.regions.len()
+    }
+
+    /// Returns true if no cells matched.
+    pub fn is_empty(&self) -> bool {
+        self.regions.is_empty()
+    }
+
+    /// The region containing the cell, if any.
+    pub fn region_of(&self, grid_coords: GridCoords) -> Option<usize> {
+        self.labels.get(&grid_coords).copied()
+    }
+
+    /// The value of every cell in the region.
+    ///
+    /// # Panics
+    /// Panics if the region doesn't exist.
+    pub fn value(&self, region: usize) -> i32 {
+        self.regions[region].value
+    }
+
+    /// The cells of the region, in no particular order.
+    ///
+    /// # Panics
+    /// Panics if the region doesn't exist.
+    pub fn cells(&self, region: usize) -> &[GridCoords] {
+        &self.regions[region].cells
+    }
+
+    /// Returns true if the region reaches the edge of the layer.
+    ///
+    /// Regions that don't are enclosed by cells of other values, or by unmatched cells.
+    ///
+    /// # Panics
+    /// Panics if the region doesn't exist.
+    pub fn touches_edge(&self, region: usize) -> bool {
+        self.regions[region].touches_edge
+    }
+
+    /// The regions sharing an edge with the region, in ascending order.
+    ///
+    /// # Panics
+    /// Panics if the region doesn't exist.
+    pub fn adjacent_regions(&self, region: usize) -> impl Iterator<Item = usize> + '_ {
+        self.regions[region].adjacent.iter().copied()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A room of walls (1) with water (3) inside, and a puddle of water outside.
+    fn int_grid() -> IntGridCells {
+        #[rustfmt::skip]
+        let int_grid_csv = [
+            1, 1, 1, 0, 0,
+            1, 3, 1, 0, 3,
+            1, 3, 1, 0, 0,
+            1, 1, 1, 0, 0,
+        ];
+
+        IntGridCells::from_int_grid_csv(&int_grid_csv, 5, 4)
+    }
+
+    #[test]
+    fn flood_fill_stays_within_matching_cells() {
+        let int_grid = int_grid();
+
+        assert_eq!(
+            flood_fill(&int_grid, GridCoords::new(1, 1), |value| value == 3),
+            HashSet::from([GridCoords::new(1, 1), GridCoords::new(1, 2)])
+        );
+        assert_eq!(
+            flood_fill(&int_grid, GridCoords::new(3, 0), |value| value == 0).len(),
+            7
+        );
+        assert!(flood_fill(&int_grid, GridCoords::new(0, 0), |value| value == 3).is_empty());
+        assert!(flood_fill(&int_grid, GridCoords::new(-1, 0), |_| true).is_empty());
+    }
+
+    #[test]
+    fn regions_are_labeled_with_adjacency() {
+        let int_grid = int_grid();
+        let regions = IntGridRegions::label(&int_grid, |value| value != 0);
+
+        assert_eq!(regions.len(), 3);
+
+        let walls = regions.region_of(GridCoords::new(0, 0)).unwrap();
+        let pool = regions.region_of(GridCoords::new(1, 1)).unwrap();
+        let puddle = regions.region_of(GridCoords::new(4, 2)).unwrap();
+
+        assert_eq!(regions.value(walls), 1);
+        assert_eq!(regions.cells(walls).len(), 10);
+        assert_eq!(regions.value(pool), 3);
+        assert_eq!(regions.region_of(GridCoords::new(3, 0)), None);
+
+        assert!(regions.touches_edge(walls));
+        assert!(!regions.touches_edge(pool));
+        assert!(regions.touches_edge(puddle));
+
+        assert_eq!(
+            regions.adjacent_regions(pool).collect::<Vec<_>>(),
+            vec![walls]
+        );
+        assert_eq!(regions.adjacent_regions(puddle).count(), 0);
+    }
+
+    #[test]
+    fn layer_instances_read_int_grid_csv() {
+        let layer_instance = LayerInstance {
+            c_wid: 2,
+            c_hei: 2,
+            int_grid_csv: vec![1, 2, 3, 4],
+            ..Default::default()
+        };
+
+        assert_eq!(layer_instance.int_grid_value(GridCoords::new(0, 1)), 1);
+        assert_eq!(layer_instance.int_grid_value(GridCoords::new(1, 0)), 4);
+        assert_eq!(layer_instance.int_grid_value(GridCoords::new(2, 0)), 0);
+    }
+}
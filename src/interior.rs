@@ -0,0 +1,283 @@
+//! Hides roof and overlay layers while an [`InteriorRevealer`] is inside a building.
+//!
+//! Top-down games often draw building interiors under a "Roof" layer, which should fade away when
+//! the player walks in.
+//! Each [`InteriorReveal`] in [`LdtkSettings::interior_reveals`] links an overlay layer to an
+//! [`InteriorTrigger`] region authored in the same level, either as int grid cells or as tagged
+//! entities.
+//! Whenever an [`InteriorRevealer`] enters or leaves the region, the plugin fades the overlay
+//! layers of that level with a [`LayerFade`].
+//! ```
+//! # use bevy::prelude::*;
+//! # use bevy_ecs_ldtk::{interior::*, prelude::*};
+//! # #[derive(Component)]
+//! # struct Player;
+//! fn main() {
+//!     App::new()
+//!         .insert_resource(LdtkSettings {
+//!             interior_reveals: vec![InteriorReveal::new(
+//!                 "Roof",
+//!                 InteriorTrigger::IntGrid {
+//!                     layer: "Floors".to_string(),
+//!                     value: 3,
+//!                 },
+//!             )],
+//!             ..default()
+//!         })
+//!         // add other systems, plugins, resources...
+//!         ;
+//! }
+//!
+//! fn setup_player(mut commands: Commands, players: Query<Entity, Added<Player>>) {
+//!     for player in players.iter() {
+//!         commands.entity(player).insert(InteriorRevealer);
+//!     }
+//! }
+//! ```
+//!
+//! [`LdtkSettings::interior_reveals`]: crate::prelude::LdtkSettings::interior_reveals
+
+use crate::{
+    components::{
+        EntityInstance, GridCoords, IntGridCell, IntGridCells, LayerFade, LayerMetadata, LevelIid,
+    },
+    level_query::LdtkLevelIndex,
+    resources::LdtkSettings,
+};
+use bevy::prelude::*;
+use bevy_ecs_tilemap::tiles::{TilePos, TileStorage};
+
+/// [`Component`] marking the entities that reveal interiors when inside them, like the player.
+///
+/// [`Component`]: https://docs.rs/bevy/latest/bevy/ecs/component/trait.Component.html
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Default, Component, Reflect)]
+#[reflect(Component)]
+pub struct InteriorRevealer;
+
+/// The region of a level that counts as the inside of an interior.
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub enum InteriorTrigger {
+    /// Cells with the given value on IntGrid layers with the given identifier.
+    IntGrid { layer: String, value: i32 },
+    /// The bounds of LDtk entities with the given tag.
+    EntityTag(String),
+}
+
+/// Links overlay layers to the [`InteriorTrigger`] that hides them, found in
+/// [`LdtkSettings::interior_reveals`].
+///
+/// [`LdtkSettings::interior_reveals`]: crate::prelude::LdtkSettings::interior_reveals
+#[derive(Clone, PartialEq, Debug)]
+pub struct InteriorReveal {
+    /// Identifier of the layers hidden while inside.
+    pub overlay_layer: String,
+    /// The region that counts as inside.
+    pub trigger: InteriorTrigger,
+    /// Opacity of the overlay layers while inside, from 0 to 1.
+    pub hidden_opacity: f32,
+    /// Seconds the overlay layers take to fade in or out.
+    pub fade_duration: f32,
+}
+
+impl InteriorReveal {
+    /// Creates a reveal that fades the overlay layers out completely over a quarter second.
+    pub fn new(overlay_layer: impl Into<String>, trigger: InteriorTrigger) -> InteriorReveal {
+        InteriorReveal {
+            overlay_layer: overlay_layer.into(),
+            trigger,
+            hidden_opacity: 0.,
+            fade_duration: 0.25,
+        }
+    }
+}
+
+/// Finds the cell of a tile layer containing the given translation, relative to the layer.
+///
+/// Tiles are centered on their layer's grid, so cells extend half a tile around their
+/// translation.
+/// Returns `None` if the translation is outside of the layer.
+pub(crate) fn tile_layer_grid_coords(
+    translation: Vec2,
+    layer_metadata: &LayerMetadata,
+) -> Option<GridCoords> {
+    let grid_coords = (translation / layer_metadata.grid_size as f32 + Vec2::splat(0.5))
+        .floor()
+        .as_ivec2();
+
+    ((0..layer_metadata.c_wid).contains(&grid_coords.x)
+        && (0..layer_metadata.c_hei).contains(&grid_coords.y))
+    .then_some(grid_coords.into())
+}
+
+/// Fades the overlay layers of [`InteriorReveal`]s as [`InteriorRevealer`]s enter or leave their
+/// triggers.
+///
+/// Triggers are only checked again when a revealer moves, the [`LdtkSettings`] change, or levels
+/// spawn or despawn.
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
+pub fn reveal_interiors(
+    mut commands: Commands,
+    ldtk_settings: Res<LdtkSettings>,
+    level_index: Res<LdtkLevelIndex>,
+    level_query: Query<&LevelIid>,
+    revealer_query: Query<&GlobalTransform, With<InteriorRevealer>>,
+    moved_revealer_query: Query<
+        (),
+        (
+            With<InteriorRevealer>,
+            Or<(Changed<GlobalTransform>, Added<InteriorRevealer>)>,
+        ),
+    >,
+    mut removed_revealers: RemovedComponents<InteriorRevealer>,
+    layer_query: Query<(
+        &LayerMetadata,
+        &GlobalTransform,
+        Option<&IntGridCells>,
+        Option<&TileStorage>,
+    )>,
+    int_grid_cell_query: Query<&IntGridCell>,
+    entity_query: Query<(&EntityInstance, &GlobalTransform)>,
+    mut fade_query: Query<Option<&mut LayerFade>>,
+) {
+    // Removed revealers are read every frame, so they don't pile up while nothing else changes
+    let revealers_removed = removed_revealers.iter().count() > 0;
+    let revealers_changed = revealers_removed || !moved_revealer_query.is_empty();
+
+    if ldtk_settings.interior_reveals.is_empty()
+        || !(revealers_changed || ldtk_settings.is_changed() || level_index.is_changed())
+    {
+        return;
+    }
+
+    let revealers: Vec<Vec3> = revealer_query
+        .iter()
+        .map(GlobalTransform::translation)
+        .collect();
+
+    for level_iid in level_query.iter() {
+        for reveal in &ldtk_settings.interior_reveals {
+            let is_inside: Box<dyn Fn(Vec3) -> bool + '_> = match &reveal.trigger {
+                InteriorTrigger::IntGrid { layer, value } => {
+                    let trigger_layers: Vec<_> = level_index
+                        .layers_in_level(level_iid)
+                        .filter_map(|layer_entity| layer_query.get(layer_entity).ok())
+                        .filter(|(layer_metadata, ..)| layer_metadata.identifier == *layer)
+                        .map(|(layer_metadata, transform, cells, storage)| {
+                            (transform.affine().inverse(), layer_metadata, cells, storage)
+                        })
+                        .collect();
+
+                    let int_grid_cell_query = &int_grid_cell_query;
+                    Box::new(move |position| {
+                        trigger_layers
+                            .iter()
+                            .any(|(inverse, layer_metadata, cells, storage)| {
+                                let translation = inverse.transform_point3(position).truncate();
+
+                                let Some(grid_coords) =
+                                    tile_layer_grid_coords(translation, layer_metadata)
+                                else {
+                                    return false;
+                                };
+
+                                let cell_value =
+                                    cells.and_then(|cells| cells.value_at(grid_coords));
+                                let tile_value = || {
+                                    let tile_entity =
+                                        storage.as_ref()?.get(&TilePos::from(grid_coords))?;
+                                    Some(int_grid_cell_query.get(tile_entity).ok()?.value)
+                                };
+
+                                cell_value.or_else(tile_value) == Some(*value)
+                            })
+                    })
+                }
+                InteriorTrigger::EntityTag(tag) => {
+                    let trigger_bounds: Vec<Rect> = level_index
+                        .entities_in_level(level_iid)
+                        .filter_map(|entity| entity_query.get(entity).ok())
+                        .filter(|(entity_instance, _)| entity_instance.tags.contains(tag))
+                        .map(|(entity_instance, transform)| {
+                            let half_size =
+                                IVec2::new(entity_instance.width, entity_instance.height).as_vec2()
+                                    / 2.;
+
+                            Rect::from_center_half_size(
+                                transform.translation().truncate(),
+                                half_size,
+                            )
+                        })
+                        .collect();
+
+                    Box::new(move |position| {
+                        trigger_bounds
+                            .iter()
+                            .any(|bounds| bounds.contains(position.truncate()))
+                    })
+                }
+            };
+
+            let target_opacity = if revealers.iter().any(|position| is_inside(*position)) {
+                reveal.hidden_opacity
+            } else {
+                1.
+            };
+
+            let overlay_layers = level_index
+                .layers_in_level(level_iid)
+                .filter(|layer_entity| {
+                    layer_query
+                        .get(*layer_entity)
+                        .is_ok_and(|(metadata, ..)| metadata.identifier == reveal.overlay_layer)
+                });
+
+            for layer_entity in overlay_layers {
+                match fade_query.get_mut(layer_entity) {
+                    Ok(Some(mut fade)) if fade.to != target_opacity => {
+                        fade.retarget(target_opacity, reveal.fade_duration);
+                    }
+                    Ok(None) if target_opacity != 1. => {
+                        commands.entity(layer_entity).insert(LayerFade::new(
+                            1.,
+                            target_opacity,
+                            reveal.fade_duration,
+                        ));
+                    }
+                    _ => (),
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn translations_find_centered_tile_cells() {
+        let layer_metadata = LayerMetadata {
+            c_wid: 4,
+            c_hei: 2,
+            grid_size: 16,
+            ..Default::default()
+        };
+
+        assert_eq!(
+            tile_layer_grid_coords(Vec2::new(-7., -7.), &layer_metadata),
+            Some(GridCoords::new(0, 0))
+        );
+        assert_eq!(
+            tile_layer_grid_coords(Vec2::new(9., 20.), &layer_metadata),
+            Some(GridCoords::new(1, 1))
+        );
+        assert_eq!(
+            tile_layer_grid_coords(Vec2::new(-9., 0.), &layer_metadata),
+            None
+        );
+        assert_eq!(
+            tile_layer_grid_coords(Vec2::new(56., 0.), &layer_metadata),
+            None
+        );
+    }
+}
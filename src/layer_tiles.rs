@@ -0,0 +1,554 @@
+//! Provides [`LayerTiles`], for finding the tile entities of spawned layers,
+//! [`LayerTileData`], for finding the metadata and enum tags of their tiles, and
+//! [`LdtkTileCommands`], for modifying their tiles.
+
+use crate::components::{
+    GridCoords, IntGridCells, LayerMetadata, TileDataTable, TileEnumTags, TileMetadata,
+};
+use bevy::{ecs::system::SystemParam, prelude::*};
+use bevy_ecs_tilemap::tiles::{TileColor, TileFlip, TilePos, TileStorage, TileTextureIndex};
+
+/// [`SystemParam`] for finding tile entities by their position in a layer.
+///
+/// IntGrid, AutoTile, and Tile layer entities have both a [`LayerMetadata`] and a
+/// `bevy_ecs_tilemap` [`TileStorage`], so you can look tiles up directly from the layer entity.
+/// However, a single LDtk layer may be spawned as several tilemaps, for example when tiles overlap,
+/// so this parameter searches all tilemaps of a layer at once.
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_ecs_ldtk::prelude::*;
+/// fn break_wall(mut commands: Commands, layer_tiles: LayerTiles, levels: Query<Entity, With<LevelIid>>) {
+///     for level in levels.iter() {
+///         if let Some(tile) = layer_tiles.tile_at(level, "Walls", GridCoords::new(3, 4)) {
+///             commands.entity(tile).despawn_recursive();
+///         }
+///     }
+/// }
+/// ```
+///
+/// [`SystemParam`]: https://docs.rs/bevy/latest/bevy/ecs/system/trait.SystemParam.html
+#[derive(SystemParam)]
+pub struct LayerTiles<'w, 's> {
+    layer_query: Query<
+        'w,
+        's,
+        (
+            &'static LayerMetadata,
+            &'static TileStorage,
+            &'static Parent,
+        ),
+    >,
+}
+
+fn tile_in_storage(storage: &TileStorage, grid_coords: GridCoords) -> Option<Entity> {
+    if grid_coords.x < 0 || grid_coords.y < 0 {
+        return None;
+    }
+
+    storage.checked_get(&TilePos::from(grid_coords))
+}
+
+impl<'w, 's> LayerTiles<'w, 's> {
+    /// Returns all tile entities at the given position in the layer with the given iid.
+    ///
+    /// Overlapping tiles are spawned in separate tilemaps, so there may be more than one.
+    pub fn tiles_at_in_layer<'a>(
+        &'a self,
+        layer_iid: &'a str,
+        grid_coords: GridCoords,
+    ) -> impl Iterator<Item = Entity> + 'a {
+        self.layer_query
+            .iter()
+            .filter(move |(layer_metadata, _, _)| layer_metadata.iid == layer_iid)
+            .filter_map(move |(_, storage, _)| tile_in_storage(storage, grid_coords))
+    }
+
+    /// Returns all tile entities at the given position in the layer with the given identifier,
+    /// in the given level.
+    ///
+    /// Overlapping tiles are spawned in separate tilemaps, so there may be more than one.
+    pub fn tiles_at<'a>(
+        &'a self,
+        level_entity: Entity,
+        layer_identifier: &'a str,
+        grid_coords: GridCoords,
+    ) -> impl Iterator<Item = Entity> + 'a {
+        self.layer_query
+            .iter()
+            .filter(move |(layer_metadata, _, parent)| {
+                parent.get() == level_entity && layer_metadata.identifier == layer_identifier
+            })
+            .filter_map(move |(_, storage, _)| tile_in_storage(storage, grid_coords))
+    }
+
+    /// Returns the first tile entity at the given position in the layer with the given
+    /// identifier, in the given level.
+    pub fn tile_at(
+        &self,
+        level_entity: Entity,
+        layer_identifier: &str,
+        grid_coords: GridCoords,
+    ) -> Option<Entity> {
+        self.tiles_at(level_entity, layer_identifier, grid_coords)
+            .next()
+    }
+}
+
+/// [`SystemParam`] for finding the [`TileMetadata`] and [`TileEnumTags`] of tiles by their
+/// position in a layer.
+///
+/// Works with either [`TileMetadataStorage`] option, reading the [`TileDataTable`]s of the
+/// layer's tilemaps and falling back to the components on its tile entities.
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_ecs_ldtk::prelude::*;
+/// fn is_slippery(tile_data: LayerTileData, levels: Query<Entity, With<LevelIid>>) -> bool {
+///     levels.iter().any(|level| {
+///         tile_data
+///             .enum_tags_at(level, "Ground", GridCoords::new(3, 4))
+///             .is_some_and(|enum_tags| enum_tags.tags.iter().any(|tag| tag == "Ice"))
+///     })
+/// }
+/// ```
+///
+/// [`SystemParam`]: https://docs.rs/bevy/latest/bevy/ecs/system/trait.SystemParam.html
+/// [`TileMetadataStorage`]: crate::prelude::TileMetadataStorage
+#[derive(SystemParam)]
+pub struct LayerTileData<'w, 's> {
+    layer_query: Query<
+        'w,
+        's,
+        (
+            &'static LayerMetadata,
+            &'static TileStorage,
+            &'static Parent,
+            Option<&'static TileDataTable>,
+        ),
+    >,
+    tile_query: Query<'w, 's, (Option<&'static TileMetadata>, Option<&'static TileEnumTags>)>,
+}
+
+impl<'w, 's> LayerTileData<'w, 's> {
+    fn layer_tilemaps<'a>(
+        &'a self,
+        level_entity: Entity,
+        layer_identifier: &'a str,
+    ) -> impl Iterator<Item = (&'a TileStorage, Option<&'a TileDataTable>)> + 'a {
+        self.layer_query
+            .iter()
+            .filter(move |(layer_metadata, _, parent, _)| {
+                parent.get() == level_entity && layer_metadata.identifier == layer_identifier
+            })
+            .map(|(_, storage, _, tile_data_table)| (storage, tile_data_table))
+    }
+
+    /// Returns the [`TileMetadata`] of the first tile with metadata at the given position in the
+    /// layer with the given identifier, in the given level.
+    pub fn metadata_at(
+        &self,
+        level_entity: Entity,
+        layer_identifier: &str,
+        grid_coords: GridCoords,
+    ) -> Option<&TileMetadata> {
+        self.layer_tilemaps(level_entity, layer_identifier)
+            .find_map(|(storage, tile_data_table)| {
+                tile_data_table
+                    .and_then(|table| table.metadata.get(&grid_coords))
+                    .or_else(|| {
+                        let tile = tile_in_storage(storage, grid_coords)?;
+                        self.tile_query.get(tile).ok()?.0
+                    })
+            })
+    }
+
+    /// Returns the [`TileEnumTags`] of the first tile with enum tags at the given position in the
+    /// layer with the given identifier, in the given level.
+    pub fn enum_tags_at(
+        &self,
+        level_entity: Entity,
+        layer_identifier: &str,
+        grid_coords: GridCoords,
+    ) -> Option<&TileEnumTags> {
+        self.layer_tilemaps(level_entity, layer_identifier)
+            .find_map(|(storage, tile_data_table)| {
+                tile_data_table
+                    .and_then(|table| table.enum_tags.get(&grid_coords))
+                    .or_else(|| {
+                        let tile = tile_in_storage(storage, grid_coords)?;
+                        self.tile_query.get(tile).ok()?.1
+                    })
+            })
+    }
+}
+
+/// [`SystemParam`] for modifying the tiles of spawned layers by their position.
+///
+/// Changes are made through the layer's tilemaps, so its [`TileStorage`]s stay in sync with the
+/// tile entities.
+/// Removing a tile also removes its entries from the layer's [`TileDataTable`]s and
+/// [`IntGridCells`], so [`LayerTileData`] and int grid lookups don't find it anymore.
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_ecs_ldtk::prelude::*;
+/// fn crack_block(mut tile_commands: LdtkTileCommands, levels: Query<Entity, With<LevelIid>>) {
+///     for level in levels.iter() {
+///         tile_commands.set_texture_index(level, "Blocks", GridCoords::new(3, 4), 17);
+///     }
+/// }
+///
+/// fn break_block(mut tile_commands: LdtkTileCommands, levels: Query<Entity, With<LevelIid>>) {
+///     for level in levels.iter() {
+///         tile_commands.remove(level, "Blocks", GridCoords::new(3, 4));
+///     }
+/// }
+/// ```
+///
+/// [`SystemParam`]: https://docs.rs/bevy/latest/bevy/ecs/system/trait.SystemParam.html
+#[derive(SystemParam)]
+pub struct LdtkTileCommands<'w, 's> {
+    commands: Commands<'w, 's>,
+    layer_query: Query<
+        'w,
+        's,
+        (
+            &'static LayerMetadata,
+            &'static mut TileStorage,
+            &'static Parent,
+            Option<&'static mut TileDataTable>,
+            Option<&'static mut IntGridCells>,
+        ),
+    >,
+    tile_query: Query<
+        'w,
+        's,
+        (
+            &'static mut TileTextureIndex,
+            &'static mut TileFlip,
+            &'static mut TileColor,
+        ),
+    >,
+}
+
+impl<'w, 's> LdtkTileCommands<'w, 's> {
+    fn modify_tile(
+        &mut self,
+        level_entity: Entity,
+        layer_identifier: &str,
+        grid_coords: GridCoords,
+        modify: impl FnOnce(Mut<TileTextureIndex>, Mut<TileFlip>, Mut<TileColor>),
+    ) -> bool {
+        let Some(tile) = self
+            .layer_query
+            .iter()
+            .filter(|(layer_metadata, _, parent, _, _)| {
+                parent.get() == level_entity && layer_metadata.identifier == layer_identifier
+            })
+            .find_map(|(_, storage, _, _, _)| tile_in_storage(storage, grid_coords))
+        else {
+            return false;
+        };
+
+        match self.tile_query.get_mut(tile) {
+            Ok((texture_index, flip, color)) => {
+                modify(texture_index, flip, color);
+                true
+            }
+            Err(_) => false,
+        }
+    }
+
+    /// Sets the texture index of the first tile at the given position in the layer with the
+    /// given identifier, in the given level.
+    ///
+    /// Returns false if there is no tile there.
+    pub fn set_texture_index(
+        &mut self,
+        level_entity: Entity,
+        layer_identifier: &str,
+        grid_coords: GridCoords,
+        texture_index: u32,
+    ) -> bool {
+        self.modify_tile(
+            level_entity,
+            layer_identifier,
+            grid_coords,
+            |mut tile_texture_index, _, _| tile_texture_index.0 = texture_index,
+        )
+    }
+
+    /// Sets the flips of the first tile at the given position in the layer with the given
+    /// identifier, in the given level.
+    ///
+    /// Returns false if there is no tile there.
+    pub fn set_flip(
+        &mut self,
+        level_entity: Entity,
+        layer_identifier: &str,
+        grid_coords: GridCoords,
+        flip: TileFlip,
+    ) -> bool {
+        self.modify_tile(
+            level_entity,
+            layer_identifier,
+            grid_coords,
+            |_, mut tile_flip, _| *tile_flip = flip,
+        )
+    }
+
+    /// Sets the color of the first tile at the given position in the layer with the given
+    /// identifier, in the given level.
+    ///
+    /// Returns false if there is no tile there.
+    pub fn set_color(
+        &mut self,
+        level_entity: Entity,
+        layer_identifier: &str,
+        grid_coords: GridCoords,
+        color: Color,
+    ) -> bool {
+        self.modify_tile(
+            level_entity,
+            layer_identifier,
+            grid_coords,
+            |_, _, mut tile_color| tile_color.0 = color,
+        )
+    }
+
+    /// Despawns all tiles at the given position in the layer with the given identifier, in the
+    /// given level, and clears their data.
+    ///
+    /// Returns false if there were no tiles there.
+    pub fn remove(
+        &mut self,
+        level_entity: Entity,
+        layer_identifier: &str,
+        grid_coords: GridCoords,
+    ) -> bool {
+        let mut removed = false;
+
+        for (layer_metadata, mut storage, parent, tile_data_table, int_grid_cells) in
+            self.layer_query.iter_mut()
+        {
+            if parent.get() != level_entity || layer_metadata.identifier != layer_identifier {
+                continue;
+            }
+
+            if let Some(mut tile_data_table) = tile_data_table {
+                tile_data_table.metadata.remove(&grid_coords);
+                tile_data_table.enum_tags.remove(&grid_coords);
+            }
+
+            if let Some(mut int_grid_cells) = int_grid_cells {
+                int_grid_cells.set(grid_coords, 0);
+            }
+
+            if let Some(tile) = tile_in_storage(&storage, grid_coords) {
+                storage.remove(&TilePos::from(grid_coords));
+                self.commands.entity(tile).despawn_recursive();
+                removed = true;
+            }
+        }
+
+        removed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::ecs::system::SystemState;
+    use bevy_ecs_tilemap::map::TilemapSize;
+
+    use super::*;
+
+    #[test]
+    fn tiles_are_found_across_sublayers() {
+        let mut world = World::new();
+
+        let level = world.spawn_empty().id();
+        let tile_a = world.spawn_empty().id();
+        let tile_b = world.spawn_empty().id();
+
+        let mut storage_a = TileStorage::empty(TilemapSize { x: 4, y: 4 });
+        storage_a.set(&TilePos::new(1, 2), tile_a);
+
+        let mut storage_b = TileStorage::empty(TilemapSize { x: 4, y: 4 });
+        storage_b.set(&TilePos::new(1, 2), tile_b);
+
+        let layer_metadata = LayerMetadata {
+            identifier: "Walls".to_string(),
+            iid: "walls-iid".to_string(),
+            ..Default::default()
+        };
+
+        for storage in [storage_a, storage_b] {
+            let layer = world.spawn((layer_metadata.clone(), storage)).id();
+            world.entity_mut(level).add_child(layer);
+        }
+
+        let mut system_state: SystemState<LayerTiles> = SystemState::new(&mut world);
+        let layer_tiles = system_state.get(&world);
+
+        assert_eq!(
+            layer_tiles.tile_at(level, "Walls", GridCoords::new(1, 2)),
+            Some(tile_a)
+        );
+        assert_eq!(
+            layer_tiles
+                .tiles_at_in_layer("walls-iid", GridCoords::new(1, 2))
+                .collect::<Vec<_>>(),
+            vec![tile_a, tile_b]
+        );
+        assert_eq!(
+            layer_tiles.tile_at(level, "Walls", GridCoords::new(0, 0)),
+            None
+        );
+        assert_eq!(
+            layer_tiles.tile_at(level, "Walls", GridCoords::new(-1, 2)),
+            None
+        );
+        assert_eq!(
+            layer_tiles.tile_at(level, "Floor", GridCoords::new(1, 2)),
+            None
+        );
+    }
+
+    #[test]
+    fn tile_data_is_found_in_tables_and_components() {
+        let mut world = World::new();
+
+        let level = world.spawn_empty().id();
+        let component_tile = world
+            .spawn(TileMetadata {
+                data: "component".to_string(),
+            })
+            .id();
+
+        let mut storage = TileStorage::empty(TilemapSize { x: 4, y: 4 });
+        storage.set(&TilePos::new(0, 0), component_tile);
+
+        let mut tile_data_table = TileDataTable::default();
+        tile_data_table.metadata.insert(
+            GridCoords::new(1, 2),
+            TileMetadata {
+                data: "table".to_string(),
+            },
+        );
+        tile_data_table.enum_tags.insert(
+            GridCoords::new(1, 2),
+            TileEnumTags {
+                tags: vec!["Ice".to_string()],
+                source_enum_uid: Some(7),
+            },
+        );
+
+        let layer_metadata = LayerMetadata {
+            identifier: "Ground".to_string(),
+            ..Default::default()
+        };
+
+        let layer = world.spawn((layer_metadata, storage, tile_data_table)).id();
+        world.entity_mut(level).add_child(layer);
+
+        let mut system_state: SystemState<LayerTileData> = SystemState::new(&mut world);
+        let tile_data = system_state.get(&world);
+
+        assert_eq!(
+            tile_data
+                .metadata_at(level, "Ground", GridCoords::new(1, 2))
+                .map(|metadata| metadata.data.as_str()),
+            Some("table")
+        );
+        assert_eq!(
+            tile_data
+                .metadata_at(level, "Ground", GridCoords::new(0, 0))
+                .map(|metadata| metadata.data.as_str()),
+            Some("component")
+        );
+        assert_eq!(
+            tile_data
+                .enum_tags_at(level, "Ground", GridCoords::new(1, 2))
+                .map(|enum_tags| enum_tags.tags.clone()),
+            Some(vec!["Ice".to_string()])
+        );
+        assert_eq!(
+            tile_data.enum_tags_at(level, "Ground", GridCoords::new(0, 0)),
+            None
+        );
+        assert_eq!(
+            tile_data.metadata_at(level, "Walls", GridCoords::new(1, 2)),
+            None
+        );
+    }
+
+    #[test]
+    fn tile_commands_modify_and_remove_tiles() {
+        let mut world = World::new();
+
+        let level = world.spawn_empty().id();
+        let tile = world
+            .spawn((
+                TileTextureIndex(3),
+                TileFlip::default(),
+                TileColor::default(),
+            ))
+            .id();
+
+        let mut storage = TileStorage::empty(TilemapSize { x: 4, y: 4 });
+        storage.set(&TilePos::new(1, 2), tile);
+
+        let mut tile_data_table = TileDataTable::default();
+        tile_data_table.metadata.insert(
+            GridCoords::new(1, 2),
+            TileMetadata {
+                data: "block".to_string(),
+            },
+        );
+
+        let layer_metadata = LayerMetadata {
+            identifier: "Blocks".to_string(),
+            ..Default::default()
+        };
+
+        let layer = world
+            .spawn((
+                layer_metadata,
+                storage,
+                tile_data_table,
+                IntGridCells::from_int_grid_csv(&[0; 16], 4, 4),
+            ))
+            .id();
+        world
+            .get_mut::<IntGridCells>(layer)
+            .unwrap()
+            .set(GridCoords::new(1, 2), 1);
+        world.entity_mut(level).add_child(layer);
+
+        let mut system_state: SystemState<LdtkTileCommands> = SystemState::new(&mut world);
+
+        let mut tile_commands = system_state.get_mut(&mut world);
+        assert!(tile_commands.set_texture_index(level, "Blocks", GridCoords::new(1, 2), 7));
+        assert!(tile_commands.set_color(level, "Blocks", GridCoords::new(1, 2), Color::RED));
+        assert!(!tile_commands.set_texture_index(level, "Blocks", GridCoords::new(0, 0), 7));
+        assert!(!tile_commands.set_texture_index(level, "Walls", GridCoords::new(1, 2), 7));
+        system_state.apply(&mut world);
+
+        assert_eq!(world.get::<TileTextureIndex>(tile).unwrap().0, 7);
+        assert_eq!(world.get::<TileColor>(tile).unwrap().0, Color::RED);
+
+        let mut tile_commands = system_state.get_mut(&mut world);
+        assert!(tile_commands.remove(level, "Blocks", GridCoords::new(1, 2)));
+        assert!(!tile_commands.remove(level, "Blocks", GridCoords::new(1, 2)));
+        system_state.apply(&mut world);
+
+        assert!(world.get_entity(tile).is_none());
+        assert_eq!(
+            world
+                .get::<TileStorage>(layer)
+                .unwrap()
+                .get(&TilePos::new(1, 2)),
+            None
+        );
+        assert!(world.get::<TileDataTable>(layer).unwrap().is_empty());
+        assert!(world.get::<IntGridCells>(layer).unwrap().is_empty());
+    }
+}
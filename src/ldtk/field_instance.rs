@@ -189,7 +189,7 @@ impl<'de> Deserialize<'de> for FieldInstance {
 }
 
 #[derive(PartialEq, Debug, Clone, Serialize, Reflect)]
-#[reflect_value()]
+#[reflect(Debug, PartialEq)]
 #[serde(untagged)]
 /// The actual value of a field instance on a [Level] or [EntityInstance].
 ///
@@ -242,3 +242,26 @@ fn serialize_points<S: Serializer>(
     let field_values: Vec<FieldValue> = points.iter().map(|p| FieldValue::Point(*p)).collect();
     field_values.serialize(serializer)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::reflect::{Enum, ReflectRef};
+
+    #[test]
+    fn field_values_reflect_as_enums() {
+        let value = FieldValue::Ints(vec![Some(3), None]);
+
+        let ReflectRef::Enum(reflected) = value.reflect_ref() else {
+            panic!("expected FieldValue to reflect as an enum");
+        };
+
+        assert_eq!(reflected.variant_name(), "Ints");
+        assert_eq!(
+            reflected
+                .field_at(0)
+                .and_then(|field| field.downcast_ref::<Vec<Option<i32>>>()),
+            Some(&vec![Some(3), None])
+        );
+    }
+}
@@ -1,4 +1,7 @@
-use crate::ldtk::{Definitions, Type};
+use crate::ldtk::{
+    Definitions, IntGridValueDefinition, IntGridValueGroupDefinition, LayerDefinition,
+    TilesetDefinition, TilesetRectangle, Type,
+};
 use bevy::{
     prelude::*,
     render::render_resource::{Extent3d, TextureDimension, TextureFormat},
@@ -32,12 +35,170 @@ impl Definitions {
     }
 }
 
+impl LayerDefinition {
+    /// Returns the definition of the given IntGrid value in this layer, if any.
+    pub fn int_grid_value_definition(&self, value: i32) -> Option<&IntGridValueDefinition> {
+        self.int_grid_values
+            .iter()
+            .find(|value_definition| value_definition.value == value)
+    }
+
+    /// Returns the group the given IntGrid value belongs to in this layer, if any.
+    pub fn int_grid_value_group(&self, value: i32) -> Option<&IntGridValueGroupDefinition> {
+        let group_uid = self.int_grid_value_definition(value)?.group_uid;
+
+        self.int_grid_values_groups
+            .iter()
+            .find(|group| group.uid == group_uid)
+    }
+}
+
+impl TilesetDefinition {
+    /// Creates a [TextureAtlas] slicing the given tileset image into this tileset's grid.
+    ///
+    /// Accounts for the tileset's padding and spacing, so atlas indices match LDtk's tile ids.
+    pub fn create_texture_atlas(&self, texture: Handle<Image>) -> TextureAtlas {
+        TextureAtlas::from_grid(
+            texture,
+            Vec2::splat(self.tile_grid_size as f32),
+            self.c_wid as usize,
+            self.c_hei as usize,
+            Some(Vec2::splat(self.spacing as f32)),
+            Some(Vec2::splat(self.padding as f32)),
+        )
+    }
+
+    /// Returns the index of the tile covered by the given rectangle in this tileset's
+    /// [TextureAtlas], as created by [TilesetDefinition::create_texture_atlas].
+    ///
+    /// Returns `None` if the rectangle isn't exactly one tile of this tileset's grid.
+    pub fn atlas_index(&self, tileset_rectangle: &TilesetRectangle) -> Option<usize> {
+        let cell_size = self.tile_grid_size + self.spacing;
+        let TilesetRectangle { x, y, w, h, .. } = *tileset_rectangle;
+
+        if cell_size <= 0
+            || w != self.tile_grid_size
+            || h != self.tile_grid_size
+            || x < self.padding
+            || y < self.padding
+            || (x - self.padding) % cell_size != 0
+            || (y - self.padding) % cell_size != 0
+        {
+            return None;
+        }
+
+        let column = (x - self.padding) / cell_size;
+        let row = (y - self.padding) / cell_size;
+
+        (column < self.c_wid && row < self.c_hei).then_some((row * self.c_wid + column) as usize)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::ldtk::LayerDefinition;
 
     use super::*;
 
+    #[test]
+    fn texture_atlas_accounts_for_padding_and_spacing() {
+        let tileset_definition = TilesetDefinition {
+            tile_grid_size: 16,
+            c_wid: 3,
+            c_hei: 2,
+            padding: 1,
+            spacing: 2,
+            ..default()
+        };
+
+        let texture_atlas = tileset_definition.create_texture_atlas(Handle::default());
+
+        assert_eq!(texture_atlas.len(), 6);
+        assert_eq!(texture_atlas.textures[4], Rect::new(19., 19., 35., 35.),);
+    }
+
+    #[test]
+    fn atlas_index_of_single_tiles() {
+        let tileset_definition = TilesetDefinition {
+            tile_grid_size: 16,
+            c_wid: 3,
+            c_hei: 2,
+            padding: 1,
+            spacing: 2,
+            ..default()
+        };
+
+        let rectangle = |x, y, w, h| TilesetRectangle {
+            x,
+            y,
+            w,
+            h,
+            ..default()
+        };
+
+        assert_eq!(
+            tileset_definition.atlas_index(&rectangle(1, 1, 16, 16)),
+            Some(0)
+        );
+        assert_eq!(
+            tileset_definition.atlas_index(&rectangle(19, 19, 16, 16)),
+            Some(4)
+        );
+        assert_eq!(
+            tileset_definition.atlas_index(&rectangle(19, 19, 32, 16)),
+            None
+        );
+        assert_eq!(
+            tileset_definition.atlas_index(&rectangle(20, 19, 16, 16)),
+            None
+        );
+        assert_eq!(
+            tileset_definition.atlas_index(&rectangle(55, 1, 16, 16)),
+            None
+        );
+    }
+
+    #[test]
+    fn int_grid_values_are_found_with_their_groups() {
+        let layer_definition = LayerDefinition {
+            int_grid_values: vec![
+                IntGridValueDefinition {
+                    value: 1,
+                    identifier: Some("wall".to_string()),
+                    group_uid: 1,
+                    ..default()
+                },
+                IntGridValueDefinition {
+                    value: 2,
+                    identifier: Some("water".to_string()),
+                    ..default()
+                },
+            ],
+            int_grid_values_groups: vec![IntGridValueGroupDefinition {
+                uid: 1,
+                identifier: Some("solid".to_string()),
+                ..default()
+            }],
+            ..default()
+        };
+
+        assert_eq!(
+            layer_definition
+                .int_grid_value_definition(2)
+                .and_then(|value_definition| value_definition.identifier.as_deref()),
+            Some("water")
+        );
+        assert_eq!(layer_definition.int_grid_value_definition(3), None);
+
+        assert_eq!(
+            layer_definition
+                .int_grid_value_group(1)
+                .and_then(|group| group.identifier.as_deref()),
+            Some("solid")
+        );
+        assert_eq!(layer_definition.int_grid_value_group(2), None);
+    }
+
     #[test]
     fn int_grid_image_is_white() {
         let definitions = Definitions {
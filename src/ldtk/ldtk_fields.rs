@@ -218,6 +218,19 @@ impl LdtkFields for Level {
     }
 }
 
+/// Creates a [FieldInstance] with the given identifier and value, leaving the rest default.
+#[cfg(test)]
+pub(crate) fn field_instance_from_value(identifier: &str, value: FieldValue) -> FieldInstance {
+    FieldInstance {
+        identifier: identifier.to_string(),
+        value,
+        field_instance_type: "".to_string(),
+        tile: None,
+        def_uid: 0,
+        real_editor_values: Vec::new(),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -228,17 +241,6 @@ mod tests {
         }
     }
 
-    fn field_instance_from_value(identifier: &str, value: FieldValue) -> FieldInstance {
-        FieldInstance {
-            identifier: identifier.to_string(),
-            value,
-            field_instance_type: "".to_string(),
-            tile: None,
-            def_uid: 0,
-            real_editor_values: Vec::new(),
-        }
-    }
-
     fn sample_field_instances() -> Vec<FieldInstance> {
         use FieldValue::*;
         vec![
@@ -205,6 +205,7 @@ mod tests {
             world_depth: 5,
             world_x: 6,
             world_y: 7,
+            extras: Default::default(),
         }
     }
 
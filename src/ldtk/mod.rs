@@ -24,6 +24,9 @@
 //! 10. All urls in docs have been changed to hyperlinks with `<>`
 //! 11. `From<&EntityInstance>` implemented for [`EntityInstance]`
 //! 12. [`LayerInstance::layer_instance_type`] changed from [`String`] to [`Type`].
+//! 13. Structs that aren't [Copy], except [TileInstance], have an `extras` field.
+//!     With the "extras" feature, it captures the fields that aren't part of this schema, so data
+//!     added by newer versions of LDtk can still be reached. Otherwise, it is always empty.
 
 use bevy::{
     prelude::{Color, Component, IVec2, Vec2},
@@ -43,6 +46,7 @@ mod field_instance;
 mod impl_definitions;
 pub mod ldtk_fields;
 pub mod loaded_level;
+pub mod neighbor_direction;
 pub mod raw_level_accessor;
 
 pub use field_instance::*;
@@ -210,6 +214,11 @@ pub struct LdtkJson {
     /// supporting this future update easily, please refer to this documentation:
     /// <https://github.com/deepnight/ldtk/issues/231>
     pub worlds: Vec<World>,
+
+    #[cfg_attr(feature = "extras", serde(flatten))]
+    #[cfg_attr(not(feature = "extras"), serde(skip))]
+    #[reflect(ignore)]
+    pub extras: HashMap<String, serde_json::Value>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default, Eq, PartialEq, Reflect)]
@@ -218,6 +227,11 @@ pub struct LdtkCustomCommand {
 
     /// Possible values: `Manual`, `AfterLoad`, `BeforeSave`, `AfterSave`
     pub when: When,
+
+    #[cfg_attr(feature = "extras", serde(flatten))]
+    #[cfg_attr(not(feature = "extras"), serde(skip))]
+    #[reflect(ignore)]
+    pub extras: HashMap<String, serde_json::Value>,
 }
 
 /// Possible values: `Manual`, `AfterLoad`, `BeforeSave`, `AfterSave`
@@ -265,6 +279,11 @@ pub struct Definitions {
 
     /// All tilesets
     pub tilesets: Vec<TilesetDefinition>,
+
+    #[cfg_attr(feature = "extras", serde(flatten))]
+    #[cfg_attr(not(feature = "extras"), serde(skip))]
+    #[reflect(ignore)]
+    pub extras: HashMap<String, serde_json::Value>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq, Reflect)]
@@ -373,6 +392,11 @@ pub struct EntityDefinition {
 
     /// Pixel width
     pub width: i32,
+
+    #[cfg_attr(feature = "extras", serde(flatten))]
+    #[cfg_attr(not(feature = "extras"), serde(skip))]
+    #[reflect(ignore)]
+    pub extras: HashMap<String, serde_json::Value>,
 }
 
 /// This section is mostly only intended for the LDtk editor app itself. You can safely
@@ -484,6 +508,11 @@ pub struct FieldDefinition {
     /// color in the editor UI. For Enum fields, this would be the color associated to their
     /// values.
     pub use_for_smart_color: bool,
+
+    #[cfg_attr(feature = "extras", serde(flatten))]
+    #[cfg_attr(not(feature = "extras"), serde(skip))]
+    #[reflect(ignore)]
+    pub extras: HashMap<String, serde_json::Value>,
 }
 
 /// Possible values: `Any`, `OnlySame`, `OnlyTags`, `OnlySpecificEntity`
@@ -727,6 +756,11 @@ pub struct EnumDefinition {
 
     /// All possible enum values, with their optional Tile infos.
     pub values: Vec<EnumValueDefinition>,
+
+    #[cfg_attr(feature = "extras", serde(flatten))]
+    #[cfg_attr(not(feature = "extras"), serde(skip))]
+    #[reflect(ignore)]
+    pub extras: HashMap<String, serde_json::Value>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default, Eq, PartialEq, Reflect)]
@@ -749,6 +783,11 @@ pub struct EnumValueDefinition {
 
     /// Optional tileset rectangle to represents this value
     pub tile_rect: Option<TilesetRectangle>,
+
+    #[cfg_attr(feature = "extras", serde(flatten))]
+    #[cfg_attr(not(feature = "extras"), serde(skip))]
+    #[reflect(ignore)]
+    pub extras: HashMap<String, serde_json::Value>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq, Reflect)]
@@ -858,6 +897,11 @@ pub struct LayerDefinition {
 
     /// Unique Int identifier
     pub uid: i32,
+
+    #[cfg_attr(feature = "extras", serde(flatten))]
+    #[cfg_attr(not(feature = "extras"), serde(skip))]
+    #[reflect(ignore)]
+    pub extras: HashMap<String, serde_json::Value>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq, Reflect)]
@@ -881,6 +925,11 @@ pub struct AutoLayerRuleGroup {
     pub uid: i32,
 
     pub uses_wizard: bool,
+
+    #[cfg_attr(feature = "extras", serde(flatten))]
+    #[cfg_attr(not(feature = "extras"), serde(skip))]
+    #[reflect(ignore)]
+    pub extras: HashMap<String, serde_json::Value>,
 }
 
 /// This complex section isn't meant to be used by game devs at all, as these rules are
@@ -972,6 +1021,11 @@ pub struct AutoLayerRuleDefinition {
 
     /// Y cell start offset
     pub y_offset: i32,
+
+    #[cfg_attr(feature = "extras", serde(flatten))]
+    #[cfg_attr(not(feature = "extras"), serde(skip))]
+    #[reflect(ignore)]
+    pub extras: HashMap<String, serde_json::Value>,
 }
 
 /// Checker mode Possible values: `None`, `Horizontal`, `Vertical`
@@ -1011,6 +1065,11 @@ pub struct IntGridValueDefinition {
 
     /// The IntGrid value itself
     pub value: i32,
+
+    #[cfg_attr(feature = "extras", serde(flatten))]
+    #[cfg_attr(not(feature = "extras"), serde(skip))]
+    #[reflect(ignore)]
+    pub extras: HashMap<String, serde_json::Value>,
 }
 
 /// IntGrid value group definition
@@ -1024,6 +1083,11 @@ pub struct IntGridValueGroupDefinition {
 
     /// Group unique ID
     pub uid: i32,
+
+    #[cfg_attr(feature = "extras", serde(flatten))]
+    #[cfg_attr(not(feature = "extras"), serde(skip))]
+    #[reflect(ignore)]
+    pub extras: HashMap<String, serde_json::Value>,
 }
 
 /// Type of the layer as Haxe Enum Possible values: `IntGrid`, `Entities`, `Tiles`,
@@ -1105,6 +1169,11 @@ pub struct TilesetDefinition {
 
     /// Unique Intidentifier
     pub uid: i32,
+
+    #[cfg_attr(feature = "extras", serde(flatten))]
+    #[cfg_attr(not(feature = "extras"), serde(skip))]
+    #[reflect(ignore)]
+    pub extras: HashMap<String, serde_json::Value>,
 }
 
 /// In a tileset definition, user defined meta-data of a tile.
@@ -1114,6 +1183,11 @@ pub struct TileCustomMetadata {
     pub data: String,
 
     pub tile_id: i32,
+
+    #[cfg_attr(feature = "extras", serde(flatten))]
+    #[cfg_attr(not(feature = "extras"), serde(skip))]
+    #[reflect(ignore)]
+    pub extras: HashMap<String, serde_json::Value>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Copy, Default, Eq, PartialEq, Reflect)]
@@ -1130,6 +1204,11 @@ pub struct EnumTagValue {
     pub enum_value_id: String,
 
     pub tile_ids: Vec<i32>,
+
+    #[cfg_attr(feature = "extras", serde(flatten))]
+    #[cfg_attr(not(feature = "extras"), serde(skip))]
+    #[reflect(ignore)]
+    pub extras: HashMap<String, serde_json::Value>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Copy, Default, Eq, PartialEq, Reflect)]
@@ -1213,6 +1292,11 @@ pub struct ForcedRefs {
     pub tileset_rect: Option<TilesetRectangle>,
 
     pub world: Option<World>,
+
+    #[cfg_attr(feature = "extras", serde(flatten))]
+    #[cfg_attr(not(feature = "extras"), serde(skip))]
+    #[reflect(ignore)]
+    pub extras: HashMap<String, serde_json::Value>,
 }
 
 /// Component added to any LDtk Entity by default.
@@ -1221,6 +1305,7 @@ pub struct ForcedRefs {
 /// `Added<EntityInstance>`.
 /// Or, you can hook into the entity's spawning process using [LdtkEntity].
 #[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq, Reflect, Component)]
+#[reflect(Component, Default, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct EntityInstance {
     /// Grid-based coordinates (`[x,y]` format)
@@ -1277,6 +1362,11 @@ pub struct EntityInstance {
     /// Entity width in pixels. For non-resizable entities, it will be the same as Entity
     /// definition.
     pub width: i32,
+
+    #[cfg_attr(feature = "extras", serde(flatten))]
+    #[cfg_attr(not(feature = "extras"), serde(skip))]
+    #[reflect(ignore)]
+    pub extras: HashMap<String, serde_json::Value>,
 }
 
 impl From<&EntityInstance> for EntityInstance {
@@ -1300,6 +1390,11 @@ pub struct ReferenceToAnEntityInstance {
 
     /// IID of the World containing the refered EntityInstance
     pub world_iid: String,
+
+    #[cfg_attr(feature = "extras", serde(flatten))]
+    #[cfg_attr(not(feature = "extras"), serde(skip))]
+    #[reflect(ignore)]
+    pub extras: HashMap<String, serde_json::Value>,
 }
 
 /// This object is just a grid-based coordinate used in Field values.
@@ -1417,6 +1512,11 @@ pub struct LayerInstance {
 
     /// Layer instance visibility
     pub visible: bool,
+
+    #[cfg_attr(feature = "extras", serde(flatten))]
+    #[cfg_attr(not(feature = "extras"), serde(skip))]
+    #[reflect(ignore)]
+    pub extras: HashMap<String, serde_json::Value>,
 }
 
 /// This structure represents a single tile from a given Tileset.
@@ -1543,6 +1643,11 @@ pub struct Level {
     /// positioning is manual (ie. GridVania, Free). For Horizontal and Vertical layouts, the
     /// value is always -1 here.
     pub world_y: i32,
+
+    #[cfg_attr(feature = "extras", serde(flatten))]
+    #[cfg_attr(not(feature = "extras"), serde(skip))]
+    #[reflect(ignore)]
+    pub extras: HashMap<String, serde_json::Value>,
 }
 
 /// Level background image position info
@@ -1561,6 +1666,11 @@ pub struct LevelBackgroundPosition {
     /// An array containing the `[x,y]` pixel coordinates of the top-left corner of the
     /// **cropped** background image, depending on `bgPos` option.
     pub top_left_px: IVec2,
+
+    #[cfg_attr(feature = "extras", serde(flatten))]
+    #[cfg_attr(not(feature = "extras"), serde(skip))]
+    #[reflect(ignore)]
+    pub extras: HashMap<String, serde_json::Value>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Copy, Default, Eq, PartialEq, Reflect)]
@@ -1594,6 +1704,11 @@ pub struct NeighbourLevel {
     /// **WARNING**: this deprecated value is no longer exported since version 1.2.0  Replaced
     /// by: `levelIid`
     pub level_uid: Option<i32>,
+
+    #[cfg_attr(feature = "extras", serde(flatten))]
+    #[cfg_attr(not(feature = "extras"), serde(skip))]
+    #[reflect(ignore)]
+    pub extras: HashMap<String, serde_json::Value>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default, Eq, PartialEq, Reflect)]
@@ -1601,6 +1716,11 @@ pub struct LdtkTableOfContentEntry {
     pub identifier: String,
 
     pub instances: Vec<ReferenceToAnEntityInstance>,
+
+    #[cfg_attr(feature = "extras", serde(flatten))]
+    #[cfg_attr(not(feature = "extras"), serde(skip))]
+    #[reflect(ignore)]
+    pub extras: HashMap<String, serde_json::Value>,
 }
 
 /// **IMPORTANT**: this type is available as a preview. You can rely on it to update your
@@ -1635,6 +1755,11 @@ pub struct World {
     /// An enum that describes how levels are organized in this project (ie. linearly or in a 2D
     /// space). Possible values: `Free`, `GridVania`, `LinearHorizontal`, `LinearVertical`, `null`
     pub world_layout: Option<WorldLayout>,
+
+    #[cfg_attr(feature = "extras", serde(flatten))]
+    #[cfg_attr(not(feature = "extras"), serde(skip))]
+    #[reflect(ignore)]
+    pub extras: HashMap<String, serde_json::Value>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Copy, Default, Eq, PartialEq, Reflect)]
@@ -1682,3 +1807,22 @@ pub enum ImageExportMode {
     #[serde(rename = "OneImagePerLevel")]
     OneImagePerLevel,
 }
+
+#[cfg(all(test, feature = "extras"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unknown_fields_are_kept_in_extras() {
+        let tile_custom_metadata: TileCustomMetadata =
+            serde_json::from_str(r#"{ "data": "solid", "tileId": 3, "futureField": [1, 2] }"#)
+                .unwrap();
+
+        assert_eq!(tile_custom_metadata.data, "solid");
+        assert_eq!(tile_custom_metadata.tile_id, 3);
+        assert_eq!(
+            tile_custom_metadata.extras,
+            HashMap::from([("futureField".to_string(), serde_json::json!([1, 2]))])
+        );
+    }
+}
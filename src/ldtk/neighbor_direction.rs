@@ -0,0 +1,96 @@
+//! Contains [`NeighborDirection`]: the location of a level's neighbor relative to it.
+use crate::ldtk::NeighbourLevel;
+
+/// Location of a neighboring level, parsed from the `dir` code of a [`NeighbourLevel`].
+///
+/// Corner directions are only exported by recent versions of LDtk.
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Hash)]
+pub enum NeighborDirection {
+    North,
+    South,
+    West,
+    East,
+    NorthWest,
+    NorthEast,
+    SouthWest,
+    SouthEast,
+    /// The neighbor's world depth is lower.
+    Lower,
+    /// The neighbor's world depth is greater.
+    Greater,
+    /// The levels overlap and share the same world depth.
+    Overlap,
+}
+
+impl NeighborDirection {
+    /// Parses LDtk's direction code, returning [`None`] for unknown codes.
+    pub fn from_dir(dir: &str) -> Option<NeighborDirection> {
+        Some(match dir {
+            "n" => NeighborDirection::North,
+            "s" => NeighborDirection::South,
+            "w" => NeighborDirection::West,
+            "e" => NeighborDirection::East,
+            "nw" => NeighborDirection::NorthWest,
+            "ne" => NeighborDirection::NorthEast,
+            "sw" => NeighborDirection::SouthWest,
+            "se" => NeighborDirection::SouthEast,
+            "<" => NeighborDirection::Lower,
+            ">" => NeighborDirection::Greater,
+            "o" => NeighborDirection::Overlap,
+            _ => return None,
+        })
+    }
+
+    /// LDtk's direction code for this direction.
+    pub fn dir(&self) -> &'static str {
+        match self {
+            NeighborDirection::North => "n",
+            NeighborDirection::South => "s",
+            NeighborDirection::West => "w",
+            NeighborDirection::East => "e",
+            NeighborDirection::NorthWest => "nw",
+            NeighborDirection::NorthEast => "ne",
+            NeighborDirection::SouthWest => "sw",
+            NeighborDirection::SouthEast => "se",
+            NeighborDirection::Lower => "<",
+            NeighborDirection::Greater => ">",
+            NeighborDirection::Overlap => "o",
+        }
+    }
+}
+
+impl NeighbourLevel {
+    /// The [`NeighborDirection`] of this neighbor, if its `dir` code is known.
+    pub fn direction(&self) -> Option<NeighborDirection> {
+        NeighborDirection::from_dir(&self.dir)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dir_codes_round_trip() {
+        for direction in [
+            NeighborDirection::North,
+            NeighborDirection::South,
+            NeighborDirection::West,
+            NeighborDirection::East,
+            NeighborDirection::NorthWest,
+            NeighborDirection::NorthEast,
+            NeighborDirection::SouthWest,
+            NeighborDirection::SouthEast,
+            NeighborDirection::Lower,
+            NeighborDirection::Greater,
+            NeighborDirection::Overlap,
+        ] {
+            assert_eq!(
+                NeighborDirection::from_dir(direction.dir()),
+                Some(direction)
+            );
+        }
+
+        assert_eq!(NeighborDirection::from_dir("x"), None);
+    }
+}
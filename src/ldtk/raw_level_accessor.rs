@@ -1,6 +1,6 @@
 //! Contains [`RawLevelAccessor`]: convenience methods for accessing raw level data by reference.
 use crate::{
-    ldtk::{LdtkJson, Level, World},
+    ldtk::{LdtkJson, Level, World, WorldLayout},
     prelude::LevelIndices,
 };
 
@@ -126,6 +126,63 @@ pub trait RawLevelAccessor {
             None => self.root_levels().get(indices.level),
         }
     }
+
+    /// The [`WorldLayout`] of this project's [root levels](RawLevelAccessor#root-vs-world-levels).
+    ///
+    /// Returns [`None`] by default, for types that don't store it.
+    fn root_world_layout(&self) -> Option<WorldLayout> {
+        None
+    }
+
+    /// The [`WorldLayout`] of the world containing the level at the given [`LevelIndices`].
+    fn world_layout_at_indices(&self, indices: &LevelIndices) -> Option<WorldLayout> {
+        match indices.world {
+            Some(world_index) => self.worlds().get(world_index)?.world_layout,
+            None => self.root_world_layout(),
+        }
+    }
+
+    /// Iids of the levels neighboring the level at the given [`LevelIndices`].
+    ///
+    /// These are the levels in its `__neighbours` list.
+    /// In [`WorldLayout::LinearHorizontal`] and [`WorldLayout::LinearVertical`] worlds, where
+    /// neighbors are implicit in the level order, the levels right before and after it are
+    /// included as well.
+    fn raw_level_neighbor_iids(&self, indices: &LevelIndices) -> Vec<&String> {
+        let Some(level) = self.get_raw_level_at_indices(indices) else {
+            return Vec::new();
+        };
+
+        let mut neighbor_iids: Vec<&String> = level
+            .neighbours
+            .iter()
+            .map(|neighbour| &neighbour.level_iid)
+            .collect();
+
+        if let Some(WorldLayout::LinearHorizontal | WorldLayout::LinearVertical) =
+            self.world_layout_at_indices(indices)
+        {
+            let adjacent_levels = indices
+                .level
+                .checked_sub(1)
+                .into_iter()
+                .chain(Some(indices.level + 1))
+                .filter_map(|level| {
+                    self.get_raw_level_at_indices(&LevelIndices {
+                        world: indices.world,
+                        level,
+                    })
+                });
+
+            for adjacent_level in adjacent_levels {
+                if !neighbor_iids.contains(&&adjacent_level.iid) {
+                    neighbor_iids.push(&adjacent_level.iid);
+                }
+            }
+        }
+
+        neighbor_iids
+    }
 }
 
 impl RawLevelAccessor for LdtkJson {
@@ -136,6 +193,10 @@ impl RawLevelAccessor for LdtkJson {
     fn worlds(&self) -> &[World] {
         &self.worlds
     }
+
+    fn root_world_layout(&self) -> Option<WorldLayout> {
+        self.world_layout
+    }
 }
 
 #[cfg(test)]
@@ -148,6 +209,7 @@ pub mod tests {
     };
 
     use super::*;
+    use crate::ldtk::NeighbourLevel;
 
     #[test]
     fn iter_levels_in_root() {
@@ -348,4 +410,57 @@ pub mod tests {
             None
         );
     }
+
+    #[test]
+    fn neighbors_follow_world_layout() {
+        let level = |iid: &str, neighbours: &[&str]| Level {
+            iid: iid.to_string(),
+            neighbours: neighbours
+                .iter()
+                .map(|neighbour| NeighbourLevel {
+                    level_iid: neighbour.to_string(),
+                    ..Default::default()
+                })
+                .collect(),
+            ..Default::default()
+        };
+
+        let mut project = LdtkJson {
+            levels: vec![level("a", &[]), level("b", &["d"]), level("c", &[])],
+            world_layout: Some(WorldLayout::LinearHorizontal),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            project.raw_level_neighbor_iids(&LevelIndices::in_root(0)),
+            vec!["b"]
+        );
+        assert_eq!(
+            project.raw_level_neighbor_iids(&LevelIndices::in_root(1)),
+            vec!["d", "a", "c"]
+        );
+        assert_eq!(
+            project.raw_level_neighbor_iids(&LevelIndices::in_root(2)),
+            vec!["b"]
+        );
+        assert!(project
+            .raw_level_neighbor_iids(&LevelIndices::in_root(3))
+            .is_empty());
+
+        project.world_layout = Some(WorldLayout::GridVania);
+        assert_eq!(
+            project.raw_level_neighbor_iids(&LevelIndices::in_root(1)),
+            vec!["d"]
+        );
+
+        project.worlds = vec![World {
+            levels: project.levels.drain(..).collect(),
+            world_layout: Some(WorldLayout::LinearVertical),
+            ..Default::default()
+        }];
+        assert_eq!(
+            project.raw_level_neighbor_iids(&LevelIndices::in_world(0, 2)),
+            vec!["b"]
+        );
+    }
 }
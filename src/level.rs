@@ -2,27 +2,38 @@
 
 use crate::{
     app::{
-        LdtkEntity, LdtkEntityMap, LdtkIntCellMap, PhantomLdtkEntity, PhantomLdtkEntityTrait,
-        PhantomLdtkIntCell, PhantomLdtkIntCellTrait,
+        EntityZContext, LayerZContext, LdtkEntity, LdtkEntityMap, LdtkIntCellMap,
+        PhantomLdtkEntity, PhantomLdtkEntityTrait, PhantomLdtkIntCell, PhantomLdtkIntCellTrait,
+        ZOrderingStrategy,
     },
     components::*,
     ldtk::{
-        loaded_level::LoadedLevel, EntityDefinition, EnumTagValue, LayerDefinition, LayerInstance,
-        LevelBackgroundPosition, TileCustomMetadata, TileInstance, TilesetDefinition, Type,
+        loaded_level::LoadedLevel, EntityDefinition, EntityInstance, EnumTagValue, FieldValue,
+        LayerDefinition, LayerInstance, LevelBackgroundPosition, TileCustomMetadata, TileInstance,
+        TilesetDefinition, Type,
+    },
+    resources::{
+        group_entity_instances, BackgroundRepeat, BundlePhase, DeferredEntityBundle,
+        DeferredEntityGroup, DeferredIntCellBundle, DeferredLdtkBundles, DeterministicSpawning,
+        EntityEditorVisuals, EntityInstanceGroup, GridShape, IntGridCellStorage, IntGridRendering,
+        IntGridTextures, LdtkEntityPool, LdtkError, LdtkSettings, LevelBackground,
+        LevelEntityInstanceGroups, PersistentEntityState, TileMetadataStorage,
     },
-    resources::{IntGridRendering, LdtkSettings, LevelBackground},
     tile_makers::*,
     utils::*,
 };
 
-use bevy::prelude::*;
+use bevy::{ecs::system::EntityCommands, prelude::*};
 use bevy_ecs_tilemap::{
     map::{
         TilemapGridSize, TilemapId, TilemapSize, TilemapSpacing, TilemapTexture, TilemapTileSize,
     },
     tiles::{TilePos, TileStorage},
 };
-use std::collections::{HashMap, HashSet};
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    sync::Arc,
+};
 
 #[cfg(feature = "render")]
 use bevy_ecs_tilemap::TilemapBundle;
@@ -105,34 +116,100 @@ pub(crate) fn tile_to_grid_coords(
     )
 }
 
+#[allow(clippy::too_many_arguments)]
 fn insert_metadata_to_tile(
     commands: &mut Commands,
     tile_instance: &TileInstance,
     tile_entity: Entity,
+    grid_coords: GridCoords,
     metadata_map: &HashMap<i32, TileMetadata>,
     enum_tags_map: &HashMap<i32, TileEnumTags>,
+    animation_map: &HashMap<i32, TileAnimation>,
+    tile_data_table: Option<&mut TileDataTable>,
 ) -> bool {
     let mut entity_commands = commands.entity(tile_entity);
 
     let mut metadata_inserted = false;
 
-    if let Some(tile_metadata) = metadata_map.get(&tile_instance.t) {
-        entity_commands.insert(tile_metadata.clone());
-        metadata_inserted = true;
+    let tile_metadata = metadata_map.get(&tile_instance.t);
+    let enum_tags = enum_tags_map.get(&tile_instance.t);
+
+    match tile_data_table {
+        Some(tile_data_table) => {
+            if let Some(tile_metadata) = tile_metadata {
+                tile_data_table
+                    .metadata
+                    .insert(grid_coords, tile_metadata.clone());
+            }
+
+            if let Some(enum_tags) = enum_tags {
+                tile_data_table
+                    .enum_tags
+                    .insert(grid_coords, enum_tags.clone());
+            }
+        }
+        None => {
+            if let Some(tile_metadata) = tile_metadata {
+                entity_commands.insert(tile_metadata.clone());
+                metadata_inserted = true;
+            }
+
+            if let Some(enum_tags) = enum_tags {
+                entity_commands.insert(enum_tags.clone());
+                metadata_inserted = true;
+            }
+        }
     }
 
-    if let Some(enum_tags) = enum_tags_map.get(&tile_instance.t) {
-        entity_commands.insert(enum_tags.clone());
+    if let Some(animation) = animation_map.get(&tile_instance.t) {
+        match animation.as_animated_tile() {
+            Some(animated_tile) => entity_commands.insert(animated_tile),
+            None => entity_commands.insert(animation.clone()),
+        };
         metadata_inserted = true;
     }
 
     metadata_inserted
 }
 
-fn spatial_bundle_for_tiles(grid_coords: GridCoords, grid_size: i32) -> SpatialBundle {
-    let translation =
-        grid_coords_to_translation_relative_to_tile_layer(grid_coords, IVec2::splat(grid_size))
-            .extend(0.);
+/// Returns true if the cells with the given value in the given layer should be spawned with an
+/// [IntGridCell] or their registered bundle.
+///
+/// With [IntGridCellStorage::Sparse], only values with an [LdtkIntCell] registration are spawned.
+///
+/// [LdtkIntCell]: crate::app::LdtkIntCell
+fn spawns_int_grid_cell(
+    int_grid_cell_storage: IntGridCellStorage,
+    ldtk_int_cell_map: &LdtkIntCellMap,
+    layer_identifier: &str,
+    value: i32,
+) -> bool {
+    if value == 0 {
+        return false;
+    }
+
+    match int_grid_cell_storage {
+        IntGridCellStorage::Entities => true,
+        IntGridCellStorage::Sparse => try_each_optional_permutation(
+            layer_identifier.to_string(),
+            value,
+            |identifier, value| ldtk_int_cell_map.get(&(identifier, value)),
+        )
+        .is_some(),
+    }
+}
+
+fn spatial_bundle_for_tiles(
+    grid_coords: GridCoords,
+    grid_size: i32,
+    grid_shape: GridShape,
+) -> SpatialBundle {
+    let translation = grid_coords_to_translation_relative_to_tile_layer_with_shape(
+        grid_coords,
+        grid_shape.cell_size(grid_size),
+        grid_shape,
+    )
+    .extend(0.);
 
     SpatialBundle::from_transform(Transform::from_translation(translation))
 }
@@ -142,7 +219,9 @@ fn insert_spatial_bundle_for_layer_tiles(
     storage: &TileStorage,
     size: &TilemapSize,
     grid_size: i32,
+    grid_shape: GridShape,
     tilemap_id: TilemapId,
+    owning_level: &OwningLevel,
 ) {
     for x in 0..size.x {
         for y in 0..size.y {
@@ -150,30 +229,58 @@ fn insert_spatial_bundle_for_layer_tiles(
             let tile_entity = storage.get(&tile_pos);
 
             if let Some(tile_entity) = tile_entity {
-                let spatial_bundle = spatial_bundle_for_tiles(tile_pos.into(), grid_size);
+                let spatial_bundle =
+                    spatial_bundle_for_tiles(tile_pos.into(), grid_size, grid_shape);
 
-                commands.entity(tile_entity).insert(spatial_bundle);
+                commands
+                    .entity(tile_entity)
+                    .insert((spatial_bundle, owning_level.clone()));
                 commands.entity(tilemap_id.0).add_child(tile_entity);
             }
         }
     }
 }
 
+/// Inserts tile metadata, enum tags, and animations for the tiles of a tilemap.
+///
+/// With [TileMetadataStorage::LayerTables], metadata and enum tags are collected into a
+/// [TileDataTable] on the tilemap entity instead of being inserted on every tile.
 #[allow(clippy::too_many_arguments)]
 fn insert_tile_metadata_for_layer(
     commands: &mut Commands,
+    tilemap_entity: Entity,
     tile_storage: &TileStorage,
     grid_tiles: &[TileInstance],
     layer_instance: &LayerInstance,
     metadata_map: &HashMap<i32, TileMetadata>,
     enum_tags_map: &HashMap<i32, TileEnumTags>,
+    animation_map: &HashMap<i32, TileAnimation>,
+    tile_metadata_storage: TileMetadataStorage,
 ) {
+    let mut tile_data_table = match tile_metadata_storage {
+        TileMetadataStorage::Components => None,
+        TileMetadataStorage::LayerTables => Some(TileDataTable::default()),
+    };
+
     for tile in grid_tiles {
         let grid_coords = tile_to_grid_coords(tile, layer_instance.c_hei, layer_instance.grid_size);
 
         let tile_entity = tile_storage.get(&grid_coords.into()).unwrap();
 
-        insert_metadata_to_tile(commands, tile, tile_entity, metadata_map, enum_tags_map);
+        insert_metadata_to_tile(
+            commands,
+            tile,
+            tile_entity,
+            grid_coords,
+            metadata_map,
+            enum_tags_map,
+            animation_map,
+            tile_data_table.as_mut(),
+        );
+    }
+
+    if let Some(tile_data_table) = tile_data_table.filter(|table| !table.is_empty()) {
+        commands.entity(tilemap_entity).insert(tile_data_table);
     }
 }
 
@@ -196,6 +303,53 @@ fn layer_grid_tiles(grid_tiles: Vec<TileInstance>) -> Vec<Vec<TileInstance>> {
     layered_grid_tiles
 }
 
+/// Groups tiles by the y coordinate of the bottom of their grid row, relative to the layer.
+fn split_grid_tiles_by_row(
+    grid_tiles: Vec<TileInstance>,
+    layer_instance: &LayerInstance,
+) -> Vec<(f32, Vec<TileInstance>)> {
+    let mut rows: BTreeMap<i32, Vec<TileInstance>> = BTreeMap::new();
+
+    for tile in grid_tiles {
+        let grid_coords =
+            tile_to_grid_coords(&tile, layer_instance.c_hei, layer_instance.grid_size);
+        rows.entry(grid_coords.y).or_default().push(tile);
+    }
+
+    rows.into_iter()
+        .map(|(row, tiles)| ((row * layer_instance.grid_size) as f32, tiles))
+        .collect()
+}
+
+/// Groups tiles by the first of their enum tags that is in `split_enum_tags`.
+///
+/// Tiles without any of those tags are grouped under [None], which comes first.
+fn split_grid_tiles_by_enum_tag(
+    grid_tiles: Vec<TileInstance>,
+    enum_tags_map: &HashMap<i32, TileEnumTags>,
+    split_enum_tags: &HashSet<String>,
+) -> Vec<(Option<String>, Vec<TileInstance>)> {
+    if split_enum_tags.is_empty() || grid_tiles.is_empty() {
+        return vec![(None, grid_tiles)];
+    }
+
+    let mut groups: BTreeMap<Option<String>, Vec<TileInstance>> = BTreeMap::new();
+
+    for tile in grid_tiles {
+        let split_enum_tag = enum_tags_map.get(&tile.t).and_then(|enum_tags| {
+            enum_tags
+                .tags
+                .iter()
+                .find(|tag| split_enum_tags.contains(*tag))
+                .cloned()
+        });
+
+        groups.entry(split_enum_tag).or_default().push(tile);
+    }
+
+    groups.into_iter().collect()
+}
+
 fn tile_in_layer_bounds(tile: &TileInstance, layer_instance: &LayerInstance) -> bool {
     tile.px.x >= 0
         && tile.px.y >= 0
@@ -203,161 +357,643 @@ fn tile_in_layer_bounds(tile: &TileInstance, layer_instance: &LayerInstance) ->
         && tile.px.y < (layer_instance.c_hei * layer_instance.grid_size)
 }
 
-#[allow(clippy::too_many_arguments)]
+/// Spawns a child sprite resembling the entity's visual in the LDtk editor.
+///
+/// The child is sized relative to the entity's definition, since the entity's transform is
+/// already scaled to the size of the instance.
+fn spawn_editor_visual_placeholder(
+    entity_commands: &mut EntityCommands,
+    entity_instance: &EntityInstance,
+    entity_definition_map: &HashMap<i32, &EntityDefinition>,
+    tileset: Option<&Handle<Image>>,
+    tileset_definition: Option<&TilesetDefinition>,
+    texture_atlases: &mut Assets<TextureAtlas>,
+) {
+    let Some(entity_definition) = entity_definition_map.get(&entity_instance.def_uid) else {
+        return;
+    };
+
+    let def_size = match &entity_definition.tile_rect {
+        Some(tile) => Vec2::new(tile.w as f32, tile.h as f32),
+        None => Vec2::new(
+            entity_definition.width as f32,
+            entity_definition.height as f32,
+        ),
+    };
+
+    match (tileset, &entity_instance.tile, tileset_definition) {
+        (Some(tileset), Some(tile), Some(tileset_definition)) => {
+            let mut texture_atlas = TextureAtlas::new_empty(
+                tileset.clone(),
+                Vec2::new(
+                    tileset_definition.px_wid as f32,
+                    tileset_definition.px_hei as f32,
+                ),
+            );
+            texture_atlas.add_texture(Rect::new(
+                tile.x as f32,
+                tile.y as f32,
+                (tile.x + tile.w) as f32,
+                (tile.y + tile.h) as f32,
+            ));
+
+            entity_commands.with_children(|parent| {
+                parent.spawn((
+                    SpriteSheetBundle {
+                        texture_atlas: texture_atlases.add(texture_atlas),
+                        sprite: TextureAtlasSprite {
+                            color: Color::WHITE.with_a(entity_definition.tile_opacity),
+                            custom_size: Some(def_size),
+                            ..default()
+                        },
+                        ..default()
+                    },
+                    EditorVisualPlaceholder,
+                ));
+            });
+        }
+        _ => {
+            entity_commands.with_children(|parent| {
+                parent.spawn((
+                    SpriteBundle {
+                        sprite: Sprite {
+                            color: entity_instance
+                                .smart_color
+                                .with_a(entity_definition.fill_opacity),
+                            custom_size: Some(def_size),
+                            ..default()
+                        },
+                        ..default()
+                    },
+                    EditorVisualPlaceholder,
+                ));
+            });
+        }
+    }
+}
+
+/// Project data and settings used by every phase of spawning a level.
+#[derive(Clone, Copy)]
+pub struct LevelSpawnContext<'a> {
+    pub ldtk_settings: &'a LdtkSettings,
+    pub project_iid: &'a str,
+    pub entity_definition_map: &'a HashMap<i32, &'a EntityDefinition>,
+    pub layer_definition_map: &'a HashMap<i32, &'a LayerDefinition>,
+    pub tileset_definition_map: &'a HashMap<i32, &'a TilesetDefinition>,
+    pub tileset_map: &'a HashMap<i32, Handle<Image>>,
+    pub z_ordering: &'a dyn ZOrderingStrategy,
+}
+
+/// Assets used by the render phase, and by the bundles of the bundle phase.
+pub struct RenderPhaseContext<'a> {
+    pub asset_server: &'a AssetServer,
+    pub images: &'a mut Assets<Image>,
+    pub texture_atlases: &'a mut Assets<TextureAtlas>,
+    pub background_image: &'a Option<Handle<Image>>,
+    pub int_grid_image_handle: &'a Option<Handle<Image>>,
+}
+
+/// State deciding which entities the logic phase spawns, and the pool they're spawned from.
+pub struct LogicPhaseContext<'a> {
+    pub worldly_set: HashSet<Worldly>,
+    pub persistent_entity_state: &'a PersistentEntityState,
+    pub entity_pool: &'a mut LdtkEntityPool,
+}
+
+/// Registrations evaluated by the bundle phase, and the queue used when it's deferred.
+pub struct BundlePhaseContext<'a> {
+    pub ldtk_entity_map: &'a LdtkEntityMap,
+    pub ldtk_int_cell_map: &'a LdtkIntCellMap,
+    pub entity_instance_groups: &'a mut LevelEntityInstanceGroups,
+    pub deferred_bundles: &'a mut DeferredLdtkBundles,
+}
+
+/// Spawns the contents of a level as children of its `ldtk_entity`.
+///
+/// Returns the errors found in the level's data, which don't stop it from spawning.
 pub fn spawn_level(
-    level: LoadedLevel,
-    background_image: &Option<Handle<Image>>,
     commands: &mut Commands,
-    asset_server: &AssetServer,
-    images: &Assets<Image>,
-    texture_atlases: &mut Assets<TextureAtlas>,
-    ldtk_entity_map: &LdtkEntityMap,
-    ldtk_int_cell_map: &LdtkIntCellMap,
-    entity_definition_map: &HashMap<i32, &EntityDefinition>,
-    layer_definition_map: &HashMap<i32, &LayerDefinition>,
-    tileset_map: &HashMap<i32, Handle<Image>>,
-    tileset_definition_map: &HashMap<i32, &TilesetDefinition>,
-    int_grid_image_handle: &Option<Handle<Image>>,
-    worldly_set: HashSet<Worldly>,
+    level: LoadedLevel,
     ldtk_entity: Entity,
-    ldtk_settings: &LdtkSettings,
-) {
+    context: LevelSpawnContext,
+    render: RenderPhaseContext,
+    logic: LogicPhaseContext,
+    bundles: BundlePhaseContext,
+) -> Vec<LdtkError> {
+    let LevelSpawnContext {
+        ldtk_settings,
+        project_iid,
+        entity_definition_map,
+        layer_definition_map,
+        tileset_definition_map,
+        tileset_map,
+        z_ordering,
+    } = context;
+    let RenderPhaseContext {
+        asset_server,
+        images,
+        texture_atlases,
+        background_image,
+        int_grid_image_handle,
+    } = render;
+    let LogicPhaseContext {
+        worldly_set,
+        persistent_entity_state,
+        entity_pool,
+    } = logic;
+    let BundlePhaseContext {
+        ldtk_entity_map,
+        ldtk_int_cell_map,
+        entity_instance_groups,
+        deferred_bundles,
+    } = bundles;
+    let mut errors = Vec::new();
+
+    let _level_span = info_span!("spawn_level", level = %level.identifier()).entered();
+
     let layer_instances = level.layer_instances();
+    let level_iid = LevelIid::new(level.iid().clone());
+    let owning_level = OwningLevel(level_iid.clone());
+    let spawn_phases = ldtk_settings.spawn_phases;
 
-    let mut layer_z = 0;
+    // Deferred bundles of the level share these instead of cloning them for every item
+    let mut shared_tileset_definitions: HashMap<i32, Arc<TilesetDefinition>> = HashMap::new();
 
-    if ldtk_settings.level_background == LevelBackground::Rendered {
-        let translation = Vec3::new(*level.px_wid() as f32, *level.px_hei() as f32, 0.) / 2.;
+    let split_enum_tags: HashSet<String> = ldtk_settings
+        .material_enum_tags
+        .iter()
+        .chain(ldtk_settings.enum_tag_z_offsets.keys())
+        .cloned()
+        .collect();
+
+    errors.extend(
+        level
+            .field_instances()
+            .iter()
+            .filter(|field_instance| {
+                ldtk_settings
+                    .post_processing_fields
+                    .contains(&field_instance.identifier)
+                    && !matches!(
+                        field_instance.value,
+                        FieldValue::Color(_) | FieldValue::Float(_) | FieldValue::Int(_)
+                    )
+            })
+            .map(|field_instance| LdtkError::BadFieldType {
+                level: level_iid.clone(),
+                field: field_instance.identifier.clone(),
+                expected: "Color, Float, or Int".to_string(),
+                actual: field_instance.field_instance_type.clone(),
+            }),
+    );
+
+    commands
+        .entity(ldtk_entity)
+        .insert(LevelPostProcessing::from_level(
+            level.raw(),
+            &ldtk_settings.post_processing_fields,
+        ));
+
+    if let Some(level_reveal) = &ldtk_settings.level_reveal {
+        commands.entity(ldtk_entity).insert(LevelReveal {
+            elapsed: 0.,
+            ..level_reveal.clone()
+        });
+    }
 
-        let background_entity = commands
-            .spawn(SpriteBundle {
-                sprite: Sprite {
-                    color: *level.bg_color(),
-                    custom_size: Some(Vec2::new(*level.px_wid() as f32, *level.px_hei() as f32)),
+    let z_spacing = &ldtk_settings.z_spacing;
+
+    let mut layer_z = z_spacing.base;
+
+    let placed_layers: Vec<&LayerInstance> = layer_instances
+        .iter()
+        .filter(|layer| {
+            !ldtk_settings
+                .exclusions
+                .layer_identifiers
+                .contains(&layer.identifier)
+        })
+        .rev()
+        .collect();
+
+    let layer_placement = &ldtk_settings.layer_placement;
+    let playfield_index = layer_placement.playfield_index(&placed_layers);
+
+    // The background is placed one layer behind the bottom layer
+    let background_depth = -1. - playfield_index as f32;
+
+    if ldtk_settings.level_background == LevelBackground::Rendered {
+        let _background_span = info_span!("spawn_level_background").entered();
+
+        let translation = (Vec2::new(*level.px_wid() as f32, *level.px_hei() as f32) / 2.)
+            .extend(layer_placement.z(layer_z, z_spacing.base, background_depth));
+
+        if spawn_phases.render {
+            let background_entity = commands
+                .spawn(SpriteBundle {
+                    sprite: Sprite {
+                        color: *level.bg_color(),
+                        custom_size: Some(Vec2::new(
+                            *level.px_wid() as f32,
+                            *level.px_hei() as f32,
+                        )),
+                        ..default()
+                    },
+                    transform: Transform::from_translation(translation),
                     ..default()
-                },
-                transform: Transform::from_translation(translation),
-                ..default()
-            })
-            .id();
+                })
+                .id();
 
-        commands.entity(ldtk_entity).add_child(background_entity);
+            commands.entity(ldtk_entity).add_child(background_entity);
+        }
 
-        layer_z += 1;
+        layer_z += z_spacing.background_offset;
 
         // Spawn background image
-        if let (Some(background_image_handle), Some(background_position)) =
+        if let (Some(_), Some(_), false) = (background_image, level.bg_pos(), spawn_phases.render) {
+            // Layers keep the z they'd have with the image, so their transforms are the same with
+            // and without the render phase
+            layer_z += z_spacing.background_offset;
+        } else if let (Some(background_image_handle), Some(background_position)) =
             (background_image, level.bg_pos())
         {
+            let background_z = layer_placement.z(layer_z, z_spacing.base, background_depth);
+            let background_image_settings = &ldtk_settings.background_image;
+
+            let is_static = background_image_settings.parallax_factor == Vec2::ZERO
+                && background_image_settings.repeat == BackgroundRepeat::None;
+
             match background_image_sprite_sheet_bundle(
                 images,
                 texture_atlases,
                 background_image_handle,
                 background_position,
                 *level.px_hei(),
-                layer_z as f32,
+                if is_static { background_z } else { 0. },
             ) {
-                Ok(sprite_sheet_bundle) => {
+                Ok(sprite_sheet_bundle) if is_static => {
                     commands.entity(ldtk_entity).with_children(|parent| {
                         parent.spawn(sprite_sheet_bundle);
                     });
 
-                    layer_z += 1;
+                    layer_z += z_spacing.background_offset;
+                }
+                Ok(sprite_sheet_bundle) => {
+                    // Parallax and repetition move the image as a whole, so the image is wrapped
+                    // in an entity that they can move without affecting the image's own transform
+                    let base_translation = Vec3::new(0., 0., background_z);
+
+                    let mut background_image_entity =
+                        commands.spawn(SpatialBundle::from_transform(Transform::from_translation(
+                            base_translation,
+                        )));
+
+                    if background_image_settings.parallax_factor != Vec2::ZERO {
+                        background_image_entity.insert(LayerParallax {
+                            factor: background_image_settings.parallax_factor,
+                            scaling: background_image_settings.parallax_scaling,
+                            level_center: Vec2::new(*level.px_wid() as f32, *level.px_hei() as f32)
+                                / 2.,
+                            base_translation,
+                        });
+                    }
+
+                    if background_image_settings.repeat == BackgroundRepeat::None {
+                        background_image_entity.with_children(|parent| {
+                            parent.spawn(sprite_sheet_bundle);
+                        });
+                    } else {
+                        let image_transform = sprite_sheet_bundle.transform;
+                        let image_size = Vec2::new(
+                            background_position.crop_rect[2],
+                            background_position.crop_rect[3],
+                        );
+
+                        let repeating_background = RepeatingBackground {
+                            repeat: background_image_settings.repeat,
+                            origin: image_transform.translation.truncate(),
+                            size: image_size * image_transform.scale.truncate(),
+                            texture_atlas: sprite_sheet_bundle.texture_atlas,
+                            scale: image_transform.scale.truncate(),
+                        };
+
+                        // The image is shown where it is placed in LDtk until it's repeated
+                        background_image_entity.with_children(|parent| {
+                            parent.spawn(
+                                repeating_background.tile_bundle(repeating_background.origin),
+                            );
+                        });
+                        background_image_entity.insert(repeating_background);
+                    }
+
+                    let background_image_entity = background_image_entity.id();
+                    commands
+                        .entity(ldtk_entity)
+                        .add_child(background_image_entity);
+
+                    layer_z += z_spacing.background_offset;
                 }
                 Err(e) => warn!("{}", e),
             }
         }
     }
 
-    for layer_instance in layer_instances
-        .iter()
-        .filter(|layer| {
-            !ldtk_settings
-                .exclusions
-                .layer_identifiers
-                .contains(&layer.identifier)
-        })
-        .rev()
-    {
+    for (layer_index, layer_instance) in placed_layers.iter().copied().enumerate() {
+        let _layer_span = info_span!(
+            "spawn_layer",
+            layer = %layer_instance.identifier,
+            layer_type = ?layer_instance.layer_instance_type,
+        )
+        .entered();
+
+        let varied_layer_instance = ldtk_settings
+            .level_variation
+            .vary_layer(&level.raw().iid, layer_instance);
+        let layer_instance = varied_layer_instance.as_ref().unwrap_or(layer_instance);
+
+        let layer_start_z = layer_z;
+        let depth = layer_index as f32 - playfield_index as f32;
+        let placed_z = |painter_z: f32| {
+            z_ordering.layer_z(&LayerZContext {
+                level: level.raw(),
+                layer_instance,
+                layer_index,
+                default_z: layer_placement.z(painter_z, layer_start_z, depth),
+            })
+        };
+
         let layer_offset = Vec2::new(
             layer_instance.px_total_offset_x as f32,
             -layer_instance.px_total_offset_y as f32,
         );
 
+        let level_size = Vec2::new(*level.px_wid() as f32, *level.px_hei() as f32);
+
+        let y_sort = ldtk_settings
+            .y_sort
+            .get(&layer_instance.identifier)
+            .copied();
+
+        let layer_parallax = |base_translation: Vec3| {
+            layer_definition_map
+                .get(&layer_instance.layer_def_uid)
+                .and_then(|layer_definition| {
+                    LayerParallax::from_layer_definition(
+                        layer_definition,
+                        level_size,
+                        base_translation,
+                    )
+                })
+        };
+
+        let mut shared_layer_instance: Option<Arc<LayerInstance>> = None;
+        let mut shared_layer_instance = || {
+            shared_layer_instance
+                .get_or_insert_with(|| Arc::new(layer_instance.clone()))
+                .clone()
+        };
+
+        let mut layer_metadata = LayerMetadata::from(layer_instance);
+        if let Some(layer_definition) = layer_definition_map.get(&layer_instance.layer_def_uid) {
+            layer_metadata = layer_metadata.with_definition(layer_definition);
+        }
+
         match layer_instance.layer_instance_type {
             Type::Entities => {
-                let layer_entity = commands
-                    .spawn(SpatialBundle::from_transform(Transform::from_translation(
-                        layer_offset.extend(layer_z as f32),
-                    )))
-                    .insert(LayerMetadata::from(layer_instance))
+                let layer_entity = entity_pool
+                    .spawn(
+                        commands,
+                        SpatialBundle::from_transform(Transform::from_translation(
+                            layer_offset.extend(placed_z(layer_z)),
+                        )),
+                    )
+                    .insert((layer_metadata.clone(), owning_level.clone()))
                     .insert(Name::new(layer_instance.identifier.to_owned()))
-                    .with_children(|commands| {
-                        for entity_instance in &layer_instance.entity_instances {
-                            let transform = calculate_transform_from_entity_instance(
-                                entity_instance,
-                                entity_definition_map,
-                                *level.px_hei(),
-                            );
-                            // Note: entities do not seem to be affected visually by layer offsets in
-                            // the editor, so no layer offset is added to the transform here.
+                    .id();
 
-                            let (tileset, tileset_definition) = match &entity_instance.tile {
-                                Some(t) => (
-                                    tileset_map.get(&t.tileset_uid),
-                                    tileset_definition_map.get(&t.tileset_uid).copied(),
-                                ),
-                                None => (None, None),
-                            };
+                let default_ldtk_entity: Box<dyn PhantomLdtkEntityTrait> =
+                    Box::new(PhantomLdtkEntity::<EntityInstanceBundle>::new());
+
+                // Variation may have deleted some entities, so the groups of varied layers can't
+                // be shared with other spawns of the level
+                let varied_entity_groups;
+                let entity_groups: &[EntityInstanceGroup] = if varied_layer_instance.is_some() {
+                    varied_entity_groups = group_entity_instances(layer_instance, ldtk_entity_map);
+                    &varied_entity_groups
+                } else {
+                    entity_instance_groups.get_or_group(layer_instance, ldtk_entity_map)
+                };
 
-                            let predicted_worldly = Worldly::bundle_entity(
-                                entity_instance,
-                                layer_instance,
-                                tileset,
-                                tileset_definition,
-                                asset_server,
-                                texture_atlases,
-                            );
+                for entity_group in entity_groups {
+                    let registration =
+                        entity_group.registration(default_ldtk_entity.as_ref(), ldtk_entity_map);
+                    let mut deferred_entities = Vec::new();
+
+                    for entity_instance in entity_group
+                        .instances()
+                        .iter()
+                        .filter_map(|index| layer_instance.entity_instances.get(*index))
+                    {
+                        let entity_iid = EntityIid::new(entity_instance.iid.to_owned());
+
+                        if persistent_entity_state.should_skip(&entity_iid) {
+                            continue;
+                        }
 
-                            if !worldly_set.contains(&predicted_worldly) {
-                                let default_ldtk_entity: Box<dyn PhantomLdtkEntityTrait> =
-                                    Box::new(PhantomLdtkEntity::<EntityInstanceBundle>::new());
-                                let mut entity_commands = commands.spawn_empty();
+                        if !entity_definition_map.contains_key(&entity_instance.def_uid) {
+                            errors.push(LdtkError::UnknownEntity {
+                                level: level_iid.clone(),
+                                identifier: entity_instance.identifier.clone(),
+                                iid: entity_instance.iid.clone(),
+                                def_uid: entity_instance.def_uid,
+                            });
+                        }
+
+                        let mut transform = calculate_transform_from_entity_instance(
+                            entity_instance,
+                            entity_definition_map,
+                            *level.px_hei(),
+                        );
+
+                        let z_index = z_ordering.entity_z(&EntityZContext {
+                            level: level.raw(),
+                            layer_instance,
+                            entity_instance,
+                            translation: transform.translation,
+                            z_index: ldtk_settings.entity_z_index.z_index(entity_instance),
+                        });
+                        if let Some(z_index) = z_index {
+                            transform.translation.z = z_index;
+                        }
+                        // Note: entities do not seem to be affected visually by layer offsets in
+                        // the editor, so no layer offset is added to the transform here.
+
+                        let (tileset, tileset_definition) = match &entity_instance.tile {
+                            Some(t) => (
+                                tileset_map.get(&t.tileset_uid),
+                                tileset_definition_map.get(&t.tileset_uid).copied(),
+                            ),
+                            None => (None, None),
+                        };
+
+                        let predicted_worldly = Worldly::bundle_entity(
+                            entity_instance,
+                            layer_instance,
+                            tileset,
+                            tileset_definition,
+                            asset_server,
+                            texture_atlases,
+                        );
 
-                                // insert Name before evaluating LdtkEntitys so that user-provided
-                                // names aren't overwritten
-                                entity_commands.insert((
-                                    EntityIid::new(entity_instance.iid.to_owned()),
-                                    Name::new(entity_instance.identifier.to_owned()),
+                        if !worldly_set.contains(&predicted_worldly) {
+                            let mut entity_commands = entity_pool.spawn_empty(commands);
+                            entity_commands.set_parent(layer_entity);
+
+                            // insert Name before evaluating LdtkEntitys so that user-provided
+                            // names aren't overwritten
+                            if let Some(flags) = persistent_entity_state.flags(&entity_iid) {
+                                let mut flags: Vec<String> = flags.iter().cloned().collect();
+                                flags.sort();
+                                entity_commands.insert(EntityStateFlags(flags));
+                            }
+
+                            if ldtk_settings.deterministic_spawning
+                                == DeterministicSpawning::Enabled
+                            {
+                                entity_commands.insert(StableEntityId::new(
+                                    project_iid,
+                                    level_iid.as_str(),
+                                    &entity_instance.iid,
                                 ));
+                            }
+
+                            entity_commands.insert((
+                                entity_iid,
+                                owning_level.clone(),
+                                Name::new(entity_instance.identifier.to_owned()),
+                            ));
+
+                            let entity_references =
+                                EntityReferences::from_entity_info(entity_instance);
+
+                            if !entity_references.iids.is_empty() {
+                                entity_commands.insert(entity_references);
+                            }
 
-                                ldtk_map_get_or_default(
-                                    layer_instance.identifier.clone(),
-                                    entity_instance.identifier.clone(),
-                                    &default_ldtk_entity,
-                                    ldtk_entity_map,
-                                )
-                                .evaluate(
+                            if !entity_instance.tags.is_empty() {
+                                entity_commands
+                                    .insert(EntityTags::from_entity_info(entity_instance));
+                            }
+
+                            if ldtk_settings.worldly_tag.is_worldly(entity_instance) {
+                                entity_commands.insert(Worldly::from_entity_info(entity_instance));
+                            }
+
+                            match spawn_phases.bundles {
+                                BundlePhase::Immediate => {
+                                    registration.evaluate(
+                                        &mut entity_commands,
+                                        entity_instance,
+                                        layer_instance,
+                                        tileset,
+                                        tileset_definition,
+                                        asset_server,
+                                        texture_atlases,
+                                    );
+                                }
+                                BundlePhase::Deferred => {
+                                    deferred_entities.push(DeferredEntityBundle {
+                                        entity: entity_commands.id(),
+                                        entity_instance: entity_instance.clone(),
+                                        layer_instance: shared_layer_instance(),
+                                        tileset: tileset.cloned(),
+                                        tileset_definition: tileset_definition.map(
+                                            |tileset_definition| {
+                                                shared_tileset_definitions
+                                                    .entry(tileset_definition.uid)
+                                                    .or_insert_with(|| {
+                                                        Arc::new(tileset_definition.clone())
+                                                    })
+                                                    .clone()
+                                            },
+                                        ),
+                                    });
+                                }
+                                BundlePhase::Disabled => (),
+                            }
+
+                            entity_commands.insert(SpatialBundle {
+                                transform,
+                                ..default()
+                            });
+                            entity_commands.add(apply_transform_override);
+
+                            if let (Some(y_sort), None) = (y_sort, z_index) {
+                                entity_commands.insert(y_sort);
+                            }
+
+                            #[cfg(feature = "lighting")]
+                            if let (true, Some(point_light)) = (
+                                spawn_phases.render,
+                                crate::lighting::PointLight2d::from_entity_info(
+                                    entity_instance,
+                                    &ldtk_settings.lighting,
+                                ),
+                            ) {
+                                entity_commands.insert(point_light);
+                            }
+
+                            #[cfg(feature = "text")]
+                            if let (true, Some(text_bundle)) = (
+                                spawn_phases.render,
+                                crate::text::text_2d_bundle_from_entity_info(
+                                    entity_instance,
+                                    &ldtk_settings.text,
+                                    asset_server,
+                                ),
+                            ) {
+                                entity_commands.with_children(|parent| {
+                                    parent.spawn((text_bundle, crate::text::LdtkText));
+                                });
+                            }
+
+                            #[cfg(feature = "bevy_audio")]
+                            if let Some(audio_bundle) = crate::audio::audio_bundle_from_entity_info(
+                                entity_instance,
+                                &ldtk_settings.audio,
+                                asset_server,
+                            ) {
+                                entity_commands.insert(audio_bundle);
+                            }
+
+                            if spawn_phases.render
+                                && ldtk_settings.entity_editor_visuals
+                                    == EntityEditorVisuals::Placeholder
+                            {
+                                spawn_editor_visual_placeholder(
                                     &mut entity_commands,
                                     entity_instance,
-                                    layer_instance,
+                                    entity_definition_map,
                                     tileset,
                                     tileset_definition,
-                                    asset_server,
                                     texture_atlases,
                                 );
-
-                                entity_commands.insert(SpatialBundle {
-                                    transform,
-                                    ..default()
-                                });
                             }
                         }
-                    })
-                    .id();
+                    }
+
+                    deferred_bundles.defer_entity_group(DeferredEntityGroup {
+                        registration: entity_group.registration_key().cloned(),
+                        bundles: deferred_entities,
+                    });
+                }
+
+                if let Some(parallax) = layer_parallax(layer_offset.extend(placed_z(layer_z))) {
+                    commands.entity(layer_entity).insert(parallax);
+                }
 
                 commands.entity(ldtk_entity).add_child(layer_entity);
-                layer_z += 1;
+                layer_z += z_spacing.layer_increment;
             }
             _ => {
                 // The remaining layers have a lot of shared code.
@@ -385,9 +1021,14 @@ pub fn spawn_level(
 
                 let grid_size = layer_instance.grid_size as f32;
 
+                let cell_size = ldtk_settings
+                    .grid_shape
+                    .cell_size(layer_instance.grid_size)
+                    .as_vec2();
+
                 let tilemap_grid_size = TilemapGridSize {
-                    x: grid_size,
-                    y: grid_size,
+                    x: cell_size.x,
+                    y: cell_size.y,
                 };
 
                 let spacing = match tileset_definition {
@@ -415,9 +1056,18 @@ pub fn spawn_level(
                 };
 
                 let texture = match (tileset_definition, int_grid_image_handle) {
-                    (Some(tileset_definition), _) => TilemapTexture::Single(
-                        tileset_map.get(&tileset_definition.uid).unwrap().clone(),
-                    ),
+                    (Some(tileset_definition), _) => match tileset_map.get(&tileset_definition.uid)
+                    {
+                        Some(tileset) => TilemapTexture::Single(tileset.clone()),
+                        None => {
+                            errors.push(LdtkError::MissingTileset {
+                                level: level_iid.clone(),
+                                layer: layer_instance.identifier.clone(),
+                                tileset_uid: tileset_definition.uid,
+                            });
+                            continue;
+                        }
+                    },
                     (None, Some(handle)) => TilemapTexture::Single(handle.clone()),
                     _ => {
                         warn!("unable to render tilemap layer, it has no tileset and no intgrid layers were expected");
@@ -430,19 +1080,32 @@ pub fn spawn_level(
                         tileset_definition
                             .custom_data
                             .iter()
-                            .map(|TileCustomMetadata { data, tile_id }| {
+                            .map(|TileCustomMetadata { data, tile_id, .. }| {
                                 (*tile_id, TileMetadata { data: data.clone() })
                             })
                             .collect()
                     })
                     .unwrap_or_default();
 
+                let animation_map: HashMap<i32, TileAnimation> = tileset_definition
+                    .map(|tileset_definition| {
+                        tileset_definition
+                            .custom_data
+                            .iter()
+                            .filter_map(|TileCustomMetadata { data, tile_id, .. }| {
+                                Some((*tile_id, TileAnimation::from_custom_data(data)?))
+                            })
+                            .collect()
+                    })
+                    .unwrap_or_default();
+
                 let mut enum_tags_map: HashMap<i32, TileEnumTags> = HashMap::new();
 
                 if let Some(tileset_definition) = tileset_definition {
                     for EnumTagValue {
                         enum_value_id,
                         tile_ids,
+                        ..
                     } in tileset_definition.enum_tags.iter()
                     {
                         for tile_id in tile_ids {
@@ -461,7 +1124,13 @@ pub fn spawn_level(
                 let mut grid_tiles = layer_instance.grid_tiles.clone();
                 grid_tiles.extend(layer_instance.auto_layer_tiles.clone());
 
-                for (i, grid_tiles) in layer_grid_tiles(grid_tiles)
+                // Tall tiles in y-sorted layers are split into one tilemap per row, so that each
+                // row can have its own z on the band shared with the y-sorted entities
+                let y_sort_rows = y_sort.filter(|y_sort| {
+                    y_sort.split_tiles && layer_instance.layer_instance_type != Type::IntGrid
+                });
+
+                for (i, (grid_tiles, band_z, z_offset, enum_tag)) in layer_grid_tiles(grid_tiles)
                     .into_iter()
                     // filter out tiles that are out of bounds
                     .map(|grid_tiles| {
@@ -470,20 +1139,55 @@ pub fn spawn_level(
                             .filter(|tile| tile_in_layer_bounds(tile, layer_instance))
                             .collect::<Vec<_>>()
                     })
+                    .flat_map(|grid_tiles| match y_sort_rows {
+                        Some(y_sort) => split_grid_tiles_by_row(grid_tiles, layer_instance)
+                            .into_iter()
+                            .map(|(row_y, row_tiles)| {
+                                (row_tiles, Some(y_sort.z(row_y + layer_offset.y)))
+                            })
+                            .collect(),
+                        None => vec![(grid_tiles, None)],
+                    })
+                    // Tiles rendered with a different material or z need their own tilemap
+                    .flat_map(|(grid_tiles, band_z)| {
+                        if layer_instance.layer_instance_type == Type::IntGrid {
+                            vec![(grid_tiles, band_z, 0., None)]
+                        } else {
+                            split_grid_tiles_by_enum_tag(
+                                grid_tiles,
+                                &enum_tags_map,
+                                &split_enum_tags,
+                            )
+                            .into_iter()
+                            .map(|(enum_tag, tiles)| {
+                                let enum_tag_z_offset = enum_tag
+                                    .as_ref()
+                                    .and_then(|enum_tag| {
+                                        ldtk_settings.enum_tag_z_offsets.get(enum_tag)
+                                    })
+                                    .copied()
+                                    .unwrap_or(0.);
+
+                                (tiles, band_z, enum_tag_z_offset, enum_tag)
+                            })
+                            .collect()
+                        }
+                    })
                     .enumerate()
                 {
-                    let layer_entity = commands.spawn_empty().id();
+                    let layer_entity = entity_pool.spawn_empty(commands).id();
 
                     let tilemap_bundle = if layer_instance.layer_instance_type == Type::IntGrid {
                         // The current spawning of IntGrid layers doesn't allow using
                         // LayerBuilder::new_batch().
                         // So, the actual LayerBuilder usage diverges greatly here
-                        let mut storage = TileStorage::empty(size);
+                        let mut storage = entity_pool.tile_storage(size);
 
                         match tileset_definition {
                             Some(_) => {
                                 set_all_tiles_with_func(
                                     commands,
+                                    entity_pool,
                                     &mut storage,
                                     size,
                                     TilemapId(layer_entity),
@@ -512,6 +1216,7 @@ pub fn spawn_level(
                                     IntGridRendering::Colorful => {
                                         set_all_tiles_with_func(
                                             commands,
+                                            entity_pool,
                                             &mut storage,
                                             size,
                                             TilemapId(layer_entity),
@@ -529,18 +1234,29 @@ pub fn spawn_level(
                                         );
                                     }
                                     IntGridRendering::Invisible => {
+                                        // Invisible tiles only exist to hold int grid cells, so
+                                        // sparse layers only need them for registered values
                                         set_all_tiles_with_func(
                                             commands,
+                                            entity_pool,
                                             &mut storage,
                                             size,
                                             TilemapId(layer_entity),
                                             tile_pos_to_tile_grid_bundle_maker(
                                                 tile_pos_to_transparent_tile_maker(
-                                                    tile_pos_to_tile_if_int_grid_nonzero_maker(
+                                                    tile_pos_to_tile_if_int_grid_value_maker(
                                                         tile_pos_to_invisible_tile,
                                                         &layer_instance.int_grid_csv,
                                                         layer_instance.c_wid,
                                                         layer_instance.c_hei,
+                                                        |value| {
+                                                            spawns_int_grid_cell(
+                                                                ldtk_settings.int_grid_cell_storage,
+                                                                ldtk_int_cell_map,
+                                                                &layer_instance.identifier,
+                                                                value,
+                                                            )
+                                                        },
                                                     ),
                                                     layer_instance.opacity,
                                                 ),
@@ -552,11 +1268,59 @@ pub fn spawn_level(
                         }
 
                         if i == 0 {
-                            for (i, value) in layer_instance
-                                .int_grid_csv
-                                .iter()
-                                .enumerate()
-                                .filter(|(_, v)| **v != 0)
+                            let default_ldtk_int_cell: Box<dyn PhantomLdtkIntCellTrait> =
+                                Box::new(PhantomLdtkIntCell::<IntGridCellBundle>::new());
+
+                            let int_cell_registrations = ldtk_map_get_all_or_default(
+                                layer_instance.identifier.clone(),
+                                layer_instance.int_grid_csv.iter().filter(|v| **v != 0),
+                                &default_ldtk_int_cell,
+                                ldtk_int_cell_map,
+                            );
+
+                            if spawn_phases.logic
+                                && ldtk_settings.int_grid_cell_storage == IntGridCellStorage::Sparse
+                            {
+                                commands.entity(layer_entity).insert(
+                                    IntGridCells::from_int_grid_csv(
+                                        &layer_instance.int_grid_csv,
+                                        layer_instance.c_wid,
+                                        layer_instance.c_hei,
+                                    ),
+                                );
+                            }
+
+                            if spawn_phases.render
+                                && ldtk_settings.int_grid_textures == IntGridTextures::Enabled
+                                && layer_instance.layer_instance_type == Type::IntGrid
+                            {
+                                commands
+                                    .entity(layer_entity)
+                                    .insert(IntGridTextureTask::spawn(
+                                        layer_instance.int_grid_csv.clone(),
+                                        layer_instance.c_wid,
+                                        layer_instance.c_hei,
+                                    ));
+                            }
+
+                            let layer_definition = layer_definition_map
+                                .get(&layer_instance.layer_def_uid)
+                                .expect("Encountered layer without definition");
+                            let mut shared_layer_definition: Option<Arc<LayerDefinition>> = None;
+
+                            for (i, value) in
+                                layer_instance
+                                    .int_grid_csv
+                                    .iter()
+                                    .enumerate()
+                                    .filter(|(_, v)| {
+                                        spawns_int_grid_cell(
+                                            ldtk_settings.int_grid_cell_storage,
+                                            ldtk_int_cell_map,
+                                            &layer_instance.identifier,
+                                            **v,
+                                        )
+                                    })
                             {
                                 let grid_coords = int_grid_index_to_grid_coords(
                                     i,
@@ -567,32 +1331,60 @@ pub fn spawn_level(
                                 if let Some(tile_entity) = storage.get(&grid_coords.into()) {
                                     let mut entity_commands = commands.entity(tile_entity);
 
-                                    let default_ldtk_int_cell: Box<dyn PhantomLdtkIntCellTrait> =
-                                        Box::new(PhantomLdtkIntCell::<IntGridCellBundle>::new());
+                                    match spawn_phases.bundles {
+                                        BundlePhase::Immediate => {
+                                            int_cell_registrations[value].evaluate(
+                                                &mut entity_commands,
+                                                IntGridCell { value: *value },
+                                                layer_instance,
+                                                layer_definition,
+                                            );
+                                        }
+                                        BundlePhase::Deferred => {
+                                            deferred_bundles.defer_int_cell(
+                                                DeferredIntCellBundle {
+                                                    entity: tile_entity,
+                                                    int_grid_cell: IntGridCell { value: *value },
+                                                    layer_instance: shared_layer_instance(),
+                                                    layer_definition: shared_layer_definition
+                                                        .get_or_insert_with(|| {
+                                                            Arc::new((*layer_definition).clone())
+                                                        })
+                                                        .clone(),
+                                                },
+                                            );
+                                        }
+                                        BundlePhase::Disabled => (),
+                                    }
 
-                                    ldtk_map_get_or_default(
-                                        layer_instance.identifier.clone(),
-                                        *value,
-                                        &default_ldtk_int_cell,
-                                        ldtk_int_cell_map,
-                                    )
-                                    .evaluate(
-                                        &mut entity_commands,
-                                        IntGridCell { value: *value },
-                                        layer_instance,
-                                    );
+                                    #[cfg(feature = "lighting")]
+                                    if let (true, Some(occluder)) = (
+                                        spawn_phases.render,
+                                        crate::lighting::LightOccluder2d::from_int_grid_value(
+                                            *value,
+                                            layer_instance.grid_size,
+                                            &ldtk_settings.lighting,
+                                        ),
+                                    ) {
+                                        entity_commands.insert(occluder);
+                                    }
                                 }
                             }
                         }
 
-                        if !(metadata_map.is_empty() && enum_tags_map.is_empty()) {
+                        if spawn_phases.logic
+                            && !(metadata_map.is_empty() && enum_tags_map.is_empty())
+                        {
                             insert_tile_metadata_for_layer(
                                 commands,
+                                layer_entity,
                                 &storage,
                                 &grid_tiles,
                                 layer_instance,
                                 &metadata_map,
                                 &enum_tags_map,
+                                &animation_map,
+                                ldtk_settings.tile_metadata_storage,
                             );
                         }
 
@@ -621,24 +1413,30 @@ pub fn spawn_level(
                         // This can't be accomplished using LayerBuilder::new_batch,
                         // so the logic for building layers with metadata is slower.
 
-                        let mut storage = TileStorage::empty(size);
+                        let mut storage = entity_pool.tile_storage(size);
 
                         set_all_tiles_with_func(
                             commands,
+                            entity_pool,
                             &mut storage,
                             size,
                             TilemapId(layer_entity),
                             tile_bundle_maker,
                         );
 
-                        if !(metadata_map.is_empty() && enum_tags_map.is_empty()) {
+                        if spawn_phases.logic
+                            && !(metadata_map.is_empty() && enum_tags_map.is_empty())
+                        {
                             insert_tile_metadata_for_layer(
                                 commands,
+                                layer_entity,
                                 &storage,
                                 &grid_tiles,
                                 layer_instance,
                                 &metadata_map,
                                 &enum_tags_map,
+                                &animation_map,
+                                ldtk_settings.tile_metadata_storage,
                             );
                         }
 
@@ -653,12 +1451,25 @@ pub fn spawn_level(
                         }
                     };
 
+                    let (render_settings, frustum_culling) = ldtk_settings
+                        .tilemap_settings_for(&layer_instance.identifier)
+                        .to_components();
+
+                    let tilemap_bundle = TilemapBundle {
+                        render_settings,
+                        frustum_culling,
+                        map_type: ldtk_settings.grid_shape.tilemap_type(),
+                        ..tilemap_bundle
+                    };
+
                     insert_spatial_bundle_for_layer_tiles(
                         commands,
                         &tilemap_bundle.storage,
                         &tilemap_bundle.size,
                         layer_instance.grid_size,
+                        ldtk_settings.grid_shape,
                         TilemapId(layer_entity),
+                        &owning_level,
                     );
 
                     let LayerDefinition {
@@ -695,24 +1506,79 @@ pub fn spawn_level(
                         -grid_tile_size_difference * tile_pivot_y,
                     );
 
+                    let layer_translation = (bottom_left_pixel
+                        + centering_adjustment
+                        + pivot_adjustment
+                        + layer_offset)
+                        .extend(band_z.unwrap_or_else(|| placed_z(layer_z)) + z_offset);
+
                     commands
                         .entity(layer_entity)
                         .insert(tilemap_bundle)
-                        .insert(SpatialBundle::from_transform(Transform::from_translation(
-                            (bottom_left_pixel
-                                + centering_adjustment
-                                + pivot_adjustment
-                                + layer_offset)
-                                .extend(layer_z as f32),
-                        )))
-                        .insert(LayerMetadata::from(layer_instance))
+                        .insert(SpatialBundle {
+                            transform: Transform::from_translation(layer_translation),
+                            // Without the render phase, tilemaps are still spawned for their
+                            // logic components, but are hidden
+                            visibility: if spawn_phases.render {
+                                Visibility::Inherited
+                            } else {
+                                Visibility::Hidden
+                            },
+                            ..default()
+                        })
+                        .insert((layer_metadata.clone(), owning_level.clone()))
                         .insert(Name::new(layer_instance.identifier.to_owned()));
 
+                    if let Some(parallax) = layer_parallax(layer_translation) {
+                        commands.entity(layer_entity).insert(parallax);
+                    }
+
+                    if let Some(enum_tag) = enum_tag {
+                        commands
+                            .entity(layer_entity)
+                            .insert(MaterialEnumTag(enum_tag));
+                    }
+
                     commands.entity(ldtk_entity).add_child(layer_entity);
 
-                    layer_z += 1;
+                    // Rows on the y-sort band don't take up a slot of the layer stack
+                    if band_z.is_none() {
+                        layer_z += z_spacing.layer_increment;
+                    }
                 }
             }
         }
     }
+
+    errors
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn grid_tiles_are_split_into_rows_from_the_bottom() {
+        let layer_instance = LayerInstance {
+            c_wid: 2,
+            c_hei: 3,
+            grid_size: 16,
+            ..default()
+        };
+        let tile = |x, y| TileInstance {
+            px: IVec2::new(x, y),
+            ..default()
+        };
+
+        let rows =
+            split_grid_tiles_by_row(vec![tile(0, 0), tile(0, 32), tile(16, 0)], &layer_instance);
+
+        assert_eq!(
+            rows,
+            vec![
+                (0., vec![tile(0, 32)]),
+                (32., vec![tile(0, 0), tile(16, 0)]),
+            ]
+        );
+    }
 }
@@ -0,0 +1,499 @@
+//! Procedural construction of levels that spawn like levels authored in LDtk.
+//!
+//! *Requires the "internal_levels" feature*
+//!
+//! [`LevelBuilder`] assembles a [`Level`] in code, using the layer, tileset, and entity
+//! definitions of an existing project.
+//! Once added to the project with [`LdtkProject::add_level`], the level is spawned by the same
+//! systems as any other level, so it gets the same components, registered bundles, and
+//! [`LevelEvent`]s.
+//! ```
+//! # use bevy::prelude::*;
+//! # use bevy_ecs_ldtk::{editing::LevelEditError, level_builder::LevelBuilder, prelude::*};
+//! fn build_room(ldtk_project: &mut LdtkProject) -> Result<(), LevelEditError> {
+//!     let defs = &ldtk_project.json_data().defs;
+//!     let mut builder = LevelBuilder::new(defs, "Room", "room-0", IVec2::new(256, 256));
+//!
+//!     for x in 0..16 {
+//!         builder.int_grid_value("Walls", GridCoords::new(x, 0), 1)?;
+//!     }
+//!
+//!     let chest = builder.entity_instance("Chest", "chest-0", IVec2::new(64, 200))?;
+//!     builder.entity("Entities", chest)?;
+//!
+//!     let level = builder.build();
+//!     ldtk_project.add_level(level, None).unwrap();
+//!     Ok(())
+//! }
+//! ```
+//!
+//! Levels authored in LDtk can be reused as pieces of a larger level with [`LevelBuilder::stitch`].
+//! This is useful for generating roguelike floors out of prefab rooms.
+//!
+//! [`LdtkProject::add_level`]: crate::prelude::LdtkProject::add_level
+//! [`LevelEvent`]: crate::prelude::LevelEvent
+
+use crate::{
+    components::GridCoords,
+    editing::{add_entity_instance, set_int_grid_value, set_tile, LevelEditError},
+    ldtk::{
+        Definitions, EntityInstance, FieldValue, LayerInstance, Level, ReferenceToAnEntityInstance,
+        TileInstance, Type,
+    },
+};
+use bevy::prelude::*;
+use std::collections::HashMap;
+
+/// Assembles a [`Level`] in code from the definitions of a project.
+///
+/// The level gets an empty instance of every layer definition, in the same order LDtk uses.
+/// AutoLayer rules are not applied, so IntGrid layers only render their IntGrid colors, or nothing
+/// if [`IntGridRendering::Invisible`] is used.
+///
+/// See the [module-level documentation](crate::level_builder) for more details.
+///
+/// [`IntGridRendering::Invisible`]: crate::prelude::IntGridRendering::Invisible
+#[derive(Clone, Debug)]
+pub struct LevelBuilder<'a> {
+    defs: &'a Definitions,
+    level: Level,
+    stitch_count: usize,
+}
+
+impl<'a> LevelBuilder<'a> {
+    /// Starts building a level of the given size in pixels.
+    pub fn new(
+        defs: &'a Definitions,
+        identifier: impl Into<String>,
+        iid: impl Into<String>,
+        px_size: IVec2,
+    ) -> Self {
+        let identifier = identifier.into();
+        let iid = iid.into();
+
+        let layer_instances = defs
+            .layers
+            .iter()
+            .map(|layer_definition| {
+                let grid_size = layer_definition.grid_size.max(1);
+                let c_wid = (px_size.x + grid_size - 1) / grid_size;
+                let c_hei = (px_size.y + grid_size - 1) / grid_size;
+
+                LayerInstance {
+                    identifier: layer_definition.identifier.clone(),
+                    layer_instance_type: layer_definition.purple_type,
+                    iid: format!("{iid}-{}", layer_definition.identifier),
+                    layer_def_uid: layer_definition.uid,
+                    grid_size,
+                    c_wid,
+                    c_hei,
+                    opacity: layer_definition.display_opacity,
+                    px_offset_x: layer_definition.px_offset_x,
+                    px_offset_y: layer_definition.px_offset_y,
+                    px_total_offset_x: layer_definition.px_offset_x,
+                    px_total_offset_y: layer_definition.px_offset_y,
+                    tileset_def_uid: layer_definition.tileset_def_uid,
+                    int_grid_csv: if layer_definition.purple_type == Type::IntGrid {
+                        vec![0; (c_wid * c_hei) as usize]
+                    } else {
+                        Vec::new()
+                    },
+                    visible: true,
+                    ..Default::default()
+                }
+            })
+            .collect();
+
+        LevelBuilder {
+            defs,
+            level: Level {
+                identifier,
+                iid,
+                px_wid: px_size.x,
+                px_hei: px_size.y,
+                layer_instances: Some(layer_instances),
+                ..Default::default()
+            },
+            stitch_count: 0,
+        }
+    }
+
+    /// Sets the level's uid.
+    pub fn uid(&mut self, uid: i32) -> &mut Self {
+        self.level.uid = uid;
+        self
+    }
+
+    /// Sets the level's position in the LDtk world, in pixels.
+    pub fn world_position(&mut self, world_px: IVec2) -> &mut Self {
+        self.level.world_x = world_px.x;
+        self.level.world_y = world_px.y;
+        self
+    }
+
+    /// Sets the level's background color.
+    pub fn bg_color(&mut self, color: Color) -> &mut Self {
+        self.level.bg_color = color;
+        self
+    }
+
+    /// Sets the value of the IntGrid layer with the given identifier at the given [`GridCoords`].
+    pub fn int_grid_value(
+        &mut self,
+        layer_identifier: &str,
+        grid_coords: GridCoords,
+        value: i32,
+    ) -> Result<&mut Self, LevelEditError> {
+        set_int_grid_value(&mut self.level, layer_identifier, grid_coords, value)?;
+        Ok(self)
+    }
+
+    /// Sets the tile of the Tiles layer with the given identifier at the given [`GridCoords`].
+    ///
+    /// Fails with [`LevelEditError::InvalidTileId`] if the tile isn't part of the layer's tileset.
+    pub fn tile(
+        &mut self,
+        layer_identifier: &str,
+        grid_coords: GridCoords,
+        tile_id: i32,
+    ) -> Result<&mut Self, LevelEditError> {
+        set_tile(
+            &mut self.level,
+            self.defs,
+            layer_identifier,
+            grid_coords,
+            Some(tile_id),
+        )?;
+        Ok(self)
+    }
+
+    /// Creates an entity instance from the entity definition with the given identifier.
+    ///
+    /// Field instances are left empty, so add any fields your bundles need before adding the
+    /// instance to the level with [`LevelBuilder::entity`].
+    pub fn entity_instance(
+        &self,
+        entity_identifier: &str,
+        iid: impl Into<String>,
+        px: IVec2,
+    ) -> Result<EntityInstance, LevelEditError> {
+        let entity_definition = self
+            .defs
+            .entities
+            .iter()
+            .find(|entity_definition| entity_definition.identifier == entity_identifier)
+            .ok_or_else(|| LevelEditError::UnknownEntity(entity_identifier.to_string()))?;
+
+        Ok(EntityInstance {
+            identifier: entity_definition.identifier.clone(),
+            def_uid: entity_definition.uid,
+            iid: iid.into(),
+            px,
+            width: entity_definition.width,
+            height: entity_definition.height,
+            pivot: Vec2::new(entity_definition.pivot_x, entity_definition.pivot_y),
+            smart_color: entity_definition.color,
+            tags: entity_definition.tags.clone(),
+            tile: entity_definition.tile_rect.clone(),
+            ..Default::default()
+        })
+    }
+
+    /// Adds an entity instance to the Entities layer with the given identifier.
+    pub fn entity(
+        &mut self,
+        layer_identifier: &str,
+        entity_instance: EntityInstance,
+    ) -> Result<&mut Self, LevelEditError> {
+        add_entity_instance(&mut self.level, layer_identifier, entity_instance)?;
+        Ok(self)
+    }
+
+    /// Copies the contents of another level into this one, offset by the given number of pixels.
+    ///
+    /// Each layer of `piece` is merged into the layer of this level with the same identifier and
+    /// grid size.
+    /// Layers without a compatible counterpart are skipped.
+    /// - IntGrid values are copied where they are nonzero, along with AutoLayer tiles, which are
+    /// not regenerated.
+    /// - Tiles outside of this level's bounds are dropped.
+    /// - Entities are re-based onto this level.
+    /// Their iids are suffixed with `@` and the number of pieces stitched before, so the same piece
+    /// can be stitched more than once.
+    /// References between entities of the same piece are updated accordingly.
+    ///
+    /// The offset must be a multiple of the grid size of every merged IntGrid, AutoLayer, and
+    /// Tiles layer.
+    pub fn stitch(&mut self, piece: &Level, offset_px: IVec2) -> Result<&mut Self, LevelEditError> {
+        let suffix = format!("@{}", self.stitch_count);
+        let (level_world_x, level_world_y) = (self.level.world_x, self.level.world_y);
+        let level_iid = self.level.iid.clone();
+
+        let piece_layers = piece.layer_instances.iter().flatten();
+
+        let entity_iids: HashMap<String, String> = piece_layers
+            .clone()
+            .flat_map(|layer_instance| layer_instance.entity_instances.iter())
+            .map(|entity_instance| {
+                (
+                    entity_instance.iid.clone(),
+                    format!("{}{suffix}", entity_instance.iid),
+                )
+            })
+            .collect();
+
+        let layer_iids: HashMap<String, String> = piece_layers
+            .clone()
+            .filter_map(|piece_layer| {
+                let layer_instance = self.compatible_layer_mut(piece_layer)?;
+                Some((piece_layer.iid.clone(), layer_instance.iid.clone()))
+            })
+            .collect();
+
+        for piece_layer in piece_layers.clone() {
+            if let Some(layer_instance) = self.compatible_layer_mut(piece_layer) {
+                if layer_instance.layer_instance_type != Type::Entities
+                    && offset_px % layer_instance.grid_size != IVec2::ZERO
+                {
+                    return Err(LevelEditError::MisalignedOffset {
+                        identifier: layer_instance.identifier.clone(),
+                        offset: offset_px,
+                    });
+                }
+            }
+        }
+
+        for piece_layer in piece_layers {
+            let Some(layer_instance) = self.compatible_layer_mut(piece_layer) else {
+                continue;
+            };
+
+            let grid_size = layer_instance.grid_size;
+            let layer_px_size = IVec2::new(layer_instance.c_wid, layer_instance.c_hei) * grid_size;
+
+            let offset_tile = |tile: &TileInstance| {
+                let px = tile.px + offset_px;
+                (px.cmpge(IVec2::ZERO).all() && px.cmplt(layer_px_size).all())
+                    .then(|| TileInstance { px, ..tile.clone() })
+            };
+
+            layer_instance
+                .grid_tiles
+                .extend(piece_layer.grid_tiles.iter().filter_map(offset_tile));
+            layer_instance
+                .auto_layer_tiles
+                .extend(piece_layer.auto_layer_tiles.iter().filter_map(offset_tile));
+
+            let offset_cells = offset_px / grid_size;
+
+            for (index, value) in piece_layer.int_grid_csv.iter().enumerate() {
+                let cell = IVec2::new(
+                    index as i32 % piece_layer.c_wid,
+                    index as i32 / piece_layer.c_wid,
+                ) + offset_cells;
+
+                if *value != 0
+                    && cell.cmpge(IVec2::ZERO).all()
+                    && cell
+                        .cmplt(IVec2::new(layer_instance.c_wid, layer_instance.c_hei))
+                        .all()
+                {
+                    if let Some(target) = layer_instance
+                        .int_grid_csv
+                        .get_mut((cell.y * layer_instance.c_wid + cell.x) as usize)
+                    {
+                        *target = *value;
+                    }
+                }
+            }
+
+            for entity_instance in piece_layer.entity_instances.iter() {
+                let mut entity_instance = entity_instance.clone();
+
+                entity_instance.iid = entity_iids[&entity_instance.iid].clone();
+                entity_instance.px += offset_px;
+                entity_instance.grid = entity_instance.px / grid_size;
+                entity_instance.world_x = level_world_x + entity_instance.px.x;
+                entity_instance.world_y = level_world_y + entity_instance.px.y;
+
+                for field_instance in entity_instance.field_instances.iter_mut() {
+                    let references: Vec<&mut ReferenceToAnEntityInstance> =
+                        match &mut field_instance.value {
+                            FieldValue::EntityRef(reference) => reference.iter_mut().collect(),
+                            FieldValue::EntityRefs(references) => {
+                                references.iter_mut().flatten().collect()
+                            }
+                            _ => Vec::new(),
+                        };
+
+                    for reference in references {
+                        if let (Some(entity_iid), Some(layer_iid)) = (
+                            entity_iids.get(&reference.entity_iid),
+                            layer_iids.get(&reference.layer_iid),
+                        ) {
+                            reference.entity_iid = entity_iid.clone();
+                            reference.layer_iid = layer_iid.clone();
+                            reference.level_iid = level_iid.clone();
+                        }
+                    }
+                }
+
+                layer_instance.entity_instances.push(entity_instance);
+            }
+        }
+
+        self.stitch_count += 1;
+
+        Ok(self)
+    }
+
+    fn compatible_layer_mut(&mut self, piece_layer: &LayerInstance) -> Option<&mut LayerInstance> {
+        self.level
+            .layer_instances
+            .iter_mut()
+            .flatten()
+            .find(|layer_instance| {
+                layer_instance.identifier == piece_layer.identifier
+                    && layer_instance.layer_instance_type == piece_layer.layer_instance_type
+                    && layer_instance.grid_size == piece_layer.grid_size
+            })
+    }
+
+    /// Finishes building the level.
+    pub fn build(&self) -> Level {
+        self.level.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ldtk::{EntityDefinition, LayerDefinition};
+
+    use super::*;
+
+    #[test]
+    fn levels_have_instances_of_every_layer() {
+        let defs = Definitions {
+            layers: vec![
+                LayerDefinition {
+                    identifier: "Entities".to_string(),
+                    purple_type: Type::Entities,
+                    grid_size: 16,
+                    uid: 1,
+                    ..Default::default()
+                },
+                LayerDefinition {
+                    identifier: "Walls".to_string(),
+                    purple_type: Type::IntGrid,
+                    grid_size: 16,
+                    uid: 2,
+                    ..Default::default()
+                },
+            ],
+            entities: vec![EntityDefinition {
+                identifier: "Chest".to_string(),
+                uid: 3,
+                width: 16,
+                height: 8,
+                tags: vec!["loot".to_string()],
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+
+        let mut builder = LevelBuilder::new(&defs, "Room", "room", IVec2::new(40, 32));
+
+        builder
+            .int_grid_value("Walls", GridCoords::new(2, 1), 1)
+            .unwrap();
+
+        let chest = builder
+            .entity_instance("Chest", "chest", IVec2::new(16, 16))
+            .unwrap();
+        builder.entity("Entities", chest).unwrap();
+
+        assert_eq!(
+            builder.entity_instance("Door", "door", IVec2::ZERO),
+            Err(LevelEditError::UnknownEntity("Door".to_string()))
+        );
+
+        let level = builder.build();
+        let layer_instances = level.layer_instances.unwrap();
+
+        assert_eq!(layer_instances.len(), 2);
+        assert_eq!(layer_instances[1].c_wid, 3);
+        assert_eq!(layer_instances[1].c_hei, 2);
+        assert_eq!(layer_instances[1].int_grid_csv, vec![0, 0, 1, 0, 0, 0]);
+
+        let chest = &layer_instances[0].entity_instances[0];
+        assert_eq!(chest.def_uid, 3);
+        assert_eq!(chest.grid, IVec2::new(1, 1));
+        assert_eq!(chest.tags, vec!["loot".to_string()]);
+    }
+
+    #[test]
+    fn stitched_pieces_are_offset_and_reiided() {
+        let defs = Definitions {
+            layers: vec![
+                LayerDefinition {
+                    identifier: "Entities".to_string(),
+                    purple_type: Type::Entities,
+                    grid_size: 8,
+                    ..Default::default()
+                },
+                LayerDefinition {
+                    identifier: "Walls".to_string(),
+                    purple_type: Type::IntGrid,
+                    grid_size: 8,
+                    ..Default::default()
+                },
+            ],
+            ..Default::default()
+        };
+
+        let mut piece = LevelBuilder::new(&defs, "Room", "room", IVec2::new(16, 16));
+        piece
+            .int_grid_value("Walls", GridCoords::new(0, 1), 1)
+            .unwrap();
+        piece
+            .entity(
+                "Entities",
+                EntityInstance {
+                    iid: "lever".to_string(),
+                    px: IVec2::new(4, 4),
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+        let piece = piece.build();
+
+        let mut floor = LevelBuilder::new(&defs, "Floor", "floor", IVec2::new(32, 16));
+        floor
+            .stitch(&piece, IVec2::ZERO)
+            .unwrap()
+            .stitch(&piece, IVec2::new(16, 0))
+            .unwrap();
+
+        assert_eq!(
+            floor.stitch(&piece, IVec2::new(4, 0)).unwrap_err(),
+            LevelEditError::MisalignedOffset {
+                identifier: "Walls".to_string(),
+                offset: IVec2::new(4, 0),
+            }
+        );
+
+        let level = floor.build();
+        let layer_instances = level.layer_instances.unwrap();
+
+        assert_eq!(
+            layer_instances[1].int_grid_csv,
+            vec![1, 0, 1, 0, 0, 0, 0, 0]
+        );
+
+        let entities = &layer_instances[0].entity_instances;
+        assert_eq!(entities[0].iid, "lever@0");
+        assert_eq!(entities[1].iid, "lever@1");
+        assert_eq!(entities[1].px, IVec2::new(20, 4));
+        assert_eq!(entities[1].grid, IVec2::new(2, 0));
+    }
+}
@@ -0,0 +1,394 @@
+//! Provides [`LdtkLevelQuery`], for finding the entities, layers, and tiles spawned in a level
+//! without walking the hierarchy, or the cell at a world position, and the [`LdtkLevelIndex`]
+//! backing it.
+
+use crate::{
+    components::{GridCoords, LayerMetadata, LevelIid},
+    utils::translation_to_layer_grid_coords,
+};
+use bevy::{ecs::system::SystemParam, prelude::*};
+use bevy_ecs_tilemap::tiles::TileStorage;
+use std::collections::{HashMap, HashSet};
+
+/// [Resource] indexing the LDtk entities and layers of every spawned level by [`LevelIid`].
+///
+/// Kept up to date by the plugin in [PreUpdate], right after levels spawn.
+/// LDtk entities are indexed by their [`EntityIid`], and layers by their [`LayerMetadata`].
+/// [`Worldly`] entities don't belong to any level, so they aren't indexed.
+///
+/// Entities despawned during an update are removed from the index in the next [PreUpdate].
+///
+/// [`EntityIid`]: crate::prelude::EntityIid
+/// [`Worldly`]: crate::prelude::Worldly
+#[derive(Clone, Eq, PartialEq, Debug, Default, Resource)]
+pub struct LdtkLevelIndex {
+    entities: HashMap<LevelIid, HashSet<Entity>>,
+    layers: HashMap<LevelIid, HashSet<Entity>>,
+    levels: HashMap<Entity, LevelIid>,
+}
+
+impl LdtkLevelIndex {
+    /// The LDtk entities spawned in the given level.
+    pub fn entities_in_level(&self, level_iid: &LevelIid) -> impl Iterator<Item = Entity> + '_ {
+        self.entities.get(level_iid).into_iter().flatten().copied()
+    }
+
+    /// The layer entities spawned in the given level.
+    ///
+    /// A single LDtk layer may be spawned as several tilemaps, so it may have several entities.
+    pub fn layers_in_level(&self, level_iid: &LevelIid) -> impl Iterator<Item = Entity> + '_ {
+        self.layers.get(level_iid).into_iter().flatten().copied()
+    }
+
+    /// The level an indexed LDtk entity or layer entity was spawned in.
+    pub fn level_of(&self, entity: Entity) -> Option<&LevelIid> {
+        self.levels.get(&entity)
+    }
+
+    pub(crate) fn insert_entity(&mut self, entity: Entity, level_iid: LevelIid) {
+        self.remove(entity);
+        self.entities
+            .entry(level_iid.clone())
+            .or_default()
+            .insert(entity);
+        self.levels.insert(entity, level_iid);
+    }
+
+    pub(crate) fn insert_layer(&mut self, layer_entity: Entity, level_iid: LevelIid) {
+        self.remove(layer_entity);
+        self.layers
+            .entry(level_iid.clone())
+            .or_default()
+            .insert(layer_entity);
+        self.levels.insert(layer_entity, level_iid);
+    }
+
+    pub(crate) fn remove(&mut self, entity: Entity) {
+        let Some(level_iid) = self.levels.remove(&entity) else {
+            return;
+        };
+
+        for index in [&mut self.entities, &mut self.layers] {
+            if let Some(entities) = index.get_mut(&level_iid) {
+                entities.remove(&entity);
+                if entities.is_empty() {
+                    index.remove(&level_iid);
+                }
+            }
+        }
+    }
+}
+
+/// The level, layer, and cell at a world position, found by [`LdtkLevelQuery::locate`].
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct LdtkLocation {
+    pub level_iid: LevelIid,
+    pub level_entity: Entity,
+    /// Identifier of the layer.
+    pub layer_identifier: String,
+    /// The layer entity containing the position.
+    ///
+    /// A single LDtk layer may be spawned as several tilemaps, so use [`LayerTiles`] with the
+    /// level entity and layer identifier to find the tiles of the cell.
+    ///
+    /// [`LayerTiles`]: crate::prelude::LayerTiles
+    pub layer_entity: Entity,
+    /// The cell containing the position, in the layer's own grid.
+    pub grid_coords: GridCoords,
+}
+
+/// [`SystemParam`] for finding the entities, layers, and tiles spawned in a level by its
+/// [`LevelIid`], and the level, layer, and cell at a world position.
+///
+/// Backed by the [`LdtkLevelIndex`], so lookups don't walk the [Parent]s of every entity.
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_ecs_ldtk::prelude::*;
+/// fn count_room_contents(level_query: LdtkLevelQuery, level_selection: Res<LevelSelection>) {
+///     if let LevelSelection::Iid(level_iid) = level_selection.as_ref() {
+///         let entities = level_query.entities_in_level(level_iid).count();
+///         let walls = level_query.tiles_on_layer(level_iid, "Collision").count();
+///         info!("{entities} entities and {walls} walls in {level_iid}");
+///     }
+/// }
+/// ```
+///
+/// [`SystemParam`]: https://docs.rs/bevy/latest/bevy/ecs/system/trait.SystemParam.html
+#[derive(SystemParam)]
+pub struct LdtkLevelQuery<'w, 's> {
+    index: Res<'w, LdtkLevelIndex>,
+    level_query: Query<'w, 's, (Entity, &'static LevelIid, &'static GlobalTransform)>,
+    layer_query: Query<
+        'w,
+        's,
+        (
+            &'static LayerMetadata,
+            Option<&'static TileStorage>,
+            &'static GlobalTransform,
+        ),
+    >,
+}
+
+impl<'w, 's> LdtkLevelQuery<'w, 's> {
+    /// The LDtk entities spawned in the given level.
+    pub fn entities_in_level<'a>(
+        &'a self,
+        level_iid: &LevelIid,
+    ) -> impl Iterator<Item = Entity> + 'a {
+        self.index.entities_in_level(level_iid)
+    }
+
+    /// The entities of the layers with the given identifier in the given level.
+    ///
+    /// A single LDtk layer may be spawned as several tilemaps, so there may be more than one.
+    pub fn layers_in_level<'a>(
+        &'a self,
+        level_iid: &LevelIid,
+        layer_identifier: &'a str,
+    ) -> impl Iterator<Item = Entity> + 'a {
+        self.index
+            .layers_in_level(level_iid)
+            .filter(move |layer_entity| {
+                self.layer_query
+                    .get(*layer_entity)
+                    .is_ok_and(|(layer_metadata, ..)| layer_metadata.identifier == layer_identifier)
+            })
+    }
+
+    /// The tile entities of the layer with the given identifier in the given level, from all of
+    /// its tilemaps.
+    pub fn tiles_on_layer<'a>(
+        &'a self,
+        level_iid: &LevelIid,
+        layer_identifier: &'a str,
+    ) -> impl Iterator<Item = Entity> + 'a {
+        self.layers_in_level(level_iid, layer_identifier)
+            .filter_map(move |layer_entity| self.layer_query.get(layer_entity).ok())
+            .filter_map(|(_, storage, _)| storage)
+            .flat_map(|storage| storage.iter().flatten().copied())
+    }
+
+    /// The level an LDtk entity or layer entity was spawned in.
+    pub fn level_of(&self, entity: Entity) -> Option<&LevelIid> {
+        self.index.level_of(entity)
+    }
+
+    /// Finds the level, layer, and cell at the given world position, among all spawned levels.
+    ///
+    /// The position is brought into each level's space with the level's [`GlobalTransform`], so
+    /// any level placement works, and then into the cells of each layer with the layer's own
+    /// grid size and offsets.
+    /// Where layers overlap, the one in front is returned, which is the one a cursor points at.
+    ///
+    /// Returns `None` if the position isn't inside any layer.
+    /// Cells are those of square grids.
+    /// ```
+    /// # use bevy::prelude::*;
+    /// # use bevy_ecs_ldtk::prelude::*;
+    /// fn pick(
+    ///     level_query: LdtkLevelQuery,
+    ///     windows: Query<&Window>,
+    ///     cameras: Query<(&Camera, &GlobalTransform)>,
+    /// ) {
+    ///     let (camera, camera_transform) = cameras.single();
+    ///     let Some(world_pos) = windows
+    ///         .single()
+    ///         .cursor_position()
+    ///         .and_then(|cursor| camera.viewport_to_world_2d(camera_transform, cursor))
+    ///     else {
+    ///         return;
+    ///     };
+    ///
+    ///     if let Some(location) = level_query.locate_on_layer(world_pos, "Ground") {
+    ///         info!("{:?} in {}", location.grid_coords, location.level_iid);
+    ///     }
+    /// }
+    /// ```
+    pub fn locate(&self, world_pos: Vec2) -> Option<LdtkLocation> {
+        self.locate_where(world_pos, |_| true)
+    }
+
+    /// Like [`LdtkLevelQuery::locate`], but only finds cells of the layers with the given
+    /// identifier.
+    pub fn locate_on_layer(&self, world_pos: Vec2, layer_identifier: &str) -> Option<LdtkLocation> {
+        self.locate_where(world_pos, |layer_metadata| {
+            layer_metadata.identifier == layer_identifier
+        })
+    }
+
+    fn locate_where(
+        &self,
+        world_pos: Vec2,
+        filter: impl Fn(&LayerMetadata) -> bool,
+    ) -> Option<LdtkLocation> {
+        let filter = &filter;
+
+        self.level_query
+            .iter()
+            .flat_map(|(level_entity, level_iid, level_transform)| {
+                let level_pos = level_transform
+                    .affine()
+                    .inverse()
+                    .transform_point3(world_pos.extend(0.))
+                    .truncate();
+
+                self.index
+                    .layers_in_level(level_iid)
+                    .filter_map(move |layer_entity| {
+                        let (layer_metadata, _, layer_transform) =
+                            self.layer_query.get(layer_entity).ok()?;
+
+                        if !filter(layer_metadata) {
+                            return None;
+                        }
+
+                        let grid_coords =
+                            translation_to_layer_grid_coords(level_pos, layer_metadata);
+
+                        let in_layer = (0..layer_metadata.c_wid).contains(&grid_coords.x)
+                            && (0..layer_metadata.c_hei).contains(&grid_coords.y);
+
+                        in_layer.then(|| {
+                            (
+                                layer_transform.translation().z,
+                                LdtkLocation {
+                                    level_iid: level_iid.clone(),
+                                    level_entity,
+                                    layer_identifier: layer_metadata.identifier.clone(),
+                                    layer_entity,
+                                    grid_coords,
+                                },
+                            )
+                        })
+                    })
+            })
+            .max_by(|(a, _), (b, _)| a.total_cmp(b))
+            .map(|(_, location)| location)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::ecs::system::SystemState;
+
+    #[test]
+    fn index_follows_inserts_and_removals() {
+        let mut world = World::new();
+        let [chest, door, walls] = [(); 3].map(|_| world.spawn_empty().id());
+        let cave = LevelIid::new("cave");
+        let town = LevelIid::new("town");
+
+        let mut index = LdtkLevelIndex::default();
+        index.insert_entity(chest, cave.clone());
+        index.insert_entity(door, cave.clone());
+        index.insert_layer(walls, cave.clone());
+
+        assert_eq!(
+            index.entities_in_level(&cave).collect::<HashSet<_>>(),
+            HashSet::from([chest, door])
+        );
+        assert_eq!(
+            index.layers_in_level(&cave).collect::<Vec<_>>(),
+            vec![walls]
+        );
+        assert_eq!(index.level_of(walls), Some(&cave));
+
+        index.insert_entity(door, town.clone());
+        assert_eq!(
+            index.entities_in_level(&cave).collect::<Vec<_>>(),
+            vec![chest]
+        );
+        assert_eq!(
+            index.entities_in_level(&town).collect::<Vec<_>>(),
+            vec![door]
+        );
+
+        index.remove(chest);
+        index.remove(walls);
+        assert_eq!(index.entities_in_level(&cave).count(), 0);
+        assert_eq!(index.layers_in_level(&cave).count(), 0);
+        assert_eq!(index.level_of(chest), None);
+        assert_eq!(index, {
+            let mut expected = LdtkLevelIndex::default();
+            expected.insert_entity(door, town);
+            expected
+        });
+    }
+
+    #[test]
+    fn locate_finds_cells_of_the_front_layer() {
+        let mut world = World::new();
+        let cave = LevelIid::new("cave");
+
+        // A 64x64 level placed 100 units right of the origin
+        let level = world
+            .spawn((
+                cave.clone(),
+                GlobalTransform::from_translation(Vec3::new(100., 0., 0.)),
+            ))
+            .id();
+
+        let ground = world
+            .spawn((
+                LayerMetadata {
+                    identifier: "Ground".to_string(),
+                    grid_size: 16,
+                    c_wid: 4,
+                    c_hei: 4,
+                    ..default()
+                },
+                GlobalTransform::from_translation(Vec3::new(100., 0., 2.)),
+            ))
+            .id();
+
+        // A finer layer behind the ground, moved down a cell
+        let collision = world
+            .spawn((
+                LayerMetadata {
+                    identifier: "Collision".to_string(),
+                    grid_size: 8,
+                    c_wid: 8,
+                    c_hei: 8,
+                    px_total_offset_y: 8,
+                    ..default()
+                },
+                GlobalTransform::from_translation(Vec3::new(100., -8., 1.)),
+            ))
+            .id();
+
+        let mut index = LdtkLevelIndex::default();
+        index.insert_layer(ground, cave.clone());
+        index.insert_layer(collision, cave.clone());
+        world.insert_resource(index);
+
+        let mut system_state: SystemState<LdtkLevelQuery> = SystemState::new(&mut world);
+        let level_query = system_state.get(&world);
+
+        assert_eq!(
+            level_query.locate(Vec2::new(120., 5.)),
+            Some(LdtkLocation {
+                level_iid: cave.clone(),
+                level_entity: level,
+                layer_identifier: "Ground".to_string(),
+                layer_entity: ground,
+                grid_coords: GridCoords::new(1, 0),
+            })
+        );
+        assert_eq!(
+            level_query
+                .locate_on_layer(Vec2::new(120., 5.), "Collision")
+                .map(|location| location.grid_coords),
+            Some(GridCoords::new(2, 1))
+        );
+
+        // Below the ground, but still inside the lowered collision layer
+        assert_eq!(
+            level_query
+                .locate(Vec2::new(120., -5.))
+                .map(|location| location.layer_entity),
+            Some(collision)
+        );
+        assert_eq!(level_query.locate(Vec2::new(50., 5.)), None);
+    }
+}
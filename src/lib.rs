@@ -31,7 +31,7 @@
 //!     commands.spawn(Camera2dBundle::default());
 //!
 //!     commands.spawn(LdtkWorldBundle {
-//!         ldtk_handle: asset_server.load("my_project.ldtk"),
+//!         ldtk_handle: asset_server.load("my_project.ldtk").into(),
 //!         ..Default::default()
 //!     });
 //! }
@@ -104,11 +104,46 @@
 //! I.e., projects that store level data within the main project file.
 //! - `external_levels`: Enable support for projects that store levels externally.
 //! I.e., projects that store data for each level in files separate from the main project file.
+//! Their level files can be loaded lazily with [`assets::ExternalLevelLoading`].
 //! - `derive`: Enables the derive macros for [LdtkEntity] and [LdtkIntCell].
 //! - `render`: Enables rendering via [bevy_ecs_tilemap]'s `render` feature. Disable it if you want
 //! to run in headless mode.
 //! - `atlas`: Enables the `atlas` feature of [bevy_ecs_tilemap]. This is required for WASM support
 //! and also for tile spacing to work on Tile and AutoTile layers.
+//! - `lighting`: Generates light and light occluder data from LDtk projects.
+//! See the [lighting] module for more details.
+//! - `physics`: Reads gravity and timestep scale from the fields of the selected level.
+//! See the [physics] module for more details.
+//! - `physics_rapier`: Enables `physics`, and applies the level's physics to `bevy_rapier2d`.
+//! - `physics_xpbd`: Enables `physics`, and applies the level's physics to `bevy_xpbd_2d`.
+//! - `text`: Spawns text displays for LDtk entities with text fields, and enables labels on
+//! [world_map::WorldMap]s.
+//! See the [text] module for more details.
+//! - `bevy_audio`: Spawns ambient audio emitters for LDtk entities with sound fields.
+//! See the [audio] module for more details.
+//! - `save`: Records runtime changes to spawned levels so they persist across respawns and
+//! sessions.
+//! See the [save] module for more details.
+//! - `scene`: Enables exporting spawned levels to bevy scenes.
+//! See the [scene] module for more details.
+//! - `debug`: Enables a debug overlay that draws level bounds, layer grids, entity bounds, and
+//! identifiers.
+//! See the [debug] module for more details.
+//! - `cli`: Builds the `ldtk-validate` binary, which checks projects for problems.
+//! See the [validation] module for more details.
+//! - `extras`: Captures fields that aren't part of this crate's LDtk schema in an `extras` field on
+//! most [ldtk] types, so data added by newer versions of LDtk can still be reached.
+//! - `test_utils`: Provides project fixtures and assertions for integration tests.
+//! See the [test_utils] module for more details.
+//! - `live_sync`: Applies entity edits made in LDtk to the running game without respawning levels.
+//! Intended for development builds.
+//! See the [live_sync] module for more details.
+//! - `asset_loader`: Tracks the loading progress of LDtk projects and their dependencies in
+//! loading states, like those of `bevy_asset_loader`.
+//! See the [asset_loader] module for more details.
+//! - `replication`: Provides serializable level spawn commands, so multiplayer servers can drive
+//! the levels spawned by their clients.
+//! See the [replication] module for more details.
 //!
 //! The `derive`, `render`, and `internal_levels` features are enabled by default.
 //! Furthermore, one or both of `internal_levels` and `external_levels` must be enabled.
@@ -122,15 +157,54 @@
 //! [bevy_ecs_tilemap]: https://docs.rs/bevy_ecs_tilemap
 
 pub mod app;
+#[cfg(feature = "asset_loader")]
+pub mod asset_loader;
 pub mod assets;
+#[cfg(feature = "bevy_audio")]
+pub mod audio;
+pub mod camera;
+pub mod checkpoint;
 mod components;
+pub mod composite;
+#[cfg(feature = "debug")]
+pub mod debug;
+pub mod diagnostics;
+#[cfg(feature = "internal_levels")]
+pub mod editing;
+pub mod entity_culling;
+pub mod int_grid_regions;
+pub mod interior;
+pub mod layer_tiles;
 pub mod ldtk;
 mod level;
+#[cfg(feature = "internal_levels")]
+pub mod level_builder;
+pub mod level_query;
+#[cfg(feature = "lighting")]
+pub mod lighting;
+#[cfg(feature = "live_sync")]
+pub mod live_sync;
+#[cfg(feature = "physics")]
+pub mod physics;
 mod plugin;
+pub mod preview;
+#[cfg(feature = "replication")]
+pub mod replication;
 mod resources;
+#[cfg(feature = "save")]
+pub mod save;
+#[cfg(feature = "scene")]
+pub mod scene;
+pub mod server_level;
 pub mod systems;
+#[cfg(feature = "test_utils")]
+pub mod test_utils;
+#[cfg(feature = "text")]
+pub mod text;
 mod tile_makers;
 pub mod utils;
+pub mod validation;
+pub mod world_map;
 
 pub use components::*;
 pub use plugin::*;
@@ -143,20 +217,39 @@ pub mod prelude {
     //! `use bevy_ecs_ldtk::prelude::*;` to import commonly used items.
 
     pub use crate::{
-        app::{LdtkEntity, LdtkEntityAppExt, LdtkIntCell, LdtkIntCellAppExt},
-        assets::{LdtkProject, LevelIndices, LevelMetadataAccessor},
+        app::{LdtkEntity, LdtkEntityAppExt, LdtkIntCell, LdtkIntCellAppExt, ProjectConfigSource},
+        assets::{LdtkProject, LdtkProjectHandle, LevelIndices, LevelMetadataAccessor},
         components::{
-            EntityIid, EntityInstance, GridCoords, IntGridCell, LayerMetadata, LdtkWorldBundle,
-            LevelIid, LevelSet, Respawn, TileEnumTags, TileMetadata, Worldly,
+            BackgroundTile, EditorVisualPlaceholder, EntityIid, EntityInstance, EntityReferences,
+            EntityStateFlags, EntityTags, FieldOverrides, GridCoords, GridCoordsAnchor,
+            GridCoordsRect, IntGridCell, IntGridCells, IntGridTexture, LayerFade, LayerMetadata,
+            LayerParallax, LdtkParallaxCamera, LdtkWorldBundle, LevelIid, LevelPostProcessing,
+            LevelReveal, LevelRevealStyle, LevelSeed, LevelSet, LevelTilesets, MaterialEnumTag,
+            OwningLevel, PreserveOnRespawn, ReferencedBy, RepeatingBackground, Respawn, SpawnFocus,
+            StableEntityId, TileAnimation, TileDataTable, TileEnumTags, TileMetadata,
+            TransformOverride, Worldly, YSort,
         },
+        layer_tiles::{LayerTileData, LayerTiles, LdtkTileCommands},
         ldtk::{
-            self, ldtk_fields::LdtkFields, raw_level_accessor::RawLevelAccessor, FieldValue,
-            LayerInstance, TilesetDefinition,
+            self, ldtk_fields::LdtkFields, neighbor_direction::NeighborDirection,
+            raw_level_accessor::RawLevelAccessor, FieldValue, LayerInstance, TilesetDefinition,
         },
-        plugin::{LdtkPlugin, ProcessLdtkApi},
+        level_query::{LdtkLevelIndex, LdtkLevelQuery, LdtkLocation},
+        plugin::{LdtkPlugin, LevelEventSet, ProcessLdtkApi, SpawnPhaseSet},
         resources::{
-            IntGridRendering, LdtkSettings, LevelBackground, LevelEvent, LevelSelection,
-            LevelSpawnBehavior, SetClearColor, SpawnExclusions,
+            BackgroundImageSettings, BackgroundRepeat, BundlePhase, DeferredLdtkBundles,
+            DespawnReason, DeterministicSpawning, DuplicateLevel, EntityEditorVisuals,
+            EntityPooling, EntityRefResolver, EntityRefTarget, EntityZIndex, GridShape,
+            IntGridCellStorage, IntGridRendering, IntGridTextures, InvalidLevelSelection,
+            LayerPlacement, LayerVariants, LdtkEntityDespawned, LdtkEntityPool, LdtkError,
+            LdtkErrorPolicy, LdtkLocalization, LdtkSettings, LevelAnchor, LevelBackground,
+            LevelCulling, LevelDuplicates, LevelEvent, LevelLifecycleEvent, LevelRunSeed,
+            LevelSelection, LevelSelectionError, LevelSpawnBehavior, LevelSpawnOverride,
+            LevelSpawnOverrides, LevelTransition, LevelTransitionEvent, LevelTransitionQueue,
+            LevelVariation, PersistentEntityState, RespawnWorld, RespawningWorld, SetClearColor,
+            SpawnBudget, SpawnExclusions, SpawnPhases, TileMetadataStorage, TilemapSettings,
+            TilesetPrewarming, TilesetSkins, TransitionPolicy, VariationRule, WorldRespawnEvent,
+            WorldlyTag, ZSpacing,
         },
     };
 
@@ -0,0 +1,200 @@
+//! Light and light occluder data generated from LDtk projects.
+//!
+//! *Requires the "lighting" feature*
+//!
+//! When enabled, the plugin inserts [`LightOccluder2d`] components on int grid cells with the
+//! values listed in [`LdtkLightingSettings::occluder_int_grid_values`], and [`PointLight2d`]
+//! components on LDtk entities tagged with [`LdtkLightingSettings::light_tag`].
+//!
+//! These components are plain data, and don't render anything by themselves.
+//! They are meant to be mirrored into the components of whatever 2D lighting crate your game uses,
+//! so that lighting setup doesn't require walking the LDtk data a second time.
+//! ```
+//! # use bevy::prelude::*;
+//! # use bevy_ecs_ldtk::lighting::PointLight2d;
+//! # #[derive(Component)]
+//! # struct MyLightingCratesLight { color: Color, radius: f32 }
+//! fn mirror_lights(mut commands: Commands, lights: Query<(Entity, &PointLight2d), Added<PointLight2d>>) {
+//!     for (entity, light) in lights.iter() {
+//!         commands.entity(entity).insert(MyLightingCratesLight {
+//!             color: light.color,
+//!             radius: light.radius,
+//!         });
+//!     }
+//! }
+//! ```
+
+use crate::ldtk::{ldtk_fields::LdtkFields, EntityInstance};
+use bevy::prelude::*;
+use std::collections::HashSet;
+
+/// Settings for generating lighting data, found in [`LdtkSettings`].
+///
+/// [`LdtkSettings`]: crate::prelude::LdtkSettings
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct LdtkLightingSettings {
+    /// Int grid values whose cells should occlude light, on any IntGrid layer.
+    pub occluder_int_grid_values: HashSet<i32>,
+    /// Entity tag that marks LDtk entities as point lights.
+    pub light_tag: String,
+    /// Identifier of the `Color` field used for a light's color.
+    ///
+    /// Falls back to the entity's smart color.
+    pub color_field: String,
+    /// Identifier of the `Float` field used for a light's intensity.
+    ///
+    /// Falls back to `1.0`.
+    pub intensity_field: String,
+    /// Identifier of the `Float` field used for a light's radius, in pixels.
+    ///
+    /// Falls back to half of the entity's largest dimension.
+    pub radius_field: String,
+}
+
+impl Default for LdtkLightingSettings {
+    fn default() -> Self {
+        LdtkLightingSettings {
+            occluder_int_grid_values: HashSet::new(),
+            light_tag: "light".to_string(),
+            color_field: "color".to_string(),
+            intensity_field: "intensity".to_string(),
+            radius_field: "radius".to_string(),
+        }
+    }
+}
+
+/// [`Component`] describing a rectangular light occluder centered on the entity.
+///
+/// [`Component`]: https://docs.rs/bevy/latest/bevy/ecs/component/trait.Component.html
+#[derive(Copy, Clone, PartialEq, Debug, Default, Component, Reflect)]
+#[reflect(Component)]
+pub struct LightOccluder2d {
+    pub half_size: Vec2,
+}
+
+/// [`Component`] describing a point light centered on the entity.
+///
+/// [`Component`]: https://docs.rs/bevy/latest/bevy/ecs/component/trait.Component.html
+#[derive(Copy, Clone, PartialEq, Debug, Default, Component, Reflect)]
+#[reflect(Component)]
+pub struct PointLight2d {
+    pub color: Color,
+    pub intensity: f32,
+    /// Radius of the light in pixels.
+    pub radius: f32,
+}
+
+impl PointLight2d {
+    /// Creates a [`PointLight2d`] from an entity instance, if it has the configured light tag.
+    pub fn from_entity_info(
+        entity_instance: &EntityInstance,
+        settings: &LdtkLightingSettings,
+    ) -> Option<PointLight2d> {
+        if !entity_instance.tags.contains(&settings.light_tag) {
+            return None;
+        }
+
+        let color = entity_instance
+            .get_color_field(&settings.color_field)
+            .copied()
+            .unwrap_or(entity_instance.smart_color);
+
+        let intensity = entity_instance
+            .get_float_field(&settings.intensity_field)
+            .copied()
+            .unwrap_or(1.);
+
+        let radius = entity_instance
+            .get_float_field(&settings.radius_field)
+            .copied()
+            .unwrap_or(entity_instance.width.max(entity_instance.height) as f32 / 2.);
+
+        Some(PointLight2d {
+            color,
+            intensity,
+            radius,
+        })
+    }
+}
+
+impl LightOccluder2d {
+    /// Creates a [`LightOccluder2d`] covering an int grid cell, if its value is configured to
+    /// occlude light.
+    pub fn from_int_grid_value(
+        value: i32,
+        grid_size: i32,
+        settings: &LdtkLightingSettings,
+    ) -> Option<LightOccluder2d> {
+        if settings.occluder_int_grid_values.contains(&value) {
+            Some(LightOccluder2d {
+                half_size: Vec2::splat(grid_size as f32 / 2.),
+            })
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ldtk::{ldtk_fields::field_instance_from_value, FieldValue};
+
+    use super::*;
+
+    #[test]
+    fn point_lights_read_fields_with_fallbacks() {
+        let settings = LdtkLightingSettings::default();
+
+        let untagged = EntityInstance::default();
+        assert_eq!(PointLight2d::from_entity_info(&untagged, &settings), None);
+
+        let defaults = EntityInstance {
+            tags: vec!["light".to_string()],
+            smart_color: Color::RED,
+            width: 16,
+            height: 32,
+            ..Default::default()
+        };
+        assert_eq!(
+            PointLight2d::from_entity_info(&defaults, &settings),
+            Some(PointLight2d {
+                color: Color::RED,
+                intensity: 1.,
+                radius: 16.,
+            })
+        );
+
+        let with_fields = EntityInstance {
+            field_instances: vec![
+                field_instance_from_value("color", FieldValue::Color(Color::BLUE)),
+                field_instance_from_value("intensity", FieldValue::Float(Some(2.))),
+                field_instance_from_value("radius", FieldValue::Float(Some(64.))),
+            ],
+            ..defaults
+        };
+        assert_eq!(
+            PointLight2d::from_entity_info(&with_fields, &settings),
+            Some(PointLight2d {
+                color: Color::BLUE,
+                intensity: 2.,
+                radius: 64.,
+            })
+        );
+    }
+
+    #[test]
+    fn occluders_only_cover_configured_values() {
+        let settings = LdtkLightingSettings {
+            occluder_int_grid_values: [1, 3].into_iter().collect(),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            LightOccluder2d::from_int_grid_value(3, 16, &settings),
+            Some(LightOccluder2d {
+                half_size: Vec2::splat(8.)
+            })
+        );
+        assert_eq!(LightOccluder2d::from_int_grid_value(2, 16, &settings), None);
+    }
+}
@@ -0,0 +1,301 @@
+//! Live updates of spawned levels while the project is edited in LDtk, for playtesting.
+//!
+//! By default, saving the project in LDtk while the game is running respawns every level of the
+//! project once the asset reloads.
+//! With the [`LdtkLiveSyncPlugin`], saves that only move entities or edit their fields are
+//! applied to the spawned entities instead, so the running game keeps its state:
+//! - Moved entities have their [`Transform`] and [`GridCoords`] shifted by the same amount.
+//! - The [`EntityInstance`] component of changed entities is replaced with the new one.
+//!
+//! Any other change, like editing tiles, adding or removing entities, or editing definitions,
+//! still respawns the levels.
+//!
+//! Bundles built from entity fields are not rebuilt.
+//! To react to edited fields, query for [`Changed<EntityInstance>`](Changed).
+//! ```no_run
+//! use bevy::{asset::ChangeWatcher, prelude::*};
+//! use bevy_ecs_ldtk::{live_sync::LdtkLiveSyncPlugin, prelude::*};
+//! use std::time::Duration;
+//!
+//! fn main() {
+//!     App::new()
+//!         .add_plugins((
+//!             DefaultPlugins.set(AssetPlugin {
+//!                 watch_for_changes: ChangeWatcher::with_delay(Duration::from_millis(100)),
+//!                 ..default()
+//!             }),
+//!             LdtkPlugin,
+//!             LdtkLiveSyncPlugin,
+//!         ))
+//!         .run();
+//! }
+//! ```
+//!
+//! This is intended for development builds only.
+//! Only projects with internal levels are synced, projects with external levels are always
+//! respawned.
+//!
+//! [`GridCoords`]: crate::prelude::GridCoords
+//! [`EntityInstance`]: crate::prelude::EntityInstance
+
+use crate::{
+    assets::LdtkProject,
+    components::{EntityIid, GridCoords},
+    ldtk::{raw_level_accessor::RawLevelAccessor, EntityInstance, LdtkJson, Level},
+    systems::process_ldtk_assets,
+};
+use bevy::prelude::*;
+use std::collections::{HashMap, HashSet};
+
+/// Change to a single LDtk entity that can be applied without respawning its level.
+#[derive(Clone, PartialEq, Debug)]
+pub struct LiveEntityChange {
+    /// The new data of the entity.
+    pub entity_instance: EntityInstance,
+    /// How far the entity moved, in LDtk pixel coordinates.
+    pub px_delta: IVec2,
+    /// How far the entity moved, in LDtk grid coordinates.
+    pub grid_delta: IVec2,
+}
+
+/// Compares two versions of a level, returning the changes to its entities.
+///
+/// Returns `None` if the level changed in any other way.
+pub fn diff_level(old: &Level, new: &Level) -> Option<Vec<LiveEntityChange>> {
+    let mut changes = Vec::new();
+    let mut normalized = new.clone();
+
+    let old_layers = old.layer_instances.iter().flatten();
+    let new_layers = normalized.layer_instances.iter_mut().flatten();
+
+    for (old_layer, new_layer) in old_layers.zip(new_layers) {
+        if old_layer.entity_instances.len() != new_layer.entity_instances.len() {
+            return None;
+        }
+
+        for (old_entity, new_entity) in old_layer
+            .entity_instances
+            .iter()
+            .zip(new_layer.entity_instances.iter_mut())
+        {
+            if old_entity.iid != new_entity.iid {
+                return None;
+            }
+
+            if old_entity != new_entity {
+                changes.push(LiveEntityChange {
+                    entity_instance: new_entity.clone(),
+                    px_delta: new_entity.px - old_entity.px,
+                    grid_delta: new_entity.grid - old_entity.grid,
+                });
+
+                // Only the position and fields may differ, the rest is compared below
+                new_entity.px = old_entity.px;
+                new_entity.grid = old_entity.grid;
+                new_entity.world_x = old_entity.world_x;
+                new_entity.world_y = old_entity.world_y;
+                new_entity.field_instances = old_entity.field_instances.clone();
+            }
+        }
+    }
+
+    (normalized == *old).then_some(changes)
+}
+
+/// Compares two versions of a project, returning the changes to the entities of all its levels.
+///
+/// Returns `None` if the project changed in any other way, or if it has external levels.
+pub fn diff_project(old: &LdtkJson, new: &LdtkJson) -> Option<Vec<LiveEntityChange>> {
+    if old.external_levels || new.external_levels {
+        return None;
+    }
+
+    let without_levels = |data: &LdtkJson| LdtkJson {
+        levels: Vec::new(),
+        worlds: data
+            .worlds
+            .iter()
+            .map(|world| crate::ldtk::World {
+                levels: Vec::new(),
+                ..world.clone()
+            })
+            .collect(),
+        ..data.clone()
+    };
+
+    if without_levels(old) != without_levels(new)
+        || old.iter_raw_levels().count() != new.iter_raw_levels().count()
+    {
+        return None;
+    }
+
+    let mut changes = Vec::new();
+
+    for (old_level, new_level) in old.iter_raw_levels().zip(new.iter_raw_levels()) {
+        changes.extend(diff_level(old_level, new_level)?);
+    }
+
+    Some(changes)
+}
+
+/// [`Resource`] holding the project data that edits are compared against.
+#[derive(Clone, Debug, Default, Resource)]
+struct LiveSyncSnapshots {
+    projects: HashMap<Handle<LdtkProject>, LdtkJson>,
+}
+
+/// [`Resource`] listing projects that were synced this update, so they aren't respawned.
+#[derive(Clone, Debug, Default, Resource)]
+pub struct LiveSyncedProjects {
+    pub(crate) handles: HashSet<Handle<LdtkProject>>,
+}
+
+/// Plugin that applies entity edits made in LDtk to the spawned entities while the game runs.
+///
+/// See the [module-level documentation](crate::live_sync) for details.
+///
+/// Not added by [`LdtkPlugin`].
+///
+/// [`LdtkPlugin`]: crate::prelude::LdtkPlugin
+#[derive(Copy, Clone, Debug, Default)]
+pub struct LdtkLiveSyncPlugin;
+
+impl Plugin for LdtkLiveSyncPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<LiveSyncSnapshots>()
+            .init_resource::<LiveSyncedProjects>()
+            .add_systems(PreUpdate, sync_edited_projects.before(process_ldtk_assets));
+    }
+}
+
+fn sync_edited_projects(
+    mut ldtk_project_events: EventReader<AssetEvent<LdtkProject>>,
+    ldtk_project_assets: Res<Assets<LdtkProject>>,
+    mut snapshots: ResMut<LiveSyncSnapshots>,
+    mut live_synced: ResMut<LiveSyncedProjects>,
+    mut entity_query: Query<(
+        &EntityIid,
+        &mut EntityInstance,
+        Option<&mut Transform>,
+        Option<&mut GridCoords>,
+    )>,
+) {
+    let mut changes = HashMap::new();
+
+    for event in ldtk_project_events.iter() {
+        match event {
+            AssetEvent::Created { handle } => {
+                if let Some(project) = ldtk_project_assets.get(handle) {
+                    snapshots
+                        .projects
+                        .insert(handle.clone_weak(), project.json_data().clone());
+                }
+            }
+            AssetEvent::Modified { handle } => {
+                let Some(project) = ldtk_project_assets.get(handle) else {
+                    continue;
+                };
+
+                let new = project.json_data().clone();
+
+                let diff = snapshots
+                    .projects
+                    .get(handle)
+                    .and_then(|old| diff_project(old, &new));
+
+                if let Some(diff) = diff {
+                    info!("Live syncing {} LDtk entity changes.", diff.len());
+                    changes.extend(diff.into_iter().map(|change| {
+                        (EntityIid::new(change.entity_instance.iid.clone()), change)
+                    }));
+                    live_synced.handles.insert(handle.clone_weak());
+                }
+
+                snapshots.projects.insert(handle.clone_weak(), new);
+            }
+            AssetEvent::Removed { handle } => {
+                snapshots.projects.remove(handle);
+            }
+        }
+    }
+
+    if changes.is_empty() {
+        return;
+    }
+
+    for (entity_iid, mut entity_instance, transform, grid_coords) in entity_query.iter_mut() {
+        let Some(change) = changes.get(entity_iid) else {
+            continue;
+        };
+
+        *entity_instance = change.entity_instance.clone();
+
+        if let Some(mut transform) = transform {
+            transform.translation.x += change.px_delta.x as f32;
+            transform.translation.y -= change.px_delta.y as f32;
+        }
+
+        if let Some(mut grid_coords) = grid_coords {
+            *grid_coords += GridCoords::new(change.grid_delta.x, -change.grid_delta.y);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ldtk::{ldtk_fields::field_instance_from_value, FieldValue, LayerInstance};
+
+    fn level(entities: Vec<EntityInstance>, int_grid_csv: Vec<i32>) -> Level {
+        Level {
+            iid: "level".to_string(),
+            layer_instances: Some(vec![
+                LayerInstance {
+                    entity_instances: entities,
+                    ..Default::default()
+                },
+                LayerInstance {
+                    int_grid_csv,
+                    ..Default::default()
+                },
+            ]),
+            ..Default::default()
+        }
+    }
+
+    fn chest(px: IVec2, gold: i32) -> EntityInstance {
+        EntityInstance {
+            iid: "chest".to_string(),
+            px,
+            grid: px / 16,
+            field_instances: vec![field_instance_from_value(
+                "gold",
+                FieldValue::Int(Some(gold)),
+            )],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn entity_edits_are_synced_and_other_edits_are_not() {
+        let old = level(vec![chest(IVec2::new(16, 32), 5)], vec![0, 1]);
+
+        let moved = level(vec![chest(IVec2::new(48, 16), 10)], vec![0, 1]);
+        assert_eq!(
+            diff_level(&old, &moved),
+            Some(vec![LiveEntityChange {
+                entity_instance: chest(IVec2::new(48, 16), 10),
+                px_delta: IVec2::new(32, -16),
+                grid_delta: IVec2::new(2, -1),
+            }])
+        );
+
+        assert_eq!(diff_level(&old, &old), Some(Vec::new()));
+
+        let repainted = level(vec![chest(IVec2::new(16, 32), 5)], vec![1, 1]);
+        assert_eq!(diff_level(&old, &repainted), None);
+
+        let removed = level(Vec::new(), vec![0, 1]);
+        assert_eq!(diff_level(&old, &removed), None);
+    }
+}
@@ -0,0 +1,367 @@
+//! Physics settings read from the fields of the selected level.
+//!
+//! *Requires the "physics" feature*
+//!
+//! When enabled, the plugin reads the fields named in [`LdtkPhysicsSettings`] from the level that
+//! becomes the [`LevelSelection`], and inserts them as the [`LevelPhysics`] resource.
+//! This lets rooms like underwater or low-gravity areas be authored entirely in LDtk.
+//!
+//! [`LevelPhysics`] is plain data, and doesn't affect any physics engine by itself.
+//! With the "physics_rapier" feature, `apply_level_physics_to_rapier` mirrors it into the
+//! `RapierConfiguration` of `bevy_rapier2d`.
+//! With the "physics_xpbd" feature, `apply_level_physics_to_xpbd` mirrors it into the `Gravity`
+//! and `PhysicsTimescale` of `bevy_xpbd_2d`.
+//! For other physics engines, mirror [`LevelPhysics`] into their configuration with a system of
+//! your own, reacting to the resource changing.
+//!
+//! [`LevelSelection`]: crate::prelude::LevelSelection
+
+use crate::{
+    assets::{LdtkProject, LdtkProjectHandle, LevelMetadataAccessor},
+    ldtk::{ldtk_fields::LdtkFields, Level},
+    preview::LevelPreview,
+    resources::{LdtkSettings, LevelSelection},
+};
+use bevy::prelude::*;
+
+#[cfg(feature = "physics_rapier")]
+use bevy_rapier2d::prelude::{RapierConfiguration, TimestepMode};
+
+#[cfg(feature = "physics_xpbd")]
+use bevy_xpbd_2d::prelude::{Gravity, PhysicsTimescale};
+
+/// Settings for reading [`LevelPhysics`] from level fields, found in [`LdtkSettings`].
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct LdtkPhysicsSettings {
+    /// Identifier of the `Float` field used for the x component of a level's gravity.
+    pub gravity_x_field: String,
+    /// Identifier of the `Float` field used for the y component of a level's gravity.
+    pub gravity_y_field: String,
+    /// Identifier of the `Float` field used for a level's timestep scale.
+    pub timestep_scale_field: String,
+}
+
+impl Default for LdtkPhysicsSettings {
+    fn default() -> Self {
+        LdtkPhysicsSettings {
+            gravity_x_field: "gravity_x".to_string(),
+            gravity_y_field: "gravity_y".to_string(),
+            timestep_scale_field: "timestep_scale".to_string(),
+        }
+    }
+}
+
+/// [Resource] storing the physics settings of the selected level.
+///
+/// Updated whenever a new level becomes the [`LevelSelection`], as soon as it's found in a loaded
+/// project.
+/// Values the level doesn't have fields for are [`None`], so your game's defaults can be used.
+#[derive(Copy, Clone, PartialEq, Debug, Default, Resource)]
+pub struct LevelPhysics {
+    /// The gravity of the level.
+    ///
+    /// Set if the level has either gravity field, with the missing component being 0.
+    pub gravity: Option<Vec2>,
+    /// How fast physics should run in the level, relative to normal speed.
+    pub timestep_scale: Option<f32>,
+}
+
+impl LevelPhysics {
+    /// Reads the physics settings of the given level.
+    pub fn from_level(level: &Level, settings: &LdtkPhysicsSettings) -> LevelPhysics {
+        let float_field = |identifier: &str| level.get_float_field(identifier).ok().copied();
+
+        let gravity_x = float_field(&settings.gravity_x_field);
+        let gravity_y = float_field(&settings.gravity_y_field);
+
+        LevelPhysics {
+            gravity: (gravity_x.is_some() || gravity_y.is_some())
+                .then(|| Vec2::new(gravity_x.unwrap_or_default(), gravity_y.unwrap_or_default())),
+            timestep_scale: float_field(&settings.timestep_scale_field),
+        }
+    }
+}
+
+/// Inserts the [`LevelPhysics`] of levels as they become the [`LevelSelection`].
+pub fn update_level_physics(
+    mut commands: Commands,
+    level_selection: Option<Res<LevelSelection>>,
+    ldtk_project_assets: Res<Assets<LdtkProject>>,
+    ldtk_query: Query<&LdtkProjectHandle, Without<LevelPreview>>,
+    ldtk_settings: Res<LdtkSettings>,
+    mut active_selection: Local<Option<LevelSelection>>,
+) {
+    let Some(level_selection) = level_selection else {
+        return;
+    };
+
+    // The selection is resolved again until its project has loaded
+    if active_selection.as_ref() == Some(&*level_selection) {
+        return;
+    }
+
+    let Some(level) = ldtk_query.iter().find_map(|handle| {
+        ldtk_project_assets
+            .get(handle)?
+            .find_raw_level_by_level_selection(&level_selection)
+    }) else {
+        return;
+    };
+
+    commands.insert_resource(LevelPhysics::from_level(level, &ldtk_settings.physics));
+    *active_selection = Some(level_selection.clone());
+}
+
+/// Applies the [`LevelPhysics`] of the selected level to the `RapierConfiguration`.
+///
+/// *Requires the "physics_rapier" feature*
+///
+/// Values the level doesn't set fall back to those the configuration had before the first level
+/// was applied.
+/// The timestep scale only applies to the variable and interpolated timestep modes.
+#[cfg(feature = "physics_rapier")]
+pub fn apply_level_physics_to_rapier(
+    level_physics: Option<Res<LevelPhysics>>,
+    rapier_config: Option<ResMut<RapierConfiguration>>,
+    mut defaults: Local<Option<(Vec2, f32)>>,
+) {
+    let (Some(level_physics), Some(mut rapier_config)) = (level_physics, rapier_config) else {
+        return;
+    };
+
+    if !level_physics.is_changed() {
+        return;
+    }
+
+    let (default_gravity, default_time_scale) = *defaults.get_or_insert_with(|| {
+        (
+            rapier_config.gravity,
+            rapier_time_scale(&mut rapier_config.timestep_mode)
+                .map(|time_scale| *time_scale)
+                .unwrap_or(1.),
+        )
+    });
+
+    rapier_config.gravity = level_physics.gravity.unwrap_or(default_gravity);
+
+    if let Some(time_scale) = rapier_time_scale(&mut rapier_config.timestep_mode) {
+        *time_scale = level_physics.timestep_scale.unwrap_or(default_time_scale);
+    }
+}
+
+#[cfg(feature = "physics_rapier")]
+fn rapier_time_scale(timestep_mode: &mut TimestepMode) -> Option<&mut f32> {
+    match timestep_mode {
+        TimestepMode::Variable { time_scale, .. }
+        | TimestepMode::Interpolated { time_scale, .. } => Some(time_scale),
+        TimestepMode::Fixed { .. } => None,
+    }
+}
+
+/// Applies the [`LevelPhysics`] of the selected level to the `Gravity` and `PhysicsTimescale` of
+/// `bevy_xpbd_2d`.
+///
+/// *Requires the "physics_xpbd" feature*
+///
+/// Values the level doesn't set fall back to those the resources had before the first level was
+/// applied.
+#[cfg(feature = "physics_xpbd")]
+pub fn apply_level_physics_to_xpbd(
+    level_physics: Option<Res<LevelPhysics>>,
+    gravity: Option<ResMut<Gravity>>,
+    timescale: Option<ResMut<PhysicsTimescale>>,
+    mut defaults: Local<Option<(Vec2, f32)>>,
+) {
+    let (Some(level_physics), Some(mut gravity), Some(mut timescale)) =
+        (level_physics, gravity, timescale)
+    else {
+        return;
+    };
+
+    if !level_physics.is_changed() {
+        return;
+    }
+
+    let (default_gravity, default_timescale) =
+        *defaults.get_or_insert_with(|| (gravity.0, timescale.0));
+
+    gravity.0 = level_physics.gravity.unwrap_or(default_gravity);
+    timescale.0 = level_physics.timestep_scale.unwrap_or(default_timescale);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ldtk::{ldtk_fields::field_instance_from_value, FieldValue};
+
+    fn level_with_floats(fields: &[(&str, f32)]) -> Level {
+        Level {
+            field_instances: fields
+                .iter()
+                .map(|(identifier, value)| {
+                    field_instance_from_value(identifier, FieldValue::Float(Some(*value)))
+                })
+                .collect(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn level_physics_are_read_from_float_fields() {
+        let settings = LdtkPhysicsSettings::default();
+
+        assert_eq!(
+            LevelPhysics::from_level(
+                &level_with_floats(&[("gravity_y", -2.), ("timestep_scale", 0.5)]),
+                &settings
+            ),
+            LevelPhysics {
+                gravity: Some(Vec2::new(0., -2.)),
+                timestep_scale: Some(0.5),
+            }
+        );
+
+        assert_eq!(
+            LevelPhysics::from_level(&level_with_floats(&[]), &settings),
+            LevelPhysics::default()
+        );
+    }
+
+    #[cfg(feature = "internal_levels")]
+    #[test]
+    fn level_physics_follow_the_level_selection() {
+        use crate::{
+            assets::{LdtkJsonWithMetadata, LdtkProjectData, LevelMetadata},
+            ldtk::{raw_level_accessor::RawLevelAccessor, LdtkJson},
+        };
+        use std::collections::HashMap;
+
+        let json = LdtkJson {
+            levels: vec![
+                Level {
+                    iid: "cave".to_string(),
+                    ..level_with_floats(&[("gravity_y", -2.)])
+                },
+                Level {
+                    iid: "sky".to_string(),
+                    ..level_with_floats(&[("timestep_scale", 0.5)])
+                },
+            ],
+            ..default()
+        };
+        let level_map = json
+            .iter_raw_levels_with_indices()
+            .map(|(indices, level)| (level.iid.clone(), LevelMetadata::new(None, indices)))
+            .collect();
+        let project = LdtkProject::new(
+            LdtkProjectData::Standalone(LdtkJsonWithMetadata::new(json, level_map)),
+            HashMap::new(),
+            None,
+        );
+
+        let mut app = App::new();
+        app.add_plugins(AssetPlugin::default())
+            .add_asset::<LdtkProject>()
+            .init_resource::<LdtkSettings>()
+            .add_systems(Update, update_level_physics);
+
+        let handle = app.world.resource_mut::<Assets<LdtkProject>>().add(project);
+        app.world.spawn(LdtkProjectHandle::from(handle));
+
+        app.update();
+        assert!(app.world.get_resource::<LevelPhysics>().is_none());
+
+        app.insert_resource(LevelSelection::iid("cave"));
+        app.update();
+        assert_eq!(
+            *app.world.resource::<LevelPhysics>(),
+            LevelPhysics {
+                gravity: Some(Vec2::new(0., -2.)),
+                timestep_scale: None,
+            }
+        );
+
+        // Changes made by the game are kept until another level is selected
+        app.world.resource_mut::<LevelPhysics>().timestep_scale = Some(3.);
+        app.update();
+        assert_eq!(
+            app.world.resource::<LevelPhysics>().timestep_scale,
+            Some(3.)
+        );
+
+        app.insert_resource(LevelSelection::iid("sky"));
+        app.update();
+        assert_eq!(
+            *app.world.resource::<LevelPhysics>(),
+            LevelPhysics {
+                gravity: None,
+                timestep_scale: Some(0.5),
+            }
+        );
+    }
+
+    #[cfg(feature = "physics_rapier")]
+    #[test]
+    fn level_physics_are_applied_to_rapier() {
+        let mut app = App::new();
+        app.insert_resource(RapierConfiguration {
+            gravity: Vec2::new(0., -10.),
+            timestep_mode: TimestepMode::Variable {
+                max_dt: 1. / 60.,
+                time_scale: 1.,
+                substeps: 1,
+            },
+            ..default()
+        })
+        .add_systems(Update, apply_level_physics_to_rapier);
+
+        let time_scale = |app: &App| match app.world.resource::<RapierConfiguration>().timestep_mode
+        {
+            TimestepMode::Variable { time_scale, .. } => time_scale,
+            _ => unreachable!(),
+        };
+
+        app.insert_resource(LevelPhysics {
+            gravity: Some(Vec2::new(1., -2.)),
+            timestep_scale: Some(0.5),
+        });
+        app.update();
+        assert_eq!(
+            app.world.resource::<RapierConfiguration>().gravity,
+            Vec2::new(1., -2.)
+        );
+        assert_eq!(time_scale(&app), 0.5);
+
+        // Values the next level doesn't set go back to the original configuration
+        app.insert_resource(LevelPhysics::default());
+        app.update();
+        assert_eq!(
+            app.world.resource::<RapierConfiguration>().gravity,
+            Vec2::new(0., -10.)
+        );
+        assert_eq!(time_scale(&app), 1.);
+    }
+
+    #[cfg(feature = "physics_xpbd")]
+    #[test]
+    fn level_physics_are_applied_to_xpbd() {
+        let mut app = App::new();
+        app.insert_resource(Gravity(Vec2::new(0., -10.)))
+            .insert_resource(PhysicsTimescale(1.))
+            .add_systems(Update, apply_level_physics_to_xpbd);
+
+        app.insert_resource(LevelPhysics {
+            gravity: Some(Vec2::new(1., -2.)),
+            timestep_scale: Some(0.5),
+        });
+        app.update();
+        assert_eq!(app.world.resource::<Gravity>().0, Vec2::new(1., -2.));
+        assert_eq!(app.world.resource::<PhysicsTimescale>().0, 0.5);
+
+        // Values the next level doesn't set go back to the original resources
+        app.insert_resource(LevelPhysics::default());
+        app.update();
+        assert_eq!(app.world.resource::<Gravity>().0, Vec2::new(0., -10.));
+        assert_eq!(app.world.resource::<PhysicsTimescale>().0, 1.);
+    }
+}
@@ -1,22 +1,59 @@
 //! Provides [LdtkPlugin] and its scheduling-related dependencies.
-use crate::{app, assets, components, resources, systems};
+use crate::{
+    app, assets, components, entity_culling, interior, ldtk, level_query, preview, resources,
+    systems, world_map,
+};
 use bevy::{
-    app::MainScheduleOrder, ecs::schedule::ScheduleLabel, prelude::*, transform::TransformSystem,
+    app::MainScheduleOrder, ecs::schedule::ScheduleLabel, prelude::*,
+    render::view::VisibilitySystems, transform::TransformSystem,
 };
 
 /// Schedule for processing this plugin's ECS API, inserted after [Update].
 ///
 /// In particular, this set processes..
+/// - [resources::LevelTransitionQueue]
 /// - [resources::LevelSelection]
 /// - [components::LevelSet]
 /// - [components::Worldly]
 /// - [components::Respawn]
+/// - [resources::RespawnWorld]
 ///
 /// As a result, you can expect minimal frame delay when updating these in
 /// [Update].
 #[derive(Copy, Clone, Eq, PartialEq, Debug, Hash, ScheduleLabel)]
 pub struct ProcessLdtkApi;
 
+/// System sets in which the plugin sends [resources::LevelEvent]s.
+///
+/// Order your systems relative to these sets to read level events in the same update they're
+/// sent.
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Hash, SystemSet)]
+pub enum LevelEventSet {
+    /// Sends [resources::LevelEvent::SpawnTriggered] in the [ProcessLdtkApi] schedule.
+    SpawnTriggered,
+    /// Spawns levels and sends [resources::LevelEvent::Spawned] in [PreUpdate].
+    Spawned,
+    /// Sends [resources::LevelEvent::Transformed] in [PostUpdate], after
+    /// [TransformSystem::TransformPropagate].
+    Transformed,
+    /// Sends [resources::LevelLifecycleEvent]s at the end of [PostUpdate], after every other level
+    /// event of the update.
+    Lifecycle,
+}
+
+/// System sets for the phases of level spawning that can run apart from the rest of it.
+///
+/// See [resources::SpawnPhases].
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Hash, SystemSet)]
+pub enum SpawnPhaseSet {
+    /// Inserts the bundles deferred by [resources::BundlePhase::Deferred] in [PreUpdate], after
+    /// [LevelEventSet::Spawned].
+    ///
+    /// Add run conditions to this set to hold the bundles back, they stay queued in
+    /// [resources::DeferredLdtkBundles] until it runs.
+    Bundles,
+}
+
 #[derive(Copy, Clone, Eq, PartialEq, Debug, Hash, SystemSet)]
 enum ProcessApiSet {
     PreClean,
@@ -48,15 +85,54 @@ impl Plugin for LdtkPlugin {
             )
             .init_non_send_resource::<app::LdtkEntityMap>()
             .init_non_send_resource::<app::LdtkIntCellMap>()
+            .init_resource::<app::LdtkZOrdering>()
             .init_resource::<resources::LdtkSettings>()
+            .init_resource::<components::LevelPostProcessing>()
+            .init_resource::<resources::TilesetSkins>()
+            .init_resource::<resources::PersistentEntityState>()
+            .init_resource::<resources::LevelDuplicates>()
+            .init_resource::<resources::LevelSpawnOverrides>()
+            .init_resource::<resources::LdtkLocalization>()
+            .init_resource::<resources::LdtkErrorPolicy>()
+            .init_resource::<resources::LevelTransitionQueue>()
+            .init_resource::<resources::LayerVariants>()
+            .init_resource::<resources::LdtkEntityPool>()
+            .init_resource::<resources::PrewarmedTilesets>()
+            .init_resource::<resources::DeferredLdtkBundles>()
+            .init_resource::<resources::EntityInstanceGroups>()
+            .init_resource::<level_query::LdtkLevelIndex>()
             .add_event::<resources::LevelEvent>()
+            .add_event::<resources::LevelLifecycleEvent>()
+            .add_event::<resources::LevelTransitionEvent>()
+            .add_event::<resources::WorldRespawnEvent>()
+            .add_event::<resources::LdtkEntityDespawned>()
+            .add_event::<resources::LdtkError>()
+            .add_event::<resources::LevelSelectionError>()
             .add_systems(
                 PreUpdate,
-                (systems::process_ldtk_assets, systems::process_ldtk_levels),
+                (
+                    systems::process_ldtk_assets,
+                    systems::clear_entity_instance_groups.before(systems::process_ldtk_levels),
+                    systems::process_ldtk_levels.in_set(LevelEventSet::Spawned),
+                    systems::update_level_index.after(systems::process_ldtk_levels),
+                    (apply_deferred, systems::insert_deferred_bundles)
+                        .chain()
+                        .in_set(SpawnPhaseSet::Bundles),
+                ),
+            )
+            .configure_set(
+                PreUpdate,
+                SpawnPhaseSet::Bundles.after(LevelEventSet::Spawned),
             )
             .add_systems(
                 ProcessLdtkApi,
-                (systems::apply_level_selection, systems::apply_level_set)
+                (
+                    systems::track_world_respawns,
+                    systems::process_level_transitions,
+                    systems::apply_level_selection,
+                    systems::apply_level_set.in_set(LevelEventSet::SpawnTriggered),
+                    systems::prewarm_tilesets,
+                )
                     .chain()
                     .in_set(ProcessApiSet::PreClean),
             )
@@ -70,15 +146,145 @@ impl Plugin for LdtkPlugin {
                 PostUpdate,
                 (
                     systems::detect_level_spawned_events
-                        .pipe(systems::fire_level_transformed_events),
+                        .pipe(systems::fire_level_transformed_events)
+                        .in_set(LevelEventSet::Transformed)
+                        .after(TransformSystem::TransformPropagate),
+                    systems::fire_level_lifecycle_events
+                        .in_set(LevelEventSet::Lifecycle)
+                        .after(LevelEventSet::Transformed),
                     systems::worldly_adoption.after(TransformSystem::TransformPropagate),
+                    systems::track_ldtk_entity_despawns.before(systems::worldly_adoption),
+                    systems::update_referenced_by,
+                    systems::animate_tiles,
+                    systems::insert_int_grid_textures,
+                    systems::despawn_redundant_editor_visuals,
+                    systems::apply_layer_parallax.before(TransformSystem::TransformPropagate),
+                    systems::repeat_level_backgrounds
+                        .after(systems::apply_layer_parallax)
+                        .before(TransformSystem::TransformPropagate),
+                    systems::apply_y_sort.before(TransformSystem::TransformPropagate),
+                    preview::process_level_previews,
+                    systems::apply_active_level_post_processing,
+                    systems::apply_tileset_skins,
+                    systems::apply_layer_variants,
+                    systems::reveal_levels,
+                    systems::fade_layers,
+                    systems::cull_levels
+                        .after(TransformSystem::TransformPropagate)
+                        .before(VisibilitySystems::VisibilityPropagate),
                 ),
             )
             .register_type::<components::LevelIid>()
+            .register_type::<components::OwningLevel>()
             .register_type::<components::EntityIid>()
+            .register_type::<components::EntityReferences>()
+            .register_type::<components::ReferencedBy>()
+            .register_type::<components::EntityTags>()
             .register_type::<components::GridCoords>()
+            .register_type::<components::GridCoordsRect>()
             .register_type::<components::TileMetadata>()
             .register_type::<components::TileEnumTags>()
-            .register_type::<components::LayerMetadata>();
+            .register_type::<components::TileAnimation>()
+            .register_type::<components::TileDataTable>()
+            .register_type::<components::LayerMetadata>()
+            .register_type::<components::LayerParallax>()
+            .register_type::<components::LayerFade>()
+            .register_type::<components::EditorVisualPlaceholder>()
+            .register_type::<components::LdtkParallaxCamera>()
+            .register_type::<components::LevelPostProcessing>()
+            .register_type::<components::LevelReveal>()
+            .register_type::<components::MaterialEnumTag>()
+            .register_type::<components::EntityStateFlags>()
+            .register_type::<components::StableEntityId>()
+            .register_type::<components::LevelSeed>()
+            .register_type::<components::IntGridCell>()
+            .register_type::<components::IntGridCells>()
+            .register_type::<components::IntGridTexture>()
+            .register_type::<components::TransformOverride>()
+            .register_type::<components::Worldly>()
+            .register_type::<components::YSort>()
+            .register_type::<components::Respawn>()
+            .register_type::<components::PreserveOnRespawn>()
+            .register_type::<components::SpawnFocus>()
+            .register_type::<assets::LdtkProjectHandle>()
+            .register_type::<ldtk::EntityInstance>()
+            .register_type::<ldtk::FieldInstance>()
+            .register_type::<ldtk::FieldValue>()
+            .register_type::<ldtk::TilesetRectangle>()
+            .register_type::<ldtk::ReferenceToAnEntityInstance>()
+            .register_type::<interior::InteriorRevealer>()
+            .register_type::<world_map::WorldMapLevel>()
+            .register_type::<entity_culling::EntityCulled>()
+            .init_resource::<entity_culling::LdtkEntitySpatialIndex>()
+            .add_systems(
+                PostUpdate,
+                (
+                    interior::reveal_interiors.before(systems::fade_layers),
+                    entity_culling::cull_entities
+                        .after(TransformSystem::TransformPropagate)
+                        .before(VisibilitySystems::VisibilityPropagate),
+                ),
+            )
+            .add_systems(
+                PreUpdate,
+                world_map::spawn_world_map_proxies.after(systems::process_ldtk_assets),
+            );
+
+        #[cfg(feature = "lighting")]
+        {
+            app.register_type::<crate::lighting::LightOccluder2d>()
+                .register_type::<crate::lighting::PointLight2d>();
+        }
+
+        #[cfg(feature = "physics")]
+        {
+            app.add_systems(
+                ProcessLdtkApi,
+                crate::physics::update_level_physics.after(systems::apply_level_selection),
+            );
+
+            #[cfg(feature = "physics_rapier")]
+            app.add_systems(
+                ProcessLdtkApi,
+                crate::physics::apply_level_physics_to_rapier
+                    .after(crate::physics::update_level_physics),
+            );
+
+            #[cfg(feature = "physics_xpbd")]
+            app.add_systems(
+                ProcessLdtkApi,
+                crate::physics::apply_level_physics_to_xpbd
+                    .after(crate::physics::update_level_physics),
+            );
+        }
+
+        #[cfg(feature = "text")]
+        {
+            app.register_type::<crate::text::LdtkText>();
+        }
+
+        #[cfg(feature = "bevy_audio")]
+        {
+            app.register_type::<crate::audio::LdtkAudioEmitter>()
+                .register_type::<crate::audio::LdtkAudioListener>()
+                .add_systems(
+                    PostUpdate,
+                    crate::audio::attenuate_audio_emitters
+                        .after(TransformSystem::TransformPropagate),
+                );
+        }
+
+        #[cfg(feature = "external_levels")]
+        {
+            app.init_resource::<assets::ExternalLevelFetches>()
+                .add_event::<assets::ExternalLevelFetchEvent>()
+                .add_systems(
+                    ProcessLdtkApi,
+                    assets::fetch_external_levels
+                        .after(systems::apply_level_selection)
+                        .before(systems::apply_level_set)
+                        .in_set(ProcessApiSet::PreClean),
+                );
+        }
     }
 }
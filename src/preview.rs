@@ -0,0 +1,397 @@
+//! Offscreen rendering of level thumbnails, for level-select menus and save-slot previews.
+//!
+//! Use the [`LdtkPreview`] system parameter to request a preview.
+//! ```
+//! # use bevy::prelude::*;
+//! # use bevy_ecs_ldtk::{preview::LdtkPreview, prelude::*};
+//! #[derive(Resource)]
+//! struct Project(LdtkProjectHandle);
+//!
+//! fn spawn_thumbnail(mut commands: Commands, mut preview: LdtkPreview, project: Res<Project>) {
+//!     let image = preview.render_level(
+//!         &project.0,
+//!         "fa26aa50-fd0f-4dac-a076-3edfb0afd358",
+//!         UVec2::new(160, 90),
+//!     );
+//!
+//!     commands.spawn(ImageBundle {
+//!         image: UiImage::new(image),
+//!         ..default()
+//!     });
+//! }
+//! ```
+
+use crate::{
+    assets::{LdtkProject, LdtkProjectHandle, LevelMetadataAccessor},
+    components::{LdtkWorldBundle, LevelIid, LevelSet, LevelTilesets},
+};
+use bevy::{
+    core_pipeline::clear_color::ClearColorConfig,
+    ecs::system::SystemParam,
+    prelude::*,
+    render::{
+        camera::{RenderTarget, ScalingMode},
+        render_resource::{
+            Extent3d, TextureDescriptor, TextureDimension, TextureFormat, TextureUsages,
+        },
+        view::RenderLayers,
+    },
+};
+
+/// [`RenderLayers`] layer used by preview cameras and the levels they render.
+///
+/// [`RenderLayers`]: https://docs.rs/bevy/latest/bevy/render/view/struct.RenderLayers.html
+pub const PREVIEW_RENDER_LAYER: u8 = 31;
+
+/// Origin of the area that preview levels are spawned in, far away from regular gameplay.
+const PREVIEW_ORIGIN: Vec3 = Vec3::new(0., -100_000., 0.);
+
+/// Horizontal distance between concurrent previews.
+const PREVIEW_SPACING: f32 = 20_000.;
+
+/// Number of concurrent previews with distinct positions.
+const PREVIEW_SLOTS: u32 = 16;
+
+/// Number of frames a preview camera stays active before the preview is despawned.
+///
+/// The camera is only activated once the level and the images it's rendered with are loaded, so
+/// this only needs to cover the frames it takes for the render world to draw them.
+const PREVIEW_RENDER_FRAMES: u32 = 2;
+
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+enum PreviewState {
+    Spawning,
+    Rendering { frames_remaining: u32 },
+}
+
+/// [`Component`] marking the world entity of a level preview.
+///
+/// World entities with this component ignore [`LevelSelection`].
+///
+/// [`Component`]: https://docs.rs/bevy/latest/bevy/ecs/component/trait.Component.html
+/// [`LevelSelection`]: crate::prelude::LevelSelection
+#[derive(Clone, Eq, PartialEq, Debug, Component)]
+pub struct LevelPreview {
+    camera: Entity,
+    level_iid: LevelIid,
+    state: PreviewState,
+}
+
+/// [`SystemParam`] for rendering level thumbnails.
+///
+/// Sprites in preview levels are moved onto [`PREVIEW_RENDER_LAYER`], so they are never seen by
+/// other cameras.
+/// Tilemaps don't support render layers, so previews are also spawned far away from the origin to
+/// keep them out of sight.
+///
+/// [`SystemParam`]: https://docs.rs/bevy/latest/bevy/ecs/system/trait.SystemParam.html
+#[derive(SystemParam)]
+pub struct LdtkPreview<'w, 's> {
+    commands: Commands<'w, 's>,
+    images: ResMut<'w, Assets<Image>>,
+    next_slot: Local<'s, u32>,
+}
+
+impl<'w, 's> LdtkPreview<'w, 's> {
+    /// Renders a level into a new image of the given size, in pixels.
+    ///
+    /// The level is spawned far away from the origin on [`PREVIEW_RENDER_LAYER`], captured by an
+    /// offscreen camera, and despawned again a few frames later.
+    /// The returned image is blank until then.
+    /// The level is fit inside the image, keeping its aspect ratio.
+    ///
+    /// Like any other level, the preview level fires [`LevelEvent`]s while it spawns.
+    ///
+    /// [`LevelEvent`]: crate::prelude::LevelEvent
+    pub fn render_level(
+        &mut self,
+        ldtk_handle: &LdtkProjectHandle,
+        level_iid: impl Into<String>,
+        size: UVec2,
+    ) -> Handle<Image> {
+        let extent = Extent3d {
+            width: size.x,
+            height: size.y,
+            ..default()
+        };
+
+        let mut image = Image {
+            texture_descriptor: TextureDescriptor {
+                label: None,
+                size: extent,
+                dimension: TextureDimension::D2,
+                format: TextureFormat::Bgra8UnormSrgb,
+                mip_level_count: 1,
+                sample_count: 1,
+                usage: TextureUsages::TEXTURE_BINDING
+                    | TextureUsages::COPY_DST
+                    | TextureUsages::RENDER_ATTACHMENT,
+                view_formats: &[],
+            },
+            ..default()
+        };
+        image.resize(extent);
+
+        let image_handle = self.images.add(image);
+
+        let camera = self
+            .commands
+            .spawn((
+                Camera2dBundle {
+                    camera: Camera {
+                        target: RenderTarget::Image(image_handle.clone()),
+                        order: -1,
+                        is_active: false,
+                        ..default()
+                    },
+                    camera_2d: Camera2d {
+                        clear_color: ClearColorConfig::Custom(Color::NONE),
+                    },
+                    ..default()
+                },
+                RenderLayers::layer(PREVIEW_RENDER_LAYER),
+            ))
+            .id();
+
+        let slot = *self.next_slot % PREVIEW_SLOTS;
+        *self.next_slot = self.next_slot.wrapping_add(1);
+
+        let level_iid = LevelIid::new(level_iid);
+
+        self.commands.spawn((
+            LdtkWorldBundle {
+                ldtk_handle: ldtk_handle.clone(),
+                level_set: LevelSet::from_iids([level_iid.get().clone()]),
+                transform: Transform::from_translation(
+                    PREVIEW_ORIGIN + Vec3::X * PREVIEW_SPACING * slot as f32,
+                ),
+                ..default()
+            },
+            LevelPreview {
+                camera,
+                level_iid,
+                state: PreviewState::Spawning,
+            },
+        ));
+
+        image_handle
+    }
+}
+
+/// Returns true once the images the previewed level can be rendered with are loaded.
+///
+/// On-demand tilesets only count if the level uses them, through its [`LevelTilesets`].
+fn preview_images_loaded(
+    project: &LdtkProject,
+    level_iid: &LevelIid,
+    level_tilesets: Option<&LevelTilesets>,
+    images: &Assets<Image>,
+) -> bool {
+    let project_tilesets = project
+        .tileset_map()
+        .iter()
+        .filter(|(tileset_uid, _)| !project.on_demand_tilesets().contains_key(tileset_uid))
+        .map(|(_, handle)| handle);
+
+    let background_image = project
+        .get_level_metadata_by_iid(level_iid.get())
+        .and_then(|level_metadata| level_metadata.bg_image().as_ref());
+
+    project_tilesets
+        .chain(level_tilesets.into_iter().flat_map(LevelTilesets::handles))
+        .chain(project.int_grid_image_handle().as_ref())
+        .chain(background_image)
+        .all(|handle| images.contains(handle))
+}
+
+/// Advances level previews, from framing the spawned level to despawning it once rendered.
+///
+/// The level is framed once it has spawned and its images have loaded, so previews aren't
+/// captured with missing tiles.
+/// Also moves every descendant of a preview world onto [`PREVIEW_RENDER_LAYER`].
+#[allow(clippy::type_complexity)]
+pub fn process_level_previews(
+    mut commands: Commands,
+    ldtk_project_assets: Res<Assets<LdtkProject>>,
+    images: Res<Assets<Image>>,
+    mut preview_query: Query<(
+        Entity,
+        &mut LevelPreview,
+        &LdtkProjectHandle,
+        &Transform,
+        &Children,
+    )>,
+    level_query: Query<(
+        &LevelIid,
+        &Transform,
+        Option<&Children>,
+        Option<&LevelTilesets>,
+    )>,
+    mut camera_query: Query<
+        (&mut Camera, &mut Transform, &mut OrthographicProjection),
+        (Without<LevelPreview>, Without<LevelIid>),
+    >,
+    children_query: Query<&Children>,
+    layerless_query: Query<(), Without<RenderLayers>>,
+) {
+    for (preview_entity, mut preview, ldtk_handle, world_transform, world_children) in
+        preview_query.iter_mut()
+    {
+        for descendant in children_query.iter_descendants(preview_entity) {
+            if layerless_query.contains(descendant) {
+                commands
+                    .entity(descendant)
+                    .insert(RenderLayers::layer(PREVIEW_RENDER_LAYER));
+            }
+        }
+
+        match preview.state {
+            PreviewState::Spawning => {
+                // Levels are considered spawned once they have children
+                let Some((level_transform, level_tilesets)) =
+                    world_children
+                        .iter()
+                        .find_map(|child| match level_query.get(*child) {
+                            Ok((level_iid, transform, Some(children), level_tilesets))
+                                if *level_iid == preview.level_iid && !children.is_empty() =>
+                            {
+                                Some((transform, level_tilesets))
+                            }
+                            _ => None,
+                        })
+                else {
+                    continue;
+                };
+
+                let Some(project) = ldtk_project_assets.get(ldtk_handle) else {
+                    continue;
+                };
+
+                if !preview_images_loaded(project, &preview.level_iid, level_tilesets, &images) {
+                    continue;
+                }
+
+                let Some(level) = project.get_raw_level_by_iid(preview.level_iid.get()) else {
+                    continue;
+                };
+
+                let level_size = Vec2::new(level.px_wid as f32, level.px_hei as f32);
+
+                if let Ok((mut camera, mut camera_transform, mut projection)) =
+                    camera_query.get_mut(preview.camera)
+                {
+                    let level_center = world_transform.translation.truncate()
+                        + level_transform.translation.truncate()
+                        + level_size / 2.;
+
+                    camera_transform.translation =
+                        level_center.extend(camera_transform.translation.z);
+
+                    projection.scaling_mode = ScalingMode::AutoMin {
+                        min_width: level_size.x,
+                        min_height: level_size.y,
+                    };
+
+                    camera.is_active = true;
+                }
+
+                preview.state = PreviewState::Rendering {
+                    frames_remaining: PREVIEW_RENDER_FRAMES,
+                };
+            }
+            PreviewState::Rendering { frames_remaining } if frames_remaining > 0 => {
+                preview.state = PreviewState::Rendering {
+                    frames_remaining: frames_remaining - 1,
+                };
+            }
+            PreviewState::Rendering { .. } => {
+                commands.entity(preview.camera).despawn_recursive();
+                commands.entity(preview_entity).despawn_recursive();
+            }
+        }
+    }
+}
+
+#[cfg(all(test, feature = "test_utils"))]
+mod tests {
+    use super::*;
+    use crate::test_utils::ProjectFixture;
+    use bevy::asset::HandleId;
+
+    #[test]
+    fn previews_render_once_images_are_loaded_then_despawn() {
+        let int_grid_image: Handle<Image> = Handle::weak(HandleId::random::<Image>());
+        let project = ProjectFixture::new()
+            .level("A", "a", IVec2::splat(32), |_| Ok(()))
+            .build_project(Some(int_grid_image.clone()));
+
+        let mut app = App::new();
+        app.add_plugins(AssetPlugin::default())
+            .add_asset::<LdtkProject>()
+            .add_asset::<Image>()
+            .add_systems(Update, process_level_previews);
+
+        let ldtk_handle = app.world.resource_mut::<Assets<LdtkProject>>().add(project);
+
+        let camera = app
+            .world
+            .spawn((
+                Camera {
+                    is_active: false,
+                    ..default()
+                },
+                Transform::default(),
+                OrthographicProjection::default(),
+            ))
+            .id();
+
+        let preview_entity = app
+            .world
+            .spawn((
+                LevelPreview {
+                    camera,
+                    level_iid: LevelIid::new("a"),
+                    state: PreviewState::Spawning,
+                },
+                LdtkProjectHandle::from(ldtk_handle),
+                Transform::default(),
+            ))
+            .with_children(|world| {
+                world
+                    .spawn((LevelIid::new("a"), Transform::default()))
+                    .with_children(|level| {
+                        level.spawn(Transform::default());
+                    });
+            })
+            .id();
+
+        let preview_state =
+            |app: &App| app.world.get::<LevelPreview>(preview_entity).unwrap().state;
+
+        // The level has spawned, but the int grid image hasn't loaded
+        app.update();
+        assert_eq!(preview_state(&app), PreviewState::Spawning);
+        assert!(!app.world.get::<Camera>(camera).unwrap().is_active);
+
+        app.world
+            .resource_mut::<Assets<Image>>()
+            .set_untracked(int_grid_image, Image::default());
+        app.update();
+        assert_eq!(
+            preview_state(&app),
+            PreviewState::Rendering {
+                frames_remaining: PREVIEW_RENDER_FRAMES
+            }
+        );
+        assert!(app.world.get::<Camera>(camera).unwrap().is_active);
+
+        for _ in 0..PREVIEW_RENDER_FRAMES {
+            app.update();
+            assert!(app.world.get_entity(preview_entity).is_some());
+        }
+
+        app.update();
+        assert!(app.world.get_entity(preview_entity).is_none());
+        assert!(app.world.get_entity(camera).is_none());
+    }
+}
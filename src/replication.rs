@@ -0,0 +1,192 @@
+//! Serializable level spawn commands, for server-driven level streaming.
+//!
+//! *Requires the "replication" feature*
+//!
+//! In multiplayer games, the server usually decides which levels are spawned.
+//! [`LevelSpawnCommand`]s describe these decisions in a form that can be sent with any networking
+//! crate, since they implement serde's `Serialize` and `Deserialize`.
+//! The server applies each command to its own world and sends it to the clients, which apply it to
+//! theirs, so both spawn identical levels:
+//! ```
+//! # use bevy::prelude::*;
+//! # use bevy_ecs_ldtk::{prelude::*, replication::{ApplyLevelSpawnCommand, LevelSpawn, LevelSpawnCommand}};
+//! # fn send_to_clients(command: &LevelSpawnCommand) {}
+//! fn open_arena(mut commands: Commands, world_query: Query<Entity, With<LdtkProjectHandle>>) {
+//!     let command = LevelSpawnCommand::Spawn(
+//!         LevelSpawn::new("e5eb2d73-60bb-4779-8b33-38a63da8d1db")
+//!             .with_transform(Transform::from_xyz(512., 0., 0.))
+//!             .with_seed(42),
+//!     );
+//!
+//!     send_to_clients(&command);
+//!
+//!     commands.add(ApplyLevelSpawnCommand {
+//!         ldtk_world: world_query.single(),
+//!         command,
+//!     });
+//! }
+//! ```
+//!
+//! Commands modify the world's [`LevelSet`] directly, so clients shouldn't use a
+//! [`LevelSelection`], or it would replace the levels chosen by the server.
+//! For the same entities to be spawned on every client, consider enabling
+//! [`DeterministicSpawning`] as well.
+//!
+//! [`LevelSet`]: crate::prelude::LevelSet
+//! [`LevelSelection`]: crate::prelude::LevelSelection
+//! [`DeterministicSpawning`]: crate::prelude::DeterministicSpawning
+
+use crate::{
+    components::{LevelIid, LevelSet},
+    resources::{LevelSpawnOverride, LevelSpawnOverrides},
+};
+use bevy::{ecs::system::Command, prelude::*};
+use serde::{Deserialize, Serialize};
+
+/// Parameters of a [`LevelSpawnCommand::Spawn`].
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct LevelSpawn {
+    /// Iid of the level to spawn.
+    pub level_iid: String,
+    /// Transform of the level relative to its world, if it shouldn't use the default one.
+    #[serde(default)]
+    pub transform: Option<Transform>,
+    /// Seed of the level's [`LevelVariation`], if it shouldn't use the default one.
+    ///
+    /// [`LevelVariation`]: crate::prelude::LevelVariation
+    #[serde(default)]
+    pub seed: Option<u64>,
+}
+
+impl LevelSpawn {
+    /// Construct a [`LevelSpawn`] of the given level, with the default transform and seed.
+    pub fn new(level_iid: impl Into<String>) -> Self {
+        LevelSpawn {
+            level_iid: level_iid.into(),
+            transform: None,
+            seed: None,
+        }
+    }
+
+    /// Sets the transform of the level relative to its world.
+    pub fn with_transform(mut self, transform: Transform) -> Self {
+        self.transform = Some(transform);
+        self
+    }
+
+    /// Sets the seed of the level's variation.
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+}
+
+/// Serializable command for spawning or despawning a level.
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub enum LevelSpawnCommand {
+    /// Spawns a level.
+    ///
+    /// If the level is already spawned, only the transform and seed used the next time it spawns
+    /// are updated.
+    Spawn(LevelSpawn),
+    /// Despawns the level with the given iid.
+    Despawn(String),
+}
+
+impl LevelSpawnCommand {
+    /// Applies the command to the [`LevelSet`] of the given world entity.
+    ///
+    /// Does nothing if the entity doesn't have a [`LevelSet`].
+    pub fn apply(&self, world: &mut World, ldtk_world: Entity) {
+        let mut spawn_overrides = world.get_resource_or_insert_with(LevelSpawnOverrides::default);
+
+        let level_iid = match self {
+            LevelSpawnCommand::Spawn(level_spawn) => {
+                let level_iid = LevelIid::new(level_spawn.level_iid.clone());
+
+                spawn_overrides.insert(
+                    level_iid.clone(),
+                    LevelSpawnOverride {
+                        transform: level_spawn.transform,
+                        seed: level_spawn.seed,
+                    },
+                );
+
+                level_iid
+            }
+            LevelSpawnCommand::Despawn(level_iid) => {
+                let level_iid = LevelIid::new(level_iid.clone());
+                spawn_overrides.remove(&level_iid);
+                level_iid
+            }
+        };
+
+        let Some(mut level_set) = world.get_mut::<LevelSet>(ldtk_world) else {
+            return;
+        };
+
+        match self {
+            LevelSpawnCommand::Spawn(_) => level_set.iids.insert(level_iid),
+            LevelSpawnCommand::Despawn(_) => level_set.iids.remove(&level_iid),
+        };
+    }
+}
+
+/// [`Command`] that applies a [`LevelSpawnCommand`] to the [`LevelSet`] of a world entity.
+///
+/// [`Command`]: https://docs.rs/bevy/latest/bevy/ecs/system/trait.Command.html
+#[derive(Clone, PartialEq, Debug)]
+pub struct ApplyLevelSpawnCommand {
+    pub ldtk_world: Entity,
+    pub command: LevelSpawnCommand,
+}
+
+impl Command for ApplyLevelSpawnCommand {
+    fn apply(self, world: &mut World) {
+        self.command.apply(world, self.ldtk_world);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn commands_round_trip_and_update_level_sets() {
+        let spawn = LevelSpawnCommand::Spawn(
+            LevelSpawn::new("arena")
+                .with_transform(Transform::from_xyz(512., 0., 0.))
+                .with_seed(42),
+        );
+
+        let json = serde_json::to_string(&spawn).unwrap();
+        assert_eq!(
+            serde_json::from_str::<LevelSpawnCommand>(&json).unwrap(),
+            spawn
+        );
+
+        let mut world = World::new();
+        let ldtk_world = world.spawn(LevelSet::default()).id();
+
+        spawn.apply(&mut world, ldtk_world);
+
+        let arena = LevelIid::new("arena");
+        assert!(world
+            .get::<LevelSet>(ldtk_world)
+            .unwrap()
+            .iids
+            .contains(&arena));
+        assert_eq!(
+            world.resource::<LevelSpawnOverrides>().get(&arena),
+            Some(&LevelSpawnOverride {
+                transform: Some(Transform::from_xyz(512., 0., 0.)),
+                seed: Some(42),
+            })
+        );
+
+        LevelSpawnCommand::Despawn("arena".to_string()).apply(&mut world, ldtk_world);
+
+        assert!(world.get::<LevelSet>(ldtk_world).unwrap().iids.is_empty());
+        assert_eq!(world.resource::<LevelSpawnOverrides>().get(&arena), None);
+    }
+}
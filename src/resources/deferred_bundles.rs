@@ -0,0 +1,138 @@
+use super::EntityRegistrationKey;
+use crate::{
+    components::IntGridCell,
+    ldtk::{EntityInstance, LayerDefinition, LayerInstance, TilesetDefinition},
+};
+use bevy::prelude::*;
+use std::sync::Arc;
+
+/// An LDtk entity waiting for its [LdtkEntity](crate::prelude::LdtkEntity) bundle.
+#[derive(Clone, Debug)]
+pub struct DeferredEntityBundle {
+    pub entity: Entity,
+    pub entity_instance: EntityInstance,
+    pub layer_instance: Arc<LayerInstance>,
+    pub tileset: Option<Handle<Image>>,
+    pub tileset_definition: Option<Arc<TilesetDefinition>>,
+}
+
+/// LDtk entities of one [EntityInstanceGroup](super::EntityInstanceGroup) waiting for their
+/// bundles, so the group's registration is only looked up once.
+#[derive(Clone, Debug)]
+pub(crate) struct DeferredEntityGroup {
+    pub registration: Option<EntityRegistrationKey>,
+    pub bundles: Vec<DeferredEntityBundle>,
+}
+
+/// An IntGrid cell waiting for its [LdtkIntCell](crate::prelude::LdtkIntCell) bundle.
+#[derive(Clone, Debug)]
+pub struct DeferredIntCellBundle {
+    pub entity: Entity,
+    pub int_grid_cell: IntGridCell,
+    pub layer_instance: Arc<LayerInstance>,
+    pub layer_definition: Arc<LayerDefinition>,
+}
+
+/// [Resource] queueing the bundles of spawned levels while the bundle phase of [SpawnPhases] is
+/// [BundlePhase::Deferred].
+///
+/// The queue is emptied by a system in [SpawnPhaseSet::Bundles].
+/// Layers are shared between the items of a level, so queueing large levels stays cheap.
+///
+/// [SpawnPhases]: super::SpawnPhases
+/// [BundlePhase::Deferred]: super::BundlePhase::Deferred
+/// [SpawnPhaseSet::Bundles]: crate::prelude::SpawnPhaseSet::Bundles
+#[derive(Clone, Debug, Default, Resource)]
+pub struct DeferredLdtkBundles {
+    entity_groups: Vec<DeferredEntityGroup>,
+    int_cells: Vec<DeferredIntCellBundle>,
+}
+
+impl DeferredLdtkBundles {
+    /// The number of bundles waiting to be inserted.
+    pub fn len(&self) -> usize {
+        self.entity_groups
+            .iter()
+            .map(|entity_group| entity_group.bundles.len())
+            .sum::<usize>()
+            + self.int_cells.len()
+    }
+
+    /// Returns true if no bundles are waiting to be inserted.
+    pub fn is_empty(&self) -> bool {
+        self.entity_groups.is_empty() && self.int_cells.is_empty()
+    }
+
+    /// The LDtk entities waiting for their bundles, in spawn order.
+    pub fn entities(&self) -> impl Iterator<Item = &DeferredEntityBundle> {
+        self.entity_groups
+            .iter()
+            .flat_map(|entity_group| entity_group.bundles.iter())
+    }
+
+    /// The IntGrid cells waiting for their bundles, in spawn order.
+    pub fn int_cells(&self) -> &[DeferredIntCellBundle] {
+        &self.int_cells
+    }
+
+    pub(crate) fn defer_entity_group(&mut self, deferred: DeferredEntityGroup) {
+        if !deferred.bundles.is_empty() {
+            self.entity_groups.push(deferred);
+        }
+    }
+
+    pub(crate) fn defer_int_cell(&mut self, deferred: DeferredIntCellBundle) {
+        self.int_cells.push(deferred);
+    }
+
+    /// Takes every queued bundle, leaving the queue empty.
+    pub(crate) fn take(&mut self) -> (Vec<DeferredEntityGroup>, Vec<DeferredIntCellBundle>) {
+        (
+            std::mem::take(&mut self.entity_groups),
+            std::mem::take(&mut self.int_cells),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn taking_bundles_empties_the_queue() {
+        let layer_instance = Arc::new(LayerInstance::default());
+        let mut deferred_bundles = DeferredLdtkBundles::default();
+        assert!(deferred_bundles.is_empty());
+
+        deferred_bundles.defer_entity_group(DeferredEntityGroup {
+            registration: None,
+            bundles: vec![DeferredEntityBundle {
+                entity: Entity::from_raw(0),
+                entity_instance: EntityInstance::default(),
+                layer_instance: layer_instance.clone(),
+                tileset: None,
+                tileset_definition: None,
+            }],
+        });
+        deferred_bundles.defer_entity_group(DeferredEntityGroup {
+            registration: None,
+            bundles: Vec::new(),
+        });
+        deferred_bundles.defer_int_cell(DeferredIntCellBundle {
+            entity: Entity::from_raw(1),
+            int_grid_cell: IntGridCell { value: 2 },
+            layer_instance,
+            layer_definition: Arc::new(LayerDefinition::default()),
+        });
+        assert_eq!(deferred_bundles.len(), 2);
+        assert_eq!(deferred_bundles.int_cells()[0].int_grid_cell.value, 2);
+
+        let (entity_groups, int_cells) = deferred_bundles.take();
+        assert_eq!((entity_groups.len(), int_cells.len()), (1, 1));
+        assert!(deferred_bundles.is_empty());
+        assert!(Arc::ptr_eq(
+            &entity_groups[0].bundles[0].layer_instance,
+            &int_cells[0].layer_instance
+        ));
+    }
+}
@@ -0,0 +1,116 @@
+use crate::components::{EntityIid, LevelIid};
+use bevy::prelude::*;
+use std::collections::{HashMap, HashSet};
+
+/// Why an LDtk entity was despawned, as reported by [LdtkEntityDespawned].
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Hash)]
+pub enum DespawnReason {
+    /// The entity's level despawned, and isn't selected anymore.
+    LevelDespawned,
+    /// The entity's level despawned to respawn, like when a [Respawn] is inserted on it or its
+    /// world.
+    /// The entity will spawn again with the level, unless it is [Worldly].
+    ///
+    /// [Respawn]: crate::prelude::Respawn
+    /// [Worldly]: crate::prelude::Worldly
+    Respawned,
+    /// The entity was despawned by something other than the plugin, like the user's systems.
+    UserDespawn,
+}
+
+/// Event fired when an LDtk entity is despawned, or when its [EntityIid] is removed.
+///
+/// Lets persistence and analytics systems tell apart entities that disappeared with their level
+/// from those that were destroyed during gameplay.
+/// Entities recycled by the [LdtkEntityPool](crate::prelude::LdtkEntityPool) count as
+/// despawned.
+///
+/// Fired in [PostUpdate], for entities that spawned as part of a level.
+#[derive(Clone, Eq, PartialEq, Debug, Event)]
+pub struct LdtkEntityDespawned {
+    pub entity_iid: EntityIid,
+    /// Iid of the level the entity spawned in.
+    pub level_iid: LevelIid,
+    pub reason: DespawnReason,
+}
+
+/// The [EntityIid]s and birth levels of spawned LDtk entities, so they can still be reported after
+/// the entities despawn.
+#[derive(Clone, Eq, PartialEq, Debug, Default)]
+pub(crate) struct TrackedLdtkEntities(HashMap<Entity, (EntityIid, LevelIid)>);
+
+impl TrackedLdtkEntities {
+    pub(crate) fn track(&mut self, entity: Entity, entity_iid: EntityIid, level_iid: LevelIid) {
+        self.0.insert(entity, (entity_iid, level_iid));
+    }
+
+    /// Stops tracking the entity, returning the event reporting its despawn.
+    ///
+    /// `despawned_levels` are the levels despawned by the plugin since the last check, and
+    /// `is_level_selected` returns whether a level is still in a [LevelSet].
+    ///
+    /// [LevelSet]: crate::prelude::LevelSet
+    pub(crate) fn untrack(
+        &mut self,
+        entity: Entity,
+        despawned_levels: &HashSet<LevelIid>,
+        is_level_selected: impl Fn(&LevelIid) -> bool,
+    ) -> Option<LdtkEntityDespawned> {
+        let (entity_iid, level_iid) = self.0.remove(&entity)?;
+
+        let reason = if !despawned_levels.contains(&level_iid) {
+            DespawnReason::UserDespawn
+        } else if is_level_selected(&level_iid) {
+            DespawnReason::Respawned
+        } else {
+            DespawnReason::LevelDespawned
+        };
+
+        Some(LdtkEntityDespawned {
+            entity_iid,
+            level_iid,
+            reason,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn despawn_reasons_depend_on_level_state() {
+        let mut world = World::new();
+        let [chest, door, player] = [(); 3].map(|_| world.spawn_empty().id());
+
+        let mut tracked = TrackedLdtkEntities::default();
+        tracked.track(chest, EntityIid::new("chest"), LevelIid::new("cave"));
+        tracked.track(door, EntityIid::new("door"), LevelIid::new("town"));
+        tracked.track(player, EntityIid::new("player"), LevelIid::new("town"));
+
+        let despawned_levels = HashSet::from([LevelIid::new("cave"), LevelIid::new("town")]);
+        let is_level_selected = |level_iid: &LevelIid| level_iid.as_str() == "town";
+
+        let reason = |tracked: &mut TrackedLdtkEntities, entity| {
+            tracked
+                .untrack(entity, &despawned_levels, is_level_selected)
+                .map(|event| event.reason)
+        };
+
+        assert_eq!(
+            reason(&mut tracked, chest),
+            Some(DespawnReason::LevelDespawned)
+        );
+        assert_eq!(reason(&mut tracked, door), Some(DespawnReason::Respawned));
+        assert_eq!(reason(&mut tracked, door), None);
+
+        assert_eq!(
+            tracked.untrack(player, &HashSet::new(), is_level_selected),
+            Some(LdtkEntityDespawned {
+                entity_iid: EntityIid::new("player"),
+                level_iid: LevelIid::new("town"),
+                reason: DespawnReason::UserDespawn,
+            })
+        );
+    }
+}
@@ -0,0 +1,228 @@
+use crate::{
+    app::{LdtkEntityMap, PhantomLdtkEntityTrait},
+    assets::LdtkProject,
+    components::LevelIid,
+    ldtk::LayerInstance,
+    utils::ldtk_map_get_key,
+};
+use bevy::{asset::HandleId, prelude::*};
+use std::collections::HashMap;
+
+/// Key of a registration in the [LdtkEntityMap], the layer and entity identifiers it was
+/// registered for.
+pub(crate) type EntityRegistrationKey = (Option<String>, Option<String>);
+
+/// The LDtk entities of an Entity layer that share an identifier, and so are spawned with the
+/// same [LdtkEntity](crate::prelude::LdtkEntity) registration.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EntityInstanceGroup {
+    registration: Option<EntityRegistrationKey>,
+    instances: Vec<usize>,
+}
+
+impl EntityInstanceGroup {
+    /// Indices of the group's entities in [LayerInstance::entity_instances], in layer order.
+    pub fn instances(&self) -> &[usize] {
+        &self.instances
+    }
+
+    /// Key of the registration the group's entities are spawned with, or `None` if they're
+    /// spawned with the default bundle.
+    pub(crate) fn registration_key(&self) -> Option<&EntityRegistrationKey> {
+        self.registration.as_ref()
+    }
+
+    /// The registration the group's entities are spawned with, or `default` if none applies.
+    pub(crate) fn registration<'a>(
+        &self,
+        default: &'a dyn PhantomLdtkEntityTrait,
+        ldtk_entity_map: &'a LdtkEntityMap,
+    ) -> &'a dyn PhantomLdtkEntityTrait {
+        registration_or_default(self.registration.as_ref(), default, ldtk_entity_map)
+    }
+}
+
+/// Looks up the registration with the given key, falling back to `default`.
+pub(crate) fn registration_or_default<'a>(
+    key: Option<&EntityRegistrationKey>,
+    default: &'a dyn PhantomLdtkEntityTrait,
+    ldtk_entity_map: &'a LdtkEntityMap,
+) -> &'a dyn PhantomLdtkEntityTrait {
+    key.and_then(|key| ldtk_entity_map.get(key))
+        .map(|registration| registration.as_ref())
+        .unwrap_or(default)
+}
+
+/// Groups the entities of an Entity layer by identifier, resolving the registration of each
+/// identifier once.
+///
+/// Groups are ordered by the first appearance of their identifier in the layer.
+pub(crate) fn group_entity_instances(
+    layer_instance: &LayerInstance,
+    ldtk_entity_map: &LdtkEntityMap,
+) -> Vec<EntityInstanceGroup> {
+    let mut group_indices: HashMap<&str, usize> = HashMap::new();
+    let mut groups: Vec<EntityInstanceGroup> = Vec::new();
+
+    for (index, entity_instance) in layer_instance.entity_instances.iter().enumerate() {
+        let group_index = *group_indices
+            .entry(entity_instance.identifier.as_str())
+            .or_insert_with(|| {
+                groups.push(EntityInstanceGroup {
+                    registration: ldtk_map_get_key(
+                        layer_instance.identifier.clone(),
+                        entity_instance.identifier.clone(),
+                        ldtk_entity_map,
+                    ),
+                    instances: Vec::new(),
+                });
+                groups.len() - 1
+            });
+
+        groups[group_index].instances.push(index);
+    }
+
+    groups
+}
+
+/// The [EntityInstanceGroup]s of each Entity layer of one level, by layer iid.
+#[derive(Clone, Debug, Default)]
+pub struct LevelEntityInstanceGroups {
+    layers: HashMap<String, Vec<EntityInstanceGroup>>,
+}
+
+impl LevelEntityInstanceGroups {
+    /// Returns the groups of the layer, grouping its entities if they haven't been already.
+    pub(crate) fn get_or_group(
+        &mut self,
+        layer_instance: &LayerInstance,
+        ldtk_entity_map: &LdtkEntityMap,
+    ) -> &[EntityInstanceGroup] {
+        self.layers
+            .entry(layer_instance.iid.clone())
+            .or_insert_with(|| group_entity_instances(layer_instance, ldtk_entity_map))
+    }
+}
+
+/// [Resource] storing the [EntityInstanceGroup]s of every level that has spawned.
+///
+/// The entities of a level are grouped by identifier the first time it spawns, and each group's
+/// registration is resolved then.
+/// Respawning the level, or inserting its deferred bundles, iterates the groups instead of
+/// matching every entity's identifier against the registrations again.
+///
+/// Groups are cleared when an [LdtkProject] or external level asset changes, or when the
+/// [LevelDuplicates](super::LevelDuplicates) change.
+#[derive(Clone, Debug, Default, Resource)]
+pub struct EntityInstanceGroups {
+    levels: HashMap<(HandleId, LevelIid), LevelEntityInstanceGroups>,
+}
+
+impl EntityInstanceGroups {
+    /// Returns the groups of a level of the given project.
+    pub fn level(
+        &mut self,
+        project: &Handle<LdtkProject>,
+        level_iid: &LevelIid,
+    ) -> &mut LevelEntityInstanceGroups {
+        self.levels
+            .entry((project.id(), level_iid.clone()))
+            .or_default()
+    }
+
+    /// Forgets the groups of every level.
+    pub fn clear(&mut self) {
+        self.levels.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{app::PhantomLdtkEntity, ldtk::EntityInstance};
+
+    fn entity(identifier: &str) -> EntityInstance {
+        EntityInstance {
+            identifier: identifier.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn entities_are_grouped_by_identifier_in_order_of_appearance() {
+        let layer_instance = LayerInstance {
+            identifier: "Entities".to_string(),
+            entity_instances: vec![
+                entity("Coin"),
+                entity("Player"),
+                entity("Coin"),
+                entity("Enemy"),
+                entity("Coin"),
+            ],
+            ..Default::default()
+        };
+
+        let mut ldtk_entity_map = LdtkEntityMap::new();
+        ldtk_entity_map.insert(
+            (None, Some("Coin".to_string())),
+            Box::new(PhantomLdtkEntity::<SpriteSheetBundle>::new()),
+        );
+        ldtk_entity_map.insert(
+            (Some("Entities".to_string()), Some("Player".to_string())),
+            Box::new(PhantomLdtkEntity::<SpriteSheetBundle>::new()),
+        );
+
+        let groups = group_entity_instances(&layer_instance, &ldtk_entity_map);
+
+        assert_eq!(groups.len(), 3);
+        assert_eq!(groups[0].instances(), &[0, 2, 4]);
+        assert_eq!(
+            groups[0].registration_key(),
+            Some(&(None, Some("Coin".to_string())))
+        );
+        assert_eq!(groups[1].instances(), &[1]);
+        assert_eq!(
+            groups[1].registration_key(),
+            Some(&(Some("Entities".to_string()), Some("Player".to_string())))
+        );
+        assert_eq!(groups[2].instances(), &[3]);
+        assert_eq!(groups[2].registration_key(), None);
+    }
+
+    #[test]
+    fn levels_are_grouped_once() {
+        let layer_instance = LayerInstance {
+            iid: "layer".to_string(),
+            entity_instances: vec![entity("Coin")],
+            ..Default::default()
+        };
+        let ldtk_entity_map = LdtkEntityMap::new();
+        let project = Handle::<LdtkProject>::default();
+        let level_iid = LevelIid::new("level");
+
+        let mut entity_instance_groups = EntityInstanceGroups::default();
+        entity_instance_groups
+            .level(&project, &level_iid)
+            .get_or_group(&layer_instance, &ldtk_entity_map);
+
+        // Later changes to the layer aren't seen until the groups are cleared
+        let mut changed_layer_instance = layer_instance.clone();
+        changed_layer_instance.entity_instances.push(entity("Coin"));
+        assert_eq!(
+            entity_instance_groups
+                .level(&project, &level_iid)
+                .get_or_group(&changed_layer_instance, &ldtk_entity_map)[0]
+                .instances(),
+            &[0]
+        );
+
+        entity_instance_groups.clear();
+        assert_eq!(
+            entity_instance_groups
+                .level(&project, &level_iid)
+                .get_or_group(&changed_layer_instance, &ldtk_entity_map)[0]
+                .instances(),
+            &[0, 1]
+        );
+    }
+}
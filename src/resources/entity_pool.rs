@@ -0,0 +1,234 @@
+use crate::components::GridCoords;
+use bevy::{ecs::system::EntityCommands, prelude::*};
+use bevy_ecs_tilemap::{
+    map::TilemapSize,
+    tiles::{TileBundle, TileStorage},
+};
+use std::collections::HashMap;
+
+/// [Resource] storing entities and tile storages recycled from respawned levels.
+///
+/// Only filled when [LdtkSettings::entity_pooling] is [EntityPooling::Enabled].
+/// Instead of despawning the descendants of a respawning level, their components are removed and
+/// the empty entities are kept here.
+/// They're reused for the layers, tiles, and LDtk entities of the next levels that spawn, so
+/// games that respawn the same level over and over don't allocate and free thousands of entities
+/// every time.
+/// The [TileStorage]s of the level's tilemaps are kept as well, so their buffers don't have to be
+/// allocated again.
+///
+/// Components can only be removed from pooled entities if they're registered with
+/// [ReflectComponent], so entities with unregistered components are despawned as usual.
+///
+/// Pooled entities are alive but empty.
+/// Use [LdtkEntityPool::drain] to despawn them, rather than despawning them directly, or the pool
+/// will hand out entities that no longer exist.
+///
+/// [LdtkSettings::entity_pooling]: crate::prelude::LdtkSettings::entity_pooling
+/// [EntityPooling::Enabled]: crate::prelude::EntityPooling::Enabled
+#[derive(Debug, Default, Resource)]
+pub struct LdtkEntityPool {
+    entities: Vec<Entity>,
+    tile_storages: HashMap<(u32, u32), Vec<TileStorage>>,
+}
+
+impl LdtkEntityPool {
+    /// Number of entities in the pool.
+    pub fn len(&self) -> usize {
+        self.entities.len()
+    }
+
+    /// Returns true if there are no entities in the pool.
+    pub fn is_empty(&self) -> bool {
+        self.entities.is_empty()
+    }
+
+    /// Empties the pool, returning its entities so they can be despawned.
+    pub fn drain(&mut self) -> Vec<Entity> {
+        self.tile_storages.clear();
+        std::mem::take(&mut self.entities)
+    }
+
+    /// Reuses an entity from the pool, or spawns a new one if the pool is empty.
+    pub(crate) fn spawn_empty<'w, 's, 'a>(
+        &mut self,
+        commands: &'a mut Commands<'w, 's>,
+    ) -> EntityCommands<'w, 's, 'a> {
+        match self.entities.pop() {
+            Some(entity) => commands.entity(entity),
+            None => commands.spawn_empty(),
+        }
+    }
+
+    /// Reuses an entity from the pool with the given bundle, or spawns a new one if the pool is
+    /// empty.
+    pub(crate) fn spawn<'w, 's, 'a>(
+        &mut self,
+        commands: &'a mut Commands<'w, 's>,
+        bundle: impl Bundle,
+    ) -> EntityCommands<'w, 's, 'a> {
+        let mut entity_commands = self.spawn_empty(commands);
+        entity_commands.insert(bundle);
+        entity_commands
+    }
+
+    /// Reuses an empty [TileStorage] of the given size from the pool, or creates a new one.
+    pub(crate) fn tile_storage(&mut self, size: TilemapSize) -> TileStorage {
+        self.tile_storages
+            .get_mut(&(size.x, size.y))
+            .and_then(Vec::pop)
+            .unwrap_or_else(|| TileStorage::empty(size))
+    }
+
+    /// Removes every component from the entity and adds it to the pool.
+    ///
+    /// If it has components that aren't registered with [ReflectComponent], it is despawned
+    /// instead.
+    /// Doesn't update the entity's parent or children.
+    fn recycle(&mut self, world: &mut World, entity: Entity) {
+        let Some(mut entity_mut) = world.get_entity_mut(entity) else {
+            return;
+        };
+
+        if let Some(mut storage) = entity_mut.take::<TileStorage>() {
+            storage.iter_mut().for_each(|tile| *tile = None);
+            self.tile_storages
+                .entry((storage.size.x, storage.size.y))
+                .or_default()
+                .push(storage);
+        }
+
+        // Most pooled entities are tiles, so their common components are removed all at once
+        entity_mut.remove::<(TileBundle, GridCoords, SpatialBundle)>();
+
+        let type_registry = world
+            .get_resource::<AppTypeRegistry>()
+            .cloned()
+            .unwrap_or_default();
+        let type_registry = type_registry.read();
+
+        let reflect_components: Option<Vec<ReflectComponent>> = world
+            .entity(entity)
+            .archetype()
+            .components()
+            .map(|component_id| {
+                let type_id = world.components().get_info(component_id)?.type_id()?;
+                type_registry
+                    .get_type_data::<ReflectComponent>(type_id)
+                    .cloned()
+            })
+            .collect();
+
+        match reflect_components {
+            Some(reflect_components) => {
+                let mut entity_mut = world.entity_mut(entity);
+                for reflect_component in reflect_components {
+                    reflect_component.remove(&mut entity_mut);
+                }
+
+                self.entities.push(entity);
+            }
+            None => {
+                world.despawn(entity);
+            }
+        }
+    }
+}
+
+/// Recycles all descendants of the given entity into the [LdtkEntityPool], and removes its
+/// [Children].
+pub(crate) fn recycle_descendants(world: &mut World, entity: Entity) {
+    let mut descendants = Vec::new();
+    let mut to_visit = vec![entity];
+
+    while let Some(parent) = to_visit.pop() {
+        if let Some(children) = world.get::<Children>(parent) {
+            descendants.extend(children.iter().copied());
+            to_visit.extend(children.iter().copied());
+        }
+    }
+
+    world.entity_mut(entity).remove::<Children>();
+
+    world.resource_scope(|world, mut entity_pool: Mut<LdtkEntityPool>| {
+        for descendant in descendants {
+            entity_pool.recycle(world, descendant);
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::components::TileMetadata;
+    use bevy_ecs_tilemap::{map::TilemapId, tiles::TilePos};
+
+    #[derive(Component)]
+    struct Unregistered;
+
+    #[test]
+    fn recycled_entities_are_emptied_and_reused() {
+        let mut world = World::new();
+        world.init_resource::<LdtkEntityPool>();
+
+        let type_registry = AppTypeRegistry::default();
+        {
+            let mut type_registry = type_registry.write();
+            type_registry.register::<Parent>();
+            type_registry.register::<Children>();
+            type_registry.register::<Name>();
+            type_registry.register::<TileMetadata>();
+        }
+        world.insert_resource(type_registry);
+
+        let size = TilemapSize { x: 2, y: 2 };
+        let level = world.spawn_empty().id();
+        let layer = world.spawn(Name::new("Tiles")).id();
+
+        let tile = world
+            .spawn((
+                TileBundle {
+                    position: TilePos { x: 1, y: 1 },
+                    tilemap_id: TilemapId(layer),
+                    ..default()
+                },
+                GridCoords::new(1, 1),
+                SpatialBundle::default(),
+                TileMetadata {
+                    data: "spikes".to_string(),
+                },
+            ))
+            .id();
+        let custom = world.spawn(Unregistered).id();
+
+        let mut storage = TileStorage::empty(size);
+        storage.set(&TilePos { x: 1, y: 1 }, tile);
+        world.entity_mut(layer).insert(storage);
+
+        world.entity_mut(layer).push_children(&[tile, custom]);
+        world.entity_mut(level).push_children(&[layer]);
+
+        recycle_descendants(&mut world, level);
+
+        assert!(world.get::<Children>(level).is_none());
+        assert!(world.get_entity(custom).is_none());
+
+        for entity in [layer, tile] {
+            assert_eq!(world.entity(entity).archetype().components().count(), 0);
+        }
+
+        let mut entity_pool = world.resource_mut::<LdtkEntityPool>();
+        assert_eq!(entity_pool.len(), 2);
+
+        let storage = entity_pool.tile_storage(size);
+        assert!(storage.iter().all(Option::is_none));
+        assert!(entity_pool.tile_storages[&(2, 2)].is_empty());
+
+        let mut drained = entity_pool.drain();
+        drained.sort();
+        let mut expected = vec![layer, tile];
+        expected.sort();
+        assert_eq!(drained, expected);
+        assert!(entity_pool.is_empty());
+    }
+}
@@ -0,0 +1,221 @@
+use crate::{
+    assets::{LdtkProject, LdtkProjectHandle, LevelMetadataAccessor},
+    components::{EntityIid, LevelIid},
+    ldtk::{raw_level_accessor::RawLevelAccessor, ReferenceToAnEntityInstance},
+};
+use bevy::{asset::HandleId, ecs::system::SystemParam, prelude::*};
+
+/// The location of an LDtk entity, found by an [EntityRefResolver].
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct EntityRefTarget {
+    /// The project containing the entity.
+    pub project: Handle<LdtkProject>,
+    /// The world entity spawned from that project, if any.
+    ///
+    /// Insert [EntityRefTarget::level_iid] into its [LevelSet](crate::prelude::LevelSet) to spawn
+    /// the entity.
+    pub world: Option<Entity>,
+    /// The level containing the entity.
+    pub level_iid: LevelIid,
+    /// The entity's iid.
+    pub entity_iid: EntityIid,
+    /// The spawned entity, if its level is spawned.
+    pub entity: Option<Entity>,
+}
+
+/// [SystemParam] for resolving entity references across all loaded [LdtkProject]s.
+///
+/// Large games often split their content across several projects, or add references between them
+/// with [LdtkPatch](crate::assets::LdtkPatch) overlays.
+/// Entity references are resolved by iid, so they work across project boundaries as long as the
+/// target's project is loaded:
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_ecs_ldtk::prelude::*;
+/// # #[derive(Component)]
+/// # struct Door;
+/// fn enter_door(
+///     door_query: Query<&EntityInstance, Added<Door>>,
+///     resolver: EntityRefResolver,
+///     mut level_set_query: Query<&mut LevelSet>,
+/// ) {
+///     for door in door_query.iter() {
+///         let Ok(destination) = door.get_entity_ref_field("destination") else {
+///             continue;
+///         };
+///
+///         if let Some(EntityRefTarget { world: Some(world), level_iid, .. }) =
+///             resolver.resolve(destination)
+///         {
+///             if let Ok(mut level_set) = level_set_query.get_mut(world) {
+///                 level_set.iids.insert(level_iid);
+///             }
+///         }
+///     }
+/// }
+/// ```
+///
+/// [ReferencedBy](crate::prelude::ReferencedBy) components are also maintained across projects,
+/// since they only depend on spawned entities.
+#[derive(SystemParam)]
+pub struct EntityRefResolver<'w, 's> {
+    ldtk_project_assets: Res<'w, Assets<LdtkProject>>,
+    world_query: Query<'w, 's, (Entity, &'static LdtkProjectHandle)>,
+    iid_query: Query<'w, 's, (Entity, &'static EntityIid)>,
+}
+
+impl EntityRefResolver<'_, '_> {
+    /// Returns the spawned entity with the given iid, if any.
+    pub fn spawned(&self, entity_iid: &EntityIid) -> Option<Entity> {
+        self.iid_query
+            .iter()
+            .find(|(_, iid)| *iid == entity_iid)
+            .map(|(entity, _)| entity)
+    }
+
+    /// Locates the entity referenced by an `EntityRef` field.
+    ///
+    /// The reference's level iid is looked up in every loaded project.
+    pub fn resolve(&self, reference: &ReferenceToAnEntityInstance) -> Option<EntityRefTarget> {
+        let (project_id, _) = self
+            .ldtk_project_assets
+            .iter()
+            .find(|(_, project)| project.get_raw_level_by_iid(&reference.level_iid).is_some())?;
+
+        Some(self.target(
+            project_id,
+            LevelIid::new(reference.level_iid.clone()),
+            EntityIid::new(reference.entity_iid.clone()),
+        ))
+    }
+
+    /// Locates the entity with the given iid, like one stored in a `String` field.
+    ///
+    /// Unlike [EntityRefResolver::resolve], this searches the entities of every level, so it
+    /// can't find entities in external levels that aren't spawned.
+    pub fn resolve_iid(&self, entity_iid: &EntityIid) -> Option<EntityRefTarget> {
+        self.ldtk_project_assets
+            .iter()
+            .find_map(|(project_id, project)| {
+                find_entity_level(project, entity_iid).map(|level_iid| (project_id, level_iid))
+            })
+            .map(|(project_id, level_iid)| self.target(project_id, level_iid, entity_iid.clone()))
+    }
+
+    fn target(
+        &self,
+        project_id: HandleId,
+        level_iid: LevelIid,
+        entity_iid: EntityIid,
+    ) -> EntityRefTarget {
+        EntityRefTarget {
+            project: Handle::weak(project_id),
+            world: self
+                .world_query
+                .iter()
+                .find(|(_, handle)| handle.id() == project_id)
+                .map(|(entity, _)| entity),
+            level_iid,
+            entity: self.spawned(&entity_iid),
+            entity_iid,
+        }
+    }
+}
+
+/// Returns the iid of the level containing the entity, searching the project's raw levels.
+fn find_entity_level(project: &LdtkProject, entity_iid: &EntityIid) -> Option<LevelIid> {
+    project
+        .iter_raw_levels()
+        .find(|level| {
+            level
+                .layer_instances
+                .iter()
+                .flatten()
+                .flat_map(|layer_instance| layer_instance.entity_instances.iter())
+                .any(|entity_instance| entity_instance.iid == entity_iid.as_str())
+        })
+        .map(|level| LevelIid::new(level.iid.clone()))
+}
+
+#[cfg(all(test, feature = "internal_levels"))]
+mod tests {
+    use super::*;
+    use crate::{
+        assets::{LdtkJsonWithMetadata, LdtkProjectData, LevelMetadata},
+        ldtk::{EntityInstance, LayerInstance, LdtkJson, Level},
+    };
+    use bevy::ecs::system::SystemState;
+    use std::collections::HashMap;
+
+    fn project(level_iid: &str, entity_iid: &str) -> LdtkProject {
+        let json = LdtkJson {
+            levels: vec![Level {
+                iid: level_iid.to_string(),
+                layer_instances: Some(vec![LayerInstance {
+                    entity_instances: vec![EntityInstance {
+                        iid: entity_iid.to_string(),
+                        ..default()
+                    }],
+                    ..default()
+                }]),
+                ..default()
+            }],
+            ..default()
+        };
+
+        let level_map = json
+            .iter_raw_levels_with_indices()
+            .map(|(indices, level)| (level.iid.clone(), LevelMetadata::new(None, indices)))
+            .collect();
+
+        LdtkProject::new(
+            LdtkProjectData::Standalone(LdtkJsonWithMetadata::new(json, level_map)),
+            HashMap::new(),
+            None,
+        )
+    }
+
+    #[test]
+    fn references_resolve_across_projects() {
+        let mut app = App::new();
+        app.add_plugins(AssetPlugin::default())
+            .add_asset::<LdtkProject>();
+        let world = &mut app.world;
+
+        let mut assets = world.resource_mut::<Assets<LdtkProject>>();
+        assets.add(project("overworld", "door"));
+        let dungeon = assets.add(project("dungeon", "stairs"));
+
+        let dungeon_world = world.spawn(LdtkProjectHandle::from(dungeon.clone())).id();
+        let stairs = world.spawn(EntityIid::new("stairs")).id();
+
+        let mut system_state: SystemState<EntityRefResolver> = SystemState::new(world);
+        let resolver = system_state.get(world);
+
+        let expected = EntityRefTarget {
+            project: dungeon.clone_weak(),
+            world: Some(dungeon_world),
+            level_iid: LevelIid::new("dungeon"),
+            entity_iid: EntityIid::new("stairs"),
+            entity: Some(stairs),
+        };
+
+        let reference = ReferenceToAnEntityInstance {
+            entity_iid: "stairs".to_string(),
+            level_iid: "dungeon".to_string(),
+            ..default()
+        };
+        assert_eq!(resolver.resolve(&reference), Some(expected.clone()));
+        assert_eq!(
+            resolver.resolve_iid(&EntityIid::new("stairs")),
+            Some(expected)
+        );
+
+        let door = resolver.resolve_iid(&EntityIid::new("door")).unwrap();
+        assert_eq!(door.level_iid, LevelIid::new("overworld"));
+        assert_eq!(door.world, None);
+        assert_eq!(door.entity, None);
+
+        assert_eq!(resolver.resolve_iid(&EntityIid::new("missing")), None);
+    }
+}
@@ -0,0 +1,134 @@
+use crate::components::LevelIid;
+use bevy::{ecs::system::SystemParam, prelude::*};
+use thiserror::Error;
+
+/// Recoverable problems encountered while spawning levels.
+///
+/// How they are handled is determined by the [LdtkErrorPolicy] resource.
+/// With [LdtkErrorPolicy::Event], they are sent as events, so games can surface them in their own
+/// tooling:
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_ecs_ldtk::prelude::*;
+/// fn show_ldtk_errors(mut errors: EventReader<LdtkError>) {
+///     for error in errors.iter() {
+///         // Display the error in an in-game console, for example
+///         info!("{error}");
+///     }
+/// }
+/// ```
+#[derive(Clone, Eq, PartialEq, Debug, Hash, Error, Event)]
+pub enum LdtkError {
+    /// A layer uses a tileset that has no image, so the layer isn't spawned.
+    #[error("layer {layer} of level {level} uses tileset {tileset_uid}, which has no image")]
+    MissingTileset {
+        level: LevelIid,
+        layer: String,
+        tileset_uid: i32,
+    },
+    /// An entity's definition doesn't exist in the project.
+    #[error("entity {identifier} ({iid}) of level {level} has no definition with uid {def_uid}")]
+    UnknownEntity {
+        level: LevelIid,
+        identifier: String,
+        iid: String,
+        def_uid: i32,
+    },
+    /// A field read by the plugin has a type it can't use, so the field is ignored.
+    #[error("field {field} of level {level} is a {actual} field, expected {expected}")]
+    BadFieldType {
+        level: LevelIid,
+        field: String,
+        expected: String,
+        actual: String,
+    },
+}
+
+/// [Resource] that determines how the plugin reacts to [LdtkError]s.
+///
+/// Defaults to [LdtkErrorPolicy::Warn].
+/// To catch problems early during development without crashing release builds:
+/// ```no_run
+/// # use bevy::prelude::*;
+/// # use bevy_ecs_ldtk::prelude::*;
+/// App::new()
+///     .add_plugins((DefaultPlugins, LdtkPlugin))
+///     .insert_resource(if cfg!(debug_assertions) {
+///         LdtkErrorPolicy::Panic
+///     } else {
+///         LdtkErrorPolicy::Warn
+///     })
+///     .run();
+/// ```
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Default, Hash, Resource)]
+pub enum LdtkErrorPolicy {
+    /// Panic with the error message.
+    Panic,
+    /// Log the error as a warning.
+    #[default]
+    Warn,
+    /// Send the error as an [LdtkError] event.
+    Event,
+}
+
+/// [SystemParam] that handles [LdtkError]s according to the [LdtkErrorPolicy].
+#[derive(SystemParam)]
+pub struct LdtkErrorReporter<'w> {
+    policy: Res<'w, LdtkErrorPolicy>,
+    errors: EventWriter<'w, LdtkError>,
+}
+
+impl LdtkErrorReporter<'_> {
+    /// Handles the error according to the [LdtkErrorPolicy].
+    pub fn report(&mut self, error: LdtkError) {
+        match *self.policy {
+            LdtkErrorPolicy::Panic => panic!("{error}"),
+            LdtkErrorPolicy::Warn => warn!("{error}"),
+            LdtkErrorPolicy::Event => self.errors.send(error),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::ecs::system::SystemState;
+
+    fn missing_tileset() -> LdtkError {
+        LdtkError::MissingTileset {
+            level: LevelIid::new("level"),
+            layer: "Tiles".to_string(),
+            tileset_uid: 3,
+        }
+    }
+
+    fn report(world: &mut World, error: LdtkError) {
+        let mut system_state: SystemState<LdtkErrorReporter> = SystemState::new(world);
+        system_state.get_mut(world).report(error);
+    }
+
+    #[test]
+    fn event_policy_sends_errors() {
+        let mut world = World::new();
+        world.init_resource::<Events<LdtkError>>();
+        world.insert_resource(LdtkErrorPolicy::Event);
+
+        report(&mut world, missing_tileset());
+
+        let events = world.resource::<Events<LdtkError>>();
+        assert_eq!(
+            events.iter_current_update_events().collect::<Vec<_>>(),
+            vec![&missing_tileset()]
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "layer Tiles of level level uses tileset 3, which has no image")]
+    fn panic_policy_panics() {
+        let mut world = World::new();
+        world.init_resource::<Events<LdtkError>>();
+        world.insert_resource(LdtkErrorPolicy::Panic);
+
+        report(&mut world, missing_tileset());
+    }
+}
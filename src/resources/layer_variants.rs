@@ -0,0 +1,148 @@
+use crate::components::LevelIid;
+use bevy::prelude::*;
+use std::collections::HashMap;
+
+/// [Resource] for switching between alternate versions of layers at runtime.
+///
+/// Layers named `<group><separator><variant>` form a group of variants, like `Deco_day` and
+/// `Deco_night` in the `Deco` group.
+/// Once a group has an active variant, only the layers of that variant are visible, and the other
+/// variants of the group are hidden.
+/// This allows world states like day and night, or intact and damaged, to be authored as separate
+/// layers in LDtk:
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_ecs_ldtk::prelude::*;
+/// fn nightfall(mut layer_variants: ResMut<LayerVariants>) {
+///     layer_variants.set_active("Deco", "night");
+/// }
+///
+/// fn collapse_bridge(mut layer_variants: ResMut<LayerVariants>) {
+///     layer_variants.set_active_in_level(LevelIid::new("bridge-level"), "Bridge", "broken");
+/// }
+/// ```
+///
+/// Layers in groups without an active variant are left alone.
+/// The variant is split from the group at the last separator, which defaults to `_`.
+///
+/// Hidden variants keep their entities and components, only their [Visibility] changes.
+#[derive(Clone, PartialEq, Eq, Debug, Resource)]
+pub struct LayerVariants {
+    separator: char,
+    active: HashMap<String, String>,
+    level_active: HashMap<LevelIid, HashMap<String, String>>,
+}
+
+impl Default for LayerVariants {
+    fn default() -> Self {
+        LayerVariants {
+            separator: '_',
+            active: HashMap::new(),
+            level_active: HashMap::new(),
+        }
+    }
+}
+
+impl LayerVariants {
+    /// Sets the character separating group names from variant names in layer identifiers.
+    pub fn set_separator(&mut self, separator: char) {
+        self.separator = separator;
+    }
+
+    /// Activates a variant of the group in all levels, unless a level has its own active variant.
+    pub fn set_active(&mut self, group: impl Into<String>, variant: impl Into<String>) {
+        self.active.insert(group.into(), variant.into());
+    }
+
+    /// Activates a variant of the group in the given level only.
+    pub fn set_active_in_level(
+        &mut self,
+        level_iid: LevelIid,
+        group: impl Into<String>,
+        variant: impl Into<String>,
+    ) {
+        self.level_active
+            .entry(level_iid)
+            .or_default()
+            .insert(group.into(), variant.into());
+    }
+
+    /// Stops managing the group's variants in all levels, except for levels with their own active
+    /// variant.
+    ///
+    /// Layers keep the visibility they had when the group was cleared.
+    /// Returns the variant that was active.
+    pub fn clear_active(&mut self, group: &str) -> Option<String> {
+        self.active.remove(group)
+    }
+
+    /// Removes the active variants set for the given level, so it uses the ones of all levels.
+    pub fn clear_level(&mut self, level_iid: &LevelIid) {
+        self.level_active.remove(level_iid);
+    }
+
+    /// Returns the active variant of the group in the given level, if any.
+    pub fn active(&self, level_iid: &LevelIid, group: &str) -> Option<&str> {
+        self.level_active
+            .get(level_iid)
+            .and_then(|groups| groups.get(group))
+            .or_else(|| self.active.get(group))
+            .map(String::as_str)
+    }
+
+    /// Returns whether the layer with the given identifier should be visible in the given level.
+    ///
+    /// Returns `None` if the layer isn't a variant of a group with an active variant.
+    pub fn is_layer_active(&self, level_iid: &LevelIid, layer_identifier: &str) -> Option<bool> {
+        let (group, variant) = layer_identifier.rsplit_once(self.separator)?;
+
+        self.active(level_iid, group)
+            .map(|active_variant| active_variant == variant)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn level_variants_take_precedence() {
+        let day_level = LevelIid::new("day");
+        let night_level = LevelIid::new("night");
+
+        let mut layer_variants = LayerVariants::default();
+        assert_eq!(layer_variants.is_layer_active(&day_level, "Deco_day"), None);
+
+        layer_variants.set_active("Deco", "day");
+        layer_variants.set_active_in_level(night_level.clone(), "Deco", "night");
+
+        assert_eq!(
+            layer_variants.is_layer_active(&day_level, "Deco_day"),
+            Some(true)
+        );
+        assert_eq!(
+            layer_variants.is_layer_active(&day_level, "Deco_night"),
+            Some(false)
+        );
+        assert_eq!(
+            layer_variants.is_layer_active(&night_level, "Deco_day"),
+            Some(false)
+        );
+        assert_eq!(layer_variants.is_layer_active(&night_level, "Walls"), None);
+        assert_eq!(
+            layer_variants.is_layer_active(&night_level, "Sky_day"),
+            None
+        );
+
+        layer_variants.clear_level(&night_level);
+        layer_variants.set_separator('.');
+        assert_eq!(
+            layer_variants.is_layer_active(&night_level, "Deco_day"),
+            None
+        );
+        assert_eq!(
+            layer_variants.is_layer_active(&night_level, "Deco.day"),
+            Some(true)
+        );
+    }
+}
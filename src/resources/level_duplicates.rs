@@ -0,0 +1,274 @@
+use crate::{
+    assets::{LdtkProject, LdtkProjectData, LdtkProjectHandle},
+    components::{LevelIid, LevelSet},
+    ldtk::{FieldValue, Level},
+};
+use bevy::{ecs::system::Command, prelude::*};
+use std::{
+    collections::HashMap,
+    sync::atomic::{AtomicU64, Ordering},
+};
+
+#[cfg(feature = "external_levels")]
+use crate::assets::LdtkExternalLevel;
+
+/// A copy of a level that is spawned as a distinct level.
+///
+/// Created by the [DuplicateLevel] command.
+#[derive(Clone, PartialEq, Debug)]
+pub struct LevelDuplicate {
+    /// Iid of the level this is a copy of.
+    pub source: LevelIid,
+    /// Transform the duplicate spawns with, relative to its world.
+    pub transform: Transform,
+    /// Data of the duplicate.
+    ///
+    /// Its iid, and the iids of its layers and entities, are distinct from the source level's.
+    pub level: Level,
+}
+
+/// [Resource] storing the level duplicates created with [DuplicateLevel].
+///
+/// The iids of duplicates can be used in a [LevelSet] like the iids of any other level.
+/// They also stay in the [LevelSet] when the [LevelSelection] changes.
+/// To despawn a duplicate, remove it from this resource and from the [LevelSet].
+///
+/// [LevelSelection]: crate::prelude::LevelSelection
+#[derive(Clone, PartialEq, Debug, Default, Resource)]
+pub struct LevelDuplicates {
+    duplicates: HashMap<LevelIid, LevelDuplicate>,
+}
+
+impl LevelDuplicates {
+    /// Returns the duplicate with the given iid.
+    pub fn get(&self, iid: &LevelIid) -> Option<&LevelDuplicate> {
+        self.duplicates.get(iid)
+    }
+
+    /// Returns whether a duplicate with the given iid exists.
+    pub fn contains(&self, iid: &LevelIid) -> bool {
+        self.duplicates.contains_key(iid)
+    }
+
+    /// Removes the duplicate with the given iid.
+    pub fn remove(&mut self, iid: &LevelIid) -> Option<LevelDuplicate> {
+        self.duplicates.remove(iid)
+    }
+
+    /// Iterates over the iids and data of all duplicates.
+    pub fn iter(&self) -> impl Iterator<Item = (&LevelIid, &LevelDuplicate)> {
+        self.duplicates.iter()
+    }
+}
+
+static NEXT_DUPLICATE: AtomicU64 = AtomicU64::new(0);
+
+/// [Command] that duplicates a spawned level under a new iid and spawns it at a new transform.
+///
+/// The duplicate is added to the [LevelSet] of the world the source level is spawned in.
+/// It goes through the same spawning process as other levels, including [LevelEvent]s, but its
+/// iid, and the iids of its layers and entities, are distinct from the source level's.
+/// This is useful for mirrored arenas and other repeated spaces.
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_ecs_ldtk::prelude::*;
+/// fn mirror_arena(mut commands: Commands) {
+///     let duplicate = DuplicateLevel::new(
+///         LevelIid::new("a2f4d3e0-6d10-11ee-b2b5-6b2dc1f0e6a1"),
+///         Transform::from_xyz(512., 0., 0.),
+///     );
+///     info!("spawning duplicate {}", duplicate.iid.get());
+///
+///     commands.add(duplicate);
+/// }
+/// ```
+///
+/// Does nothing if the source level isn't spawned.
+///
+/// [Command]: bevy::ecs::system::Command
+/// [LevelEvent]: crate::prelude::LevelEvent
+#[derive(Clone, PartialEq, Debug)]
+pub struct DuplicateLevel {
+    pub source: LevelIid,
+    /// Iid of the duplicate.
+    pub iid: LevelIid,
+    pub transform: Transform,
+}
+
+impl DuplicateLevel {
+    /// Creates a [DuplicateLevel] command with a fresh iid.
+    pub fn new(source: LevelIid, transform: Transform) -> DuplicateLevel {
+        let iid = LevelIid::new(format!(
+            "{}-duplicate-{}",
+            source.get(),
+            NEXT_DUPLICATE.fetch_add(1, Ordering::Relaxed)
+        ));
+
+        DuplicateLevel {
+            source,
+            iid,
+            transform,
+        }
+    }
+}
+
+impl Command for DuplicateLevel {
+    fn apply(self, world: &mut World) {
+        let Some(world_entity) = world
+            .iter_entities()
+            .find(|entity_ref| entity_ref.get::<LevelIid>() == Some(&self.source))
+            .and_then(|entity_ref| entity_ref.get::<Parent>())
+            .map(Parent::get)
+        else {
+            warn!(
+                "cannot duplicate level {}, it isn't spawned",
+                self.source.get()
+            );
+            return;
+        };
+
+        let Some(ldtk_handle) = world.get::<LdtkProjectHandle>(world_entity) else {
+            return;
+        };
+
+        let Some(ldtk_project) = world.resource::<Assets<LdtkProject>>().get(ldtk_handle) else {
+            return;
+        };
+
+        let source_level = match ldtk_project.data() {
+            #[cfg(feature = "internal_levels")]
+            LdtkProjectData::Standalone(project) => project
+                .get_loaded_level_by_iid(self.source.get())
+                .map(|loaded_level| loaded_level.raw().clone()),
+            #[cfg(feature = "external_levels")]
+            LdtkProjectData::Parent(project) => project
+                .get_external_level_by_iid(
+                    world.resource::<Assets<LdtkExternalLevel>>(),
+                    self.source.get(),
+                )
+                .map(|loaded_level| loaded_level.raw().clone()),
+        };
+
+        let Some(mut level) = source_level else {
+            return;
+        };
+
+        rename_level_iids(&mut level, self.iid.get());
+
+        world.resource_mut::<LevelDuplicates>().duplicates.insert(
+            self.iid.clone(),
+            LevelDuplicate {
+                source: self.source,
+                transform: self.transform,
+                level,
+            },
+        );
+
+        if let Some(mut level_set) = world.get_mut::<LevelSet>(world_entity) {
+            level_set.iids.insert(self.iid);
+        }
+    }
+}
+
+/// Gives the level, its layers, and its entities new iids derived from `new_iid`.
+///
+/// Entity references within the level are updated accordingly.
+fn rename_level_iids(level: &mut Level, new_iid: &str) {
+    let old_iid = std::mem::replace(&mut level.iid, new_iid.to_string());
+    let rename = |iid: &str| format!("{iid}@{new_iid}");
+
+    let layer_instances = level.layer_instances.iter_mut().flatten();
+    let entity_instances: Vec<_> = layer_instances
+        .flat_map(|layer_instance| {
+            layer_instance.iid = rename(&layer_instance.iid);
+            layer_instance.entity_instances.iter_mut()
+        })
+        .collect();
+
+    let renamed_entities: HashMap<String, String> = entity_instances
+        .iter()
+        .map(|entity_instance| (entity_instance.iid.clone(), rename(&entity_instance.iid)))
+        .collect();
+
+    for entity_instance in entity_instances {
+        entity_instance.iid = renamed_entities[&entity_instance.iid].clone();
+
+        for field_instance in entity_instance.field_instances.iter_mut() {
+            let references: Vec<_> = match &mut field_instance.value {
+                FieldValue::EntityRef(reference) => reference.iter_mut().collect(),
+                FieldValue::EntityRefs(references) => references.iter_mut().flatten().collect(),
+                _ => Vec::new(),
+            };
+
+            for reference in references {
+                if reference.level_iid == old_iid {
+                    if let Some(entity_iid) = renamed_entities.get(&reference.entity_iid) {
+                        reference.entity_iid = entity_iid.clone();
+                        reference.layer_iid = rename(&reference.layer_iid);
+                        reference.level_iid = new_iid.to_string();
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ldtk::{
+        ldtk_fields::field_instance_from_value, EntityInstance, LayerInstance,
+        ReferenceToAnEntityInstance,
+    };
+
+    #[test]
+    fn duplicates_rename_iids_and_internal_references() {
+        let reference = ReferenceToAnEntityInstance {
+            entity_iid: "door".to_string(),
+            layer_iid: "entities".to_string(),
+            level_iid: "arena".to_string(),
+            world_iid: "world".to_string(),
+            ..Default::default()
+        };
+
+        let mut level = Level {
+            iid: "arena".to_string(),
+            layer_instances: Some(vec![LayerInstance {
+                iid: "entities".to_string(),
+                entity_instances: vec![
+                    EntityInstance {
+                        iid: "lever".to_string(),
+                        field_instances: vec![field_instance_from_value(
+                            "target",
+                            FieldValue::EntityRef(Some(reference)),
+                        )],
+                        ..Default::default()
+                    },
+                    EntityInstance {
+                        iid: "door".to_string(),
+                        ..Default::default()
+                    },
+                ],
+                ..Default::default()
+            }]),
+            ..Default::default()
+        };
+
+        rename_level_iids(&mut level, "arena-2");
+
+        assert_eq!(level.iid, "arena-2");
+
+        let layer_instance = &level.layer_instances.as_ref().unwrap()[0];
+        assert_eq!(layer_instance.iid, "entities@arena-2");
+        assert_eq!(layer_instance.entity_instances[1].iid, "door@arena-2");
+
+        let FieldValue::EntityRef(Some(reference)) =
+            &layer_instance.entity_instances[0].field_instances[0].value
+        else {
+            panic!("expected an entity reference");
+        };
+        assert_eq!(reference.entity_iid, "door@arena-2");
+        assert_eq!(reference.layer_iid, "entities@arena-2");
+        assert_eq!(reference.level_iid, "arena-2");
+    }
+}
@@ -5,24 +5,118 @@ use crate::LevelIid;
 /// Events fired by the plugin related to level spawning/despawning.
 ///
 /// Each variant stores the level's `iid` in LDtk.
+///
+/// The plugin sends each kind of event in its own [`LevelEventSet`], so systems can be ordered
+/// relative to them.
+/// A level's [`LevelEvent::Spawned`] and [`LevelEvent::Transformed`] events are always sent in
+/// the same update, while its [`LevelEvent::SpawnTriggered`] event is sent in an earlier update.
+/// To read all of them as a single ordered stream, see [`LevelLifecycleEvent`].
+///
+/// [`LevelEventSet`]: crate::prelude::LevelEventSet
 #[derive(Clone, Eq, PartialEq, Debug, Hash, Event)]
 pub enum LevelEvent {
     /// Indicates that a level has been triggered to spawn, but hasn't been spawned yet.
+    ///
+    /// Sent in [`LevelEventSet::SpawnTriggered`], in the [`ProcessLdtkApi`] schedule.
+    ///
+    /// [`LevelEventSet::SpawnTriggered`]: crate::prelude::LevelEventSet::SpawnTriggered
+    /// [`ProcessLdtkApi`]: crate::prelude::ProcessLdtkApi
     SpawnTriggered(LevelIid),
     /// The level, with all of its layers, entities, etc., has spawned.
     ///
+    /// Sent in [`LevelEventSet::Spawned`], in [`PreUpdate`].
+    ///
     /// Note: due to the frame-delay of [`GlobalTransform`] being updated, this may not be the
     /// event you want to listen for.
     /// If your systems are [`GlobalTransform`]-dependent, see [`LevelEvent::Transformed`].
     ///
+    /// [`LevelEventSet::Spawned`]: crate::prelude::LevelEventSet::Spawned
+    /// [`PreUpdate`]: https://docs.rs/bevy/latest/bevy/app/struct.PreUpdate.html
     /// [`GlobalTransform`]: https://docs.rs/bevy/latest/bevy/prelude/struct.GlobalTransform.html
     Spawned(LevelIid),
     /// Occurs during the [`PostUpdate`] after the level has spawned, so all [`GlobalTransform`]s
     /// of the level should be updated.
     ///
+    /// Sent in [`LevelEventSet::Transformed`], after [`GlobalTransform`]s are propagated.
+    ///
+    /// [`LevelEventSet::Transformed`]: crate::prelude::LevelEventSet::Transformed
     /// [`PostUpdate`]: https://docs.rs/bevy/latest/bevy/app/struct.PostUpdate.html
     /// [`GlobalTransform`]: https://docs.rs/bevy/latest/bevy/prelude/struct.GlobalTransform.html
     Transformed(LevelIid),
     /// Indicates that a level has despawned.
     Despawned(LevelIid),
 }
+
+impl LevelEvent {
+    /// The iid of the level this event is about.
+    pub fn level_iid(&self) -> &LevelIid {
+        match self {
+            LevelEvent::SpawnTriggered(level_iid)
+            | LevelEvent::Spawned(level_iid)
+            | LevelEvent::Transformed(level_iid)
+            | LevelEvent::Despawned(level_iid) => level_iid,
+        }
+    }
+}
+
+/// A [`LevelEvent`], with metadata for ordering it among all level events.
+///
+/// Sent in [`LevelEventSet::Lifecycle`], at the end of the [`PostUpdate`] of the update that the
+/// [`LevelEvent`] was sent in, so every level event of an update can be read together in the
+/// order they were sent.
+/// Useful for state machines that track levels across updates, which could otherwise miss events
+/// sent in schedules they don't run in.
+///
+/// [`LevelEventSet::Lifecycle`]: crate::prelude::LevelEventSet::Lifecycle
+/// [`PostUpdate`]: https://docs.rs/bevy/latest/bevy/app/struct.PostUpdate.html
+#[derive(Clone, Eq, PartialEq, Debug, Hash, Event)]
+pub struct LevelLifecycleEvent {
+    pub event: LevelEvent,
+    /// The [`FrameCount`] of the update the event was sent in, or 0 if there is no [`FrameCount`].
+    ///
+    /// [`FrameCount`]: https://docs.rs/bevy/latest/bevy/core/struct.FrameCount.html
+    pub frame: u32,
+    /// Position of the event among all level events sent since the app started.
+    pub sequence: u64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::systems::fire_level_lifecycle_events;
+
+    #[test]
+    fn lifecycle_events_keep_order_across_updates() {
+        let mut app = App::new();
+        app.add_event::<LevelEvent>()
+            .add_event::<LevelLifecycleEvent>()
+            .add_systems(Update, fire_level_lifecycle_events);
+
+        let level_iid = LevelIid::new("cave");
+
+        app.world
+            .send_event(LevelEvent::SpawnTriggered(level_iid.clone()));
+        app.update();
+
+        app.world.send_event(LevelEvent::Spawned(level_iid.clone()));
+        app.world
+            .send_event(LevelEvent::Transformed(level_iid.clone()));
+        app.update();
+
+        let lifecycle_events = app.world.resource::<Events<LevelLifecycleEvent>>();
+        let events: Vec<_> = lifecycle_events
+            .get_reader()
+            .iter(lifecycle_events)
+            .map(|lifecycle_event| (lifecycle_event.sequence, lifecycle_event.event.clone()))
+            .collect();
+
+        assert_eq!(
+            events,
+            vec![
+                (0, LevelEvent::SpawnTriggered(level_iid.clone())),
+                (1, LevelEvent::Spawned(level_iid.clone())),
+                (2, LevelEvent::Transformed(level_iid)),
+            ]
+        );
+    }
+}
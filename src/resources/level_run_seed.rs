@@ -0,0 +1,10 @@
+use bevy::prelude::*;
+
+/// [Resource] mixed into the [LevelSeed](crate::prelude::LevelSeed) of every level spawned while
+/// it exists.
+///
+/// Use a new run seed for every playthrough to vary randomized content between runs, and store it
+/// to reproduce a run later.
+/// Levels that are already spawned keep their seeds until they respawn.
+#[derive(Copy, Clone, Debug, Default, Hash, Eq, PartialEq, Resource)]
+pub struct LevelRunSeed(pub u64);
@@ -1,5 +1,10 @@
-use crate::{assets::LevelIndices, ldtk::Level, LevelIid};
+use crate::{
+    assets::{LevelIndices, LevelMetadataAccessor},
+    ldtk::Level,
+    LevelIid,
+};
 use bevy::prelude::*;
+use thiserror::Error;
 
 /// [`Resource`] for choosing which level(s) to spawn.
 ///
@@ -10,6 +15,8 @@ use bevy::prelude::*;
 /// If you need more control over the spawned levels than this resource provides,
 /// you can choose not to insert this resource and interface with [`LevelSet`] directly instead.
 ///
+/// If the selection doesn't match any level in the project, a [`LevelSelectionError`] is sent.
+///
 /// [`LevelSpawnBehavior`]: crate::prelude::LevelSpawnBehavior
 /// [`LdtkWorldBundle`]: crate::prelude::LdtkWorldBundle
 /// [`LevelSet`]: crate::prelude::LevelSet
@@ -96,4 +103,80 @@ impl LevelSelection {
             LevelSelection::Uid(u) => *u == level.uid,
         }
     }
+
+    /// Converts this selection to a [`LevelSelection::Iid`] of the same level in the given
+    /// project.
+    ///
+    /// Returns [`None`] if no level in the project matches this selection.
+    pub fn to_iid(&self, project: &impl LevelMetadataAccessor) -> Option<LevelSelection> {
+        project
+            .find_level_iid_by_level_selection(self)
+            .map(LevelSelection::Iid)
+    }
+
+    /// Converts this selection to a [`LevelSelection::Indices`] of the same level in the given
+    /// project.
+    ///
+    /// Returns [`None`] if no level in the project matches this selection.
+    pub fn to_indices(&self, project: &impl LevelMetadataAccessor) -> Option<LevelSelection> {
+        project
+            .find_level_indices_by_level_selection(self)
+            .map(LevelSelection::Indices)
+    }
+}
+
+impl From<LevelIndices> for LevelSelection {
+    fn from(indices: LevelIndices) -> Self {
+        LevelSelection::Indices(indices)
+    }
+}
+
+impl From<LevelIid> for LevelSelection {
+    fn from(iid: LevelIid) -> Self {
+        LevelSelection::Iid(iid)
+    }
+}
+
+/// Option in [`LdtkSettings`] that determines what happens to the spawned levels when the
+/// [`LevelSelection`] doesn't match any level in the project.
+///
+/// Either way, a [`LevelSelectionError`] is sent.
+///
+/// [`LdtkSettings`]: crate::prelude::LdtkSettings
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Default, Hash)]
+pub enum InvalidLevelSelection {
+    /// Keep the previously selected level spawned.
+    #[default]
+    KeepPrevious,
+    /// Despawn the previously selected level, and its neighbors.
+    DespawnLevels,
+}
+
+/// Event sent when the [`LevelSelection`] doesn't match any level in a loaded project, like when
+/// its identifier has a typo.
+///
+/// Sent once per world each time the selection changes to an invalid one.
+/// What happens to the spawned levels is determined by [`InvalidLevelSelection`].
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_ecs_ldtk::prelude::*;
+/// fn fall_back_to_first_level(
+///     mut commands: Commands,
+///     mut selection_errors: EventReader<LevelSelectionError>,
+/// ) {
+///     for error in selection_errors.iter() {
+///         error!("{error}");
+///         commands.insert_resource(LevelSelection::index(0));
+///     }
+/// }
+/// ```
+#[derive(Clone, Eq, PartialEq, Debug, Error, Event)]
+#[error("level selection {selection:?} doesn't match any level in the project of world {world:?}")]
+pub struct LevelSelectionError {
+    /// The invalid selection.
+    pub selection: LevelSelection,
+    /// The [`LdtkWorldBundle`] entity whose project has no matching level.
+    ///
+    /// [`LdtkWorldBundle`]: crate::prelude::LdtkWorldBundle
+    pub world: Entity,
 }
@@ -0,0 +1,39 @@
+use crate::components::LevelIid;
+use bevy::prelude::*;
+use std::collections::HashMap;
+
+/// Changes to how a single level spawns, stored in [LevelSpawnOverrides].
+#[derive(Copy, Clone, PartialEq, Debug, Default)]
+pub struct LevelSpawnOverride {
+    /// Transform the level spawns with, relative to its world, instead of the one determined by
+    /// the [LevelSpawnBehavior](crate::prelude::LevelSpawnBehavior).
+    pub transform: Option<Transform>,
+    /// Seed used instead of the [LevelVariation](crate::prelude::LevelVariation) seed.
+    pub seed: Option<u64>,
+}
+
+/// [Resource] storing per-level changes to how levels spawn, by level iid.
+///
+/// Overrides take effect the next time the level spawns, they don't affect levels that are
+/// already spawned.
+#[derive(Clone, PartialEq, Debug, Default, Resource)]
+pub struct LevelSpawnOverrides {
+    overrides: HashMap<LevelIid, LevelSpawnOverride>,
+}
+
+impl LevelSpawnOverrides {
+    /// Sets the override of the level with the given iid.
+    pub fn insert(&mut self, level_iid: LevelIid, spawn_override: LevelSpawnOverride) {
+        self.overrides.insert(level_iid, spawn_override);
+    }
+
+    /// Returns the override of the level with the given iid.
+    pub fn get(&self, level_iid: &LevelIid) -> Option<&LevelSpawnOverride> {
+        self.overrides.get(level_iid)
+    }
+
+    /// Removes the override of the level with the given iid.
+    pub fn remove(&mut self, level_iid: &LevelIid) -> Option<LevelSpawnOverride> {
+        self.overrides.remove(level_iid)
+    }
+}
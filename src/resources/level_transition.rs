@@ -0,0 +1,197 @@
+use crate::{resources::LevelSelection, LevelIid};
+use bevy::prelude::*;
+use std::{collections::VecDeque, time::Duration};
+
+/// Determines what happens when a [LevelTransition] is pushed to a busy [LevelTransitionQueue].
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Default, Hash)]
+pub enum TransitionPolicy {
+    /// Wait for the transitions before it to complete.
+    #[default]
+    Enqueue,
+    /// Drop the transitions waiting in the queue, but let the current one complete first.
+    ReplacePending,
+    /// Drop this transition if another one is in progress or waiting.
+    IgnoreIfBusy,
+}
+
+/// A change of [LevelSelection] performed by the [LevelTransitionQueue].
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct LevelTransition {
+    /// The level to select.
+    pub target: LevelSelection,
+    /// Time between the [LevelTransitionEvent::Started] event and the selection changing, like
+    /// for fading out the current level.
+    pub delay: Duration,
+    /// What to do if the queue is busy when this transition is pushed.
+    pub policy: TransitionPolicy,
+}
+
+impl LevelTransition {
+    /// Construct a [LevelTransition] to the given level, with no delay and the default policy.
+    pub fn to(target: LevelSelection) -> Self {
+        LevelTransition {
+            target,
+            delay: Duration::ZERO,
+            policy: TransitionPolicy::default(),
+        }
+    }
+
+    /// Sets the delay before the selection changes.
+    pub fn with_delay(mut self, delay: Duration) -> Self {
+        self.delay = delay;
+        self
+    }
+
+    /// Sets the policy used when the queue is busy.
+    pub fn with_policy(mut self, policy: TransitionPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+}
+
+/// Events fired by the [LevelTransitionQueue] as it processes transitions.
+///
+/// Each variant stores the transition's target.
+#[derive(Clone, Eq, PartialEq, Debug, Event)]
+pub enum LevelTransitionEvent {
+    /// The transition has started, and its delay is running.
+    Started(LevelSelection),
+    /// The [LevelSelection] has changed and the target level has spawned.
+    ///
+    /// Also fired if the target level was already spawned.
+    TargetSpawned(LevelSelection),
+    /// The target level's transforms are up to date, and the next transition can start.
+    Completed(LevelSelection),
+}
+
+#[derive(Clone, Debug)]
+pub(crate) enum TransitionStage {
+    Delaying(Timer),
+    Spawning(LevelIid),
+    Transforming(LevelIid),
+}
+
+/// [Resource] for changing the [LevelSelection] through a queue of transitions.
+///
+/// Changing the [LevelSelection] directly from several systems in the same update can spawn levels
+/// that are immediately despawned again, or change the selection before the previous level has
+/// finished spawning.
+/// Transitions pushed to this queue are processed one at a time instead, and report their progress
+/// with [LevelTransitionEvent]s:
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_ecs_ldtk::prelude::*;
+/// # use std::time::Duration;
+/// fn enter_door(mut transitions: ResMut<LevelTransitionQueue>) {
+///     transitions.push(
+///         LevelTransition::to(LevelSelection::iid("e5eb2d73-60bb-4779-8b33-38a63da8d1db"))
+///             .with_delay(Duration::from_secs_f32(0.5)),
+///     );
+/// }
+///
+/// fn fade(mut transition_events: EventReader<LevelTransitionEvent>) {
+///     for event in transition_events.iter() {
+///         match event {
+///             LevelTransitionEvent::Started(_) => info!("fade out"),
+///             LevelTransitionEvent::TargetSpawned(_) => info!("fade in"),
+///             LevelTransitionEvent::Completed(_) => info!("give control back to the player"),
+///         }
+///     }
+/// }
+/// ```
+///
+/// The queue inserts the [LevelSelection] resource if it doesn't exist.
+/// Changing the [LevelSelection] directly while a transition is in progress may prevent it from
+/// completing.
+#[derive(Clone, Debug, Default, Resource)]
+pub struct LevelTransitionQueue {
+    pending: VecDeque<LevelTransition>,
+    pub(crate) active: Option<(LevelTransition, TransitionStage)>,
+}
+
+impl LevelTransitionQueue {
+    /// Adds a transition to the queue, according to its [TransitionPolicy].
+    ///
+    /// Returns `false` if the transition was dropped.
+    pub fn push(&mut self, transition: LevelTransition) -> bool {
+        match transition.policy {
+            TransitionPolicy::Enqueue => (),
+            TransitionPolicy::ReplacePending => self.pending.clear(),
+            TransitionPolicy::IgnoreIfBusy if self.is_busy() => return false,
+            TransitionPolicy::IgnoreIfBusy => (),
+        }
+
+        self.pending.push_back(transition);
+        true
+    }
+
+    /// Returns `true` if a transition is in progress or waiting.
+    pub fn is_busy(&self) -> bool {
+        self.active.is_some() || !self.pending.is_empty()
+    }
+
+    /// The transition in progress, if any.
+    pub fn active(&self) -> Option<&LevelTransition> {
+        self.active.as_ref().map(|(transition, _)| transition)
+    }
+
+    /// The transitions waiting for the current one to complete.
+    pub fn pending(&self) -> impl Iterator<Item = &LevelTransition> {
+        self.pending.iter()
+    }
+
+    /// Drops the transitions waiting in the queue.
+    ///
+    /// The transition in progress still completes.
+    pub fn clear_pending(&mut self) {
+        self.pending.clear();
+    }
+
+    /// Starts the next transition if there isn't one in progress, returning its target.
+    pub(crate) fn start_next(&mut self) -> Option<LevelSelection> {
+        if self.active.is_some() {
+            return None;
+        }
+
+        let transition = self.pending.pop_front()?;
+        let target = transition.target.clone();
+        let timer = Timer::new(transition.delay, TimerMode::Once);
+
+        self.active = Some((transition, TransitionStage::Delaying(timer)));
+        Some(target)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn push_follows_transition_policy() {
+        let mut queue = LevelTransitionQueue::default();
+        assert!(!queue.is_busy());
+
+        assert!(queue.push(LevelTransition::to(LevelSelection::index(1))));
+        assert!(queue.push(LevelTransition::to(LevelSelection::index(2))));
+        assert_eq!(queue.start_next(), Some(LevelSelection::index(1)));
+        assert_eq!(queue.start_next(), None);
+
+        assert!(!queue.push(
+            LevelTransition::to(LevelSelection::index(3))
+                .with_policy(TransitionPolicy::IgnoreIfBusy)
+        ));
+        assert!(queue.push(
+            LevelTransition::to(LevelSelection::index(4))
+                .with_policy(TransitionPolicy::ReplacePending)
+        ));
+
+        assert_eq!(queue.active().unwrap().target, LevelSelection::index(1));
+        assert_eq!(
+            queue
+                .pending()
+                .map(|transition| transition.target.clone())
+                .collect::<Vec<_>>(),
+            vec![LevelSelection::index(4)]
+        );
+    }
+}
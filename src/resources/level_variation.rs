@@ -0,0 +1,227 @@
+use crate::ldtk::{LayerInstance, Type};
+use serde::{Deserialize, Serialize};
+
+/// A single rule of a [LevelVariation].
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub enum VariationRule {
+    /// Replaces IntGrid values.
+    SubstituteIntGrid {
+        /// Identifier of the affected layer, or `None` for all IntGrid layers.
+        #[serde(default)]
+        layer: Option<String>,
+        from: i32,
+        to: i32,
+        /// Chance of each matching cell being substituted, from 0 to 1.
+        chance: f32,
+    },
+    /// Removes entities that have the given tag.
+    DeleteTaggedEntities {
+        tag: String,
+        /// Chance of each matching entity being removed, from 0 to 1.
+        chance: f32,
+    },
+}
+
+/// Randomly varies levels before they spawn, for light roguelite variation of hand-authored
+/// rooms.
+///
+/// The rules are applied in order.
+/// The outcome only depends on the `seed`, the level, and the cell or entity, so a level spawns
+/// the same way every time for the same seed.
+/// Set the seed along with the [LevelSelection] to pick a variation, like when entering a new run.
+///
+/// Rules can be configured in code or loaded from RON:
+/// ```
+/// # use bevy_ecs_ldtk::prelude::*;
+/// let level_variation = LevelVariation::from_ron(
+///     r#"(
+///         seed: 42,
+///         rules: [
+///             SubstituteIntGrid(layer: Some("Walls"), from: 1, to: 0, chance: 0.1),
+///             DeleteTaggedEntities(tag: "optional", chance: 0.5),
+///         ],
+///     )"#,
+/// )
+/// .unwrap();
+///
+/// let ldtk_settings = LdtkSettings {
+///     level_variation,
+///     ..Default::default()
+/// };
+/// ```
+///
+/// Tiles are not affected, only the values of IntGrid cells and their rendering if the layer has
+/// no tileset.
+///
+/// [LevelSelection]: crate::prelude::LevelSelection
+#[derive(Clone, PartialEq, Debug, Default, Serialize, Deserialize)]
+pub struct LevelVariation {
+    #[serde(default)]
+    pub seed: u64,
+    #[serde(default)]
+    pub rules: Vec<VariationRule>,
+}
+
+impl LevelVariation {
+    /// Parses a [LevelVariation] from RON.
+    pub fn from_ron(ron: &str) -> Result<LevelVariation, ron::error::SpannedError> {
+        ron::from_str(ron)
+    }
+
+    /// Returns a varied copy of the layer, or `None` if no rules affect it.
+    pub fn vary_layer(
+        &self,
+        level_iid: &str,
+        layer_instance: &LayerInstance,
+    ) -> Option<LayerInstance> {
+        let applies = self.rules.iter().any(|rule| match rule {
+            VariationRule::SubstituteIntGrid { layer, .. } => {
+                layer_instance.layer_instance_type == Type::IntGrid
+                    && layer
+                        .as_ref()
+                        .map_or(true, |l| *l == layer_instance.identifier)
+            }
+            VariationRule::DeleteTaggedEntities { .. } => {
+                layer_instance.layer_instance_type == Type::Entities
+            }
+        });
+
+        if !applies {
+            return None;
+        }
+
+        let mut varied = layer_instance.clone();
+
+        for (rule_index, rule) in self.rules.iter().enumerate() {
+            match rule {
+                VariationRule::SubstituteIntGrid {
+                    layer,
+                    from,
+                    to,
+                    chance,
+                } => {
+                    if varied.layer_instance_type != Type::IntGrid
+                        || layer.as_ref().map_or(false, |l| *l != varied.identifier)
+                    {
+                        continue;
+                    }
+
+                    for (cell_index, value) in varied.int_grid_csv.iter_mut().enumerate() {
+                        let key = format!("{}/{cell_index}", varied.iid);
+
+                        if *value == *from && self.roll(level_iid, rule_index, &key) < *chance {
+                            *value = *to;
+                        }
+                    }
+                }
+                VariationRule::DeleteTaggedEntities { tag, chance } => {
+                    varied.entity_instances.retain(|entity_instance| {
+                        !(entity_instance.tags.contains(tag)
+                            && self.roll(level_iid, rule_index, &entity_instance.iid) < *chance)
+                    });
+                }
+            }
+        }
+
+        Some(varied)
+    }
+
+    /// Returns a deterministic number from 0 to 1 for the given seed and inputs.
+    fn roll(&self, level_iid: &str, rule_index: usize, key: &str) -> f32 {
+        // FNV-1a, followed by the splitmix64 finalizer
+        let mut hash: u64 = 0xcbf29ce484222325;
+        let bytes = self
+            .seed
+            .to_le_bytes()
+            .into_iter()
+            .chain(level_iid.bytes())
+            .chain((rule_index as u64).to_le_bytes())
+            .chain(key.bytes());
+
+        for byte in bytes {
+            hash ^= byte as u64;
+            hash = hash.wrapping_mul(0x100000001b3);
+        }
+
+        hash ^= hash >> 30;
+        hash = hash.wrapping_mul(0xbf58476d1ce4e5b9);
+        hash ^= hash >> 27;
+        hash = hash.wrapping_mul(0x94d049bb133111eb);
+        hash ^= hash >> 31;
+
+        (hash >> 40) as f32 / (1u64 << 24) as f32
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ldtk::EntityInstance;
+
+    fn walls() -> LayerInstance {
+        LayerInstance {
+            identifier: "Walls".to_string(),
+            iid: "walls".to_string(),
+            layer_instance_type: Type::IntGrid,
+            int_grid_csv: vec![1; 64],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn variation_is_deterministic_per_seed() {
+        let mut level_variation = LevelVariation {
+            seed: 7,
+            rules: vec![VariationRule::SubstituteIntGrid {
+                layer: Some("Walls".to_string()),
+                from: 1,
+                to: 2,
+                chance: 0.5,
+            }],
+        };
+
+        let first = level_variation.vary_layer("level", &walls()).unwrap();
+        let second = level_variation.vary_layer("level", &walls()).unwrap();
+        assert_eq!(first, second);
+        assert!(first.int_grid_csv.contains(&1));
+        assert!(first.int_grid_csv.contains(&2));
+
+        level_variation.seed = 8;
+        let reseeded = level_variation.vary_layer("level", &walls()).unwrap();
+        assert_ne!(first, reseeded);
+
+        let other_layer = LayerInstance {
+            identifier: "Water".to_string(),
+            ..walls()
+        };
+        assert_eq!(level_variation.vary_layer("level", &other_layer), None);
+    }
+
+    #[test]
+    fn certain_rules_always_apply() {
+        let level_variation = LevelVariation::from_ron(
+            r#"(rules: [DeleteTaggedEntities(tag: "optional", chance: 1.0)])"#,
+        )
+        .unwrap();
+
+        let entities = LayerInstance {
+            layer_instance_type: Type::Entities,
+            entity_instances: vec![
+                EntityInstance {
+                    iid: "crate".to_string(),
+                    tags: vec!["optional".to_string()],
+                    ..Default::default()
+                },
+                EntityInstance {
+                    iid: "door".to_string(),
+                    ..Default::default()
+                },
+            ],
+            ..Default::default()
+        };
+
+        let varied = level_variation.vary_layer("level", &entities).unwrap();
+        assert_eq!(varied.entity_instances.len(), 1);
+        assert_eq!(varied.entity_instances[0].iid, "door");
+    }
+}
@@ -0,0 +1,197 @@
+use bevy::prelude::*;
+use std::{
+    collections::{HashMap, HashSet},
+    fmt,
+    sync::Arc,
+};
+
+use crate::ldtk::{FieldInstance, FieldValue, Level};
+
+type Translator = Arc<dyn Fn(&str) -> Option<String> + Send + Sync>;
+
+/// [Resource] for localizing string fields authored as translation keys in LDtk.
+///
+/// The values of localized fields are looked up as keys before levels spawn, so
+/// [LdtkEntity](crate::prelude::LdtkEntity) implementations and other bundle constructors receive
+/// the translated text.
+/// Keys are looked up in the translations added with [LdtkLocalization::insert] first, and then
+/// with the translator, if any.
+/// Keys without a translation are left as they are.
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_ecs_ldtk::prelude::*;
+/// fn load_french(mut localization: ResMut<LdtkLocalization>) {
+///     localization.localize_field("dialogue");
+///     localization.insert("sign.cave.1", "Attention aux chauves-souris !");
+/// }
+/// ```
+///
+/// Translators can also wrap an existing localization library, like fluent:
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_ecs_ldtk::prelude::*;
+/// # fn lookup(key: &str) -> Option<String> { None }
+/// fn setup_localization(mut localization: ResMut<LdtkLocalization>) {
+///     localization.localize_field("dialogue");
+///     localization.set_translator(|key| lookup(key));
+/// }
+/// ```
+///
+/// Changing this resource doesn't affect levels that are already spawned, insert
+/// [Respawn](crate::prelude::Respawn) on the world entity to spawn them with the new text.
+#[derive(Clone, Default, Resource)]
+pub struct LdtkLocalization {
+    fields: HashSet<String>,
+    translations: HashMap<String, String>,
+    translator: Option<Translator>,
+}
+
+impl fmt::Debug for LdtkLocalization {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LdtkLocalization")
+            .field("fields", &self.fields)
+            .field("translations", &self.translations)
+            .field("translator", &self.translator.is_some())
+            .finish()
+    }
+}
+
+impl LdtkLocalization {
+    /// Localizes the String and Multilines fields with the given identifier, on both levels and
+    /// entities.
+    pub fn localize_field(&mut self, field_identifier: impl Into<String>) {
+        self.fields.insert(field_identifier.into());
+    }
+
+    /// Adds a translation for the given key.
+    pub fn insert(&mut self, key: impl Into<String>, text: impl Into<String>) {
+        self.translations.insert(key.into(), text.into());
+    }
+
+    /// Removes all translations added with [LdtkLocalization::insert], like when switching
+    /// languages.
+    pub fn clear_translations(&mut self) {
+        self.translations.clear();
+    }
+
+    /// Uses the given callback to translate keys that don't have a translation in this resource.
+    pub fn set_translator(
+        &mut self,
+        translator: impl Fn(&str) -> Option<String> + Send + Sync + 'static,
+    ) {
+        self.translator = Some(Arc::new(translator));
+    }
+
+    /// Returns the translation of the given key, if any.
+    pub fn translate(&self, key: &str) -> Option<String> {
+        self.translations
+            .get(key)
+            .cloned()
+            .or_else(|| self.translator.as_ref()?(key))
+    }
+
+    /// Returns a copy of the level with its localized fields translated, or `None` if it doesn't
+    /// need any translations.
+    pub fn localize_level(&self, level: &Level) -> Option<Level> {
+        let entity_fields = level
+            .layer_instances
+            .iter()
+            .flatten()
+            .flat_map(|layer_instance| layer_instance.entity_instances.iter())
+            .flat_map(|entity_instance| entity_instance.field_instances.iter());
+
+        if !level
+            .field_instances
+            .iter()
+            .chain(entity_fields)
+            .any(|field_instance| self.fields.contains(&field_instance.identifier))
+        {
+            return None;
+        }
+
+        let mut level = level.clone();
+
+        self.localize_fields(&mut level.field_instances);
+
+        for layer_instance in level.layer_instances.iter_mut().flatten() {
+            for entity_instance in layer_instance.entity_instances.iter_mut() {
+                self.localize_fields(&mut entity_instance.field_instances);
+            }
+        }
+
+        Some(level)
+    }
+
+    fn localize_fields(&self, field_instances: &mut [FieldInstance]) {
+        let localize = |text: &mut Option<String>| {
+            if let Some(translation) = text.as_deref().and_then(|key| self.translate(key)) {
+                *text = Some(translation);
+            }
+        };
+
+        for field_instance in field_instances {
+            if !self.fields.contains(&field_instance.identifier) {
+                continue;
+            }
+
+            match &mut field_instance.value {
+                FieldValue::String(text) => localize(text),
+                FieldValue::Strings(texts) => texts.iter_mut().for_each(localize),
+                _ => (),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ldtk::{ldtk_fields::field_instance_from_value, EntityInstance, LayerInstance};
+
+    fn string_field(identifier: &str, value: &str) -> FieldInstance {
+        field_instance_from_value(identifier, FieldValue::String(Some(value.to_string())))
+    }
+
+    #[test]
+    fn localized_fields_are_translated() {
+        let level = Level {
+            field_instances: vec![string_field("title", "level.cave")],
+            layer_instances: Some(vec![LayerInstance {
+                entity_instances: vec![EntityInstance {
+                    field_instances: vec![
+                        string_field("dialogue", "sign.cave.1"),
+                        string_field("dialogue", "sign.cave.2"),
+                        string_field("name", "sign.cave.1"),
+                    ],
+                    ..Default::default()
+                }],
+                ..Default::default()
+            }]),
+            ..Default::default()
+        };
+
+        let mut localization = LdtkLocalization::default();
+        assert!(localization.localize_level(&level).is_none());
+
+        localization.localize_field("dialogue");
+        localization.insert("sign.cave.1", "Beware of bats!");
+        localization.set_translator(|key| (key == "level.cave").then(|| "Cave".to_string()));
+
+        let localized = localization.localize_level(&level).unwrap();
+        assert_eq!(localized.field_instances[0], level.field_instances[0]);
+
+        let sign = &localized.layer_instances.unwrap()[0].entity_instances[0];
+        assert_eq!(
+            sign.field_instances[0].value,
+            FieldValue::String(Some("Beware of bats!".to_string()))
+        );
+        assert_eq!(
+            sign.field_instances[1].value,
+            FieldValue::String(Some("sign.cave.2".to_string()))
+        );
+        assert_eq!(
+            sign.field_instances[2].value,
+            FieldValue::String(Some("sign.cave.1".to_string()))
+        );
+    }
+}
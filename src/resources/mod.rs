@@ -1,5 +1,13 @@
 //! Resources and events used by the plugin.
 use bevy::prelude::*;
+use bevy_ecs_tilemap::{
+    map::{IsoCoordSystem, TilemapRenderSettings, TilemapType},
+    FrustumCulling,
+};
+use std::collections::{HashMap, HashSet};
+
+use crate::components::YSort;
+use crate::ldtk::{ldtk_fields::LdtkFields, EntityInstance, FieldValue};
 
 #[allow(unused_imports)]
 use crate::assets::LdtkProject;
@@ -7,10 +15,71 @@ use crate::assets::LdtkProject;
 use crate::components::LdtkWorldBundle;
 
 mod level_selection;
-pub use level_selection::LevelSelection;
+pub use level_selection::{InvalidLevelSelection, LevelSelection, LevelSelectionError};
 
 mod level_event;
-pub use level_event::LevelEvent;
+pub use level_event::{LevelEvent, LevelLifecycleEvent};
+
+pub(crate) mod level_transition;
+pub use level_transition::{
+    LevelTransition, LevelTransitionEvent, LevelTransitionQueue, TransitionPolicy,
+};
+
+mod world_respawn;
+pub use world_respawn::{RespawnWorld, RespawningWorld, WorldRespawnEvent};
+
+mod layer_variants;
+pub use layer_variants::LayerVariants;
+
+mod level_duplicates;
+pub use level_duplicates::{DuplicateLevel, LevelDuplicate, LevelDuplicates};
+
+mod entity_ref_resolver;
+pub use entity_ref_resolver::{EntityRefResolver, EntityRefTarget};
+
+mod error_policy;
+pub use error_policy::{LdtkError, LdtkErrorPolicy, LdtkErrorReporter};
+
+mod level_run_seed;
+pub use level_run_seed::LevelRunSeed;
+
+mod level_spawn_overrides;
+pub use level_spawn_overrides::{LevelSpawnOverride, LevelSpawnOverrides};
+
+mod localization;
+pub use localization::LdtkLocalization;
+
+mod level_variation;
+pub use level_variation::{LevelVariation, VariationRule};
+
+mod persistent_entity_state;
+pub use persistent_entity_state::{PersistentEntityState, CONSUMED_FLAG, DESTROYED_FLAG};
+
+mod tileset_skins;
+pub use tileset_skins::{TilesetSkinError, TilesetSkins};
+
+mod entity_despawn_event;
+pub(crate) use entity_despawn_event::TrackedLdtkEntities;
+pub use entity_despawn_event::{DespawnReason, LdtkEntityDespawned};
+
+mod entity_pool;
+pub(crate) use entity_pool::recycle_descendants;
+pub use entity_pool::LdtkEntityPool;
+
+mod tileset_prewarming;
+pub use tileset_prewarming::{PrewarmedTilesets, TilesetPrewarming};
+
+mod deferred_bundles;
+pub(crate) use deferred_bundles::DeferredEntityGroup;
+pub use deferred_bundles::{DeferredEntityBundle, DeferredIntCellBundle, DeferredLdtkBundles};
+
+mod entity_instance_groups;
+pub(crate) use entity_instance_groups::{
+    group_entity_instances, registration_or_default, EntityRegistrationKey,
+};
+pub use entity_instance_groups::{
+    EntityInstanceGroup, EntityInstanceGroups, LevelEntityInstanceGroups,
+};
 
 /// Option in [LdtkSettings] that determines clear color behavior.
 #[derive(Copy, Clone, Eq, PartialEq, Debug)]
@@ -40,14 +109,60 @@ pub enum LevelSpawnBehavior {
     /// Newly spawned levels will be spawned with translations like their location in the LDtk
     /// world.
     ///
-    /// Useful for "2d free map" and "GridVania" layouts.
+    /// Useful for "2d free map", "GridVania", and linear layouts.
     UseWorldTranslation {
         /// When used with the [LevelSelection] resource, levels in the `__level_neighbors` list of
         /// the selected level will be spawned in addition to the selected level.
+        ///
+        /// In "LinearHorizontal" and "LinearVertical" layouts, the levels before and after the
+        /// selected level in the world's level order are spawned as well.
         load_level_neighbors: bool,
     },
 }
 
+/// Option in [LdtkSettings] that determines which point of a level is placed at its transform.
+///
+/// Only applies with [LevelSpawnBehavior::UseZeroTranslation], since levels spawned with
+/// [LevelSpawnBehavior::UseWorldTranslation] need to line up with their neighbors.
+/// The level's contents are still laid out from its bottom left corner, so the level entity's
+/// transform is offset instead.
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_ecs_ldtk::prelude::*;
+/// // Center the room at the origin in a single-level game
+/// App::new().insert_resource(LdtkSettings {
+///     level_anchor: LevelAnchor::Center,
+///     ..default()
+/// });
+/// ```
+#[derive(Copy, Clone, PartialEq, Debug, Default)]
+pub enum LevelAnchor {
+    #[default]
+    BottomLeft,
+    Center,
+    TopLeft,
+    /// Anchor given as a fraction of the level size, with `(0, 0)` at the bottom left corner and
+    /// `(1, 1)` at the top right corner.
+    Custom(Vec2),
+}
+
+impl LevelAnchor {
+    /// The anchor as a fraction of the level size, with `(0, 0)` at the bottom left corner.
+    pub fn as_vec(&self) -> Vec2 {
+        match self {
+            LevelAnchor::BottomLeft => Vec2::ZERO,
+            LevelAnchor::Center => Vec2::splat(0.5),
+            LevelAnchor::TopLeft => Vec2::Y,
+            LevelAnchor::Custom(anchor) => *anchor,
+        }
+    }
+
+    /// Offset of the anchor from the bottom left corner of a level with the given pixel size.
+    pub fn offset(&self, level_size: Vec2) -> Vec2 {
+        self.as_vec() * level_size
+    }
+}
+
 /// Option in [LdtkSettings] that determines the visual representation of IntGrid layers when they don't have AutoTile rules.
 #[derive(Copy, Clone, Eq, PartialEq, Debug, Default)]
 pub enum IntGridRendering {
@@ -69,6 +184,522 @@ pub enum LevelBackground {
     Nonexistent,
 }
 
+/// Option in [LdtkSettings] that determines how level background images repeat.
+///
+/// Repeated images fill the view of the [LdtkParallaxCamera], so one must exist for them to
+/// repeat.
+///
+/// [LdtkParallaxCamera]: crate::prelude::LdtkParallaxCamera
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Default)]
+pub enum BackgroundRepeat {
+    /// The image is only drawn once, where it is placed in LDtk.
+    #[default]
+    None,
+    /// The image repeats horizontally.
+    X,
+    /// The image repeats vertically.
+    Y,
+    /// The image repeats both horizontally and vertically.
+    XY,
+}
+
+impl BackgroundRepeat {
+    /// Returns whether the image repeats horizontally and vertically, respectively.
+    pub fn axes(&self) -> BVec2 {
+        match self {
+            BackgroundRepeat::None => BVec2::FALSE,
+            BackgroundRepeat::X => BVec2::new(true, false),
+            BackgroundRepeat::Y => BVec2::new(false, true),
+            BackgroundRepeat::XY => BVec2::TRUE,
+        }
+    }
+}
+
+/// Option in [LdtkSettings] that determines how level background images are displayed, when
+/// [LevelBackground::Rendered].
+///
+/// By default, the image is drawn once, where it is placed in LDtk.
+/// Side-scrollers can instead make it a parallax layer, that repeats to fill the view:
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_ecs_ldtk::prelude::*;
+/// # fn f(app: &mut App) {
+/// app.insert_resource(LdtkSettings {
+///     background_image: BackgroundImageSettings {
+///         parallax_factor: Vec2::new(0.8, 0.),
+///         repeat: BackgroundRepeat::X,
+///         ..default()
+///     },
+///     ..default()
+/// });
+/// # }
+/// ```
+#[derive(Copy, Clone, PartialEq, Debug, Default)]
+pub struct BackgroundImageSettings {
+    /// Parallax factor of the image, with the same meaning as the parallax factors of layers.
+    ///
+    /// Zero disables parallax.
+    /// See [LayerParallax](crate::prelude::LayerParallax) for more details.
+    pub parallax_factor: Vec2,
+    /// Whether the image is also scaled according to its parallax factor.
+    pub parallax_scaling: bool,
+    pub repeat: BackgroundRepeat,
+}
+
+/// Option in [LdtkSettings] that determines whether LDtk entities are rendered like they appear in
+/// the editor.
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Default)]
+pub enum EntityEditorVisuals {
+    /// LDtk entities are only rendered if their registered bundle provides visuals.
+    #[default]
+    Hidden,
+    /// LDtk entities whose bundle doesn't provide a [`Sprite`] or [`TextureAtlasSprite`] are
+    /// rendered with their editor tile, or with a rectangle of their editor color.
+    ///
+    /// Useful for prototyping, so every placed entity is visible before it has real art.
+    /// The placeholder visual is spawned as a child with an [`EditorVisualPlaceholder`]
+    /// component.
+    ///
+    /// [`EditorVisualPlaceholder`]: crate::prelude::EditorVisualPlaceholder
+    Placeholder,
+}
+
+/// Option in [LdtkSettings] that determines whether levels outside of the view are hidden.
+///
+/// When enabled, the [Visibility] of each level entity is set to [Visibility::Hidden] while its
+/// bounds don't overlap the view of any active orthographic camera, and [Visibility::Inherited]
+/// otherwise.
+/// This is useful when many levels are spawned at once, like when loading level neighbors.
+/// Hidden levels are not despawned.
+///
+/// Since the plugin takes over the [Visibility] of level entities, you shouldn't set it yourself
+/// while this is enabled.
+#[derive(Copy, Clone, PartialEq, Debug, Default)]
+pub enum LevelCulling {
+    #[default]
+    Disabled,
+    Enabled {
+        /// Distance, in world units, that views are extended by before checking for overlap.
+        ///
+        /// Use this to show levels slightly before they enter the view.
+        margin: f32,
+    },
+}
+
+/// Option in [LdtkSettings] that spreads the spawning of levels over several frames.
+///
+/// Spawning many levels at once, like all the neighbors in a [LevelSet](crate::prelude::LevelSet)
+/// or a whole world, can cause a noticeable hitch.
+/// With [SpawnBudget::LevelsPerFrame], levels waiting to spawn are spawned a few at a time, nearest
+/// first.
+/// Distances are measured from every [SpawnFocus](crate::prelude::SpawnFocus), or from every
+/// active camera if there are none, so the visible levels appear first and the rest fill in over
+/// the following frames.
+///
+/// Levels are the unit of the budget: each level still spawns all of its layers and tiles in a
+/// single frame, and the layers within a level aren't prioritized by distance.
+/// Projects whose individual levels are too large to spawn in one frame should split them into
+/// smaller levels in LDtk.
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Default)]
+pub enum SpawnBudget {
+    #[default]
+    Unlimited,
+    /// The maximum number of levels spawned per frame, at least one.
+    LevelsPerFrame(usize),
+}
+
+/// How the registered [LdtkEntity](crate::prelude::LdtkEntity) and
+/// [LdtkIntCell](crate::prelude::LdtkIntCell) bundles are inserted, in [SpawnPhases].
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Default)]
+pub enum BundlePhase {
+    /// Bundles are inserted while the level spawns.
+    #[default]
+    Immediate,
+    /// Bundles are queued in [DeferredLdtkBundles], and inserted later by a system in
+    /// [SpawnPhaseSet::Bundles](crate::prelude::SpawnPhaseSet::Bundles).
+    Deferred,
+    /// Bundles are never inserted.
+    ///
+    /// This includes the default bundles, so entities and cells without a registration don't get
+    /// an [EntityInstance] or [IntGridCell](crate::prelude::IntGridCell) either.
+    Disabled,
+}
+
+/// Option in [LdtkSettings] that splits level spawning into phases, which can be turned off or
+/// deferred individually.
+///
+/// - The render phase spawns level backgrounds, editor visual placeholders, [IntGridTexture]s,
+///   and the lighting and text components of entities and cells.
+///   Without it, tilemaps are spawned hidden.
+/// - The logic phase inserts [IntGridCells] and tile metadata.
+/// - The bundle phase inserts the bundles registered for LDtk entities and IntGrid cells, which
+///   is usually where heavy components like physics colliders come from.
+///
+/// Layers, tiles, and entities are always spawned with the same transforms, so an authoritative
+/// server can turn off rendering while its clients spawn identical levels.
+/// Clients can defer the bundle phase to run it in a point of the frame they choose, by ordering
+/// [SpawnPhaseSet::Bundles](crate::prelude::SpawnPhaseSet::Bundles) or adding run conditions to
+/// it.
+///
+/// [IntGridTexture]: crate::prelude::IntGridTexture
+/// [IntGridCells]: crate::prelude::IntGridCells
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub struct SpawnPhases {
+    pub render: bool,
+    pub logic: bool,
+    pub bundles: BundlePhase,
+}
+
+impl Default for SpawnPhases {
+    fn default() -> Self {
+        SpawnPhases {
+            render: true,
+            logic: true,
+            bundles: BundlePhase::Immediate,
+        }
+    }
+}
+
+/// Option in [LdtkSettings] that makes level spawning reproducible, for rollback and replay.
+///
+/// When enabled, levels added to a [LevelSet](crate::prelude::LevelSet) at the same time spawn in
+/// the order of their iids, and spawned LDtk entities get a
+/// [StableEntityId](crate::prelude::StableEntityId).
+/// Everything spawned from a level is spawned in the order it appears in the level data, so the
+/// same [Entity] ids are allocated for the same levels, given that the rest of the app is
+/// deterministic too.
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Default)]
+pub enum DeterministicSpawning {
+    #[default]
+    Disabled,
+    Enabled,
+}
+
+/// Option in [LdtkSettings] that determines how the values of IntGrid cells are stored.
+///
+/// Spawning an entity for every nonzero cell of large levels creates a lot of entities that only
+/// exist to hold an [IntGridCell].
+/// With [IntGridCellStorage::Sparse], each IntGrid layer stores its values in a single
+/// [IntGridCells] component instead, and only cells whose value has an [LdtkIntCell] registration
+/// are spawned with their bundle.
+/// Registering bundles for specific layers and values with [LdtkIntCellAppExt] still works as
+/// usual.
+///
+/// IntGrid layers that are rendered, via a tileset or [IntGridRendering::Colorful], still spawn
+/// tile entities for every cell they draw, but these don't get an [IntGridCell] unless registered.
+///
+/// [IntGridCell]: crate::prelude::IntGridCell
+/// [IntGridCells]: crate::prelude::IntGridCells
+/// [LdtkIntCell]: crate::prelude::LdtkIntCell
+/// [LdtkIntCellAppExt]: crate::prelude::LdtkIntCellAppExt
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Default)]
+pub enum IntGridCellStorage {
+    /// Spawns every nonzero cell with an [IntGridCell](crate::prelude::IntGridCell), or its
+    /// registered bundle.
+    #[default]
+    Entities,
+    /// Stores cell values in an [IntGridCells](crate::prelude::IntGridCells) component on each
+    /// IntGrid layer, and only spawns cells with registered bundles.
+    Sparse,
+}
+
+/// Option in [LdtkSettings] that determines whether IntGrid layers get a data texture of their
+/// values.
+///
+/// With [IntGridTextures::Enabled], every IntGrid layer gets an
+/// [IntGridTexture](crate::prelude::IntGridTexture) component, holding an image with one texel
+/// per cell that shaders can sample.
+/// The image is created in a background task, so the component arrives shortly after the layer
+/// spawns.
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Default)]
+pub enum IntGridTextures {
+    /// IntGrid layers don't get data textures.
+    #[default]
+    Disabled,
+    /// Creates a data texture for every IntGrid layer.
+    Enabled,
+}
+
+/// Option in [LdtkSettings] that determines whether the entities of respawning levels are
+/// recycled.
+///
+/// Retry-heavy games may respawn the same level many times.
+/// With [EntityPooling::Enabled], the layers, tiles, and LDtk entities of respawning levels are
+/// emptied and kept in the [LdtkEntityPool], then reused when levels spawn, instead of being
+/// despawned and spawned again.
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Default)]
+pub enum EntityPooling {
+    /// Despawns the descendants of respawning levels.
+    #[default]
+    Disabled,
+    /// Recycles the descendants of respawning levels into the [LdtkEntityPool].
+    Enabled,
+}
+
+/// Option in [LdtkSettings] that determines where the [TileMetadata] and [TileEnumTags] of tiles
+/// are stored.
+///
+/// Inserting these components on every tile of metadata-heavy tilesets can be slow and use a lot
+/// of memory.
+/// With [TileMetadataStorage::LayerTables], each tilemap stores the data of its tiles in a single
+/// [TileDataTable] instead.
+/// [LayerTileData] can look tile data up with either option.
+///
+/// [TileMetadata]: crate::prelude::TileMetadata
+/// [TileEnumTags]: crate::prelude::TileEnumTags
+/// [TileDataTable]: crate::prelude::TileDataTable
+/// [LayerTileData]: crate::prelude::LayerTileData
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Default)]
+pub enum TileMetadataStorage {
+    /// Inserts the data as components on every tile.
+    #[default]
+    Components,
+    /// Stores the data in a [TileDataTable](crate::prelude::TileDataTable) on each tilemap.
+    LayerTables,
+}
+
+/// Option in [LdtkSettings] naming the reserved entity field that overrides the z of LDtk entities.
+///
+/// When an LDtk entity has an `Int` or `Float` field with this identifier, and its value isn't
+/// null, the value is used as the entity's z within its layer.
+/// This lets designers fix the overlap order of props in the editor.
+/// Entities with a z index aren't affected by [YSort].
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct EntityZIndex {
+    /// Identifier of the field, `"z_index"` by default.
+    pub field_identifier: String,
+}
+
+impl Default for EntityZIndex {
+    fn default() -> Self {
+        EntityZIndex {
+            field_identifier: "z_index".to_string(),
+        }
+    }
+}
+
+impl EntityZIndex {
+    /// Returns the z index of the given entity, if it has one.
+    pub fn z_index(&self, entity_instance: &EntityInstance) -> Option<f32> {
+        match entity_instance.get_field(&self.field_identifier) {
+            Ok(FieldValue::Int(Some(z_index))) => Some(*z_index as f32),
+            Ok(FieldValue::Float(Some(z_index))) => Some(*z_index),
+            _ => None,
+        }
+    }
+}
+
+/// Option in [LdtkSettings] naming the reserved entity tag that makes LDtk entities [Worldly].
+///
+/// Entities whose definition has this tag get a [Worldly] component as they spawn, like those
+/// spawned with a `#[worldly]` bundle.
+/// This lets designers promote an entity to persist across levels from the editor, without
+/// changing its bundle.
+///
+/// [Worldly]: crate::prelude::Worldly
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct WorldlyTag {
+    /// The tag, `"global"` by default.
+    pub tag: String,
+}
+
+impl Default for WorldlyTag {
+    fn default() -> Self {
+        WorldlyTag {
+            tag: "global".to_string(),
+        }
+    }
+}
+
+impl WorldlyTag {
+    /// Returns true if the given entity has the tag.
+    pub fn is_worldly(&self, entity_instance: &EntityInstance) -> bool {
+        entity_instance.tags.iter().any(|tag| *tag == self.tag)
+    }
+}
+
+/// Option in [LdtkSettings] that determines the z values of the background and layers of a level,
+/// relative to the level.
+///
+/// Everything in a level is spawned in order, from the background up to the top layer.
+/// The first item is spawned at `base`, and each following item is spawned further ahead by the
+/// offset of the item before it.
+/// Knowing these values lets you interleave your own rendering between LDtk layers.
+///
+/// The defaults spawn the background color at z 0, the background image at z 1, and layers one
+/// z apart after that.
+///
+/// Depth rules these settings can't express can be implemented with a
+/// [ZOrderingStrategy](crate::app::ZOrderingStrategy).
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct ZSpacing {
+    /// Z of the first item spawned in a level.
+    pub base: f32,
+    /// Z difference between consecutive layers, including overlapping tile sublayers.
+    pub layer_increment: f32,
+    /// Z difference between the background color, the background image, and the first layer.
+    ///
+    /// Only applies if [LevelBackground::Rendered].
+    pub background_offset: f32,
+}
+
+impl Default for ZSpacing {
+    fn default() -> Self {
+        ZSpacing {
+            base: 0.,
+            layer_increment: 1.,
+            background_offset: 1.,
+        }
+    }
+}
+
+/// Option in [LdtkSettings] for tuning the `bevy_ecs_tilemap` tilemaps spawned for IntGrid,
+/// AutoTile, and Tile layers.
+///
+/// The best values depend on the layer.
+/// For example, a huge, sparse decoration layer may benefit from larger chunks than a small, dense
+/// collision layer.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub struct TilemapSettings {
+    /// Size of the chunks the tilemap is rendered in, in tiles.
+    pub render_chunk_size: UVec2,
+    /// Whether or not the chunks of the tilemap are y-sorted when rendering.
+    pub render_y_sort: bool,
+    /// Whether or not chunks outside the view are culled.
+    pub frustum_culling: bool,
+}
+
+impl Default for TilemapSettings {
+    fn default() -> Self {
+        let render_settings = TilemapRenderSettings::default();
+
+        TilemapSettings {
+            render_chunk_size: render_settings.render_chunk_size,
+            render_y_sort: render_settings.y_sort,
+            frustum_culling: FrustumCulling::default().0,
+        }
+    }
+}
+
+impl TilemapSettings {
+    /// Converts these settings into the equivalent `bevy_ecs_tilemap` components.
+    pub fn to_components(&self) -> (TilemapRenderSettings, FrustumCulling) {
+        (
+            TilemapRenderSettings {
+                render_chunk_size: self.render_chunk_size,
+                y_sort: self.render_y_sort,
+            },
+            FrustumCulling(self.frustum_culling),
+        )
+    }
+}
+
+/// Option in [LdtkSettings] that determines the shape of the grid that IntGrid, AutoTile, and Tile
+/// layers are spawned in.
+///
+/// LDtk only supports square grids, but projects can still fake other grids by authoring a square
+/// grid and projecting it.
+/// With a non-square shape, each LDtk cell is spawned as a 2:1 diamond that is as wide as the
+/// layer's grid size, and the tilemaps use the matching `bevy_ecs_tilemap` [TilemapType].
+///
+/// Use [GridShape::cell_size] and the `_with_shape` functions in [crate::utils] to convert between
+/// [GridCoords] and translations on these grids.
+/// Entity layers are still spawned on the square LDtk grid.
+///
+/// [GridCoords]: crate::prelude::GridCoords
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Default)]
+pub enum GridShape {
+    #[default]
+    Square,
+    /// Cells are laid out in a diamond, with the x and y axes running diagonally up and to the
+    /// right, and diagonally up and to the left respectively.
+    IsometricDiamond,
+    /// Cells are laid out in rows, with each row offset by half a cell from the row below it.
+    ///
+    /// Follows `bevy_ecs_tilemap`'s staggered isometric coordinate system.
+    IsometricStaggered,
+}
+
+impl GridShape {
+    /// The `bevy_ecs_tilemap` [TilemapType] for this shape.
+    pub fn tilemap_type(&self) -> TilemapType {
+        match self {
+            GridShape::Square => TilemapType::Square,
+            GridShape::IsometricDiamond => TilemapType::Isometric(IsoCoordSystem::Diamond),
+            GridShape::IsometricStaggered => TilemapType::Isometric(IsoCoordSystem::Staggered),
+        }
+    }
+
+    /// Size of a cell in world space for a layer with the given LDtk grid size.
+    pub fn cell_size(&self, grid_size: i32) -> IVec2 {
+        match self {
+            GridShape::Square => IVec2::splat(grid_size),
+            GridShape::IsometricDiamond | GridShape::IsometricStaggered => {
+                IVec2::new(grid_size, grid_size / 2)
+            }
+        }
+    }
+}
+
+/// Option in [LdtkSettings] that determines how layers are separated along the z axis.
+#[derive(Clone, PartialEq, Debug, Default)]
+pub enum LayerPlacement {
+    /// Layers are separated according to [ZSpacing], which only determines the order they are
+    /// drawn in with an orthographic camera.
+    #[default]
+    PainterOrder,
+    /// Layers are separated by a real distance, for use with a perspective camera in 2.5D games.
+    ///
+    /// The playfield layer is placed at z 0, layers below it are placed further away, and layers
+    /// above it are placed closer to the camera.
+    /// The level background is placed one layer below the bottom layer.
+    /// [ZSpacing] still applies within a layer, like between overlapping tiles.
+    ///
+    /// To work in world units other than pixels, scale your [LdtkWorldBundle] by
+    /// `1. / pixels_per_unit`.
+    Depth {
+        /// Distance between consecutive layers, in world units.
+        layer_distance: f32,
+        /// Number of LDtk pixels per world unit.
+        pixels_per_unit: f32,
+        /// Identifier of the layer placed at z 0.
+        ///
+        /// If [None], or no layer has this identifier, the bottom layer is used.
+        playfield_layer: Option<String>,
+    },
+}
+
+impl LayerPlacement {
+    /// Index of the playfield layer among the given layers, ordered from bottom to top.
+    pub(crate) fn playfield_index(&self, layers: &[&crate::ldtk::LayerInstance]) -> usize {
+        match self {
+            LayerPlacement::Depth {
+                playfield_layer: Some(playfield_layer),
+                ..
+            } => layers
+                .iter()
+                .position(|layer| layer.identifier == *playfield_layer)
+                .unwrap_or(0),
+            _ => 0,
+        }
+    }
+
+    /// Calculates the z of an item in a layer, relative to the level.
+    ///
+    /// `painter_z` is the z given by [ZSpacing], `layer_start_z` is the [ZSpacing] z of the start
+    /// of the item's layer, and `depth` is the layer's index relative to the playfield layer.
+    pub(crate) fn z(&self, painter_z: f32, layer_start_z: f32, depth: f32) -> f32 {
+        match self {
+            LayerPlacement::PainterOrder => painter_z,
+            LayerPlacement::Depth {
+                layer_distance,
+                pixels_per_unit,
+                ..
+            } => depth * layer_distance * pixels_per_unit + (painter_z - layer_start_z),
+        }
+    }
+}
+
 /// Specifies data that should be ignored completely when spawning levels. Excluded items will still
 /// be present in the [`LdtkProject`] but will not cause any entities to be spawned in the world.
 #[derive(Clone, Eq, PartialEq, Debug, Default)]
@@ -79,11 +710,156 @@ pub struct SpawnExclusions {
 
 /// Settings resource for the plugin.
 /// Check out the documentation for each field type to learn more.
-#[derive(Clone, Eq, PartialEq, Debug, Default, Resource)]
+#[derive(Clone, PartialEq, Debug, Default, Resource)]
 pub struct LdtkSettings {
     pub level_spawn_behavior: LevelSpawnBehavior,
+    pub level_anchor: LevelAnchor,
     pub set_clear_color: SetClearColor,
     pub int_grid_rendering: IntGridRendering,
     pub level_background: LevelBackground,
+    pub background_image: BackgroundImageSettings,
     pub exclusions: SpawnExclusions,
+    pub entity_editor_visuals: EntityEditorVisuals,
+    /// Layer identifiers mapped to the [YSort] settings used for them.
+    pub y_sort: HashMap<String, YSort>,
+    pub entity_z_index: EntityZIndex,
+    pub worldly_tag: WorldlyTag,
+    pub z_spacing: ZSpacing,
+    pub layer_placement: LayerPlacement,
+    pub grid_shape: GridShape,
+    /// Tilemap settings used for layers that don't have an entry in `layer_tilemap_settings`.
+    pub tilemap_settings: TilemapSettings,
+    /// Layer identifiers mapped to the [TilemapSettings] used for them.
+    pub layer_tilemap_settings: HashMap<String, TilemapSettings>,
+    /// Identifiers of the level fields read into [LevelPostProcessing].
+    ///
+    /// [LevelPostProcessing]: crate::prelude::LevelPostProcessing
+    pub post_processing_fields: HashSet<String>,
+    /// Tile enum tags whose tiles are spawned in their own tilemaps on Tile and AutoTile layers,
+    /// so they can be rendered with a different material.
+    ///
+    /// See [MaterialEnumTag] and `LdtkTilemapMaterialAppExt::register_ldtk_enum_tag_material`.
+    ///
+    /// [MaterialEnumTag]: crate::prelude::MaterialEnumTag
+    pub material_enum_tags: HashSet<String>,
+    /// Tile enum tags whose tiles are spawned in their own tilemaps on Tile and AutoTile layers,
+    /// mapped to the z offset of these tilemaps from the rest of their layer.
+    ///
+    /// Lets designers keep one authoring layer, while tiles tagged "Foreground", for example,
+    /// render above the level's entities.
+    /// The tilemaps get a [MaterialEnumTag] like those split off for `material_enum_tags`.
+    ///
+    /// [MaterialEnumTag]: crate::prelude::MaterialEnumTag
+    pub enum_tag_z_offsets: HashMap<String, f32>,
+    pub level_culling: LevelCulling,
+    /// [LevelReveal] inserted on every newly spawned level, if any.
+    ///
+    /// [LevelReveal]: crate::prelude::LevelReveal
+    pub level_reveal: Option<crate::components::LevelReveal>,
+    pub level_variation: LevelVariation,
+    pub deterministic_spawning: DeterministicSpawning,
+    pub tile_metadata_storage: TileMetadataStorage,
+    pub int_grid_cell_storage: IntGridCellStorage,
+    pub int_grid_textures: IntGridTextures,
+    pub entity_pooling: EntityPooling,
+    pub tileset_prewarming: TilesetPrewarming,
+    pub invalid_level_selection: InvalidLevelSelection,
+    /// Overlay layers hidden while an [InteriorRevealer] is inside their trigger regions.
+    ///
+    /// [InteriorRevealer]: crate::interior::InteriorRevealer
+    pub interior_reveals: Vec<crate::interior::InteriorReveal>,
+    pub spawn_budget: SpawnBudget,
+    pub entity_culling: crate::entity_culling::EntityCulling,
+    pub spawn_phases: SpawnPhases,
+    #[cfg(feature = "lighting")]
+    pub lighting: crate::lighting::LdtkLightingSettings,
+    #[cfg(feature = "physics")]
+    pub physics: crate::physics::LdtkPhysicsSettings,
+    #[cfg(feature = "text")]
+    pub text: crate::text::LdtkTextSettings,
+    #[cfg(feature = "bevy_audio")]
+    pub audio: crate::audio::LdtkAudioSettings,
+}
+
+impl LdtkSettings {
+    /// Returns the [TilemapSettings] used for layers with the given identifier.
+    pub fn tilemap_settings_for(&self, layer_identifier: &str) -> TilemapSettings {
+        self.layer_tilemap_settings
+            .get(layer_identifier)
+            .copied()
+            .unwrap_or(self.tilemap_settings)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ldtk::ldtk_fields::field_instance_from_value;
+
+    #[test]
+    fn level_anchor_offsets() {
+        let level_size = Vec2::new(320., 180.);
+
+        assert_eq!(LevelAnchor::BottomLeft.offset(level_size), Vec2::ZERO);
+        assert_eq!(LevelAnchor::Center.offset(level_size), Vec2::new(160., 90.));
+        assert_eq!(LevelAnchor::TopLeft.offset(level_size), Vec2::new(0., 180.));
+        assert_eq!(
+            LevelAnchor::Custom(Vec2::new(0.25, 1.)).offset(level_size),
+            Vec2::new(80., 180.)
+        );
+    }
+
+    #[test]
+    fn entity_z_index_is_read_from_numeric_fields() {
+        let entity_with_z_index = |value| EntityInstance {
+            field_instances: vec![field_instance_from_value("z_index", value)],
+            ..Default::default()
+        };
+
+        let entity_z_index = EntityZIndex::default();
+
+        assert_eq!(
+            entity_z_index.z_index(&entity_with_z_index(FieldValue::Int(Some(3)))),
+            Some(3.)
+        );
+        assert_eq!(
+            entity_z_index.z_index(&entity_with_z_index(FieldValue::Float(Some(0.5)))),
+            Some(0.5)
+        );
+        assert_eq!(
+            entity_z_index.z_index(&entity_with_z_index(FieldValue::Int(None))),
+            None
+        );
+        assert_eq!(entity_z_index.z_index(&EntityInstance::default()), None);
+    }
+
+    #[test]
+    fn worldly_tag_matches_entity_tags() {
+        let worldly_tag = WorldlyTag::default();
+
+        let entity_with_tags = |tags: &[&str]| EntityInstance {
+            tags: tags.iter().map(|tag| tag.to_string()).collect(),
+            ..Default::default()
+        };
+
+        assert!(worldly_tag.is_worldly(&entity_with_tags(&["enemy", "global"])));
+        assert!(!worldly_tag.is_worldly(&entity_with_tags(&["enemy"])));
+        assert!(!worldly_tag.is_worldly(&EntityInstance::default()));
+    }
+
+    #[test]
+    fn depth_placement_separates_layers_around_playfield() {
+        let placement = LayerPlacement::Depth {
+            layer_distance: 2.,
+            pixels_per_unit: 16.,
+            playfield_layer: None,
+        };
+
+        assert_eq!(placement.z(5., 5., 0.), 0.);
+        assert_eq!(placement.z(6., 5., 0.), 1.);
+        assert_eq!(placement.z(7., 7., 1.), 32.);
+        assert_eq!(placement.z(3., 3., -2.), -64.);
+
+        assert_eq!(LayerPlacement::PainterOrder.z(6., 5., 3.), 6.);
+    }
 }
@@ -0,0 +1,147 @@
+use crate::components::EntityIid;
+use bevy::prelude::*;
+use std::collections::{HashMap, HashSet};
+
+/// Flag set by [PersistentEntityState::consume].
+pub const CONSUMED_FLAG: &str = "consumed";
+
+/// Flag set by [PersistentEntityState::destroy].
+pub const DESTROYED_FLAG: &str = "destroyed";
+
+/// [Resource] recording flags for LDtk entities that outlive the levels they spawn in.
+///
+/// Whenever a level spawns, its entities are checked against this resource:
+/// - Entities with any of the [skip_flags](PersistentEntityState::skip_flags) are not spawned.
+/// By default, these are [CONSUMED_FLAG] and [DESTROYED_FLAG].
+/// - Other entities with flags spawn with an [EntityStateFlags] component, so your systems can
+/// adjust them, like opening a chest that has already been looted.
+///
+/// Spawned levels aren't affected by changes to this resource until they respawn.
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_ecs_ldtk::prelude::*;
+/// # #[derive(Component)]
+/// # struct Coin;
+/// fn collect_coins(
+///     mut commands: Commands,
+///     mut persistent_entity_state: ResMut<PersistentEntityState>,
+///     coins: Query<(Entity, &EntityIid), With<Coin>>,
+/// ) {
+///     for (entity, entity_iid) in &coins {
+///         // ... if the player touches the coin
+///         persistent_entity_state.consume(entity_iid.clone());
+///         commands.entity(entity).despawn_recursive();
+///     }
+/// }
+/// ```
+///
+/// [EntityStateFlags]: crate::prelude::EntityStateFlags
+#[derive(Clone, Eq, PartialEq, Debug, Resource)]
+pub struct PersistentEntityState {
+    flags: HashMap<EntityIid, HashSet<String>>,
+    /// Flags that prevent an entity from spawning.
+    pub skip_flags: HashSet<String>,
+}
+
+impl Default for PersistentEntityState {
+    fn default() -> Self {
+        PersistentEntityState {
+            flags: HashMap::default(),
+            skip_flags: [CONSUMED_FLAG, DESTROYED_FLAG]
+                .into_iter()
+                .map(str::to_string)
+                .collect(),
+        }
+    }
+}
+
+impl PersistentEntityState {
+    /// Records a flag for the entity with the given iid.
+    pub fn set(&mut self, entity_iid: impl Into<EntityIid>, flag: impl Into<String>) {
+        self.flags
+            .entry(entity_iid.into())
+            .or_default()
+            .insert(flag.into());
+    }
+
+    /// Removes a flag from the entity with the given iid.
+    ///
+    /// Returns whether the flag was set.
+    pub fn unset(&mut self, entity_iid: &EntityIid, flag: &str) -> bool {
+        let Some(flags) = self.flags.get_mut(entity_iid) else {
+            return false;
+        };
+
+        let removed = flags.remove(flag);
+
+        if flags.is_empty() {
+            self.flags.remove(entity_iid);
+        }
+
+        removed
+    }
+
+    /// Returns whether the entity with the given iid has a flag.
+    pub fn has(&self, entity_iid: &EntityIid, flag: &str) -> bool {
+        self.flags
+            .get(entity_iid)
+            .map_or(false, |flags| flags.contains(flag))
+    }
+
+    /// Returns all flags of the entity with the given iid.
+    pub fn flags(&self, entity_iid: &EntityIid) -> Option<&HashSet<String>> {
+        self.flags.get(entity_iid)
+    }
+
+    /// Removes all flags of the entity with the given iid, so it spawns like it did originally.
+    pub fn clear(&mut self, entity_iid: &EntityIid) {
+        self.flags.remove(entity_iid);
+    }
+
+    /// Marks the entity with the given iid as consumed, like a collected coin.
+    pub fn consume(&mut self, entity_iid: impl Into<EntityIid>) {
+        self.set(entity_iid, CONSUMED_FLAG);
+    }
+
+    /// Marks the entity with the given iid as destroyed, like a broken wall.
+    pub fn destroy(&mut self, entity_iid: impl Into<EntityIid>) {
+        self.set(entity_iid, DESTROYED_FLAG);
+    }
+
+    /// Returns whether the entity with the given iid should be skipped when its level spawns.
+    pub fn should_skip(&self, entity_iid: &EntityIid) -> bool {
+        self.flags
+            .get(entity_iid)
+            .map_or(false, |flags| !flags.is_disjoint(&self.skip_flags))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn skip_flags_prevent_spawning() {
+        let mut state = PersistentEntityState::default();
+        let coin = EntityIid::new("coin");
+        let chest = EntityIid::new("chest");
+
+        state.consume(coin.clone());
+        state.set(chest.clone(), "opened");
+
+        assert!(state.should_skip(&coin));
+        assert!(!state.should_skip(&chest));
+        assert!(state.has(&chest, "opened"));
+
+        state.skip_flags.insert("opened".to_string());
+        assert!(state.should_skip(&chest));
+
+        assert!(state.unset(&chest, "opened"));
+        assert!(!state.unset(&chest, "opened"));
+        assert_eq!(state.flags(&chest), None);
+
+        state.clear(&coin);
+        assert!(!state.should_skip(&coin));
+    }
+}
@@ -0,0 +1,99 @@
+use bevy::{asset::AssetPath, prelude::*};
+use std::collections::{HashMap, HashSet, VecDeque};
+
+/// Option in [LdtkSettings] that determines whether on-demand tilesets are loaded before their
+/// levels spawn.
+///
+/// Projects loaded with [LdtkLoaderSettings::tilesets_on_demand] only load a tileset when a level
+/// using it spawns, and large tileset images decoded and uploaded to the GPU all at once can
+/// cause hitches.
+/// With [TilesetPrewarming::Enabled], the tilesets of the levels in a [LevelSet], and of their
+/// neighbors, start loading ahead of time, a few per update.
+/// The loaded tilesets are kept in the [PrewarmedTilesets] resource until their levels aren't
+/// selected anymore.
+///
+/// [LdtkSettings]: crate::prelude::LdtkSettings
+/// [LdtkLoaderSettings::tilesets_on_demand]: crate::assets::LdtkLoaderSettings::tilesets_on_demand
+/// [LevelSet]: crate::prelude::LevelSet
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Default)]
+pub enum TilesetPrewarming {
+    #[default]
+    Disabled,
+    Enabled {
+        /// How many tilesets start loading per update, at most.
+        tilesets_per_update: usize,
+    },
+}
+
+/// [Resource] holding the tileset images loaded ahead of time by [TilesetPrewarming].
+#[derive(Clone, Debug, Default, Resource)]
+pub struct PrewarmedTilesets {
+    handles: HashMap<AssetPath<'static>, Handle<Image>>,
+    queue: VecDeque<AssetPath<'static>>,
+}
+
+impl PrewarmedTilesets {
+    /// Strong handles to the tileset images that have started loading.
+    pub fn handles(&self) -> impl Iterator<Item = &Handle<Image>> {
+        self.handles.values()
+    }
+
+    /// Releases the tilesets that aren't wanted anymore, queues the new ones, and starts loading
+    /// up to `tilesets_per_update` of the queued ones with `load`.
+    pub(crate) fn update(
+        &mut self,
+        wanted: Vec<AssetPath<'static>>,
+        tilesets_per_update: usize,
+        mut load: impl FnMut(&AssetPath<'static>) -> Handle<Image>,
+    ) {
+        let wanted_set: HashSet<&AssetPath<'static>> = wanted.iter().collect();
+
+        self.handles
+            .retain(|asset_path, _| wanted_set.contains(asset_path));
+        self.queue
+            .retain(|asset_path| wanted_set.contains(asset_path));
+
+        for asset_path in wanted.iter() {
+            if !self.handles.contains_key(asset_path) && !self.queue.contains(asset_path) {
+                self.queue.push_back(asset_path.clone());
+            }
+        }
+
+        for _ in 0..tilesets_per_update {
+            let Some(asset_path) = self.queue.pop_front() else {
+                break;
+            };
+
+            let handle = load(&asset_path);
+            self.handles.insert(asset_path, handle);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tilesets_load_staggered_and_release_when_unwanted() {
+        let [a, b, c]: [AssetPath<'static>; 3] =
+            ["a.png", "b.png", "c.png"].map(|path| AssetPath::from(path).to_owned());
+
+        let mut prewarmed = PrewarmedTilesets::default();
+        let mut loaded = Vec::new();
+        let mut load = |asset_path: &AssetPath<'static>| {
+            loaded.push(asset_path.clone());
+            Handle::default()
+        };
+
+        prewarmed.update(vec![a.clone(), b.clone(), c.clone()], 2, &mut load);
+        prewarmed.update(vec![b.clone(), c.clone()], 2, &mut load);
+        prewarmed.update(vec![b.clone(), c.clone()], 2, &mut load);
+
+        assert_eq!(loaded, vec![a, b.clone(), c.clone()]);
+        assert_eq!(
+            prewarmed.handles.keys().collect::<HashSet<_>>(),
+            HashSet::from([&b, &c])
+        );
+    }
+}
@@ -0,0 +1,122 @@
+use crate::ldtk::TilesetDefinition;
+use bevy::prelude::*;
+use std::collections::HashMap;
+use thiserror::Error;
+
+/// Errors that can occur when validating a tileset skin.
+#[derive(Debug, PartialEq, Eq, Error)]
+pub enum TilesetSkinError {
+    /// The skin's dimensions differ from the tileset's.
+    #[error("skin for tileset {uid} is {actual:?} pixels, but the tileset is {expected:?} pixels")]
+    DimensionMismatch {
+        uid: i32,
+        expected: UVec2,
+        actual: UVec2,
+    },
+}
+
+/// [Resource] for swapping the images of tilesets at runtime, like for winter/summer variants of
+/// the same tileset.
+///
+/// Every spawned tilemap using a skinned tileset is updated in place, and tilemaps spawned later
+/// use the skin as well.
+/// Skins must have the exact same dimensions as the tileset they replace, otherwise they are
+/// ignored with a warning.
+/// Skins that haven't finished loading are applied once they do.
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_ecs_ldtk::prelude::*;
+/// fn enter_winter(mut skins: ResMut<TilesetSkins>, asset_server: Res<AssetServer>) {
+///     skins.set(1, asset_server.load("tilesets/winter.png"));
+/// }
+///
+/// fn leave_winter(mut skins: ResMut<TilesetSkins>) {
+///     skins.clear(1);
+/// }
+/// ```
+#[derive(Clone, Eq, PartialEq, Debug, Default, Resource)]
+pub struct TilesetSkins {
+    skins: HashMap<i32, Handle<Image>>,
+}
+
+impl TilesetSkins {
+    /// Uses the given image for the tileset with the given uid.
+    pub fn set(&mut self, tileset_uid: i32, image: Handle<Image>) {
+        self.skins.insert(tileset_uid, image);
+    }
+
+    /// Restores the original image of the tileset with the given uid.
+    pub fn clear(&mut self, tileset_uid: i32) {
+        self.skins.remove(&tileset_uid);
+    }
+
+    /// Returns the skin used for the tileset with the given uid, if any.
+    pub fn get(&self, tileset_uid: i32) -> Option<&Handle<Image>> {
+        self.skins.get(&tileset_uid)
+    }
+
+    /// Checks that an image can be used as a skin for the given tileset.
+    pub fn validate(
+        image: &Image,
+        tileset_definition: &TilesetDefinition,
+    ) -> Result<(), TilesetSkinError> {
+        let expected = UVec2::new(
+            tileset_definition.px_wid as u32,
+            tileset_definition.px_hei as u32,
+        );
+        let actual = UVec2::new(
+            image.texture_descriptor.size.width,
+            image.texture_descriptor.size.height,
+        );
+
+        if expected == actual {
+            Ok(())
+        } else {
+            Err(TilesetSkinError::DimensionMismatch {
+                uid: tileset_definition.uid,
+                expected,
+                actual,
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::render::render_resource::Extent3d;
+
+    use super::*;
+
+    #[test]
+    fn skins_must_match_tileset_dimensions() {
+        let tileset_definition = TilesetDefinition {
+            uid: 7,
+            px_wid: 64,
+            px_hei: 32,
+            ..Default::default()
+        };
+
+        let mut image = Image::default();
+
+        image.resize(Extent3d {
+            width: 64,
+            height: 32,
+            depth_or_array_layers: 1,
+        });
+        assert_eq!(TilesetSkins::validate(&image, &tileset_definition), Ok(()));
+
+        image.resize(Extent3d {
+            width: 32,
+            height: 32,
+            depth_or_array_layers: 1,
+        });
+        assert_eq!(
+            TilesetSkins::validate(&image, &tileset_definition),
+            Err(TilesetSkinError::DimensionMismatch {
+                uid: 7,
+                expected: UVec2::new(64, 32),
+                actual: UVec2::new(32, 32),
+            })
+        );
+    }
+}
@@ -0,0 +1,214 @@
+use crate::{
+    assets::LdtkProjectHandle,
+    components::{LevelIid, LevelSet, Worldly},
+    resources::{
+        recycle_descendants, EntityPooling, LdtkSettings, LevelEvent, LevelTransitionQueue,
+    },
+};
+use bevy::{ecs::system::Command, prelude::*};
+use std::collections::HashSet;
+
+/// [Command] that despawns every level of an [LdtkWorldBundle] and spawns them again.
+///
+/// Useful for "reset run" functionality, where the whole world needs to start from scratch.
+/// Compared to inserting [Respawn] on the world, the respawn is carried out immediately, and its
+/// effects are clearly defined:
+/// - The levels in the world's [LevelSet] when the command is applied are the ones that respawn.
+/// The [LevelSet] and [LevelSelection] themselves are left unchanged.
+/// - [Worldly] entities are despawned and spawned again from the level data, unless
+/// [RespawnWorld::keeping_worldly] is used.
+/// Kept [Worldly] entities aren't spawned a second time.
+/// - Transitions waiting in the [LevelTransitionQueue] are dropped, unless
+/// [RespawnWorld::keeping_transitions] is used.
+/// The transition in progress, if any, still completes.
+///
+/// [WorldRespawnEvent]s are sent when the respawn starts and when all of its levels have spawned
+/// again:
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_ecs_ldtk::prelude::*;
+/// fn reset_run(mut commands: Commands, world_query: Query<Entity, With<LdtkProjectHandle>>) {
+///     for ldtk_world in world_query.iter() {
+///         commands.add(RespawnWorld::new(ldtk_world));
+///     }
+/// }
+///
+/// fn on_respawn(mut respawn_events: EventReader<WorldRespawnEvent>) {
+///     for event in respawn_events.iter() {
+///         match event {
+///             WorldRespawnEvent::Started(_) => info!("fade out"),
+///             WorldRespawnEvent::Completed(_) => info!("fade in"),
+///         }
+///     }
+/// }
+/// ```
+///
+/// [Command]: https://docs.rs/bevy/latest/bevy/ecs/system/trait.Command.html
+/// [LdtkWorldBundle]: crate::prelude::LdtkWorldBundle
+/// [Respawn]: crate::prelude::Respawn
+/// [LevelSelection]: crate::prelude::LevelSelection
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub struct RespawnWorld {
+    pub ldtk_world: Entity,
+    pub keep_worldly: bool,
+    pub keep_transitions: bool,
+}
+
+impl RespawnWorld {
+    /// Construct a [RespawnWorld] for the given world entity, that respawns [Worldly] entities and
+    /// drops pending transitions.
+    pub fn new(ldtk_world: Entity) -> Self {
+        RespawnWorld {
+            ldtk_world,
+            keep_worldly: false,
+            keep_transitions: false,
+        }
+    }
+
+    /// Keeps the world's [Worldly] entities instead of respawning them.
+    pub fn keeping_worldly(mut self) -> Self {
+        self.keep_worldly = true;
+        self
+    }
+
+    /// Keeps the transitions waiting in the [LevelTransitionQueue].
+    pub fn keeping_transitions(mut self) -> Self {
+        self.keep_transitions = true;
+        self
+    }
+}
+
+impl Command for RespawnWorld {
+    fn apply(self, world: &mut World) {
+        let Some(level_set) = world
+            .get_entity(self.ldtk_world)
+            .filter(|entity| entity.contains::<LdtkProjectHandle>())
+            .and_then(|entity| entity.get::<LevelSet>())
+            .cloned()
+        else {
+            return;
+        };
+
+        if !self.keep_transitions {
+            if let Some(mut transition_queue) = world.get_resource_mut::<LevelTransitionQueue>() {
+                transition_queue.clear_pending();
+            }
+        }
+
+        if let Some(mut respawn_events) = world.get_resource_mut::<Events<WorldRespawnEvent>>() {
+            respawn_events.send(WorldRespawnEvent::Started(self.ldtk_world));
+        }
+
+        let children: Vec<Entity> = world
+            .get::<Children>(self.ldtk_world)
+            .map(|children| children.to_vec())
+            .unwrap_or_default();
+
+        let entity_pooling = world
+            .get_resource::<LdtkSettings>()
+            .map(|ldtk_settings| ldtk_settings.entity_pooling);
+
+        for child in children {
+            if let Some(level_iid) = world.get::<LevelIid>(child).cloned() {
+                if entity_pooling == Some(EntityPooling::Enabled) {
+                    recycle_descendants(world, child);
+                }
+
+                world.entity_mut(child).despawn_recursive();
+
+                if let Some(mut level_events) = world.get_resource_mut::<Events<LevelEvent>>() {
+                    level_events.send(LevelEvent::Despawned(level_iid));
+                }
+            } else if !self.keep_worldly && world.get::<Worldly>(child).is_some() {
+                world.entity_mut(child).despawn_recursive();
+            }
+        }
+
+        world.entity_mut(self.ldtk_world).insert(RespawningWorld {
+            remaining: level_set.iids,
+        });
+    }
+}
+
+/// Events fired while a [RespawnWorld] command is carried out.
+///
+/// Each variant stores the world entity.
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Event)]
+pub enum WorldRespawnEvent {
+    /// The world's levels have been despawned.
+    Started(Entity),
+    /// All levels that were despawned have spawned again.
+    Completed(Entity),
+}
+
+/// [Component] on world entities that are being respawned by a [RespawnWorld] command.
+///
+/// Removed once the respawn completes.
+#[derive(Clone, Eq, PartialEq, Debug, Default, Component)]
+pub struct RespawningWorld {
+    remaining: HashSet<LevelIid>,
+}
+
+impl RespawningWorld {
+    /// The levels that haven't spawned again yet.
+    pub fn remaining(&self) -> &HashSet<LevelIid> {
+        &self.remaining
+    }
+
+    /// Marks the given levels as spawned, and forgets levels that were removed from the [LevelSet].
+    ///
+    /// Returns `true` if no levels remain.
+    pub(crate) fn update(&mut self, spawned: &[&LevelIid], level_set: &LevelSet) -> bool {
+        self.remaining
+            .retain(|iid| !spawned.contains(&iid) && level_set.iids.contains(iid));
+        self.remaining.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::resources::LevelTransition;
+    use crate::LevelSelection;
+
+    #[test]
+    fn respawn_despawns_levels_and_worldly_entities() {
+        let mut world = World::new();
+        world.init_resource::<Events<LevelEvent>>();
+        world.init_resource::<Events<WorldRespawnEvent>>();
+
+        let mut transition_queue = LevelTransitionQueue::default();
+        transition_queue.push(LevelTransition::to(LevelSelection::index(1)));
+        world.insert_resource(transition_queue);
+
+        let level_iid = LevelIid::new("level");
+        let ldtk_world = world
+            .spawn((LdtkProjectHandle::default(), LevelSet::from_iids(["level"])))
+            .id();
+
+        let level = world.spawn(level_iid.clone()).id();
+        let worldly = world.spawn(Worldly::default()).id();
+        world
+            .entity_mut(ldtk_world)
+            .push_children(&[level, worldly]);
+
+        RespawnWorld::new(ldtk_world).apply(&mut world);
+
+        assert!(world.get_entity(level).is_none());
+        assert!(world.get_entity(worldly).is_none());
+        assert!(!world.resource::<LevelTransitionQueue>().is_busy());
+        assert_eq!(
+            world
+                .resource::<Events<WorldRespawnEvent>>()
+                .iter_current_update_events()
+                .collect::<Vec<_>>(),
+            vec![&WorldRespawnEvent::Started(ldtk_world)]
+        );
+
+        let mut respawning = world.get::<RespawningWorld>(ldtk_world).unwrap().clone();
+        assert_eq!(respawning.remaining(), &HashSet::from([level_iid.clone()]));
+
+        let level_set = world.get::<LevelSet>(ldtk_world).unwrap();
+        assert!(respawning.update(&[&level_iid], level_set));
+    }
+}
@@ -0,0 +1,345 @@
+//! Persistence of runtime changes to spawned LDtk worlds.
+//!
+//! *Requires the "save" feature*
+//!
+//! Levels are always spawned from the [`LdtkProject`], so changes made at runtime are lost when a
+//! level respawns or the game restarts.
+//! [`LdtkSavePlugin`] records these changes into the [`LdtkSaveState`] resource, and re-applies
+//! them to levels whenever they spawn again.
+//!
+//! The following changes are recorded:
+//! - Changed values of [`IntGridCell`]s.
+//! - LDtk entities that were despawned while their parent remained spawned, like collected items.
+//! - The [`Transform`]s of [`Worldly`] entities.
+//!
+//! [`LdtkSaveState`] implements serde's `Serialize` and `Deserialize`.
+//! To restore a save from a previous session, insert the deserialized [`LdtkSaveState`] before
+//! the levels spawn.
+//! ```no_run
+//! # use bevy::prelude::*;
+//! # use bevy_ecs_ldtk::{prelude::*, save::{LdtkSavePlugin, LdtkSaveState}};
+//! fn main() {
+//!     let save_state: LdtkSaveState = std::fs::read_to_string("save.json")
+//!         .ok()
+//!         .and_then(|json| serde_json::from_str(&json).ok())
+//!         .unwrap_or_default();
+//!
+//!     App::new()
+//!         .add_plugins((DefaultPlugins, LdtkPlugin, LdtkSavePlugin))
+//!         .insert_resource(save_state)
+//!         .run();
+//! }
+//!
+//! fn save_game(save_state: Res<LdtkSaveState>) {
+//!     let json = serde_json::to_string(&*save_state).unwrap();
+//!     std::fs::write("save.json", json).unwrap();
+//! }
+//! ```
+//!
+//! [`LdtkProject`]: crate::prelude::LdtkProject
+//! [`IntGridCell`]: crate::prelude::IntGridCell
+//! [`Worldly`]: crate::prelude::Worldly
+//! [`Transform`]: https://docs.rs/bevy/latest/bevy/transform/components/struct.Transform.html
+
+use crate::{
+    assets::LdtkProjectHandle,
+    components::{EntityIid, GridCoords, IntGridCell, LayerMetadata, Worldly},
+};
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+/// The value of an IntGrid cell that changed at runtime.
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Serialize, Deserialize)]
+pub struct SavedIntGridCell {
+    pub x: i32,
+    pub y: i32,
+    pub value: i32,
+}
+
+/// Serializable equivalent of a [`Transform`].
+///
+/// [`Transform`]: https://docs.rs/bevy/latest/bevy/transform/components/struct.Transform.html
+#[derive(Copy, Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct SavedTransform {
+    pub translation: [f32; 3],
+    pub rotation: [f32; 4],
+    pub scale: [f32; 3],
+}
+
+impl From<Transform> for SavedTransform {
+    fn from(transform: Transform) -> Self {
+        SavedTransform {
+            translation: transform.translation.to_array(),
+            rotation: transform.rotation.to_array(),
+            scale: transform.scale.to_array(),
+        }
+    }
+}
+
+impl From<SavedTransform> for Transform {
+    fn from(saved: SavedTransform) -> Self {
+        Transform {
+            translation: Vec3::from_array(saved.translation),
+            rotation: Quat::from_array(saved.rotation),
+            scale: Vec3::from_array(saved.scale),
+        }
+    }
+}
+
+/// [`Resource`] recording runtime changes to spawned LDtk worlds.
+///
+/// Kept up to date and re-applied by [`LdtkSavePlugin`].
+/// See the [module-level documentation](crate::save) for more details.
+///
+/// [`Resource`]: https://docs.rs/bevy/latest/bevy/ecs/system/trait.Resource.html
+#[derive(Clone, PartialEq, Debug, Default, Serialize, Deserialize, Resource)]
+pub struct LdtkSaveState {
+    /// Layer iids mapped to the IntGrid cells of that layer that changed at runtime.
+    pub int_grid_cells: BTreeMap<String, Vec<SavedIntGridCell>>,
+    /// Iids of LDtk entities that were despawned at runtime.
+    pub despawned_entities: BTreeSet<String>,
+    /// Entity iids of [`Worldly`] entities mapped to their last [`Transform`].
+    ///
+    /// [`Worldly`]: crate::prelude::Worldly
+    /// [`Transform`]: https://docs.rs/bevy/latest/bevy/transform/components/struct.Transform.html
+    pub worldly_transforms: BTreeMap<String, SavedTransform>,
+}
+
+impl LdtkSaveState {
+    /// Returns the saved value of the IntGrid cell at the given coordinates, if it changed.
+    pub fn int_grid_cell(&self, layer_iid: &str, grid_coords: GridCoords) -> Option<i32> {
+        self.int_grid_cells
+            .get(layer_iid)?
+            .iter()
+            .find(|cell| cell.x == grid_coords.x && cell.y == grid_coords.y)
+            .map(|cell| cell.value)
+    }
+
+    /// Records the value of the IntGrid cell at the given coordinates.
+    pub fn set_int_grid_cell(&mut self, layer_iid: &str, grid_coords: GridCoords, value: i32) {
+        let cells = self
+            .int_grid_cells
+            .entry(layer_iid.to_string())
+            .or_default();
+
+        match cells
+            .iter_mut()
+            .find(|cell| cell.x == grid_coords.x && cell.y == grid_coords.y)
+        {
+            Some(cell) => cell.value = value,
+            None => cells.push(SavedIntGridCell {
+                x: grid_coords.x,
+                y: grid_coords.y,
+                value,
+            }),
+        }
+    }
+}
+
+/// Plugin that records and re-applies [`LdtkSaveState`].
+///
+/// Not added by [`LdtkPlugin`].
+///
+/// [`LdtkPlugin`]: crate::prelude::LdtkPlugin
+#[derive(Copy, Clone, Debug, Default)]
+pub struct LdtkSavePlugin;
+
+impl Plugin for LdtkSavePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<LdtkSaveState>().add_systems(
+            PostUpdate,
+            (
+                (
+                    restore_int_grid_cells,
+                    restore_despawned_entities,
+                    restore_worldly_transforms,
+                ),
+                (
+                    record_int_grid_cells,
+                    record_despawned_entities,
+                    record_worldly_transforms,
+                ),
+            )
+                .chain(),
+        );
+    }
+}
+
+/// Applies saved values to newly spawned [`IntGridCell`]s.
+pub fn restore_int_grid_cells(
+    save_state: Res<LdtkSaveState>,
+    layer_query: Query<&LayerMetadata>,
+    mut cell_query: Query<(&mut IntGridCell, &GridCoords, &Parent), Added<IntGridCell>>,
+) {
+    for (mut cell, grid_coords, parent) in cell_query.iter_mut() {
+        let Ok(layer_metadata) = layer_query.get(parent.get()) else {
+            continue;
+        };
+
+        if let Some(value) = save_state.int_grid_cell(&layer_metadata.iid, *grid_coords) {
+            cell.value = value;
+        }
+    }
+}
+
+/// Despawns newly spawned LDtk entities that were despawned before.
+pub fn restore_despawned_entities(
+    mut commands: Commands,
+    save_state: Res<LdtkSaveState>,
+    entity_query: Query<(Entity, &EntityIid), Added<EntityIid>>,
+) {
+    for (entity, entity_iid) in entity_query.iter() {
+        if save_state.despawned_entities.contains(entity_iid.as_str()) {
+            commands.entity(entity).despawn_recursive();
+        }
+    }
+}
+
+/// Applies saved transforms to [`Worldly`] entities once they become children of their world.
+#[allow(clippy::type_complexity)]
+pub fn restore_worldly_transforms(
+    save_state: Res<LdtkSaveState>,
+    world_query: Query<(), With<LdtkProjectHandle>>,
+    mut worldly_query: Query<(&Worldly, &Parent, &mut Transform), Changed<Parent>>,
+) {
+    for (worldly, parent, mut transform) in worldly_query.iter_mut() {
+        if !world_query.contains(parent.get()) {
+            continue;
+        }
+
+        if let Some(saved) = save_state.worldly_transforms.get(&worldly.entity_iid) {
+            *transform = (*saved).into();
+        }
+    }
+}
+
+/// Records [`IntGridCell`] values that changed after spawning.
+pub fn record_int_grid_cells(
+    mut save_state: ResMut<LdtkSaveState>,
+    layer_query: Query<&LayerMetadata>,
+    cell_query: Query<(Ref<IntGridCell>, &GridCoords, &Parent)>,
+) {
+    for (cell, grid_coords, parent) in cell_query.iter() {
+        if !cell.is_changed() || cell.is_added() {
+            continue;
+        }
+
+        let Ok(layer_metadata) = layer_query.get(parent.get()) else {
+            continue;
+        };
+
+        save_state.set_int_grid_cell(&layer_metadata.iid, *grid_coords, cell.value);
+    }
+}
+
+/// Records LDtk entities that were despawned while their parent remained spawned.
+///
+/// Entities despawned along with their level, like when the level unloads, are not recorded.
+pub fn record_despawned_entities(
+    mut save_state: ResMut<LdtkSaveState>,
+    entity_query: Query<(Entity, &EntityIid, &Parent), Or<(Added<EntityIid>, Changed<Parent>)>>,
+    parent_query: Query<Entity>,
+    mut removed_iids: RemovedComponents<EntityIid>,
+    mut spawned: Local<HashMap<Entity, (String, Entity)>>,
+) {
+    for (entity, entity_iid, parent) in entity_query.iter() {
+        spawned.insert(entity, (entity_iid.as_str().to_string(), parent.get()));
+    }
+
+    for entity in removed_iids.iter() {
+        let Some((entity_iid, parent)) = spawned.remove(&entity) else {
+            continue;
+        };
+
+        if parent_query.contains(parent) {
+            save_state.despawned_entities.insert(entity_iid);
+        }
+    }
+}
+
+/// Records the [`Transform`]s of [`Worldly`] entities that are children of their world.
+#[allow(clippy::type_complexity)]
+pub fn record_worldly_transforms(
+    mut save_state: ResMut<LdtkSaveState>,
+    world_query: Query<(), With<LdtkProjectHandle>>,
+    worldly_query: Query<(&Worldly, &Parent, &Transform), Changed<Transform>>,
+) {
+    for (worldly, parent, transform) in worldly_query.iter() {
+        if world_query.contains(parent.get()) {
+            save_state
+                .worldly_transforms
+                .insert(worldly.entity_iid.clone(), (*transform).into());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn int_grid_cells_are_overwritten() {
+        let mut save_state = LdtkSaveState::default();
+
+        save_state.set_int_grid_cell("layer", GridCoords::new(1, 2), 3);
+        save_state.set_int_grid_cell("layer", GridCoords::new(1, 2), 0);
+        save_state.set_int_grid_cell("layer", GridCoords::new(4, 2), 1);
+
+        assert_eq!(
+            save_state.int_grid_cell("layer", GridCoords::new(1, 2)),
+            Some(0)
+        );
+        assert_eq!(
+            save_state.int_grid_cell("layer", GridCoords::new(4, 2)),
+            Some(1)
+        );
+        assert_eq!(
+            save_state.int_grid_cell("other", GridCoords::new(1, 2)),
+            None
+        );
+        assert_eq!(save_state.int_grid_cells["layer"].len(), 2);
+    }
+
+    #[test]
+    fn save_state_round_trips_through_json() {
+        let mut save_state = LdtkSaveState::default();
+        save_state.set_int_grid_cell("layer", GridCoords::new(1, 2), 3);
+        save_state.despawned_entities.insert("coin".to_string());
+        save_state
+            .worldly_transforms
+            .insert("player".to_string(), Transform::from_xyz(1., 2., 3.).into());
+
+        let json = serde_json::to_string(&save_state).unwrap();
+
+        assert_eq!(
+            serde_json::from_str::<LdtkSaveState>(&json).unwrap(),
+            save_state
+        );
+    }
+
+    #[test]
+    fn despawned_entities_are_recorded_unless_parent_despawned() {
+        let mut app = App::new();
+        app.init_resource::<LdtkSaveState>()
+            .add_systems(Update, record_despawned_entities);
+
+        let layer = app.world.spawn_empty().id();
+        let coin = app.world.spawn(EntityIid::new("coin")).id();
+        let chest = app.world.spawn(EntityIid::new("chest")).id();
+        app.world.entity_mut(layer).push_children(&[coin, chest]);
+
+        app.update();
+
+        app.world.entity_mut(coin).despawn_recursive();
+        app.update();
+
+        app.world.entity_mut(layer).despawn_recursive();
+        app.update();
+
+        assert_eq!(
+            app.world.resource::<LdtkSaveState>().despawned_entities,
+            BTreeSet::from(["coin".to_string()])
+        );
+    }
+}
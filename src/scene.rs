@@ -0,0 +1,158 @@
+//! Baking spawned levels into bevy scenes.
+//!
+//! *Requires the "scene" feature*
+//!
+//! Spawning a level from an LDtk project involves a lot of work at runtime: reading the project,
+//! building tilemaps, and running every registered [`LdtkEntity`] and [`LdtkIntCell`] bundle.
+//! [`LdtkSceneExporter`] captures the result of that work into a [`DynamicScene`], which can be
+//! saved during development and loaded by the shipped game instead.
+//!
+//! Levels are only fully spawned once the plugin has sent [`LevelEvent::Transformed`] for them.
+//! ```no_run
+//! # use bevy::prelude::*;
+//! # use bevy_ecs_ldtk::{prelude::*, scene::LdtkSceneExporter};
+//! fn bake_level(world: &mut World) {
+//!     let level_iid = LevelIid::new("a2f4d3e0-6d10-11ee-b2b5-6b2dc1f0e6a1");
+//!
+//!     if let Some(scene) = LdtkSceneExporter::default().export_level(world, &level_iid) {
+//!         let type_registry = world.resource::<AppTypeRegistry>();
+//!         let ron = scene.serialize_ron(type_registry).unwrap();
+//!         std::fs::write("assets/levels/level_0.scn.ron", ron).unwrap();
+//!     }
+//! }
+//! ```
+//!
+//! Only components that are registered in the [`AppTypeRegistry`] and reflect [`Component`] are
+//! captured.
+//! Make sure to register the types of the components in your bundles with
+//! [`App::register_type`].
+//!
+//! The level entity is exported without its [`Parent`], so the scene can be spawned anywhere.
+//! Since it keeps its [`LevelIid`] and children, the plugin won't try to spawn it again from
+//! the project.
+//!
+//! [`LdtkEntity`]: crate::app::LdtkEntity
+//! [`LdtkIntCell`]: crate::app::LdtkIntCell
+//! [`LevelEvent::Transformed`]: crate::prelude::LevelEvent::Transformed
+//! [`DynamicScene`]: https://docs.rs/bevy/latest/bevy/scene/struct.DynamicScene.html
+//! [`AppTypeRegistry`]: https://docs.rs/bevy/latest/bevy/ecs/reflect/struct.AppTypeRegistry.html
+//! [`Component`]: https://docs.rs/bevy/latest/bevy/ecs/component/trait.Component.html
+//! [`App::register_type`]: https://docs.rs/bevy/latest/bevy/app/struct.App.html#method.register_type
+//! [`Parent`]: https://docs.rs/bevy/latest/bevy/hierarchy/struct.Parent.html
+
+use crate::components::LevelIid;
+use bevy::{
+    prelude::*,
+    scene::{DynamicScene, DynamicSceneBuilder, SceneFilter},
+};
+
+/// Captures spawned levels into [`DynamicScene`]s.
+///
+/// See the [module-level documentation](crate::scene) for more details.
+///
+/// [`DynamicScene`]: https://docs.rs/bevy/latest/bevy/scene/struct.DynamicScene.html
+#[derive(Clone, Debug, Default)]
+pub struct LdtkSceneExporter {
+    /// Determines which component types are captured.
+    ///
+    /// All registered components are captured by default.
+    pub filter: SceneFilter,
+}
+
+impl LdtkSceneExporter {
+    /// Captures the spawned level with the given iid and all of its descendants.
+    ///
+    /// Returns `None` if no such level is spawned.
+    pub fn export_level(&self, world: &World, level_iid: &LevelIid) -> Option<DynamicScene> {
+        let level_entity = world
+            .iter_entities()
+            .find(|entity_ref| entity_ref.get::<LevelIid>() == Some(level_iid))?
+            .id();
+
+        Some(self.export_level_entity(world, level_entity))
+    }
+
+    /// Captures the given level entity and all of its descendants.
+    pub fn export_level_entity(&self, world: &World, level_entity: Entity) -> DynamicScene {
+        let mut entities = vec![level_entity];
+        let mut index = 0;
+
+        while let Some(entity) = entities.get(index).copied() {
+            if let Some(children) = world.get::<Children>(entity) {
+                entities.extend(children.iter().copied());
+            }
+
+            index += 1;
+        }
+
+        let mut builder = DynamicSceneBuilder::from_world(world).with_filter(self.filter.clone());
+        builder.extract_entities(entities.into_iter());
+        let mut scene = builder.build();
+
+        if let Some(level) = scene
+            .entities
+            .iter_mut()
+            .find(|dynamic_entity| dynamic_entity.entity == level_entity)
+        {
+            level
+                .components
+                .retain(|component| component.type_name() != std::any::type_name::<Parent>());
+        }
+
+        scene
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn exports_level_hierarchy_without_parent() {
+        let mut world = World::new();
+        world.init_resource::<AppTypeRegistry>();
+        {
+            let mut type_registry = world.resource::<AppTypeRegistry>().write();
+            type_registry.register::<LevelIid>();
+            type_registry.register::<Parent>();
+            type_registry.register::<Children>();
+            type_registry.register::<Transform>();
+        }
+
+        let world_entity = world.spawn_empty().id();
+        let level_entity = world.spawn(LevelIid::new("level")).id();
+        world
+            .entity_mut(world_entity)
+            .push_children(&[level_entity]);
+        world.entity_mut(level_entity).with_children(|level| {
+            level.spawn(Transform::default()).with_children(|layer| {
+                layer.spawn(Transform::default());
+            });
+        });
+        world.spawn(LevelIid::new("other"));
+
+        let scene = LdtkSceneExporter::default()
+            .export_level(&world, &LevelIid::new("level"))
+            .unwrap();
+
+        assert_eq!(scene.entities.len(), 3);
+
+        let level = scene
+            .entities
+            .iter()
+            .find(|dynamic_entity| dynamic_entity.entity == level_entity)
+            .unwrap();
+        assert!(level
+            .components
+            .iter()
+            .all(|component| component.type_name() != std::any::type_name::<Parent>()));
+        assert!(level
+            .components
+            .iter()
+            .any(|component| component.type_name() == std::any::type_name::<LevelIid>()));
+
+        assert!(LdtkSceneExporter::default()
+            .export_level(&world, &LevelIid::new("missing"))
+            .is_none());
+    }
+}
@@ -0,0 +1,650 @@
+//! Compact, render-free level data for authoritative game servers.
+//!
+//! A [`ServerLevel`] holds the parts of a level that game logic usually depends on:
+//! - The values of every IntGrid layer.
+//! - Collision rectangles, merged from the IntGrid cells with collision values.
+//! - The placements of all LDtk entities.
+//!
+//! It can be exported to a small binary blob with [`ServerLevel::to_bytes`], and loaded back with
+//! [`ServerLevel::from_bytes`], so servers can share the exact same level data as the client
+//! without parsing the full LDtk project.
+//! ```
+//! # use bevy_ecs_ldtk::{ldtk::Level, server_level::ServerLevel};
+//! # use std::collections::HashSet;
+//! fn export(level: &Level) -> Vec<u8> {
+//!     let collision_values = HashSet::from([1, 2]);
+//!     ServerLevel::from_level(level, &collision_values).to_bytes()
+//! }
+//!
+//! fn load(bytes: &[u8]) -> ServerLevel {
+//!     ServerLevel::from_bytes(bytes).expect("level data should be valid")
+//! }
+//! ```
+//!
+//! IntGrid cells can be edited at runtime with [`ServerIntGrid::set_value`], which only merges the
+//! collision rectangles near the edited cell again.
+//!
+//! All coordinates use LDtk's orientation, with the origin at the top-left corner of the level
+//! and y pointing down.
+
+use crate::ldtk::{Level, Type};
+use bevy::prelude::*;
+use std::{collections::HashSet, ops::Range};
+use thiserror::Error;
+
+const MAGIC: &[u8; 4] = b"LDSL";
+const VERSION: u8 = 1;
+
+/// Errors that can occur when loading a [`ServerLevel`].
+#[derive(Debug, PartialEq, Eq, Error)]
+pub enum ServerLevelError {
+    #[error("data is not a server level")]
+    InvalidMagic,
+    #[error("unsupported server level version {0}")]
+    UnsupportedVersion(u8),
+    #[error("unexpected end of server level data")]
+    UnexpectedEnd,
+    #[error("server level contains invalid utf-8")]
+    InvalidUtf8,
+}
+
+/// A rectangle of IntGrid cells, in grid coordinates.
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Default)]
+pub struct CollisionRect {
+    /// Coordinates of the top-left cell.
+    pub min: IVec2,
+    /// Size of the rectangle in cells.
+    pub size: IVec2,
+}
+
+/// The values of an IntGrid layer and its merged collision rectangles.
+#[derive(Clone, Eq, PartialEq, Debug, Default)]
+pub struct ServerIntGrid {
+    pub identifier: String,
+    pub grid_size: i32,
+    /// Size of the layer in cells.
+    pub size: IVec2,
+    /// Values of the cells, row by row from the top-left corner.
+    pub values: Vec<i32>,
+    pub collision_rects: Vec<CollisionRect>,
+}
+
+impl ServerIntGrid {
+    /// Returns the value of the cell at the given grid coordinates.
+    pub fn value(&self, cell: IVec2) -> Option<i32> {
+        if cell.cmplt(IVec2::ZERO).any() || cell.cmpge(self.size).any() {
+            return None;
+        }
+
+        self.values
+            .get((cell.y * self.size.x + cell.x) as usize)
+            .copied()
+    }
+
+    /// Sets the value of the cell at the given grid coordinates, and updates the collision
+    /// rectangles.
+    ///
+    /// Only the rectangles in the rows around the edited cell are merged again, rather than the
+    /// whole layer, so the cost of editing destructible terrain stays bounded.
+    /// The rectangles cover the same cells a full merge would, but they may be split differently.
+    ///
+    /// Returns `false` if the cell is out of bounds.
+    pub fn set_value(&mut self, cell: IVec2, value: i32, collision_values: &HashSet<i32>) -> bool {
+        if cell.cmplt(IVec2::ZERO).any() || cell.cmpge(self.size).any() {
+            return false;
+        }
+
+        let Some(cell_value) = self
+            .values
+            .get_mut((cell.y * self.size.x + cell.x) as usize)
+        else {
+            return false;
+        };
+
+        let previous = std::mem::replace(cell_value, value);
+        if collision_values.contains(&previous) == collision_values.contains(&value) {
+            return true;
+        }
+
+        let overlaps = |rect: &CollisionRect, rows: &Range<i32>| {
+            rect.min.y < rows.end && rect.min.y + rect.size.y > rows.start
+        };
+
+        // Rectangles span several rows, so the rows are extended until no rectangle crosses them
+        let mut rows = cell.y..cell.y + 1;
+        loop {
+            let extended = self
+                .collision_rects
+                .iter()
+                .filter(|rect| overlaps(rect, &rows))
+                .fold(rows.clone(), |rows, rect| {
+                    rows.start.min(rect.min.y)..rows.end.max(rect.min.y + rect.size.y)
+                });
+
+            if extended == rows {
+                break;
+            }
+            rows = extended;
+        }
+
+        self.collision_rects.retain(|rect| !overlaps(rect, &rows));
+
+        let start = ((rows.start * self.size.x) as usize).min(self.values.len());
+        let end = ((rows.end * self.size.x) as usize).min(self.values.len());
+        let merged = merge_collision_rects(
+            &self.values[start..end],
+            IVec2::new(self.size.x, rows.end - rows.start),
+            collision_values,
+        );
+
+        self.collision_rects
+            .extend(merged.into_iter().map(|rect| CollisionRect {
+                min: rect.min + IVec2::new(0, rows.start),
+                ..rect
+            }));
+        self.collision_rects
+            .sort_by_key(|rect| (rect.min.y, rect.min.x));
+
+        true
+    }
+}
+
+/// The placement of an LDtk entity.
+#[derive(Clone, Eq, PartialEq, Debug, Default)]
+pub struct ServerEntity {
+    pub identifier: String,
+    pub iid: String,
+    /// Identifier of the layer the entity belongs to.
+    pub layer: String,
+    /// Pixel coordinates of the entity's pivot, relative to the level.
+    pub px: IVec2,
+    /// Size of the entity in pixels.
+    pub size: IVec2,
+    pub tags: Vec<String>,
+}
+
+/// Compact level data for servers.
+///
+/// See the [module-level documentation](crate::server_level) for more details.
+#[derive(Clone, Eq, PartialEq, Debug, Default)]
+pub struct ServerLevel {
+    pub iid: String,
+    pub identifier: String,
+    /// Size of the level in pixels.
+    pub px_size: IVec2,
+    pub int_grids: Vec<ServerIntGrid>,
+    pub entities: Vec<ServerEntity>,
+}
+
+impl ServerLevel {
+    /// Extracts the server data of a level.
+    ///
+    /// IntGrid cells with any of the `collision_values` are merged into collision rectangles.
+    ///
+    /// The level's layer instances must be loaded.
+    /// For projects with external levels, use the levels of [`LdtkExternalLevel`] assets.
+    ///
+    /// [`LdtkExternalLevel`]: crate::assets::LdtkExternalLevel
+    pub fn from_level(level: &Level, collision_values: &HashSet<i32>) -> ServerLevel {
+        let mut server_level = ServerLevel {
+            iid: level.iid.clone(),
+            identifier: level.identifier.clone(),
+            px_size: IVec2::new(level.px_wid, level.px_hei),
+            ..Default::default()
+        };
+
+        for layer_instance in level.layer_instances.iter().flatten() {
+            match layer_instance.layer_instance_type {
+                Type::IntGrid => {
+                    let size = IVec2::new(layer_instance.c_wid, layer_instance.c_hei);
+                    let collision_rects =
+                        merge_collision_rects(&layer_instance.int_grid_csv, size, collision_values);
+
+                    server_level.int_grids.push(ServerIntGrid {
+                        identifier: layer_instance.identifier.clone(),
+                        grid_size: layer_instance.grid_size,
+                        size,
+                        values: layer_instance.int_grid_csv.clone(),
+                        collision_rects,
+                    });
+                }
+                Type::Entities => {
+                    for entity_instance in &layer_instance.entity_instances {
+                        server_level.entities.push(ServerEntity {
+                            identifier: entity_instance.identifier.clone(),
+                            iid: entity_instance.iid.clone(),
+                            layer: layer_instance.identifier.clone(),
+                            px: entity_instance.px,
+                            size: IVec2::new(entity_instance.width, entity_instance.height),
+                            tags: entity_instance.tags.clone(),
+                        });
+                    }
+                }
+                _ => (),
+            }
+        }
+
+        server_level
+    }
+
+    /// Returns the IntGrid layer with the given identifier.
+    pub fn int_grid(&self, identifier: &str) -> Option<&ServerIntGrid> {
+        self.int_grids
+            .iter()
+            .find(|int_grid| int_grid.identifier == identifier)
+    }
+
+    /// Returns mutable access to the IntGrid layer with the given identifier.
+    ///
+    /// Use [`ServerIntGrid::set_value`] to edit its cells, so its collision rectangles stay up to
+    /// date.
+    pub fn int_grid_mut(&mut self, identifier: &str) -> Option<&mut ServerIntGrid> {
+        self.int_grids
+            .iter_mut()
+            .find(|int_grid| int_grid.identifier == identifier)
+    }
+
+    /// Encodes the level into its binary format.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut writer = Writer(MAGIC.to_vec());
+        writer.0.push(VERSION);
+
+        writer.string(&self.iid);
+        writer.string(&self.identifier);
+        writer.ivec2(self.px_size);
+
+        writer.unsigned(self.int_grids.len() as u64);
+        for int_grid in &self.int_grids {
+            writer.string(&int_grid.identifier);
+            writer.signed(int_grid.grid_size);
+            writer.ivec2(int_grid.size);
+
+            writer.unsigned(int_grid.values.len() as u64);
+            for value in &int_grid.values {
+                writer.signed(*value);
+            }
+
+            writer.unsigned(int_grid.collision_rects.len() as u64);
+            for rect in &int_grid.collision_rects {
+                writer.ivec2(rect.min);
+                writer.ivec2(rect.size);
+            }
+        }
+
+        writer.unsigned(self.entities.len() as u64);
+        for entity in &self.entities {
+            writer.string(&entity.identifier);
+            writer.string(&entity.iid);
+            writer.string(&entity.layer);
+            writer.ivec2(entity.px);
+            writer.ivec2(entity.size);
+
+            writer.unsigned(entity.tags.len() as u64);
+            for tag in &entity.tags {
+                writer.string(tag);
+            }
+        }
+
+        writer.0
+    }
+
+    /// Decodes a level from its binary format.
+    pub fn from_bytes(bytes: &[u8]) -> Result<ServerLevel, ServerLevelError> {
+        let mut reader = Reader(bytes);
+
+        if reader.take(MAGIC.len())? != MAGIC {
+            return Err(ServerLevelError::InvalidMagic);
+        }
+
+        let version = reader.take(1)?[0];
+        if version != VERSION {
+            return Err(ServerLevelError::UnsupportedVersion(version));
+        }
+
+        let iid = reader.string()?;
+        let identifier = reader.string()?;
+        let px_size = reader.ivec2()?;
+
+        let int_grids = (0..reader.len()?)
+            .map(|_| {
+                let identifier = reader.string()?;
+                let grid_size = reader.signed()?;
+                let size = reader.ivec2()?;
+                let values = (0..reader.len()?)
+                    .map(|_| reader.signed())
+                    .collect::<Result<_, _>>()?;
+                let collision_rects = (0..reader.len()?)
+                    .map(|_| {
+                        Ok(CollisionRect {
+                            min: reader.ivec2()?,
+                            size: reader.ivec2()?,
+                        })
+                    })
+                    .collect::<Result<_, _>>()?;
+
+                Ok(ServerIntGrid {
+                    identifier,
+                    grid_size,
+                    size,
+                    values,
+                    collision_rects,
+                })
+            })
+            .collect::<Result<_, _>>()?;
+
+        let entities = (0..reader.len()?)
+            .map(|_| {
+                Ok(ServerEntity {
+                    identifier: reader.string()?,
+                    iid: reader.string()?,
+                    layer: reader.string()?,
+                    px: reader.ivec2()?,
+                    size: reader.ivec2()?,
+                    tags: (0..reader.len()?)
+                        .map(|_| reader.string())
+                        .collect::<Result<_, _>>()?,
+                })
+            })
+            .collect::<Result<_, _>>()?;
+
+        Ok(ServerLevel {
+            iid,
+            identifier,
+            px_size,
+            int_grids,
+            entities,
+        })
+    }
+}
+
+/// Merges the cells with collision values into as few rectangles as is easy to find.
+///
+/// Cells are first merged into horizontal runs, then runs spanning the same columns in
+/// consecutive rows are merged into rectangles.
+fn merge_collision_rects(
+    values: &[i32],
+    size: IVec2,
+    collision_values: &HashSet<i32>,
+) -> Vec<CollisionRect> {
+    let mut finished = Vec::new();
+    let mut open: Vec<CollisionRect> = Vec::new();
+
+    for y in 0..size.y {
+        let mut runs = Vec::new();
+        let mut run_start = None;
+
+        for x in 0..=size.x {
+            let solid = x < size.x
+                && values
+                    .get((y * size.x + x) as usize)
+                    .map_or(false, |value| collision_values.contains(value));
+
+            match (solid, run_start) {
+                (true, None) => run_start = Some(x),
+                (false, Some(start)) => {
+                    runs.push((start, x - start));
+                    run_start = None;
+                }
+                _ => (),
+            }
+        }
+
+        let mut next_open = Vec::new();
+
+        for (start, width) in runs {
+            match open
+                .iter()
+                .position(|rect| rect.min.x == start && rect.size.x == width)
+            {
+                Some(index) => {
+                    let mut rect = open.swap_remove(index);
+                    rect.size.y += 1;
+                    next_open.push(rect);
+                }
+                None => next_open.push(CollisionRect {
+                    min: IVec2::new(start, y),
+                    size: IVec2::new(width, 1),
+                }),
+            }
+        }
+
+        finished.append(&mut open);
+        open = next_open;
+    }
+
+    finished.append(&mut open);
+    finished.sort_by_key(|rect| (rect.min.y, rect.min.x));
+    finished
+}
+
+struct Writer(Vec<u8>);
+
+impl Writer {
+    fn unsigned(&mut self, mut value: u64) {
+        loop {
+            let byte = (value & 0x7f) as u8;
+            value >>= 7;
+
+            if value == 0 {
+                self.0.push(byte);
+                return;
+            }
+
+            self.0.push(byte | 0x80);
+        }
+    }
+
+    fn signed(&mut self, value: i32) {
+        self.unsigned(((value << 1) ^ (value >> 31)) as u32 as u64);
+    }
+
+    fn ivec2(&mut self, value: IVec2) {
+        self.signed(value.x);
+        self.signed(value.y);
+    }
+
+    fn string(&mut self, value: &str) {
+        self.unsigned(value.len() as u64);
+        self.0.extend_from_slice(value.as_bytes());
+    }
+}
+
+struct Reader<'a>(&'a [u8]);
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8], ServerLevelError> {
+        if self.0.len() < len {
+            return Err(ServerLevelError::UnexpectedEnd);
+        }
+
+        let (taken, rest) = self.0.split_at(len);
+        self.0 = rest;
+        Ok(taken)
+    }
+
+    fn unsigned(&mut self) -> Result<u64, ServerLevelError> {
+        let mut value = 0;
+
+        for shift in (0..64).step_by(7) {
+            let byte = self.take(1)?[0];
+            value |= ((byte & 0x7f) as u64) << shift;
+
+            if byte & 0x80 == 0 {
+                break;
+            }
+        }
+
+        Ok(value)
+    }
+
+    fn len(&mut self) -> Result<usize, ServerLevelError> {
+        let len = self.unsigned()? as usize;
+
+        // Every element takes at least one byte, so longer lengths can only come from bad data.
+        if len > self.0.len() {
+            return Err(ServerLevelError::UnexpectedEnd);
+        }
+
+        Ok(len)
+    }
+
+    fn signed(&mut self) -> Result<i32, ServerLevelError> {
+        let zigzag = self.unsigned()? as u32;
+        Ok((zigzag >> 1) as i32 ^ -((zigzag & 1) as i32))
+    }
+
+    fn ivec2(&mut self) -> Result<IVec2, ServerLevelError> {
+        Ok(IVec2::new(self.signed()?, self.signed()?))
+    }
+
+    fn string(&mut self) -> Result<String, ServerLevelError> {
+        let len = self.len()?;
+        String::from_utf8(self.take(len)?.to_vec()).map_err(|_| ServerLevelError::InvalidUtf8)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ldtk::{EntityInstance, LayerInstance};
+
+    #[test]
+    fn collision_cells_merge_into_rects() {
+        #[rustfmt::skip]
+        let values = vec![
+            1, 1, 0, 2,
+            1, 1, 0, 2,
+            0, 1, 0, 0,
+        ];
+
+        let rects = merge_collision_rects(&values, IVec2::new(4, 3), &HashSet::from([1, 2]));
+
+        assert_eq!(
+            rects,
+            vec![
+                CollisionRect {
+                    min: IVec2::new(0, 0),
+                    size: IVec2::new(2, 2),
+                },
+                CollisionRect {
+                    min: IVec2::new(3, 0),
+                    size: IVec2::new(1, 2),
+                },
+                CollisionRect {
+                    min: IVec2::new(1, 2),
+                    size: IVec2::new(1, 1),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn edits_only_merge_nearby_rects_again() {
+        #[rustfmt::skip]
+        let values = vec![
+            1, 1, 0, 0,
+            1, 1, 0, 0,
+            0, 0, 0, 0,
+            0, 0, 1, 1,
+        ];
+        let collision_values = HashSet::from([1]);
+
+        let mut int_grid = ServerIntGrid {
+            size: IVec2::new(4, 4),
+            collision_rects: merge_collision_rects(&values, IVec2::new(4, 4), &collision_values),
+            values,
+            ..Default::default()
+        };
+
+        assert!(int_grid.set_value(IVec2::new(1, 1), 0, &collision_values));
+        assert_eq!(int_grid.value(IVec2::new(1, 1)), Some(0));
+        assert_eq!(
+            int_grid.collision_rects,
+            vec![
+                CollisionRect {
+                    min: IVec2::new(0, 0),
+                    size: IVec2::new(2, 1),
+                },
+                CollisionRect {
+                    min: IVec2::new(0, 1),
+                    size: IVec2::new(1, 1),
+                },
+                CollisionRect {
+                    min: IVec2::new(2, 3),
+                    size: IVec2::new(2, 1),
+                },
+            ]
+        );
+
+        // Non-colliding values don't change the rects
+        assert!(int_grid.set_value(IVec2::new(3, 0), 2, &collision_values));
+        assert_eq!(int_grid.collision_rects.len(), 3);
+
+        assert!(!int_grid.set_value(IVec2::new(4, 0), 1, &collision_values));
+    }
+
+    #[test]
+    fn server_levels_round_trip_through_bytes() {
+        let level = Level {
+            iid: "level".to_string(),
+            identifier: "Level_0".to_string(),
+            px_wid: 32,
+            px_hei: 16,
+            layer_instances: Some(vec![
+                LayerInstance {
+                    identifier: "Walls".to_string(),
+                    layer_instance_type: Type::IntGrid,
+                    grid_size: 16,
+                    c_wid: 2,
+                    c_hei: 1,
+                    int_grid_csv: vec![1, -3],
+                    ..Default::default()
+                },
+                LayerInstance {
+                    identifier: "Entities".to_string(),
+                    layer_instance_type: Type::Entities,
+                    entity_instances: vec![EntityInstance {
+                        identifier: "Player".to_string(),
+                        iid: "player".to_string(),
+                        px: IVec2::new(8, 8),
+                        width: 16,
+                        height: 16,
+                        tags: vec!["actor".to_string()],
+                        ..Default::default()
+                    }],
+                    ..Default::default()
+                },
+            ]),
+            ..Default::default()
+        };
+
+        let server_level = ServerLevel::from_level(&level, &HashSet::from([1]));
+
+        assert_eq!(
+            server_level.int_grid("Walls").unwrap().collision_rects,
+            vec![CollisionRect {
+                min: IVec2::ZERO,
+                size: IVec2::ONE,
+            }]
+        );
+        assert_eq!(
+            server_level
+                .int_grid("Walls")
+                .unwrap()
+                .value(IVec2::new(1, 0)),
+            Some(-3)
+        );
+
+        let bytes = server_level.to_bytes();
+        assert_eq!(ServerLevel::from_bytes(&bytes), Ok(server_level));
+
+        assert_eq!(
+            ServerLevel::from_bytes(&bytes[..bytes.len() - 1]),
+            Err(ServerLevelError::UnexpectedEnd)
+        );
+        assert_eq!(
+            ServerLevel::from_bytes(b"nope"),
+            Err(ServerLevelError::InvalidMagic)
+        );
+    }
+}
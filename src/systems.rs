@@ -3,30 +3,69 @@
 #[cfg(feature = "render")]
 use crate::resources::SetClearColor;
 use crate::{
-    app::{LdtkEntityMap, LdtkIntCellMap},
-    assets::{LdtkProject, LdtkProjectData, LevelMetadataAccessor},
+    app::{
+        LayerDefinitionCallbacks, LdtkEntityMap, LdtkIntCellMap, LdtkZOrdering,
+        LevelFieldCallbacks, LevelFieldChange, LevelPostProcessingMaterials, PhantomLdtkEntity,
+        PhantomLdtkEntityTrait, PhantomLdtkIntCell, PhantomLdtkIntCellTrait,
+        ProjectConfigCallbacks,
+    },
+    assets::{LdtkProject, LdtkProjectData, LdtkProjectHandle, LevelMetadataAccessor},
     components::*,
-    ldtk::{Level, TilesetDefinition},
-    level::spawn_level,
-    resources::{LdtkSettings, LevelEvent, LevelSelection, LevelSpawnBehavior},
+    composite::{compose_level, LevelComposites},
+    ldtk::{
+        loaded_level::LoadedLevel, raw_level_accessor::RawLevelAccessor, FieldInstance, Level,
+        TilesetDefinition,
+    },
+    level::{
+        spawn_level, BundlePhaseContext, LevelSpawnContext, LogicPhaseContext, RenderPhaseContext,
+    },
+    level_query::LdtkLevelIndex,
+    preview::LevelPreview,
+    resources::{
+        level_transition::TransitionStage, recycle_descendants, registration_or_default,
+        DeferredLdtkBundles, DeterministicSpawning, EntityInstanceGroups, EntityPooling,
+        InvalidLevelSelection, LayerVariants, LdtkEntityDespawned, LdtkEntityPool,
+        LdtkErrorReporter, LdtkLocalization, LdtkSettings, LevelCulling, LevelDuplicates,
+        LevelEvent, LevelLifecycleEvent, LevelRunSeed, LevelSelection, LevelSelectionError,
+        LevelSpawnBehavior, LevelSpawnOverrides, LevelTransitionEvent, LevelTransitionQueue,
+        PersistentEntityState, RespawningWorld, SpawnBudget, TilesetSkins, TrackedLdtkEntities,
+        WorldRespawnEvent,
+    },
     utils::*,
 };
 
 #[cfg(feature = "external_levels")]
 use crate::assets::LdtkExternalLevel;
 
-use bevy::{ecs::system::SystemState, prelude::*};
-use std::collections::{HashMap, HashSet};
+#[cfg(feature = "render")]
+use crate::app::LdtkTilemapMaterials;
+#[cfg(feature = "render")]
+use bevy_ecs_tilemap::prelude::{MaterialTilemap, StandardTilemapMaterial};
+
+use bevy::{
+    asset::Asset,
+    core::FrameCount,
+    ecs::system::{SystemParam, SystemState},
+    prelude::*,
+};
+use bevy_ecs_tilemap::{
+    map::TilemapTexture,
+    tiles::{TileColor, TilePos, TileTextureIndex},
+};
+use std::collections::{BTreeSet, HashMap, HashSet};
 
 /// Detects [LdtkProject] events and spawns levels as children of the [LdtkWorldBundle].
 #[allow(clippy::too_many_arguments)]
 pub fn process_ldtk_assets(
     mut commands: Commands,
     mut ldtk_project_events: EventReader<AssetEvent<LdtkProject>>,
-    ldtk_world_query: Query<(Entity, &Handle<LdtkProject>)>,
+    ldtk_world_query: Query<(Entity, &LdtkProjectHandle)>,
     #[cfg(feature = "render")] ldtk_settings: Res<LdtkSettings>,
     #[cfg(feature = "render")] mut clear_color: ResMut<ClearColor>,
     #[cfg(feature = "render")] ldtk_project_assets: Res<Assets<LdtkProject>>,
+    #[cfg(feature = "live_sync")] mut live_synced: Option<
+        ResMut<crate::live_sync::LiveSyncedProjects>,
+    >,
 ) {
     let mut ldtk_handles_to_respawn = HashSet::new();
     let mut ldtk_handles_for_clear_color = HashSet::new();
@@ -54,63 +93,224 @@ pub fn process_ldtk_assets(
     #[cfg(feature = "render")]
     if ldtk_settings.set_clear_color == SetClearColor::FromEditorBackground {
         for handle in ldtk_handles_for_clear_color.iter() {
-            if let Some(project) = &ldtk_project_assets.get(handle) {
+            if let Some(project) = ldtk_project_assets
+                .get(handle)
+                .filter(|project| *project.sets_clear_color())
+            {
                 clear_color.0 = project.json_data().bg_color;
             }
         }
     }
 
+    // Projects synced by the live sync plugin have already been updated in place
+    #[cfg(feature = "live_sync")]
+    if let Some(live_synced) = live_synced.as_mut() {
+        ldtk_handles_to_respawn.retain(|handle| !live_synced.handles.contains(*handle));
+        live_synced.handles.clear();
+    }
+
     for (entity, handle) in ldtk_world_query.iter() {
-        if ldtk_handles_to_respawn.contains(handle) {
+        if ldtk_handles_to_respawn.contains(&handle.handle) {
             commands.entity(entity).insert(Respawn);
         }
     }
 }
 
+/// Processes the [LevelTransitionQueue], updating the [LevelSelection] one transition at a time.
+#[allow(clippy::too_many_arguments)]
+pub fn process_level_transitions(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut transition_queue: ResMut<LevelTransitionQueue>,
+    mut transition_events: EventWriter<LevelTransitionEvent>,
+    mut level_events: EventReader<LevelEvent>,
+    level_selection: Option<ResMut<LevelSelection>>,
+    ldtk_project_assets: Res<Assets<LdtkProject>>,
+    ldtk_world_query: Query<&LdtkProjectHandle, Without<LevelPreview>>,
+    level_query: Query<&LevelIid>,
+) {
+    if let Some(target) = transition_queue.start_next() {
+        transition_events.send(LevelTransitionEvent::Started(target));
+    }
+
+    let Some((transition, stage)) = &mut transition_queue.active else {
+        level_events.clear();
+        return;
+    };
+
+    if let TransitionStage::Delaying(timer) = stage {
+        if !timer.tick(time.delta()).finished() {
+            level_events.clear();
+            return;
+        }
+
+        // The target can only be resolved once its project has loaded
+        let Some(target_iid) = ldtk_world_query.iter().find_map(|handle| {
+            ldtk_project_assets
+                .get(handle)?
+                .find_raw_level_by_level_selection(&transition.target)
+                .map(|level| LevelIid::new(level.iid.clone()))
+        }) else {
+            level_events.clear();
+            return;
+        };
+
+        if let Some(mut level_selection) = level_selection {
+            level_selection.set_if_neq(transition.target.clone());
+        } else {
+            commands.insert_resource(transition.target.clone());
+        }
+
+        if level_query.iter().any(|iid| *iid == target_iid) {
+            let target = transition.target.clone();
+            transition_queue.active = None;
+
+            transition_events.send(LevelTransitionEvent::TargetSpawned(target.clone()));
+            transition_events.send(LevelTransitionEvent::Completed(target));
+            level_events.clear();
+            return;
+        }
+
+        *stage = TransitionStage::Spawning(target_iid);
+    }
+
+    for event in level_events.iter() {
+        match (&*stage, event) {
+            (TransitionStage::Spawning(target_iid), LevelEvent::Spawned(iid))
+                if iid == target_iid =>
+            {
+                transition_events.send(LevelTransitionEvent::TargetSpawned(
+                    transition.target.clone(),
+                ));
+                *stage = TransitionStage::Transforming(iid.clone());
+            }
+            (TransitionStage::Transforming(target_iid), LevelEvent::Transformed(iid))
+                if iid == target_iid =>
+            {
+                transition_events.send(LevelTransitionEvent::Completed(transition.target.clone()));
+                transition_queue.active = None;
+                break;
+            }
+            _ => (),
+        }
+    }
+}
+
+/// Sends [WorldRespawnEvent::Completed] once all levels of a [RespawnWorld] have spawned again.
+pub fn track_world_respawns(
+    mut commands: Commands,
+    mut level_events: EventReader<LevelEvent>,
+    mut world_query: Query<(Entity, &mut RespawningWorld, &LevelSet)>,
+    mut respawn_events: EventWriter<WorldRespawnEvent>,
+) {
+    let spawned: Vec<&LevelIid> = level_events
+        .iter()
+        .filter_map(|event| match event {
+            LevelEvent::Spawned(iid) => Some(iid),
+            _ => None,
+        })
+        .collect();
+
+    for (world_entity, mut respawning_world, level_set) in world_query.iter_mut() {
+        // Levels spawned earlier in the same update were spawned before the respawn started
+        let spawned: &[&LevelIid] = if respawning_world.is_added() {
+            &[]
+        } else {
+            &spawned
+        };
+
+        if respawning_world.update(spawned, level_set) {
+            commands.entity(world_entity).remove::<RespawningWorld>();
+            respawn_events.send(WorldRespawnEvent::Completed(world_entity));
+        }
+    }
+}
+
 /// Updates all LevelSet components according to the LevelSelection
 pub fn apply_level_selection(
     level_selection: Option<Res<LevelSelection>>,
     ldtk_settings: Res<LdtkSettings>,
     ldtk_project_assets: Res<Assets<LdtkProject>>,
-    mut level_set_query: Query<(&Handle<LdtkProject>, &mut LevelSet)>,
+    level_duplicates: Res<LevelDuplicates>,
+    mut level_set_query: Query<(Entity, &LdtkProjectHandle, &mut LevelSet), Without<LevelPreview>>,
+    mut selection_errors: EventWriter<LevelSelectionError>,
+    mut reported_errors: Local<HashMap<Entity, LevelSelection>>,
     #[cfg(feature = "render")] mut clear_color: ResMut<ClearColor>,
 ) {
-    if let Some(level_selection) = level_selection {
-        for (ldtk_handle, mut level_set) in level_set_query.iter_mut() {
-            if let Some(project) = &ldtk_project_assets.get(ldtk_handle) {
-                if let Some(level) = project.find_raw_level_by_level_selection(&level_selection) {
-                    let new_level_set = {
-                        let mut iids = HashSet::new();
-                        iids.insert(LevelIid::new(level.iid.clone()));
-
-                        if let LevelSpawnBehavior::UseWorldTranslation {
-                            load_level_neighbors,
-                        } = ldtk_settings.level_spawn_behavior
-                        {
-                            if load_level_neighbors {
-                                iids.extend(
-                                    level
-                                        .neighbours
-                                        .iter()
-                                        .map(|n| LevelIid::new(n.level_iid.clone())),
-                                );
-                            }
-                        }
+    // Forget the errors of despawned worlds
+    reported_errors.retain(|world_entity, _| level_set_query.contains(*world_entity));
 
-                        LevelSet { iids }
-                    };
+    let Some(level_selection) = level_selection else {
+        return;
+    };
 
-                    if *level_set != new_level_set {
-                        *level_set = new_level_set;
+    for (world_entity, ldtk_handle, mut level_set) in level_set_query.iter_mut() {
+        let Some(project) = ldtk_project_assets.get(ldtk_handle) else {
+            continue;
+        };
 
-                        #[cfg(feature = "render")]
-                        if ldtk_settings.set_clear_color == SetClearColor::FromLevelBackground {
-                            clear_color.0 = level.bg_color;
-                        }
-                    }
+        // Duplicates are spawned independently of the selection
+        let mut iids: HashSet<LevelIid> = level_set
+            .iids
+            .iter()
+            .filter(|iid| level_duplicates.contains(iid))
+            .cloned()
+            .collect();
+
+        let Some(level) = project.find_raw_level_by_level_selection(&level_selection) else {
+            if reported_errors.get(&world_entity) != Some(&level_selection) {
+                let error = LevelSelectionError {
+                    selection: level_selection.clone(),
+                    world: world_entity,
+                };
+                warn!("{error}");
+                selection_errors.send(error);
+                reported_errors.insert(world_entity, level_selection.clone());
+            }
+
+            let new_level_set = LevelSet { iids };
+
+            if ldtk_settings.invalid_level_selection == InvalidLevelSelection::DespawnLevels
+                && *level_set != new_level_set
+            {
+                *level_set = new_level_set;
+            }
+
+            continue;
+        };
+
+        reported_errors.remove(&world_entity);
+
+        iids.insert(LevelIid::new(level.iid.clone()));
+
+        if let LevelSpawnBehavior::UseWorldTranslation {
+            load_level_neighbors,
+        } = ldtk_settings.level_spawn_behavior
+        {
+            if load_level_neighbors {
+                if let Some(level_metadata) = project.get_level_metadata_by_iid(&level.iid) {
+                    iids.extend(
+                        project
+                            .raw_level_neighbor_iids(level_metadata.indices())
+                            .into_iter()
+                            .map(|iid| LevelIid::new(iid.clone())),
+                    );
                 }
             }
         }
+
+        let new_level_set = LevelSet { iids };
+
+        if *level_set != new_level_set {
+            *level_set = new_level_set;
+
+            #[cfg(feature = "render")]
+            if ldtk_settings.set_clear_color == SetClearColor::FromLevelBackground
+                && *project.sets_clear_color()
+            {
+                clear_color.0 = level.bg_color;
+            }
+        }
     }
 }
 
@@ -122,12 +322,15 @@ pub fn apply_level_set(
         Entity,
         &LevelSet,
         Option<&Children>,
-        &Handle<LdtkProject>,
+        &LdtkProjectHandle,
         Option<&Respawn>,
     )>,
     ldtk_level_query: Query<(&LevelIid, Entity)>,
     ldtk_project_assets: Res<Assets<LdtkProject>>,
+    #[cfg(feature = "external_levels")] level_assets: Res<Assets<LdtkExternalLevel>>,
     ldtk_settings: Res<LdtkSettings>,
+    level_duplicates: Res<LevelDuplicates>,
+    spawn_overrides: Res<LevelSpawnOverrides>,
     mut level_events: EventWriter<LevelEvent>,
 ) {
     for (world_entity, level_set, children, ldtk_asset_handle, respawn) in ldtk_world_query.iter() {
@@ -145,20 +348,50 @@ pub fn apply_level_set(
 
             let level_set_as_ref = level_set.iids.iter().collect::<HashSet<_>>();
 
+            let mut levels_to_spawn: Vec<_> = level_set_as_ref.difference(&previous_iids).collect();
+            let mut levels_to_despawn: Vec<_> =
+                previous_iids.difference(&level_set_as_ref).collect();
+
+            if ldtk_settings.deterministic_spawning == DeterministicSpawning::Enabled {
+                levels_to_spawn.sort_by(|a, b| a.as_str().cmp(b.as_str()));
+                levels_to_despawn.sort_by(|a, b| a.as_str().cmp(b.as_str()));
+            }
+
             // Spawn levels that should be spawned but aren't
-            let spawned_levels = level_set_as_ref
-                .difference(&previous_iids)
-                .filter_map(|&iid| project.get_raw_level_by_iid(iid.get()))
-                .map(|level| {
+            let spawned_levels = levels_to_spawn
+                .into_iter()
+                .filter(|&&iid| {
+                    // Lazily-loaded level files may not have loaded yet, so wait for them
+                    #[cfg(feature = "external_levels")]
+                    if let (LdtkProjectData::Parent(project), None) =
+                        (project.data(), level_duplicates.get(iid))
+                    {
+                        return project.level_map().get(iid.get()).map_or(true, |metadata| {
+                            level_assets.contains(metadata.external_handle())
+                        });
+                    }
+
+                    true
+                })
+                .filter_map(|&iid| match level_duplicates.get(iid) {
+                    Some(duplicate) => Some((&duplicate.level, Some(duplicate.transform))),
+                    None => Some((
+                        project.get_raw_level_by_iid(iid.get())?,
+                        spawn_overrides
+                            .get(iid)
+                            .and_then(|spawn_override| spawn_override.transform),
+                    )),
+                })
+                .map(|(level, transform)| {
                     level_events.send(LevelEvent::SpawnTriggered(LevelIid::new(level.iid.clone())));
-                    pre_spawn_level(&mut commands, level, &ldtk_settings)
+                    pre_spawn_level(&mut commands, level, transform, &ldtk_settings)
                 })
                 .collect::<Vec<_>>();
 
             commands.entity(world_entity).push_children(&spawned_levels);
 
             // Despawn levels that shouldn't be spawned but are
-            for &iid in previous_iids.difference(&level_set_as_ref) {
+            for &iid in levels_to_despawn {
                 let map_entity = previous_level_maps.get(iid).expect(
                 "The set of previous_iids and the keys in previous_level_maps should be the same.",
             );
@@ -178,90 +411,265 @@ pub fn apply_level_set(
     }
 }
 
-fn pre_spawn_level(commands: &mut Commands, level: &Level, ldtk_settings: &LdtkSettings) -> Entity {
-    let mut translation = Vec3::ZERO;
+fn pre_spawn_level(
+    commands: &mut Commands,
+    level: &Level,
+    transform: Option<Transform>,
+    ldtk_settings: &LdtkSettings,
+) -> Entity {
+    let transform = match ldtk_settings.level_spawn_behavior {
+        LevelSpawnBehavior::UseWorldTranslation { .. } => {
+            let level_coords = ldtk_pixel_coords_to_translation(
+                IVec2::new(level.world_x, level.world_y + level.px_hei),
+                0,
+            );
 
-    if let LevelSpawnBehavior::UseWorldTranslation { .. } = ldtk_settings.level_spawn_behavior {
-        let level_coords = ldtk_pixel_coords_to_translation(
-            IVec2::new(level.world_x, level.world_y + level.px_hei),
-            0,
-        );
-        translation.x = level_coords.x;
-        translation.y = level_coords.y;
-    }
+            transform.unwrap_or(Transform::from_translation(level_coords.extend(0.)))
+        }
+        LevelSpawnBehavior::UseZeroTranslation => {
+            let anchor_offset = ldtk_settings
+                .level_anchor
+                .offset(Vec2::new(level.px_wid as f32, level.px_hei as f32));
+
+            transform
+                .unwrap_or_default()
+                .mul_transform(Transform::from_translation(-anchor_offset.extend(0.)))
+        }
+    };
 
     commands
         .spawn(LevelIid::new(level.iid.clone()))
         .insert(SpatialBundle {
-            transform: Transform::from_translation(translation),
+            transform,
             ..default()
         })
         .insert(Name::new(level.identifier.clone()))
         .id()
 }
 
+/// Resources that change the data of levels as they spawn, or provide the entities they spawn.
+///
+/// Grouped into one parameter to keep [process_ldtk_levels] within the system parameter limit.
+#[derive(SystemParam)]
+pub struct LevelModifiers<'w> {
+    level_duplicates: Res<'w, LevelDuplicates>,
+    localization: Res<'w, LdtkLocalization>,
+    persistent_entity_state: Res<'w, PersistentEntityState>,
+    spawn_overrides: Res<'w, LevelSpawnOverrides>,
+    entity_pool: ResMut<'w, LdtkEntityPool>,
+    run_seed: Option<Res<'w, LevelRunSeed>>,
+    z_ordering: Res<'w, LdtkZOrdering>,
+    deferred_bundles: ResMut<'w, DeferredLdtkBundles>,
+    entity_instance_groups: ResMut<'w, EntityInstanceGroups>,
+}
+
+/// Queries used to decide which levels are spawned this frame, according to the [SpawnBudget].
+///
+/// Grouped into one parameter to keep [process_ldtk_levels] within the system parameter limit.
+#[derive(SystemParam)]
+pub struct LevelSpawnQueue<'w, 's> {
+    worldly_query: Query<'w, 's, &'static Worldly>,
+    preserved_query: Query<'w, 's, (), With<PreserveOnRespawn>>,
+    world_query: Query<'w, 's, &'static GlobalTransform, With<LdtkProjectHandle>>,
+    focus_query: Query<'w, 's, &'static GlobalTransform, With<SpawnFocus>>,
+    camera_query: Query<'w, 's, (&'static Camera, &'static GlobalTransform)>,
+}
+
+impl LevelSpawnQueue<'_, '_> {
+    /// The points that levels are prioritized by, every [SpawnFocus] or else every active camera.
+    fn focus_points(&self) -> Vec<Vec2> {
+        let focus_points: Vec<Vec2> = self
+            .focus_query
+            .iter()
+            .map(|transform| transform.translation().truncate())
+            .collect();
+
+        if !focus_points.is_empty() {
+            return focus_points;
+        }
+
+        self.camera_query
+            .iter()
+            .filter(|(camera, _)| camera.is_active)
+            .map(|(_, transform)| transform.translation().truncate())
+            .collect()
+    }
+
+    /// The world space bounds of a level that hasn't been spawned yet.
+    ///
+    /// The level's [GlobalTransform] may not have been propagated yet, so it's computed from its
+    /// world's.
+    fn level_bounds(&self, parent: &Parent, transform: &Transform, level_size: Vec2) -> Rect {
+        let world_transform = self
+            .world_query
+            .get(parent.get())
+            .copied()
+            .unwrap_or_default();
+        let min = world_transform
+            .mul_transform(*transform)
+            .translation()
+            .truncate();
+
+        Rect::from_corners(min, min + level_size)
+    }
+}
+
+/// The smallest distance between any of the points and the rect, or infinity if there are none.
+fn distance_to_rect(points: &[Vec2], rect: Rect) -> f32 {
+    points
+        .iter()
+        .map(|point| point.distance(point.clamp(rect.min, rect.max)))
+        .fold(f32::INFINITY, f32::min)
+}
+
+/// Sorts the pending levels nearest first, and splits off the ones beyond the per-frame budget.
+///
+/// Ties are broken by iid so that the order stays deterministic.
+fn split_off_over_budget<T>(
+    pending_levels: &mut Vec<T>,
+    levels_per_frame: usize,
+    distance: impl Fn(&T) -> f32,
+    level_iid: impl Fn(&T) -> &str,
+) -> Vec<T> {
+    let levels_per_frame = levels_per_frame.max(1);
+
+    if pending_levels.len() <= levels_per_frame {
+        return Vec::new();
+    }
+
+    pending_levels.sort_by(|a, b| {
+        distance(a)
+            .total_cmp(&distance(b))
+            .then_with(|| level_iid(a).cmp(level_iid(b)))
+    });
+
+    pending_levels.split_off(levels_per_frame)
+}
+
 /// Performs all the spawning of levels, layers, chunks, bundles, entities, tiles, etc. when a
 /// LevelIid is added or respawned.
 #[allow(clippy::too_many_arguments, clippy::type_complexity)]
 pub fn process_ldtk_levels(
     mut commands: Commands,
     asset_server: Res<AssetServer>,
-    images: ResMut<Assets<Image>>,
+    mut images: ResMut<Assets<Image>>,
     mut texture_atlases: ResMut<Assets<TextureAtlas>>,
     ldtk_project_assets: Res<Assets<LdtkProject>>,
     #[cfg(feature = "external_levels")] level_assets: Res<Assets<LdtkExternalLevel>>,
     ldtk_entity_map: NonSend<LdtkEntityMap>,
     ldtk_int_cell_map: NonSend<LdtkIntCellMap>,
-    ldtk_query: Query<&Handle<LdtkProject>>,
+    ldtk_query: Query<(&LdtkProjectHandle, Option<&FieldOverrides>)>,
     level_query: Query<
         (
             Entity,
             &LevelIid,
             &Parent,
+            &Transform,
             Option<&Respawn>,
             Option<&Children>,
+            Option<&DeferredLevelSpawn>,
         ),
-        Or<(Added<LevelIid>, With<Respawn>)>,
+        Or<(Added<LevelIid>, With<Respawn>, With<DeferredLevelSpawn>)>,
     >,
-    worldly_query: Query<&Worldly>,
+    level_spawn_queue: LevelSpawnQueue,
     mut level_events: EventWriter<LevelEvent>,
     ldtk_settings: Res<LdtkSettings>,
+    mut level_modifiers: LevelModifiers,
+    mut error_reporter: LdtkErrorReporter,
+    mut level_composites: Option<ResMut<LevelComposites>>,
 ) {
-    for (ldtk_entity, level_iid, parent, respawn, children) in level_query.iter() {
-        // Checking if the level has any children is an okay method of checking whether it has
-        // already been processed.
-        // Users will most likely not be adding children to the level entity betwen its creation
-        // and its processing.
-        //
-        // Furthermore, there are no circumstances where an already-processed level entity needs to
-        // be processed again.
-        // In the case of respawning levels, the level entity will have its descendants *despawned*
-        // first, by a separate system.
-        let already_processed = matches!(children, Some(children) if !children.is_empty());
-
-        if !already_processed {
-            if let Ok(ldtk_handle) = ldtk_query.get(parent.get()) {
-                if let Some(ldtk_project) = ldtk_project_assets.get(ldtk_handle) {
-                    // Commence the spawning
-                    let tileset_definition_map: HashMap<i32, &TilesetDefinition> = ldtk_project
-                        .json_data()
-                        .defs
-                        .tilesets
-                        .iter()
-                        .map(|t| (t.uid, t))
-                        .collect();
-
-                    let entity_definition_map =
-                        create_entity_definition_map(&ldtk_project.json_data().defs.entities);
-
-                    let layer_definition_map =
-                        create_layer_definition_map(&ldtk_project.json_data().defs.layers);
-
-                    let int_grid_image_handle = &ldtk_project.int_grid_image_handle();
-
-                    let worldly_set = worldly_query.iter().cloned().collect();
-
-                    let maybe_level_data = match ldtk_project.data() {
+    let mut pending_levels: Vec<_> = level_query
+        .iter()
+        .filter(|(.., children, _)| {
+            // Checking if the level has any children is an okay method of checking whether it has
+            // already been processed.
+            // Users will most likely not be adding children to the level entity betwen its
+            // creation and its processing.
+            //
+            // Furthermore, there are no circumstances where an already-processed level entity
+            // needs to be processed again.
+            // In the case of respawning levels, the level entity will have its descendants
+            // *despawned* first, by a separate system.
+            // Only children kept with PreserveOnRespawn can remain.
+            let already_processed = children.is_some_and(|children| {
+                children
+                    .iter()
+                    .any(|child| !level_spawn_queue.preserved_query.contains(*child))
+            });
+
+            !already_processed
+        })
+        .collect();
+
+    if let SpawnBudget::LevelsPerFrame(levels_per_frame) = ldtk_settings.spawn_budget {
+        if pending_levels.len() > levels_per_frame.max(1) {
+            let focus_points = level_spawn_queue.focus_points();
+
+            let mut distances: HashMap<Entity, f32> = HashMap::new();
+            for (level_entity, level_iid, parent, transform, ..) in pending_levels.iter() {
+                let level_size = match level_modifiers.level_duplicates.get(level_iid) {
+                    Some(duplicate) => Some(&duplicate.level),
+                    None => ldtk_query
+                        .get(parent.get())
+                        .ok()
+                        .and_then(|(ldtk_handle, _)| ldtk_project_assets.get(ldtk_handle))
+                        .and_then(|ldtk_project| {
+                            ldtk_project.get_raw_level_by_iid(level_iid.get())
+                        }),
+                }
+                .map(|level| IVec2::new(level.px_wid, level.px_hei).as_vec2())
+                .unwrap_or_default();
+
+                let bounds = level_spawn_queue.level_bounds(parent, transform, level_size);
+                distances.insert(*level_entity, distance_to_rect(&focus_points, bounds));
+            }
+
+            let over_budget = split_off_over_budget(
+                &mut pending_levels,
+                levels_per_frame,
+                |level| distances[&level.0],
+                |level| level.1.get(),
+            );
+
+            for (level_entity, _, _, _, respawn, ..) in over_budget {
+                let mut entity_commands = commands.entity(level_entity);
+                entity_commands.insert(DeferredLevelSpawn);
+
+                // The level has already been cleaned, it shouldn't be cleaned again every frame
+                if respawn.is_some() {
+                    entity_commands.remove::<Respawn>();
+                }
+            }
+        }
+    }
+
+    for (ldtk_entity, level_iid, parent, _, respawn, _, deferred) in pending_levels {
+        if let Ok((ldtk_handle, field_overrides)) = ldtk_query.get(parent.get()) {
+            if let Some(ldtk_project) = ldtk_project_assets.get(ldtk_handle) {
+                // Commence the spawning
+                let tileset_definition_map: HashMap<i32, &TilesetDefinition> = ldtk_project
+                    .json_data()
+                    .defs
+                    .tilesets
+                    .iter()
+                    .map(|t| (t.uid, t))
+                    .collect();
+
+                let entity_definition_map =
+                    create_entity_definition_map(&ldtk_project.json_data().defs.entities);
+
+                let layer_definition_map =
+                    create_layer_definition_map(&ldtk_project.json_data().defs.layers);
+
+                let int_grid_image_handle = &ldtk_project.int_grid_image_handle();
+
+                let worldly_set = level_spawn_queue.worldly_query.iter().cloned().collect();
+
+                let maybe_level_data = match level_modifiers.level_duplicates.get(level_iid) {
+                    Some(duplicate) => ldtk_project
+                        .get_level_metadata_by_iid(duplicate.source.get())
+                        .zip(LoadedLevel::try_from(&duplicate.level).ok()),
+                    None => match ldtk_project.data() {
                         #[cfg(feature = "internal_levels")]
                         LdtkProjectData::Standalone(project) => project
                             .level_map()
@@ -284,35 +692,123 @@ pub fn process_ldtk_levels(
 
                                 Some((level_metadata.metadata(), loaded_level))
                             }),
-                    };
+                    },
+                };
 
-                    if let Some((level_metadata, loaded_level)) = maybe_level_data {
-                        spawn_level(
-                            loaded_level,
-                            level_metadata.bg_image(),
-                            &mut commands,
-                            &asset_server,
-                            &images,
-                            &mut texture_atlases,
-                            &ldtk_entity_map,
-                            &ldtk_int_cell_map,
-                            &entity_definition_map,
-                            &layer_definition_map,
-                            ldtk_project.tileset_map(),
-                            &tileset_definition_map,
+                let modified_level = maybe_level_data.and_then(|(_, loaded_level)| {
+                    let overridden_level = field_overrides
+                        .and_then(|overrides| overrides.apply_to_level(loaded_level.raw()));
+
+                    level_modifiers
+                        .localization
+                        .localize_level(overridden_level.as_ref().unwrap_or(loaded_level.raw()))
+                        .or(overridden_level)
+                });
+
+                let maybe_level_data = match &modified_level {
+                    Some(level) => maybe_level_data.and_then(|(level_metadata, _)| {
+                        Some((level_metadata, LoadedLevel::try_from(level).ok()?))
+                    }),
+                    None => maybe_level_data,
+                };
+
+                if let Some((level_metadata, loaded_level)) = maybe_level_data {
+                    let seeded_settings = level_modifiers
+                        .spawn_overrides
+                        .get(level_iid)
+                        .and_then(|spawn_override| spawn_override.seed)
+                        .map(|seed| {
+                            let mut seeded_settings = ldtk_settings.clone();
+                            seeded_settings.level_variation.seed = seed;
+                            seeded_settings
+                        });
+
+                    let errors = spawn_level(
+                        &mut commands,
+                        loaded_level,
+                        ldtk_entity,
+                        LevelSpawnContext {
+                            ldtk_settings: seeded_settings.as_ref().unwrap_or(&ldtk_settings),
+                            project_iid: &ldtk_project.json_data().iid,
+                            entity_definition_map: &entity_definition_map,
+                            layer_definition_map: &layer_definition_map,
+                            tileset_definition_map: &tileset_definition_map,
+                            tileset_map: ldtk_project.tileset_map(),
+                            z_ordering: level_modifiers.z_ordering.0.as_ref(),
+                        },
+                        RenderPhaseContext {
+                            asset_server: &asset_server,
+                            images: &mut images,
+                            texture_atlases: &mut texture_atlases,
+                            background_image: level_metadata.bg_image(),
                             int_grid_image_handle,
+                        },
+                        LogicPhaseContext {
                             worldly_set,
-                            ldtk_entity,
-                            &ldtk_settings,
-                        );
-                        level_events.send(LevelEvent::Spawned(LevelIid::new(
-                            loaded_level.iid().clone(),
-                        )));
+                            persistent_entity_state: &level_modifiers.persistent_entity_state,
+                            entity_pool: &mut level_modifiers.entity_pool,
+                        },
+                        BundlePhaseContext {
+                            ldtk_entity_map: &ldtk_entity_map,
+                            ldtk_int_cell_map: &ldtk_int_cell_map,
+                            entity_instance_groups: level_modifiers
+                                .entity_instance_groups
+                                .level(ldtk_handle, level_iid),
+                            deferred_bundles: &mut level_modifiers.deferred_bundles,
+                        },
+                    );
+
+                    let level_seed = LevelSeed::new(&ldtk_project.json_data().iid, level_iid.get());
+                    commands
+                        .entity(ldtk_entity)
+                        .insert(match &level_modifiers.run_seed {
+                            Some(run_seed) => level_seed.with_run_seed(run_seed.0),
+                            None => level_seed,
+                        });
+
+                    if !ldtk_project.on_demand_tilesets().is_empty() {
+                        commands.entity(ldtk_entity).insert(LevelTilesets::load(
+                            &loaded_level,
+                            ldtk_project.on_demand_tilesets(),
+                            &asset_server,
+                        ));
+                    }
+
+                    for error in errors {
+                        error_reporter.report(error);
                     }
 
-                    if respawn.is_some() {
-                        commands.entity(ldtk_entity).remove::<Respawn>();
+                    if let Some(level_composites) = level_composites.as_mut() {
+                        let _span = info_span!("compose_level").entered();
+
+                        match compose_level(
+                            &loaded_level,
+                            ldtk_project,
+                            &images,
+                            &level_composites.options,
+                        ) {
+                            Ok(image) => {
+                                let handle = images.add(image);
+                                level_composites
+                                    .images
+                                    .insert(loaded_level.iid().clone(), handle);
+                            }
+                            Err(e) => warn!("unable to composite level: {}", e),
+                        }
                     }
+
+                    level_events.send(LevelEvent::Spawned(LevelIid::new(
+                        loaded_level.iid().clone(),
+                    )));
+                }
+
+                if respawn.is_some() {
+                    commands.entity(ldtk_entity).remove::<Respawn>();
+                }
+
+                // Kept until the project is loaded, otherwise nothing would pick the level up again
+                if deferred.is_some() {
+                    commands.entity(ldtk_entity).remove::<DeferredLevelSpawn>();
                 }
             }
         }
@@ -327,7 +823,7 @@ pub fn process_ldtk_levels(
 pub fn clean_respawn_entities(world: &mut World) {
     #[allow(clippy::type_complexity)]
     let mut system_state: SystemState<(
-        Query<&Children, (With<Handle<LdtkProject>>, With<Respawn>)>,
+        Query<&Children, (With<LdtkProjectHandle>, With<Respawn>)>,
         Query<(Entity, &LevelIid), With<Respawn>>,
         Query<&LevelIid, Without<Respawn>>,
         Query<Entity, With<Worldly>>,
@@ -366,13 +862,55 @@ pub fn clean_respawn_entities(world: &mut World) {
         }
     }
 
+    let entity_pooling = world.resource::<LdtkSettings>().entity_pooling;
+
     for entity in entities_to_despawn_recursively {
+        if entity_pooling == EntityPooling::Enabled && world.get::<LevelIid>(entity).is_some() {
+            recycle_descendants(world, entity);
+        }
+
         world.entity_mut(entity).despawn_recursive();
     }
 
     for entity in entities_to_despawn_descendants {
-        world.entity_mut(entity).despawn_descendants();
+        // Preserved descendants are detached while the rest are despawned, then given back
+        let preserved = preserved_descendants(world, entity);
+        for preserved_entity in &preserved {
+            world.entity_mut(*preserved_entity).remove_parent_in_place();
+        }
+
+        match entity_pooling {
+            EntityPooling::Enabled => recycle_descendants(world, entity),
+            EntityPooling::Disabled => {
+                world.entity_mut(entity).despawn_descendants();
+            }
+        }
+
+        for preserved_entity in preserved {
+            world
+                .entity_mut(preserved_entity)
+                .set_parent_in_place(entity);
+        }
+    }
+}
+
+/// The descendants of an entity with [PreserveOnRespawn], excluding those that are already kept
+/// by a preserved ancestor.
+fn preserved_descendants(world: &World, entity: Entity) -> Vec<Entity> {
+    let mut preserved = Vec::new();
+    let mut to_visit = vec![entity];
+
+    while let Some(parent) = to_visit.pop() {
+        for child in world.get::<Children>(parent).into_iter().flatten() {
+            if world.get::<PreserveOnRespawn>(*child).is_some() {
+                preserved.push(*child);
+            } else {
+                to_visit.push(*child);
+            }
+        }
     }
+
+    preserved
 }
 
 /// Implements the functionality for `Worldly` components.
@@ -393,31 +931,1350 @@ pub fn worldly_adoption(
         } else {
             commands.entity(worldly_entity).remove_parent_in_place();
         }
+
+        commands.entity(worldly_entity).remove::<OwningLevel>();
     }
 }
 
-/// Returns the `iid`s of levels that have spawned in this update.
+/// Starts loading the on-demand tilesets of selected levels and their neighbors, according to
+/// [TilesetPrewarming].
+pub fn prewarm_tilesets(
+    ldtk_settings: Res<LdtkSettings>,
+    mut prewarmed_tilesets: ResMut<PrewarmedTilesets>,
+    world_query: Query<(&LevelSet, &LdtkProjectHandle)>,
+    ldtk_project_assets: Res<Assets<LdtkProject>>,
+    #[cfg(feature = "external_levels")] level_assets: Res<Assets<LdtkExternalLevel>>,
+    asset_server: Res<AssetServer>,
+) {
+    let TilesetPrewarming::Enabled {
+        tilesets_per_update,
+    } = ldtk_settings.tileset_prewarming
+    else {
+        return;
+    };
+
+    #[cfg(feature = "external_levels")]
+    let external_level_assets = Some(level_assets.as_ref());
+    #[cfg(not(feature = "external_levels"))]
+    let external_level_assets = None;
+
+    let mut wanted = Vec::new();
+
+    for (level_set, project_handle) in world_query.iter() {
+        let Some(ldtk_project) = ldtk_project_assets.get(project_handle) else {
+            continue;
+        };
+
+        if ldtk_project.on_demand_tilesets().is_empty() {
+            continue;
+        }
+
+        let neighbor_iids = level_set.iids.iter().flat_map(|level_iid| {
+            ldtk_project
+                .get_raw_level_by_iid(level_iid.get())
+                .into_iter()
+                .flat_map(|level| level.neighbours.iter())
+                .map(|neighbour| neighbour.level_iid.clone())
+        });
+
+        let level_iids: BTreeSet<String> = level_set
+            .iids
+            .iter()
+            .map(|level_iid| level_iid.get().clone())
+            .chain(neighbor_iids)
+            .collect();
+
+        for level_iid in level_iids.iter() {
+            let Some(level) =
+                ldtk_project.get_loaded_level_by_iid(external_level_assets, level_iid)
+            else {
+                continue;
+            };
+
+            for asset_path in used_tileset_uids(&level)
+                .into_iter()
+                .filter_map(|tileset_uid| ldtk_project.on_demand_tilesets().get(&tileset_uid))
+            {
+                if !wanted.contains(asset_path) {
+                    wanted.push(asset_path.clone());
+                }
+            }
+        }
+    }
+
+    prewarmed_tilesets.update(wanted, tilesets_per_update, |asset_path| {
+        asset_server.load(asset_path.clone())
+    });
+}
+
+/// Fires [LdtkEntityDespawned] events for LDtk entities that despawned since the last update.
 ///
-/// Mean to be used in a chain with [fire_level_transformed_events].
-pub fn detect_level_spawned_events(mut reader: EventReader<LevelEvent>) -> Vec<LevelIid> {
-    let mut spawned_ids = Vec::new();
-    for event in reader.iter() {
-        if let LevelEvent::Spawned(id) = event {
-            spawned_ids.push(id.clone());
+/// Entities are tracked as they spawn, so their iids can still be reported after they despawn.
+pub fn track_ldtk_entity_despawns(
+    spawned_query: Query<(Entity, &EntityIid), Added<EntityIid>>,
+    mut removed_iids: RemovedComponents<EntityIid>,
+    ancestors: Query<&Parent>,
+    level_query: Query<&LevelIid>,
+    level_set_query: Query<&LevelSet>,
+    mut level_events: EventReader<LevelEvent>,
+    mut despawn_events: EventWriter<LdtkEntityDespawned>,
+    mut tracked: Local<TrackedLdtkEntities>,
+) {
+    let despawned_levels: HashSet<LevelIid> = level_events
+        .iter()
+        .filter_map(|event| match event {
+            LevelEvent::Despawned(level_iid) => Some(level_iid.clone()),
+            _ => None,
+        })
+        .collect();
+
+    let is_level_selected = |level_iid: &LevelIid| {
+        level_set_query
+            .iter()
+            .any(|level_set| level_set.iids.contains(level_iid))
+    };
+
+    for entity in removed_iids.iter() {
+        if let Some(event) = tracked.untrack(entity, &despawned_levels, is_level_selected) {
+            despawn_events.send(event);
         }
     }
-    spawned_ids
+
+    for (entity, entity_iid) in spawned_query.iter() {
+        if let Some(level_iid) = ancestors
+            .iter_ancestors(entity)
+            .find_map(|ancestor| level_query.get(ancestor).ok())
+        {
+            tracked.track(entity, entity_iid.clone(), level_iid.clone());
+        }
+    }
+}
+
+/// Clears the [EntityInstanceGroups] when the levels they were grouped from may have changed.
+pub fn clear_entity_instance_groups(
+    mut entity_instance_groups: ResMut<EntityInstanceGroups>,
+    mut ldtk_project_events: EventReader<AssetEvent<LdtkProject>>,
+    #[cfg(feature = "external_levels")] mut level_asset_events: EventReader<
+        AssetEvent<LdtkExternalLevel>,
+    >,
+    level_duplicates: Res<LevelDuplicates>,
+) {
+    let projects_changed = ldtk_project_events
+        .iter()
+        .filter(|event| !matches!(event, AssetEvent::Created { .. }))
+        .count()
+        > 0;
+
+    #[cfg(feature = "external_levels")]
+    let external_levels_changed = level_asset_events
+        .iter()
+        .filter(|event| !matches!(event, AssetEvent::Created { .. }))
+        .count()
+        > 0;
+    #[cfg(not(feature = "external_levels"))]
+    let external_levels_changed = false;
+
+    if projects_changed || external_levels_changed || level_duplicates.is_changed() {
+        entity_instance_groups.clear();
+    }
 }
 
-/// Fires [LevelEvent::Transformed] events for all the entities that spawned in the previous
-/// update.
+/// Inserts the bundles queued in [DeferredLdtkBundles], for the bundle phase of
+/// [SpawnPhases](crate::prelude::SpawnPhases).
 ///
-/// Meant to be used in a chain with [detect_level_spawned_events].
-pub fn fire_level_transformed_events(
-    In(spawned_ids): In<Vec<LevelIid>>,
-    mut writer: EventWriter<LevelEvent>,
+/// Bundles are inserted like they are while levels spawn, so they can't move or hide their
+/// entities, other than with a [TransformOverride].
+#[allow(clippy::too_many_arguments)]
+pub fn insert_deferred_bundles(
+    mut commands: Commands,
+    mut deferred_bundles: ResMut<DeferredLdtkBundles>,
+    ldtk_entity_map: NonSend<LdtkEntityMap>,
+    ldtk_int_cell_map: NonSend<LdtkIntCellMap>,
+    asset_server: Res<AssetServer>,
+    mut texture_atlases: ResMut<Assets<TextureAtlas>>,
+    spatial_query: Query<(&Transform, &Visibility)>,
 ) {
-    for id in spawned_ids {
-        writer.send(LevelEvent::Transformed(id));
+    if deferred_bundles.is_empty() {
+        return;
+    }
+
+    let default_ldtk_entity: Box<dyn PhantomLdtkEntityTrait> =
+        Box::new(PhantomLdtkEntity::<EntityInstanceBundle>::new());
+    let default_ldtk_int_cell: Box<dyn PhantomLdtkIntCellTrait> =
+        Box::new(PhantomLdtkIntCell::<IntGridCellBundle>::new());
+
+    let (deferred_entity_groups, deferred_int_cells) = deferred_bundles.take();
+
+    for deferred_group in deferred_entity_groups {
+        let registration = registration_or_default(
+            deferred_group.registration.as_ref(),
+            default_ldtk_entity.as_ref(),
+            &ldtk_entity_map,
+        );
+
+        for deferred in deferred_group.bundles {
+            // The entity may have been despawned while it waited
+            let Some(mut entity_commands) = commands.get_entity(deferred.entity) else {
+                continue;
+            };
+
+            registration.evaluate(
+                &mut entity_commands,
+                &deferred.entity_instance,
+                &deferred.layer_instance,
+                deferred.tileset.as_ref(),
+                deferred.tileset_definition.as_deref(),
+                &asset_server,
+                &mut texture_atlases,
+            );
+
+            if let Ok((transform, visibility)) = spatial_query.get(deferred.entity) {
+                entity_commands.insert(SpatialBundle {
+                    transform: *transform,
+                    visibility: *visibility,
+                    ..default()
+                });
+            }
+            entity_commands.add(apply_transform_override);
+        }
+    }
+
+    for deferred in deferred_int_cells {
+        let Some(mut entity_commands) = commands.get_entity(deferred.entity) else {
+            continue;
+        };
+
+        ldtk_map_get_or_default(
+            deferred.layer_instance.identifier.clone(),
+            deferred.int_grid_cell.value,
+            &default_ldtk_int_cell,
+            &*ldtk_int_cell_map,
+        )
+        .evaluate(
+            &mut entity_commands,
+            deferred.int_grid_cell,
+            &deferred.layer_instance,
+            &deferred.layer_definition,
+        );
+    }
+}
+
+/// Keeps the [LdtkLevelIndex] up to date with the LDtk entities and layers of spawned levels.
+#[allow(clippy::type_complexity)]
+pub fn update_level_index(
+    mut level_index: ResMut<LdtkLevelIndex>,
+    spawned_entities: Query<Entity, (Added<EntityIid>, Without<Worldly>)>,
+    spawned_layers: Query<(Entity, &Parent), Added<LayerMetadata>>,
+    adopted_entities: Query<Entity, (Added<Worldly>, With<EntityIid>)>,
+    mut removed_iids: RemovedComponents<EntityIid>,
+    mut removed_layers: RemovedComponents<LayerMetadata>,
+    ancestors: Query<&Parent>,
+    level_query: Query<&LevelIid>,
+) {
+    for entity in removed_iids
+        .iter()
+        .chain(removed_layers.iter())
+        .chain(adopted_entities.iter())
+    {
+        level_index.remove(entity);
+    }
+
+    for entity in spawned_entities.iter() {
+        if let Some(level_iid) = ancestors
+            .iter_ancestors(entity)
+            .find_map(|ancestor| level_query.get(ancestor).ok())
+        {
+            level_index.insert_entity(entity, level_iid.clone());
+        }
+    }
+
+    for (layer_entity, parent) in spawned_layers.iter() {
+        if let Ok(level_iid) = level_query.get(parent.get()) {
+            level_index.insert_layer(layer_entity, level_iid.clone());
+        }
+    }
+}
+
+/// Keeps [ReferencedBy] components in sync with the [EntityReferences] of spawned LDtk entities.
+///
+/// Only does work when LDtk entities or their references have been added or removed this update,
+/// in which case all backlinks are recalculated.
+#[allow(clippy::type_complexity)]
+pub fn update_referenced_by(
+    mut commands: Commands,
+    changed_query: Query<(), Or<(Added<EntityIid>, Added<EntityReferences>)>>,
+    mut removed_iids: RemovedComponents<EntityIid>,
+    mut removed_references: RemovedComponents<EntityReferences>,
+    iid_query: Query<(Entity, &EntityIid, Option<&ReferencedBy>)>,
+    references_query: Query<(Entity, &EntityReferences)>,
+) {
+    let removed = removed_iids.iter().count() + removed_references.iter().count() > 0;
+
+    if changed_query.is_empty() && !removed {
+        return;
+    }
+
+    let iid_map: HashMap<&EntityIid, Entity> = iid_query
+        .iter()
+        .map(|(entity, iid, _)| (iid, entity))
+        .collect();
+
+    let mut backlinks: HashMap<Entity, BTreeSet<Entity>> = HashMap::new();
+
+    for (referencing_entity, entity_references) in references_query.iter() {
+        for target in entity_references
+            .iids
+            .iter()
+            .filter_map(|iid| iid_map.get(iid))
+        {
+            backlinks
+                .entry(*target)
+                .or_default()
+                .insert(referencing_entity);
+        }
+    }
+
+    for (entity, _, referenced_by) in iid_query.iter() {
+        match (backlinks.remove(&entity), referenced_by) {
+            (Some(referencing_entities), referenced_by) => {
+                let new_referenced_by = ReferencedBy(referencing_entities.into_iter().collect());
+
+                if referenced_by != Some(&new_referenced_by) {
+                    commands.entity(entity).insert(new_referenced_by);
+                }
+            }
+            (None, Some(_)) => {
+                commands.entity(entity).remove::<ReferencedBy>();
+            }
+            (None, None) => (),
+        }
+    }
+}
+
+/// Swaps the standard tilemap material of newly spawned layers for their registered custom
+/// material, according to [LdtkTilemapMaterials].
+///
+/// Materials registered for a [MaterialEnumTag] take priority over materials registered for the
+/// layer.
+///
+/// Added to the app by [LdtkTilemapMaterialAppExt] once per material type.
+///
+/// [LdtkTilemapMaterialAppExt]: crate::app::LdtkTilemapMaterialAppExt
+#[cfg(feature = "render")]
+#[allow(clippy::type_complexity)]
+pub fn apply_ldtk_tilemap_materials<M: MaterialTilemap>(
+    mut commands: Commands,
+    layer_materials: Res<LdtkTilemapMaterials<M>>,
+    layer_query: Query<
+        (Entity, &LayerMetadata, Option<&MaterialEnumTag>),
+        Added<Handle<StandardTilemapMaterial>>,
+    >,
+) {
+    for (layer_entity, layer_metadata, material_enum_tag) in layer_query.iter() {
+        let material = material_enum_tag
+            .and_then(|MaterialEnumTag(enum_tag)| layer_materials.enum_tag_materials.get(enum_tag))
+            .or_else(|| layer_materials.materials.get(&layer_metadata.identifier));
+
+        if let Some(material) = material {
+            commands
+                .entity(layer_entity)
+                .remove::<Handle<StandardTilemapMaterial>>()
+                .insert(material.clone());
+        }
+    }
+}
+
+/// Despawns [EditorVisualPlaceholder]s of LDtk entities that provide their own visuals.
+#[allow(clippy::type_complexity)]
+pub fn despawn_redundant_editor_visuals(
+    mut commands: Commands,
+    placeholder_query: Query<(Entity, &Parent), Added<EditorVisualPlaceholder>>,
+    visuals_query: Query<(), Or<(With<Sprite>, With<TextureAtlasSprite>)>>,
+) {
+    for (placeholder_entity, parent) in placeholder_query.iter() {
+        if visuals_query.contains(parent.get()) {
+            commands.entity(placeholder_entity).despawn_recursive();
+        }
+    }
+}
+
+/// Offsets layers with a [LayerParallax] relative to the [LdtkParallaxCamera].
+pub fn apply_layer_parallax(
+    camera_query: Query<&Transform, (With<LdtkParallaxCamera>, Without<LayerParallax>)>,
+    level_query: Query<&GlobalTransform, With<LevelIid>>,
+    mut layer_query: Query<(&LayerParallax, &Parent, &mut Transform)>,
+) {
+    let Ok(camera_transform) = camera_query.get_single() else {
+        return;
+    };
+
+    for (parallax, parent, mut transform) in layer_query.iter_mut() {
+        if let Ok(level_transform) = level_query.get(parent.get()) {
+            let camera_position = level_transform
+                .affine()
+                .inverse()
+                .transform_point3(camera_transform.translation)
+                .truncate();
+
+            let new_transform = parallax.layer_transform(camera_position);
+
+            if *transform != new_transform {
+                *transform = new_transform;
+            }
+        }
+    }
+}
+
+/// Spawns and despawns the [BackgroundTile]s of [RepeatingBackground]s so they fill the view of the
+/// [LdtkParallaxCamera].
+pub fn repeat_level_backgrounds(
+    mut commands: Commands,
+    camera_query: Query<
+        (&Transform, &OrthographicProjection),
+        (With<LdtkParallaxCamera>, Without<RepeatingBackground>),
+    >,
+    level_query: Query<&GlobalTransform, With<LevelIid>>,
+    background_query: Query<(
+        Entity,
+        &RepeatingBackground,
+        &Transform,
+        &Parent,
+        Option<&Children>,
+    )>,
+    tile_query: Query<&Transform, (With<BackgroundTile>, Without<RepeatingBackground>)>,
+) {
+    let Ok((camera_transform, projection)) = camera_query.get_single() else {
+        return;
+    };
+
+    for (entity, repeating_background, transform, parent, children) in background_query.iter() {
+        let Ok(level_transform) = level_query.get(parent.get()) else {
+            continue;
+        };
+
+        // The background's own transform is used rather than its global transform, since
+        // parallax may have just changed it
+        let background_transform = level_transform
+            .mul_transform(*transform)
+            .compute_transform();
+
+        let to_local = background_transform.compute_affine().inverse();
+        let camera_position = to_local.transform_point3(camera_transform.translation);
+        let half_view_size = projection.area.half_size() * camera_transform.scale.truncate()
+            / background_transform.scale.truncate();
+
+        let view = Rect::from_center_half_size(camera_position.truncate(), half_view_size);
+
+        let tile_centers = repeating_background.tile_centers(view);
+
+        let tiles: Vec<(Entity, Vec2)> = children
+            .into_iter()
+            .flatten()
+            .filter_map(|child| {
+                let tile_transform = tile_query.get(*child).ok()?;
+                Some((*child, tile_transform.translation.truncate()))
+            })
+            .collect();
+
+        if tiles.len() == tile_centers.len()
+            && tiles
+                .iter()
+                .all(|(_, center)| tile_centers.contains(center))
+        {
+            continue;
+        }
+
+        for (tile, _) in tiles {
+            commands.entity(tile).despawn_recursive();
+        }
+
+        commands.entity(entity).with_children(|parent| {
+            for center in tile_centers {
+                parent.spawn(repeating_background.tile_bundle(center));
+            }
+        });
+    }
+}
+
+/// Inserts the [IntGridTexture] of IntGrid layers once it has been created.
+pub(crate) fn insert_int_grid_textures(
+    mut commands: Commands,
+    mut images: ResMut<Assets<Image>>,
+    mut task_query: Query<(Entity, &mut IntGridTextureTask)>,
+) {
+    for (layer_entity, mut task) in task_query.iter_mut() {
+        if let Some(image) = task.poll() {
+            commands
+                .entity(layer_entity)
+                .remove::<IntGridTextureTask>()
+                .insert(IntGridTexture {
+                    image: images.add(image),
+                });
+        }
+    }
+}
+
+/// Keeps the z of entities with a [YSort] up to date with their y coordinate.
+///
+/// The z is placed on the band shared by the y-sorted layers of the level, so the translation of
+/// the entity's layer is factored out.
+pub fn apply_y_sort(
+    mut query: Query<(&YSort, &mut Transform, Option<&Parent>), Changed<Transform>>,
+    layer_query: Query<&Transform, Without<YSort>>,
+) {
+    for (y_sort, mut transform, parent) in query.iter_mut() {
+        let layer_translation = parent
+            .and_then(|parent| layer_query.get(parent.get()).ok())
+            .map(|layer_transform| layer_transform.translation)
+            .unwrap_or_default();
+
+        let z = y_sort.z(transform.translation.y + layer_translation.y) - layer_translation.z;
+
+        if transform.translation.z != z {
+            transform.translation.z = z;
+        }
+    }
+}
+
+/// Keeps the [LevelPostProcessing] resource in sync with the active level.
+///
+/// The active level is the one matching the [LevelSelection], or the most recently spawned level
+/// if there is no [LevelSelection].
+pub fn apply_active_level_post_processing(
+    level_selection: Option<Res<LevelSelection>>,
+    ldtk_project_assets: Res<Assets<LdtkProject>>,
+    ldtk_query: Query<&LdtkProjectHandle, Without<LevelPreview>>,
+    level_query: Query<(&LevelIid, &Parent, &LevelPostProcessing)>,
+    added_query: Query<(&Parent, &LevelPostProcessing), Added<LevelPostProcessing>>,
+    mut active_post_processing: ResMut<LevelPostProcessing>,
+) {
+    let active_level = match level_selection {
+        Some(level_selection) => {
+            if !level_selection.is_changed() && added_query.is_empty() {
+                return;
+            }
+
+            level_query
+                .iter()
+                .find_map(|(level_iid, parent, post_processing)| {
+                    let ldtk_project =
+                        ldtk_project_assets.get(ldtk_query.get(parent.get()).ok()?)?;
+
+                    let level_metadata = ldtk_project.get_level_metadata_by_iid(level_iid.get())?;
+                    let level = ldtk_project.get_raw_level_by_iid(level_iid.get())?;
+
+                    level_selection
+                        .is_match(level_metadata.indices(), level)
+                        .then_some(post_processing)
+                })
+        }
+        None => added_query
+            .iter()
+            .filter(|(parent, _)| ldtk_query.contains(parent.get()))
+            .map(|(_, post_processing)| post_processing)
+            .last(),
+    };
+
+    if let Some(post_processing) = active_level {
+        if *active_post_processing != *post_processing {
+            *active_post_processing = post_processing.clone();
+        }
+    }
+}
+
+/// Applies the active [LevelPostProcessing] to the materials registered with
+/// [LevelPostProcessingAppExt] whenever it changes.
+///
+/// Added to the app by [LevelPostProcessingAppExt] once per material type.
+///
+/// [LevelPostProcessingAppExt]: crate::app::LevelPostProcessingAppExt
+pub fn apply_level_post_processing_materials<M: Asset>(
+    post_processing: Res<LevelPostProcessing>,
+    registered: Res<LevelPostProcessingMaterials<M>>,
+    mut materials: ResMut<Assets<M>>,
+) {
+    if !post_processing.is_changed() && !registered.is_changed() {
+        return;
+    }
+
+    for (handle, apply) in registered.materials.iter() {
+        if let Some(material) = materials.get_mut(handle) {
+            apply(&post_processing, material);
+        }
+    }
+}
+
+/// Calls the [LevelFieldCallbacks] of the fields of the selected level, when it changes.
+pub fn call_level_field_callbacks(
+    mut commands: Commands,
+    level_selection: Option<Res<LevelSelection>>,
+    ldtk_project_assets: Res<Assets<LdtkProject>>,
+    ldtk_query: Query<&LdtkProjectHandle, Without<LevelPreview>>,
+    level_field_callbacks: Res<LevelFieldCallbacks>,
+    mut active_level: Local<Option<(LevelSelection, String, Vec<FieldInstance>)>>,
+) {
+    let Some(level_selection) = level_selection else {
+        return;
+    };
+
+    // The selection is resolved again until its project has loaded
+    if active_level
+        .as_ref()
+        .map_or(false, |(selection, _, _)| *selection == *level_selection)
+    {
+        return;
+    }
+
+    let Some(level) = ldtk_query.iter().find_map(|handle| {
+        ldtk_project_assets
+            .get(handle)?
+            .find_raw_level_by_level_selection(&level_selection)
+    }) else {
+        return;
+    };
+
+    let previous_fields = active_level
+        .as_ref()
+        .filter(|(_, previous_iid, _)| *previous_iid != level.iid)
+        .map(|(_, _, previous_fields)| previous_fields);
+
+    if previous_fields.is_some() || active_level.is_none() {
+        let level_iid = LevelIid::new(level.iid.clone());
+
+        for (field_identifier, callback) in level_field_callbacks.callbacks.iter() {
+            let Some(field_instance) = level
+                .field_instances
+                .iter()
+                .find(|field_instance| field_instance.identifier == *field_identifier)
+            else {
+                continue;
+            };
+
+            let previous = previous_fields.and_then(|previous_fields| {
+                previous_fields
+                    .iter()
+                    .find(|field_instance| field_instance.identifier == *field_identifier)
+                    .map(|field_instance| &field_instance.value)
+            });
+
+            callback(
+                &mut commands,
+                &LevelFieldChange {
+                    level_iid: &level_iid,
+                    value: &field_instance.value,
+                    previous,
+                },
+            );
+        }
+    }
+
+    *active_level = Some((
+        level_selection.clone(),
+        level.iid.clone(),
+        level.field_instances.clone(),
+    ));
+}
+
+/// Calls the [ProjectConfigCallbacks] registered for the configuration sources of newly loaded or
+/// modified projects.
+pub fn call_project_config_callbacks(
+    mut commands: Commands,
+    mut ldtk_project_events: EventReader<AssetEvent<LdtkProject>>,
+    ldtk_project_assets: Res<Assets<LdtkProject>>,
+    project_config_callbacks: Res<ProjectConfigCallbacks>,
+) {
+    for event in ldtk_project_events.iter() {
+        let (AssetEvent::Created { handle } | AssetEvent::Modified { handle }) = event else {
+            continue;
+        };
+
+        let Some(ldtk_project) = ldtk_project_assets.get(handle) else {
+            continue;
+        };
+
+        for (source, callback) in project_config_callbacks.callbacks.iter() {
+            if let Some(fields) = source.find_fields(ldtk_project) {
+                callback(&mut commands, fields);
+            }
+        }
+    }
+}
+
+/// Calls the [LayerDefinitionCallbacks] registered for the identifiers of newly spawned layers.
+pub fn call_layer_definition_callbacks(
+    mut commands: Commands,
+    layer_query: Query<(Entity, &LayerMetadata, &Parent), Added<LayerMetadata>>,
+    parent_query: Query<&Parent>,
+    ldtk_query: Query<&LdtkProjectHandle>,
+    ldtk_project_assets: Res<Assets<LdtkProject>>,
+    layer_definition_callbacks: Res<LayerDefinitionCallbacks>,
+) {
+    for (layer_entity, layer_metadata, level_parent) in layer_query.iter() {
+        let mut callbacks = layer_definition_callbacks
+            .callbacks
+            .iter()
+            .filter(|(identifier, _)| *identifier == layer_metadata.identifier)
+            .peekable();
+
+        if callbacks.peek().is_none() {
+            continue;
+        }
+
+        let Some(layer_definition) = parent_query
+            .get(level_parent.get())
+            .ok()
+            .and_then(|world_parent| ldtk_query.get(world_parent.get()).ok())
+            .and_then(|ldtk_handle| ldtk_project_assets.get(ldtk_handle))
+            .and_then(|ldtk_project| {
+                ldtk_project
+                    .json_data()
+                    .defs
+                    .layers
+                    .iter()
+                    .find(|layer_definition| layer_definition.uid == layer_metadata.layer_def_uid)
+            })
+        else {
+            continue;
+        };
+
+        let mut entity_commands = commands.entity(layer_entity);
+        for (_, callback) in callbacks {
+            callback(&mut entity_commands, layer_definition);
+        }
+    }
+}
+
+/// Shows the active variants of layer groups and hides the others, according to [LayerVariants].
+pub fn apply_layer_variants(
+    layer_variants: Res<LayerVariants>,
+    level_query: Query<&LevelIid>,
+    mut layer_query: Query<(Ref<LayerMetadata>, &Parent, &mut Visibility)>,
+) {
+    for (layer_metadata, parent, mut visibility) in layer_query.iter_mut() {
+        if !layer_variants.is_changed() && !layer_metadata.is_added() {
+            continue;
+        }
+
+        let Ok(level_iid) = level_query.get(parent.get()) else {
+            continue;
+        };
+
+        let Some(active) = layer_variants.is_layer_active(level_iid, &layer_metadata.identifier)
+        else {
+            continue;
+        };
+
+        let new_visibility = if active {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        };
+
+        if *visibility != new_visibility {
+            *visibility = new_visibility;
+        }
+    }
+}
+
+/// Hides levels that are outside the view of every active orthographic camera, according to
+/// [LevelCulling].
+pub fn cull_levels(
+    ldtk_settings: Res<LdtkSettings>,
+    ldtk_project_assets: Res<Assets<LdtkProject>>,
+    ldtk_query: Query<&LdtkProjectHandle>,
+    camera_query: Query<(&Camera, &GlobalTransform, &OrthographicProjection)>,
+    mut level_query: Query<(&LevelIid, &GlobalTransform, &Parent, &mut Visibility)>,
+) {
+    let LevelCulling::Enabled { margin } = ldtk_settings.level_culling else {
+        return;
+    };
+
+    let views: Vec<Rect> = camera_query
+        .iter()
+        .filter(|(camera, _, _)| camera.is_active)
+        .map(|(_, camera_transform, projection)| {
+            let center = camera_transform.translation().truncate();
+            Rect {
+                min: center + projection.area.min - margin,
+                max: center + projection.area.max + margin,
+            }
+        })
+        .collect();
+
+    if views.is_empty() {
+        return;
+    }
+
+    for (level_iid, level_transform, parent, mut visibility) in level_query.iter_mut() {
+        let Some(level) = ldtk_query
+            .get(parent.get())
+            .ok()
+            .and_then(|ldtk_handle| ldtk_project_assets.get(ldtk_handle))
+            .and_then(|ldtk_project| ldtk_project.get_raw_level_by_iid(level_iid.get()))
+        else {
+            continue;
+        };
+
+        let min = level_transform.translation().truncate();
+        let bounds = Rect::from_corners(
+            min,
+            min + Vec2::new(level.px_wid as f32, level.px_hei as f32),
+        );
+
+        let in_view = views.iter().any(|view| !view.intersect(bounds).is_empty());
+
+        let new_visibility = if in_view {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        };
+
+        if *visibility != new_visibility {
+            *visibility = new_visibility;
+        }
+    }
+}
+
+/// Applies [TilesetSkins] to spawned tilemaps when they change, and to newly spawned tilemaps.
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
+pub fn apply_tileset_skins(
+    tileset_skins: Res<TilesetSkins>,
+    images: Res<Assets<Image>>,
+    ldtk_project_assets: Res<Assets<LdtkProject>>,
+    ldtk_query: Query<&LdtkProjectHandle>,
+    level_query: Query<&Parent, With<LevelIid>>,
+    new_tilemap_query: Query<(), Added<TilemapTexture>>,
+    mut layer_query: Query<(&LayerMetadata, &Parent, &mut TilemapTexture)>,
+    mut skins_pending: Local<bool>,
+) {
+    if !tileset_skins.is_changed() && new_tilemap_query.is_empty() && !*skins_pending {
+        return;
+    }
+
+    *skins_pending = false;
+
+    for (layer_metadata, layer_parent, mut texture) in layer_query.iter_mut() {
+        let Some(tileset_uid) = layer_metadata.tileset_def_uid else {
+            continue;
+        };
+
+        let Some(ldtk_project) = level_query
+            .get(layer_parent.get())
+            .ok()
+            .and_then(|level_parent| ldtk_query.get(level_parent.get()).ok())
+            .and_then(|ldtk_handle| ldtk_project_assets.get(ldtk_handle))
+        else {
+            continue;
+        };
+
+        let (Some(original), Some(tileset_definition)) = (
+            ldtk_project.tileset_map().get(&tileset_uid),
+            ldtk_project
+                .json_data()
+                .defs
+                .tilesets
+                .iter()
+                .find(|tileset_definition| tileset_definition.uid == tileset_uid),
+        ) else {
+            continue;
+        };
+
+        let desired = match tileset_skins.get(tileset_uid) {
+            Some(skin) => match images.get(skin) {
+                Some(image) => match TilesetSkins::validate(image, tileset_definition) {
+                    Ok(()) => skin,
+                    Err(e) => {
+                        warn!("{}", e);
+                        original
+                    }
+                },
+                None => {
+                    // Try again once the skin has loaded
+                    *skins_pending = true;
+                    continue;
+                }
+            },
+            None => original,
+        };
+
+        if !matches!(&*texture, TilemapTexture::Single(current) if current == desired) {
+            *texture = TilemapTexture::Single(desired.clone());
+        }
+    }
+}
+
+/// Fades in the sprites and tiles of levels with a [LevelReveal], removing it once they're done.
+///
+/// Levels with [LevelRevealStyle::Custom] are skipped.
+#[allow(clippy::type_complexity)]
+pub fn reveal_levels(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut level_query: Query<(Entity, &mut LevelReveal)>,
+    children_query: Query<&Children>,
+    mut visual_query: Query<(
+        Option<&RevealBaseAlpha>,
+        Option<&TilePos>,
+        AnyOf<(&mut Sprite, &mut TextureAtlasSprite, &mut TileColor)>,
+    )>,
+) {
+    for (level_entity, mut reveal) in level_query.iter_mut() {
+        if reveal.style == LevelRevealStyle::Custom {
+            continue;
+        }
+
+        reveal.elapsed += time.delta_seconds();
+
+        let mut finished = true;
+
+        for entity in children_query.iter_descendants(level_entity) {
+            let Ok((base_alpha, tile_pos, (sprite, atlas_sprite, tile_color))) =
+                visual_query.get_mut(entity)
+            else {
+                continue;
+            };
+
+            let color = if let Some(sprite) = sprite {
+                &mut sprite.into_inner().color
+            } else if let Some(atlas_sprite) = atlas_sprite {
+                &mut atlas_sprite.into_inner().color
+            } else if let Some(tile_color) = tile_color {
+                &mut tile_color.into_inner().0
+            } else {
+                continue;
+            };
+
+            let base_alpha = match base_alpha {
+                Some(RevealBaseAlpha(base_alpha)) => *base_alpha,
+                None => {
+                    commands.entity(entity).insert(RevealBaseAlpha(color.a()));
+                    color.a()
+                }
+            };
+
+            let progress = reveal.progress(tile_pos);
+            finished &= progress >= 1.;
+
+            color.set_a(base_alpha * progress);
+        }
+
+        if finished {
+            commands.entity(level_entity).remove::<LevelReveal>();
+
+            for entity in children_query.iter_descendants(level_entity) {
+                if let Some(mut entity_commands) = commands.get_entity(entity) {
+                    entity_commands.remove::<RevealBaseAlpha>();
+                }
+            }
+        }
+    }
+}
+
+/// Advances [LayerFade]s, applying their opacity to the tiles and sprites of their layers.
+pub fn fade_layers(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut layer_query: Query<(Entity, &mut LayerFade)>,
+    children_query: Query<&Children>,
+    mut visual_query: Query<(
+        Option<&FadeBaseAlpha>,
+        AnyOf<(&mut Sprite, &mut TextureAtlasSprite, &mut TileColor)>,
+    )>,
+) {
+    for (layer_entity, mut fade) in layer_query.iter_mut() {
+        // finished fades only need applying again if they were replaced or retargeted
+        if !fade.is_finished() {
+            fade.elapsed += time.delta_seconds();
+        } else if !fade.is_changed() {
+            continue;
+        }
+
+        let opacity = fade.opacity();
+
+        for entity in children_query.iter_descendants(layer_entity) {
+            let Ok((base_alpha, (sprite, atlas_sprite, tile_color))) = visual_query.get_mut(entity)
+            else {
+                continue;
+            };
+
+            let color = if let Some(sprite) = sprite {
+                &mut sprite.into_inner().color
+            } else if let Some(atlas_sprite) = atlas_sprite {
+                &mut atlas_sprite.into_inner().color
+            } else if let Some(tile_color) = tile_color {
+                &mut tile_color.into_inner().0
+            } else {
+                continue;
+            };
+
+            let base_alpha = match base_alpha {
+                Some(FadeBaseAlpha(base_alpha)) => *base_alpha,
+                None => {
+                    commands.entity(entity).insert(FadeBaseAlpha(color.a()));
+                    color.a()
+                }
+            };
+
+            color.set_a(base_alpha * opacity);
+        }
+    }
+}
+
+/// Advances [TileAnimation]s that can't be animated by `bevy_ecs_tilemap` directly.
+pub fn animate_tiles(
+    time: Res<Time>,
+    mut tile_query: Query<(&mut TileAnimation, &mut TileTextureIndex)>,
+) {
+    for (mut animation, mut texture_index) in tile_query.iter_mut() {
+        if let Some(tile_id) = animation.tick(time.delta_seconds()) {
+            if texture_index.0 != tile_id {
+                texture_index.0 = tile_id;
+            }
+        }
+    }
+}
+
+/// Returns the `iid`s of levels that have spawned in this update.
+///
+/// Mean to be used in a chain with [fire_level_transformed_events].
+pub fn detect_level_spawned_events(mut reader: EventReader<LevelEvent>) -> Vec<LevelIid> {
+    let mut spawned_ids = Vec::new();
+    for event in reader.iter() {
+        if let LevelEvent::Spawned(id) = event {
+            spawned_ids.push(id.clone());
+        }
+    }
+    spawned_ids
+}
+
+/// Fires [LevelEvent::Transformed] events for all the levels that spawned in this update.
+///
+/// Meant to be used in a chain with [detect_level_spawned_events].
+pub fn fire_level_transformed_events(
+    In(spawned_ids): In<Vec<LevelIid>>,
+    mut writer: EventWriter<LevelEvent>,
+) {
+    for id in spawned_ids {
+        writer.send(LevelEvent::Transformed(id));
+    }
+}
+
+/// Fires a [LevelLifecycleEvent] for every [LevelEvent] sent since the last update, in order.
+pub fn fire_level_lifecycle_events(
+    mut level_events: EventReader<LevelEvent>,
+    mut lifecycle_events: EventWriter<LevelLifecycleEvent>,
+    frame_count: Option<Res<FrameCount>>,
+    mut sequence: Local<u64>,
+) {
+    let frame = frame_count.map_or(0, |frame_count| frame_count.0);
+
+    for event in level_events.iter() {
+        lifecycle_events.send(LevelLifecycleEvent {
+            event: event.clone(),
+            frame,
+            sequence: *sequence,
+        });
+        *sequence += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn levels_over_budget_are_the_furthest() {
+        let focus_points = [Vec2::ZERO, Vec2::new(1000., 0.)];
+        let level_rect = |x: f32| Rect::new(x, 0., x + 100., 100.);
+
+        // Distances to the nearest focus point: 300, 0, 50, 300
+        let mut pending_levels = vec![
+            ("d", level_rect(300.)),
+            ("a", level_rect(-50.)),
+            ("c", level_rect(1050.)),
+            ("b", level_rect(-400.)),
+        ];
+
+        let over_budget = split_off_over_budget(
+            &mut pending_levels,
+            2,
+            |(_, rect)| distance_to_rect(&focus_points, *rect),
+            |(iid, _)| *iid,
+        );
+
+        let iids =
+            |levels: &[(&str, Rect)]| -> Vec<&str> { levels.iter().map(|(iid, _)| *iid).collect() };
+        assert_eq!(iids(&pending_levels), vec!["a", "c"]);
+        // Equally distant levels are ordered by iid
+        assert_eq!(iids(&over_budget), vec!["b", "d"]);
+    }
+
+    #[test]
+    fn levels_within_budget_keep_their_order() {
+        let mut pending_levels = vec!["b", "a"];
+
+        assert!(split_off_over_budget(&mut pending_levels, 2, |_| 0., |iid| *iid).is_empty());
+        assert_eq!(pending_levels, vec!["b", "a"]);
+
+        // At least one level spawns per frame
+        let over_budget = split_off_over_budget(&mut pending_levels, 0, |_| 0., |iid| *iid);
+        assert_eq!((pending_levels, over_budget), (vec!["a"], vec!["b"]));
+    }
+
+    #[test]
+    fn distance_to_rect_is_zero_inside() {
+        let rect = Rect::new(0., 0., 10., 10.);
+
+        assert_eq!(distance_to_rect(&[Vec2::new(5., 5.)], rect), 0.);
+        assert_eq!(distance_to_rect(&[Vec2::new(13., 14.)], rect), 5.);
+        assert_eq!(distance_to_rect(&[], rect), f32::INFINITY);
+    }
+
+    #[test]
+    fn y_sorted_entities_share_the_band_of_their_level() {
+        let mut app = App::new();
+        app.add_systems(Update, apply_y_sort);
+
+        let y_sort = YSort {
+            scale: 0.5,
+            offset: 5.,
+            split_tiles: false,
+        };
+        let layer = app.world.spawn(Transform::from_xyz(0., 20., 2.)).id();
+        let entity = app
+            .world
+            .spawn((y_sort, Transform::from_xyz(0., 80., 0.)))
+            .id();
+        app.world.entity_mut(layer).add_child(entity);
+
+        app.update();
+
+        // 100 pixels up the level puts the entity at 5 - 50 on the band, under a layer at z 2
+        let transform = app.world.get::<Transform>(entity).unwrap();
+        assert_eq!(transform.translation.z, -47.);
+
+        app.world
+            .get_mut::<Transform>(entity)
+            .unwrap()
+            .translation
+            .y = -20.;
+        app.update();
+
+        let transform = app.world.get::<Transform>(entity).unwrap();
+        assert_eq!(transform.translation.z, 3.);
+    }
+
+    #[test]
+    fn respawning_levels_keep_preserved_subtrees() {
+        let mut world = World::new();
+        world.init_resource::<LdtkSettings>();
+        world.init_resource::<Events<LevelEvent>>();
+
+        let level = world.spawn((LevelIid::new("level"), Respawn)).id();
+        let layer = world.spawn_empty().set_parent(level).id();
+        let preserved = world.spawn(PreserveOnRespawn).set_parent(layer).id();
+        let preserved_child = world.spawn_empty().set_parent(preserved).id();
+        let sibling = world.spawn_empty().set_parent(layer).id();
+
+        clean_respawn_entities(&mut world);
+
+        assert!(world.get_entity(layer).is_none());
+        assert!(world.get_entity(sibling).is_none());
+
+        assert_eq!(world.get::<Parent>(preserved).map(Parent::get), Some(level));
+        assert_eq!(
+            world.get::<Parent>(preserved_child).map(Parent::get),
+            Some(preserved)
+        );
+        assert_eq!(
+            world
+                .get::<Children>(level)
+                .map(|children| children.to_vec()),
+            Some(vec![preserved])
+        );
+    }
+
+    #[cfg(feature = "test_utils")]
+    fn level_selection_app(invalid_level_selection: InvalidLevelSelection) -> (App, Entity) {
+        let project = crate::test_utils::ProjectFixture::new()
+            .level("A", "a", IVec2::splat(16), |_| Ok(()))
+            .level("B", "b", IVec2::splat(16), |_| Ok(()))
+            .build_project(None);
+
+        let mut app = App::new();
+        app.add_plugins(AssetPlugin::default())
+            .add_asset::<LdtkProject>()
+            .insert_resource(LdtkSettings {
+                invalid_level_selection,
+                ..default()
+            })
+            .init_resource::<LevelDuplicates>()
+            .init_resource::<ClearColor>()
+            .add_event::<LevelSelectionError>()
+            .add_systems(Update, apply_level_selection);
+
+        let handle = app.world.resource_mut::<Assets<LdtkProject>>().add(project);
+        let world_entity = app
+            .world
+            .spawn((LdtkProjectHandle::from(handle), LevelSet::default()))
+            .id();
+
+        (app, world_entity)
+    }
+
+    #[cfg(feature = "test_utils")]
+    fn level_set_iids(app: &App, world_entity: Entity) -> Vec<LevelIid> {
+        let mut iids: Vec<_> = app
+            .world
+            .get::<LevelSet>(world_entity)
+            .unwrap()
+            .iids
+            .iter()
+            .cloned()
+            .collect();
+        iids.sort_by(|a, b| a.as_str().cmp(b.as_str()));
+        iids
+    }
+
+    #[cfg(feature = "test_utils")]
+    #[test]
+    fn invalid_selections_keep_previous_levels() {
+        let (mut app, world_entity) = level_selection_app(InvalidLevelSelection::KeepPrevious);
+
+        app.insert_resource(LevelSelection::iid("a"));
+        app.update();
+        assert_eq!(level_set_iids(&app, world_entity), [LevelIid::new("a")]);
+
+        app.insert_resource(LevelSelection::iid("missing"));
+        app.update();
+        assert_eq!(level_set_iids(&app, world_entity), [LevelIid::new("a")]);
+
+        app.insert_resource(LevelSelection::iid("b"));
+        app.update();
+        assert_eq!(level_set_iids(&app, world_entity), [LevelIid::new("b")]);
+    }
+
+    #[cfg(feature = "test_utils")]
+    #[test]
+    fn invalid_selections_can_despawn_levels() {
+        let (mut app, world_entity) = level_selection_app(InvalidLevelSelection::DespawnLevels);
+
+        app.insert_resource(LevelSelection::iid("a"));
+        app.update();
+        assert_eq!(level_set_iids(&app, world_entity), [LevelIid::new("a")]);
+
+        app.insert_resource(LevelSelection::iid("missing"));
+        app.update();
+        assert!(level_set_iids(&app, world_entity).is_empty());
+    }
+
+    #[cfg(feature = "test_utils")]
+    #[test]
+    fn invalid_selections_are_reported_once() {
+        use bevy::ecs::event::ManualEventReader;
+
+        let (mut app, world_entity) = level_selection_app(InvalidLevelSelection::KeepPrevious);
+        let mut reader = ManualEventReader::<LevelSelectionError>::default();
+        let mut read_errors = |app: &App| -> Vec<LevelSelectionError> {
+            reader
+                .iter(app.world.resource::<Events<LevelSelectionError>>())
+                .cloned()
+                .collect()
+        };
+
+        app.insert_resource(LevelSelection::iid("missing"));
+        app.update();
+        app.update();
+        assert_eq!(
+            read_errors(&app),
+            [LevelSelectionError {
+                selection: LevelSelection::iid("missing"),
+                world: world_entity,
+            }]
+        );
+
+        // Another invalid selection is reported again
+        app.insert_resource(LevelSelection::iid("gone"));
+        app.update();
+        assert_eq!(read_errors(&app).len(), 1);
+
+        // So is the same one, once a valid selection was found in between
+        app.insert_resource(LevelSelection::iid("a"));
+        app.update();
+        app.insert_resource(LevelSelection::iid("gone"));
+        app.update();
+        assert_eq!(read_errors(&app).len(), 1);
+    }
+
+    #[cfg(feature = "render")]
+    #[derive(
+        bevy::render::render_resource::AsBindGroup,
+        bevy::reflect::TypeUuid,
+        bevy::reflect::TypePath,
+        Debug,
+        Clone,
+    )]
+    #[uuid = "a3d5d7c2-4f0b-4c55-8a4e-2f6a4c1b9e37"]
+    struct TestTilemapMaterial {
+        #[uniform(0)]
+        strength: f32,
+    }
+
+    #[cfg(feature = "render")]
+    impl MaterialTilemap for TestTilemapMaterial {}
+
+    #[cfg(feature = "render")]
+    #[test]
+    fn tilemap_materials_match_enum_tags_before_layers() {
+        use bevy::asset::HandleId;
+
+        let foliage_material = Handle::weak(HandleId::random::<TestTilemapMaterial>());
+        let lava_material = Handle::weak(HandleId::random::<TestTilemapMaterial>());
+
+        let mut app = App::new();
+        app.insert_resource(LdtkTilemapMaterials::<TestTilemapMaterial> {
+            materials: HashMap::from([("Foliage".to_string(), foliage_material.clone())]),
+            enum_tag_materials: HashMap::from([("Lava".to_string(), lava_material.clone())]),
+        })
+        .add_systems(Update, apply_ldtk_tilemap_materials::<TestTilemapMaterial>);
+
+        let mut spawn_layer = |identifier: &str, enum_tag: Option<&str>| {
+            let layer_metadata = LayerMetadata::from(&crate::ldtk::LayerInstance {
+                identifier: identifier.to_string(),
+                ..default()
+            });
+            let mut layer = app
+                .world
+                .spawn((layer_metadata, Handle::<StandardTilemapMaterial>::default()));
+            if let Some(enum_tag) = enum_tag {
+                layer.insert(MaterialEnumTag(enum_tag.to_string()));
+            }
+            layer.id()
+        };
+
+        let foliage = spawn_layer("Foliage", None);
+        let lava = spawn_layer("Foliage", Some("Lava"));
+        let water = spawn_layer("Foliage", Some("Water"));
+        let ground = spawn_layer("Ground", None);
+
+        app.update();
+
+        let material = |entity| {
+            app.world
+                .get::<Handle<TestTilemapMaterial>>(entity)
+                .cloned()
+        };
+        assert_eq!(material(foliage), Some(foliage_material.clone()));
+        assert_eq!(material(lava), Some(lava_material));
+        assert_eq!(material(water), Some(foliage_material));
+        assert_eq!(material(ground), None);
+
+        let has_standard_material = |entity| {
+            app.world
+                .get::<Handle<StandardTilemapMaterial>>(entity)
+                .is_some()
+        };
+        assert!(!has_standard_material(foliage));
+        assert!(has_standard_material(ground));
     }
 }
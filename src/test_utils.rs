@@ -0,0 +1,344 @@
+//! Fixtures and assertions for integration tests of games using this plugin.
+//!
+//! *Requires the "test_utils" feature*
+//!
+//! Writing integration tests against real LDtk files is slow to set up and brittle.
+//! This module provides the pieces to build small projects in code, spawn them in a headless
+//! [`App`], and check the spawned hierarchy:
+//! - [`ProjectFixture`] builds minimal [`LdtkJson`] projects.
+//! - [`test_app`] creates a headless app with the [`LdtkPlugin`].
+//! - [`spawn_fixture`] and [`update_until_spawned`] spawn a project's levels.
+//! - [`assert_layer_tile_count`] and [`assert_entity_has`] check the spawned hierarchy.
+//!
+//! ```no_run
+//! # use bevy::prelude::*;
+//! # use bevy_ecs_ldtk::{prelude::*, test_utils::*};
+//! #[derive(Default, Component)]
+//! struct Player;
+//!
+//! #[derive(Default, Bundle, LdtkEntity)]
+//! struct PlayerBundle {
+//!     player: Player,
+//! }
+//!
+//! let mut app = test_app();
+//! app.register_ldtk_entity::<PlayerBundle>("Player");
+//!
+//! let fixture = ProjectFixture::new()
+//!     .int_grid_layer("Walls", 16, 1)
+//!     .entities_layer("Entities", 16)
+//!     .entity("Player", IVec2::splat(16))
+//!     .level("Level_0", "level-0", IVec2::new(64, 64), |level| {
+//!         level.int_grid_value("Walls", GridCoords::new(0, 0), 1)?;
+//!         let player = level.entity_instance("Player", "player-0", IVec2::new(24, 24))?;
+//!         level.entity("Entities", player)?;
+//!         Ok(())
+//!     });
+//!
+//! spawn_fixture(&mut app, fixture);
+//! update_until_spawned(&mut app, &LevelIid::new("level-0"), 10);
+//!
+//! assert_layer_tile_count(&mut app.world, "Walls", 1);
+//! assert_entity_has::<Player>(&mut app.world, "Player");
+//! ```
+//!
+//! [`LdtkPlugin`]: crate::prelude::LdtkPlugin
+
+use crate::{
+    assets::{LdtkJsonWithMetadata, LdtkProject, LdtkProjectData, LevelMetadata},
+    components::{LayerMetadata, LdtkWorldBundle, LevelIid, LevelSet},
+    editing::LevelEditError,
+    ldtk::{
+        raw_level_accessor::RawLevelAccessor, EntityDefinition, EntityInstance,
+        IntGridValueDefinition, LayerDefinition, LdtkJson, Type,
+    },
+    level_builder::LevelBuilder,
+    plugin::LdtkPlugin,
+    resources::LevelEvent,
+};
+use bevy::{ecs::event::ManualEventReader, prelude::*};
+use bevy_ecs_tilemap::tiles::TilePos;
+use std::collections::HashMap;
+
+/// Builder for minimal [`LdtkJson`] projects.
+///
+/// Layers and entities are given uids in the order they are added.
+/// Levels are built with a [`LevelBuilder`] using the definitions added before them.
+#[derive(Clone, Debug)]
+pub struct ProjectFixture {
+    json: LdtkJson,
+    next_uid: i32,
+}
+
+impl Default for ProjectFixture {
+    fn default() -> Self {
+        ProjectFixture::new()
+    }
+}
+
+impl ProjectFixture {
+    /// Starts building an empty project.
+    pub fn new() -> Self {
+        ProjectFixture {
+            json: LdtkJson {
+                json_version: "1.4.1".to_string(),
+                default_grid_size: 16,
+                ..default()
+            },
+            next_uid: 1,
+        }
+    }
+
+    fn uid(&mut self) -> i32 {
+        self.next_uid += 1;
+        self.next_uid - 1
+    }
+
+    fn layer(mut self, identifier: &str, layer_type: Type, grid_size: i32) -> Self {
+        let layer_definition = LayerDefinition {
+            identifier: identifier.to_string(),
+            uid: self.uid(),
+            layer_definition_type: format!("{layer_type:?}"),
+            purple_type: layer_type,
+            grid_size,
+            display_opacity: 1.,
+            ..default()
+        };
+
+        // LDtk lists layers from top to bottom, so new layers go below the others
+        self.json.defs.layers.push(layer_definition);
+        self
+    }
+
+    /// Adds an IntGrid layer with values from 1 to `max_value`.
+    pub fn int_grid_layer(self, identifier: &str, grid_size: i32, max_value: i32) -> Self {
+        let mut fixture = self.layer(identifier, Type::IntGrid, grid_size);
+
+        let layer_definition = fixture.json.defs.layers.last_mut().unwrap();
+        layer_definition.int_grid_values = (1..=max_value)
+            .map(|value| IntGridValueDefinition {
+                value,
+                color: Color::WHITE,
+                ..default()
+            })
+            .collect();
+
+        fixture
+    }
+
+    /// Adds an Entities layer.
+    pub fn entities_layer(self, identifier: &str, grid_size: i32) -> Self {
+        self.layer(identifier, Type::Entities, grid_size)
+    }
+
+    /// Adds an entity definition of the given size, with its pivot at its center.
+    pub fn entity(mut self, identifier: &str, size: IVec2) -> Self {
+        let entity_definition = EntityDefinition {
+            identifier: identifier.to_string(),
+            uid: self.uid(),
+            width: size.x,
+            height: size.y,
+            pivot_x: 0.5,
+            pivot_y: 0.5,
+            ..default()
+        };
+
+        self.json.defs.entities.push(entity_definition);
+        self
+    }
+
+    /// Adds a level, built with a [`LevelBuilder`].
+    ///
+    /// # Panics
+    /// Panics if `build` returns an error.
+    pub fn level(
+        mut self,
+        identifier: &str,
+        iid: &str,
+        px_size: IVec2,
+        build: impl FnOnce(&mut LevelBuilder) -> Result<(), LevelEditError>,
+    ) -> Self {
+        let mut builder = LevelBuilder::new(&self.json.defs, identifier, iid, px_size);
+        builder.uid(self.next_uid);
+
+        build(&mut builder)
+            .unwrap_or_else(|e| panic!("unable to build fixture level {identifier}: {e}"));
+
+        let level = builder.build();
+        self.next_uid += 1;
+        self.json.levels.push(level);
+        self
+    }
+
+    /// Finishes building the project.
+    pub fn build(self) -> LdtkJson {
+        self.json
+    }
+
+    /// Finishes building the project as an [`LdtkProject`] asset.
+    ///
+    /// The project has no tilesets, and its IntGrid colors are rendered with the given image, if
+    /// any.
+    pub fn build_project(self, int_grid_image_handle: Option<Handle<Image>>) -> LdtkProject {
+        let level_map = self
+            .json
+            .iter_raw_levels_with_indices()
+            .map(|(indices, level)| (level.iid.clone(), LevelMetadata::new(None, indices)))
+            .collect();
+
+        LdtkProject::new(
+            LdtkProjectData::Standalone(LdtkJsonWithMetadata::new(self.json, level_map)),
+            HashMap::new(),
+            int_grid_image_handle,
+        )
+    }
+}
+
+/// Creates a headless [`App`] with the [`LdtkPlugin`] and the plugins it depends on.
+pub fn test_app() -> App {
+    let mut app = App::new();
+    app.add_plugins((
+        MinimalPlugins,
+        AssetPlugin::default(),
+        TransformPlugin,
+        HierarchyPlugin,
+    ))
+    .add_asset::<Image>()
+    .add_asset::<TextureAtlas>()
+    .add_plugins(LdtkPlugin);
+    app
+}
+
+/// Adds the fixture to the app as an [`LdtkProject`] asset, and spawns an [`LdtkWorldBundle`]
+/// with all of its levels in its [`LevelSet`].
+///
+/// Returns the world entity.
+pub fn spawn_fixture(app: &mut App, fixture: ProjectFixture) -> Entity {
+    let int_grid_image_handle = fixture
+        .json
+        .defs
+        .create_int_grid_image()
+        .map(|image| app.world.resource_mut::<Assets<Image>>().add(image));
+
+    let project = fixture.build_project(int_grid_image_handle);
+    let level_set = LevelSet::from_iids(project.iter_raw_levels().map(|level| level.iid.clone()));
+    let ldtk_handle = app.world.resource_mut::<Assets<LdtkProject>>().add(project);
+
+    app.world
+        .spawn(LdtkWorldBundle {
+            ldtk_handle: ldtk_handle.into(),
+            level_set,
+            ..default()
+        })
+        .id()
+}
+
+/// Updates the app until the level with the given iid sends a [`LevelEvent::Spawned`].
+///
+/// # Panics
+/// Panics if the level doesn't spawn within `max_updates` updates.
+pub fn update_until_spawned(app: &mut App, level_iid: &LevelIid, max_updates: usize) {
+    let mut reader = ManualEventReader::<LevelEvent>::default();
+
+    for _ in 0..max_updates {
+        app.update();
+
+        let events = app.world.resource::<Events<LevelEvent>>();
+        if reader
+            .iter(events)
+            .any(|event| *event == LevelEvent::Spawned(level_iid.clone()))
+        {
+            return;
+        }
+    }
+
+    panic!("level {level_iid} didn't spawn within {max_updates} updates");
+}
+
+/// Asserts that the layers with the given identifier have `expected` tiles in total.
+pub fn assert_layer_tile_count(world: &mut World, layer_identifier: &str, expected: usize) {
+    let layers: Vec<Entity> = world
+        .query::<(Entity, &LayerMetadata)>()
+        .iter(world)
+        .filter(|(_, layer_metadata)| layer_metadata.identifier == layer_identifier)
+        .map(|(entity, _)| entity)
+        .collect();
+
+    assert!(!layers.is_empty(), "no layer named {layer_identifier}");
+
+    let mut children_query = world.query::<&Children>();
+    let mut tile_query = world.query_filtered::<(), With<TilePos>>();
+
+    let actual = layers
+        .into_iter()
+        .flat_map(|layer| {
+            children_query
+                .get(world, layer)
+                .map(|children| children.to_vec())
+                .unwrap_or_default()
+        })
+        .filter(|&child| tile_query.get(world, child).is_ok())
+        .count();
+
+    assert_eq!(
+        actual, expected,
+        "expected layer {layer_identifier} to have {expected} tiles, found {actual}"
+    );
+}
+
+/// Asserts that there is at least one spawned LDtk entity with the given identifier, and that all
+/// of them have the component `C`.
+pub fn assert_entity_has<C: Component>(world: &mut World, entity_identifier: &str) {
+    let entities: Vec<(Entity, bool)> = world
+        .query::<(Entity, &EntityInstance, Option<&C>)>()
+        .iter(world)
+        .filter(|(_, entity_instance, _)| entity_instance.identifier == entity_identifier)
+        .map(|(entity, _, component)| (entity, component.is_some()))
+        .collect();
+
+    assert!(!entities.is_empty(), "no entity named {entity_identifier}");
+
+    for (entity, has_component) in entities {
+        assert!(
+            has_component,
+            "entity {entity_identifier} ({entity:?}) doesn't have {}",
+            std::any::type_name::<C>()
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::components::GridCoords;
+
+    #[test]
+    fn fixtures_build_levels_from_definitions() {
+        let project = ProjectFixture::new()
+            .int_grid_layer("Walls", 16, 2)
+            .entities_layer("Entities", 16)
+            .entity("Player", IVec2::splat(16))
+            .level("Level_0", "level-0", IVec2::new(64, 32), |level| {
+                level.int_grid_value("Walls", GridCoords::new(1, 0), 2)?;
+                let player = level.entity_instance("Player", "player-0", IVec2::new(8, 8))?;
+                level.entity("Entities", player)?;
+                Ok(())
+            })
+            .build();
+
+        let uids: Vec<_> = project
+            .defs
+            .layers
+            .iter()
+            .map(|layer| layer.uid)
+            .chain(project.defs.entities.iter().map(|entity| entity.uid))
+            .chain(project.levels.iter().map(|level| level.uid))
+            .collect();
+        assert_eq!(uids, vec![1, 2, 3, 4]);
+
+        let layers = project.levels[0].layer_instances.as_ref().unwrap();
+        assert_eq!(layers[0].identifier, "Walls");
+        assert_eq!(layers[0].int_grid_csv, vec![0, 0, 0, 0, 0, 2, 0, 0]);
+        assert_eq!(layers[1].entity_instances[0].identifier, "Player");
+    }
+}
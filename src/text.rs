@@ -0,0 +1,206 @@
+//! Text displays generated from LDtk entities.
+//!
+//! *Requires the "text" feature*
+//!
+//! When enabled, the plugin spawns a [`Text2dBundle`] as a child of every LDtk entity tagged with
+//! [`LdtkTextSettings::tag`], displaying the entity's text field.
+//! This covers signs, tutorial labels, and other text authored directly in LDtk.
+//!
+//! The text is centered on the entity and wrapped to the entity's width.
+//! Its font, size, and color are read from other fields of the entity, falling back to the
+//! defaults in [`LdtkTextSettings`].
+//!
+//! [`Text2dBundle`]: https://docs.rs/bevy/latest/bevy/text/struct.Text2dBundle.html
+
+use crate::ldtk::{ldtk_fields::LdtkFields, EntityInstance};
+use bevy::{prelude::*, text::Text2dBounds};
+
+/// Settings for generating text displays, found in [`LdtkSettings`].
+///
+/// [`LdtkSettings`]: crate::prelude::LdtkSettings
+#[derive(Clone, PartialEq, Debug)]
+pub struct LdtkTextSettings {
+    /// Entity tag that marks LDtk entities as text displays.
+    pub tag: String,
+    /// Identifier of the `String` or `Multilines` field containing the text.
+    pub text_field: String,
+    /// Identifier of the `String` field containing the asset path of the font.
+    ///
+    /// Falls back to `default_font`.
+    pub font_field: String,
+    /// Identifier of the `Float` field used for the font size.
+    ///
+    /// Falls back to `default_font_size`.
+    pub font_size_field: String,
+    /// Identifier of the `Color` field used for the text color.
+    ///
+    /// Falls back to `default_color`.
+    pub color_field: String,
+    pub default_font: Handle<Font>,
+    pub default_font_size: f32,
+    pub default_color: Color,
+}
+
+impl Default for LdtkTextSettings {
+    fn default() -> Self {
+        LdtkTextSettings {
+            tag: "text".to_string(),
+            text_field: "text".to_string(),
+            font_field: "font".to_string(),
+            font_size_field: "font_size".to_string(),
+            color_field: "color".to_string(),
+            default_font: Handle::default(),
+            default_font_size: 16.,
+            default_color: Color::WHITE,
+        }
+    }
+}
+
+/// [`Component`] marking the text display spawned for a text entity.
+///
+/// [`Component`]: https://docs.rs/bevy/latest/bevy/ecs/component/trait.Component.html
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Default, Hash, Component, Reflect)]
+#[reflect(Component)]
+pub struct LdtkText;
+
+fn text_2d_bundle_with_font_loader(
+    entity_instance: &EntityInstance,
+    settings: &LdtkTextSettings,
+    load_font: impl FnOnce(&str) -> Handle<Font>,
+) -> Option<Text2dBundle> {
+    if !entity_instance.tags.contains(&settings.tag) {
+        return None;
+    }
+
+    let text = entity_instance
+        .get_string_field(&settings.text_field)
+        .ok()?
+        .clone();
+
+    let font = entity_instance
+        .get_string_field(&settings.font_field)
+        .map(|path| load_font(path))
+        .unwrap_or_else(|_| settings.default_font.clone());
+
+    let font_size = entity_instance
+        .get_float_field(&settings.font_size_field)
+        .copied()
+        .unwrap_or(settings.default_font_size);
+
+    let color = entity_instance
+        .get_color_field(&settings.color_field)
+        .copied()
+        .unwrap_or(settings.default_color);
+
+    Some(Text2dBundle {
+        text: Text::from_section(
+            text,
+            TextStyle {
+                font,
+                font_size,
+                color,
+            },
+        )
+        .with_alignment(TextAlignment::Center),
+        text_2d_bounds: Text2dBounds {
+            size: Vec2::new(entity_instance.width as f32, f32::INFINITY),
+        },
+        // Draw the text slightly in front of the entity's own visuals
+        transform: Transform::from_xyz(0., 0., 0.1),
+        ..default()
+    })
+}
+
+/// Creates a [`Text2dBundle`] from an entity instance, if it has the configured text tag and a
+/// non-null text field.
+///
+/// Fonts are loaded with the given [`AssetServer`].
+///
+/// [`Text2dBundle`]: https://docs.rs/bevy/latest/bevy/text/struct.Text2dBundle.html
+/// [`AssetServer`]: https://docs.rs/bevy/latest/bevy/asset/struct.AssetServer.html
+pub fn text_2d_bundle_from_entity_info(
+    entity_instance: &EntityInstance,
+    settings: &LdtkTextSettings,
+    asset_server: &AssetServer,
+) -> Option<Text2dBundle> {
+    text_2d_bundle_with_font_loader(entity_instance, settings, |path| asset_server.load(path))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ldtk::{ldtk_fields::field_instance_from_value, FieldValue};
+
+    use super::*;
+
+    #[test]
+    fn text_entities_need_tag_and_text() {
+        let settings = LdtkTextSettings::default();
+
+        let untagged = EntityInstance {
+            field_instances: vec![field_instance_from_value(
+                "text",
+                FieldValue::String(Some("Hi".to_string())),
+            )],
+            ..Default::default()
+        };
+        assert!(
+            text_2d_bundle_with_font_loader(&untagged, &settings, |_| unreachable!()).is_none()
+        );
+
+        let null_text = EntityInstance {
+            tags: vec!["text".to_string()],
+            field_instances: vec![field_instance_from_value("text", FieldValue::String(None))],
+            ..Default::default()
+        };
+        assert!(
+            text_2d_bundle_with_font_loader(&null_text, &settings, |_| unreachable!()).is_none()
+        );
+    }
+
+    #[test]
+    fn text_style_reads_fields_with_fallbacks() {
+        let settings = LdtkTextSettings {
+            default_font_size: 12.,
+            ..Default::default()
+        };
+
+        let defaults = EntityInstance {
+            tags: vec!["text".to_string()],
+            width: 64,
+            field_instances: vec![field_instance_from_value(
+                "text",
+                FieldValue::String(Some("Press jump".to_string())),
+            )],
+            ..Default::default()
+        };
+
+        let bundle =
+            text_2d_bundle_with_font_loader(&defaults, &settings, |_| unreachable!()).unwrap();
+
+        assert_eq!(bundle.text.sections[0].value, "Press jump");
+        assert_eq!(bundle.text.sections[0].style.font_size, 12.);
+        assert_eq!(bundle.text.sections[0].style.color, Color::WHITE);
+        assert_eq!(bundle.text_2d_bounds.size.x, 64.);
+
+        let mut with_fields = defaults.clone();
+        with_fields.field_instances.extend([
+            field_instance_from_value(
+                "font",
+                FieldValue::String(Some("fonts/sign.ttf".to_string())),
+            ),
+            field_instance_from_value("font_size", FieldValue::Float(Some(24.))),
+            field_instance_from_value("color", FieldValue::Color(Color::YELLOW)),
+        ]);
+
+        let mut loaded_path = None;
+        let bundle = text_2d_bundle_with_font_loader(&with_fields, &settings, |path| {
+            loaded_path = Some(path.to_string());
+            Handle::default()
+        })
+        .unwrap();
+
+        assert_eq!(loaded_path.as_deref(), Some("fonts/sign.ttf"));
+        assert_eq!(bundle.text.sections[0].style.font_size, 24.);
+        assert_eq!(bundle.text.sections[0].style.color, Color::YELLOW);
+    }
+}
@@ -137,10 +137,31 @@ pub(crate) fn tile_pos_to_tile_maker(
 ///
 /// Used for spawning IntGrid layers with AutoTile functionality.
 pub(crate) fn tile_pos_to_tile_if_int_grid_nonzero_maker(
+    tile_maker: impl FnMut(TilePos) -> Option<TileBundle>,
+    int_grid_csv: &[i32],
+    layer_width_in_tiles: i32,
+    layer_height_in_tiles: i32,
+) -> impl FnMut(TilePos) -> Option<TileBundle> {
+    tile_pos_to_tile_if_int_grid_value_maker(
+        tile_maker,
+        int_grid_csv,
+        layer_width_in_tiles,
+        layer_height_in_tiles,
+        |_| true,
+    )
+}
+
+/// Creates a tile maker that returns the result of the provided tile maker IF the int grid value
+/// for that tile position is nonzero and passes the provided filter.
+/// Otherwise, the tile maker returns None.
+///
+/// Used for spawning IntGrid layers with sparse int grid cells.
+pub(crate) fn tile_pos_to_tile_if_int_grid_value_maker(
     mut tile_maker: impl FnMut(TilePos) -> Option<TileBundle>,
     int_grid_csv: &[i32],
     layer_width_in_tiles: i32,
     layer_height_in_tiles: i32,
+    mut filter: impl FnMut(i32) -> bool,
 ) -> impl FnMut(TilePos) -> Option<TileBundle> {
     let int_grid_map =
         tile_pos_to_int_grid_map(int_grid_csv, layer_width_in_tiles, layer_height_in_tiles);
@@ -148,6 +169,7 @@ pub(crate) fn tile_pos_to_tile_if_int_grid_nonzero_maker(
     move |tile_pos: TilePos| -> Option<TileBundle> {
         int_grid_map
             .get(&tile_pos)
+            .filter(|value| filter(**value))
             .and_then(|_| tile_maker(tile_pos))
     }
 }
@@ -468,4 +490,22 @@ mod tests {
             Color::RED
         );
     }
+
+    #[test]
+    fn test_tile_pos_to_tile_if_int_grid_value_maker() {
+        let int_grid_csv = vec![0, 1, 2, 0];
+
+        let mut tile_maker = tile_pos_to_tile_if_int_grid_value_maker(
+            tile_pos_to_invisible_tile,
+            &int_grid_csv,
+            2,
+            2,
+            |value| value == 2,
+        );
+
+        assert!(tile_maker(TilePos { x: 0, y: 0 }).is_some());
+        assert!(tile_maker(TilePos { x: 1, y: 0 }).is_none());
+        assert!(tile_maker(TilePos { x: 0, y: 1 }).is_none());
+        assert!(tile_maker(TilePos { x: 1, y: 1 }).is_none());
+    }
 }
@@ -6,7 +6,11 @@ use crate::{
     components::{GridCoords, IntGridCell},
 };
 
-use crate::{components::TileGridBundle, ldtk::*};
+use crate::{
+    components::{LayerMetadata, TileGridBundle},
+    ldtk::*,
+    resources::{GridShape, LdtkEntityPool},
+};
 use bevy::prelude::*;
 use bevy_ecs_tilemap::{
     map::{TilemapId, TilemapSize},
@@ -172,6 +176,59 @@ pub fn grid_coords_to_translation(grid_coords: GridCoords, tile_size: IVec2) ->
         + (tile_size.as_vec2() / 2.)
 }
 
+/// Performs [GridCoords] to translation conversion (relative to the layer) on a grid of the given
+/// [GridShape], so that the resulting translation is in the center of the tile.
+///
+/// `cell_size` is the size of a cell in world space, see [GridShape::cell_size].
+/// For [GridShape::Square], this is the same as
+/// [grid_coords_to_translation_relative_to_tile_layer].
+pub fn grid_coords_to_translation_relative_to_tile_layer_with_shape(
+    grid_coords: GridCoords,
+    cell_size: IVec2,
+    grid_shape: GridShape,
+) -> Vec2 {
+    let coords = IVec2::from(grid_coords).as_vec2();
+    let half_cell = cell_size.as_vec2() / 2.;
+
+    match grid_shape {
+        GridShape::Square => {
+            grid_coords_to_translation_relative_to_tile_layer(grid_coords, cell_size)
+        }
+        GridShape::IsometricDiamond => {
+            half_cell * Vec2::new(coords.x + coords.y, coords.y - coords.x)
+        }
+        GridShape::IsometricStaggered => half_cell * Vec2::new(2. * coords.x + coords.y, coords.y),
+    }
+}
+
+/// Performs translation (relative to the layer) to [GridCoords] conversion on a grid of the given
+/// [GridShape], returning the cell whose center is closest to the translation.
+///
+/// This is the inverse of [grid_coords_to_translation_relative_to_tile_layer_with_shape].
+/// Like it, `cell_size` is the size of a cell in world space, see [GridShape::cell_size].
+pub fn translation_relative_to_tile_layer_to_grid_coords_with_shape(
+    translation: Vec2,
+    cell_size: IVec2,
+    grid_shape: GridShape,
+) -> GridCoords {
+    let cells = translation / cell_size.as_vec2();
+
+    // On isometric grids, half cells are the unit of both axes
+    let diamond_coords = || {
+        let (u, v) = (cells.x * 2., cells.y * 2.);
+        IVec2::new(((u - v) / 2.).round() as i32, ((u + v) / 2.).round() as i32)
+    };
+
+    match grid_shape {
+        GridShape::Square => cells.round().as_ivec2().into(),
+        GridShape::IsometricDiamond => diamond_coords().into(),
+        GridShape::IsometricStaggered => {
+            let diamond = diamond_coords();
+            GridCoords::new(diamond.x, diamond.y - diamond.x)
+        }
+    }
+}
+
 /// Performs LDtk pixel coordinate to [GridCoords] conversion.
 ///
 /// This is inherently lossy since `GridCoords` space is less detailed than ldtk pixel coord space.
@@ -236,12 +293,95 @@ pub fn ldtk_pixel_coords_to_translation_pivoted(
     pivot_point + offset
 }
 
+/// The translation of the bottom left corner of a layer's [GridCoords] space, relative to the
+/// level.
+fn layer_origin(layer_metadata: &LayerMetadata) -> IVec2 {
+    IVec2::new(
+        layer_metadata.px_total_offset_x,
+        -layer_metadata.px_total_offset_y,
+    )
+}
+
+/// Performs [GridCoords] to translation conversion for a layer with its own grid size, so that
+/// the resulting translation is in the center of the cell.
+///
+/// Unlike [grid_coords_to_translation], this accounts for the layer's grid size and offsets, so
+/// the translation is relative to the level even if the layer's grid differs from the level's
+/// default.
+pub fn layer_grid_coords_to_translation(
+    grid_coords: GridCoords,
+    layer_metadata: &LayerMetadata,
+) -> Vec2 {
+    grid_coords_to_translation(grid_coords, IVec2::splat(layer_metadata.grid_size))
+        + layer_origin(layer_metadata).as_vec2()
+}
+
+/// Performs translation (relative to the level) to [GridCoords] conversion for a layer with its
+/// own grid size, returning the cell containing the translation.
+///
+/// This is the inverse of [layer_grid_coords_to_translation].
+/// Unlike [translation_to_grid_coords], this rounds down on both sides of the origin, so
+/// translations just left of or below a layer don't end up in its first column or row.
+pub fn translation_to_layer_grid_coords(
+    translation: Vec2,
+    layer_metadata: &LayerMetadata,
+) -> GridCoords {
+    ((translation - layer_origin(layer_metadata).as_vec2()) / layer_metadata.grid_size as f32)
+        .floor()
+        .as_ivec2()
+        .into()
+}
+
+/// Converts [GridCoords] on one layer to the [GridCoords] of the cell containing its center on
+/// another layer of the same level.
+///
+/// Useful for setups mixing grid sizes, like an 8px collision layer under 16px tiles.
+/// Converting a fine cell to a coarse layer finds the coarse cell it's part of, while converting a
+/// coarse cell to a fine layer finds one of the cells near its center.
+/// To find every fine cell a coarse cell covers, see [grid_coords_covered_on_layer].
+pub fn convert_grid_coords_between_layers(
+    grid_coords: GridCoords,
+    from: &LayerMetadata,
+    to: &LayerMetadata,
+) -> GridCoords {
+    translation_to_layer_grid_coords(layer_grid_coords_to_translation(grid_coords, from), to)
+}
+
+/// Returns the [GridCoords] of every cell on the `to` layer that overlaps the cell at the given
+/// [GridCoords] on the `from` layer, in the same level.
+///
+/// For example, a cell on a 16px layer covers 4 cells of an aligned 8px layer.
+/// Cells that are only partially overlapped, due to unaligned offsets or grid sizes that don't
+/// divide each other, are included.
+pub fn grid_coords_covered_on_layer(
+    grid_coords: GridCoords,
+    from: &LayerMetadata,
+    to: &LayerMetadata,
+) -> impl Iterator<Item = GridCoords> {
+    let min = IVec2::from(grid_coords) * from.grid_size + layer_origin(from) - layer_origin(to);
+    let max = min + IVec2::splat(from.grid_size);
+
+    let to_grid_size = to.grid_size;
+    let first = IVec2::new(
+        min.x.div_euclid(to_grid_size),
+        min.y.div_euclid(to_grid_size),
+    );
+    // The last cell whose minimum is below the exclusive maximum
+    let last = IVec2::new(
+        (max.x - 1).div_euclid(to_grid_size),
+        (max.y - 1).div_euclid(to_grid_size),
+    );
+
+    (first.y..=last.y).flat_map(move |y| (first.x..=last.x).map(move |x| GridCoords::new(x, y)))
+}
+
 /// Similar to [LayerBuilder::new_batch], except it doesn't consume the [LayerBuilder]
 ///
 /// This allows for more methods to be performed on the [LayerBuilder] before building it.
 /// However, the performance cons of using non-batch methods still apply here.
 pub(crate) fn set_all_tiles_with_func(
     commands: &mut Commands,
+    entity_pool: &mut LdtkEntityPool,
     storage: &mut TileStorage,
     size: TilemapSize,
     tilemap_id: TilemapId,
@@ -250,8 +390,12 @@ pub(crate) fn set_all_tiles_with_func(
     for x in 0..size.x {
         for y in 0..size.y {
             let tile_pos = TilePos { x, y };
-            let tile_entity = func(tile_pos)
-                .map(|tile_bundle| commands.spawn(tile_bundle).insert(tilemap_id).id());
+            let tile_entity = func(tile_pos).map(|tile_bundle| {
+                entity_pool
+                    .spawn(commands, tile_bundle)
+                    .insert(tilemap_id)
+                    .id()
+            });
             match tile_entity {
                 Some(tile_entity) => storage.set(&tile_pos, tile_entity),
                 None => storage.remove(&tile_pos),
@@ -305,6 +449,52 @@ where
     try_each_optional_permutation(a, b, |x, y| map.get(&(x, y))).unwrap_or(default)
 }
 
+/// Like [ldtk_map_get_or_default], but returns the key of the registration that applies instead,
+/// or `None` if the default applies.
+pub(crate) fn ldtk_map_get_key<A, B, L>(
+    a: A,
+    b: B,
+    map: &HashMap<(Option<A>, Option<B>), L>,
+) -> Option<(Option<A>, Option<B>)>
+where
+    A: Hash + Eq + Clone,
+    B: Hash + Eq + Clone,
+{
+    try_each_optional_permutation(a, b, |x, y| {
+        let key = (x, y);
+        map.contains_key(&key).then_some(key)
+    })
+}
+
+/// Resolves the registrations of many `b`s that share the same `a`, like
+/// [ldtk_map_get_or_default].
+///
+/// Each distinct `b` is only resolved once, so the optional permutations don't have to be tried
+/// again for every LDtk entity or int grid tile with the same identifier or value.
+pub(crate) fn ldtk_map_get_all_or_default<'a, 'b, A, B, L>(
+    a: A,
+    bs: impl IntoIterator<Item = &'b B>,
+    default: &'a L,
+    map: &'a HashMap<(Option<A>, Option<B>), L>,
+) -> HashMap<B, &'a L>
+where
+    A: Hash + Eq + Clone,
+    B: Hash + Eq + Clone + 'b,
+{
+    let mut resolved = HashMap::new();
+
+    for b in bs {
+        if !resolved.contains_key(b) {
+            resolved.insert(
+                b.clone(),
+                ldtk_map_get_or_default(a.clone(), b.clone(), default, map),
+            );
+        }
+    }
+
+    resolved
+}
+
 /// Creates a [SpriteSheetBundle] from the entity information available to the
 /// [LdtkEntity::bundle_entity] method.
 ///
@@ -635,6 +825,58 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_grid_coords_translation_conversion_with_shape() {
+        let cell_size = IVec2::new(32, 16);
+
+        for (grid_shape, grid_coords, translation) in [
+            (
+                GridShape::Square,
+                GridCoords::new(1, 2),
+                Vec2::new(32., 32.),
+            ),
+            (
+                GridShape::IsometricDiamond,
+                GridCoords::new(1, 0),
+                Vec2::new(16., -8.),
+            ),
+            (
+                GridShape::IsometricDiamond,
+                GridCoords::new(2, 3),
+                Vec2::new(80., 8.),
+            ),
+            (
+                GridShape::IsometricStaggered,
+                GridCoords::new(1, 1),
+                Vec2::new(48., 8.),
+            ),
+            (
+                GridShape::IsometricStaggered,
+                GridCoords::new(-1, 2),
+                Vec2::new(0., 16.),
+            ),
+        ] {
+            assert_eq!(
+                grid_coords_to_translation_relative_to_tile_layer_with_shape(
+                    grid_coords,
+                    cell_size,
+                    grid_shape
+                ),
+                translation
+            );
+
+            // Anywhere close to the center maps back to the same cell
+            assert_eq!(
+                translation_relative_to_tile_layer_to_grid_coords_with_shape(
+                    translation + Vec2::new(3., -2.),
+                    cell_size,
+                    grid_shape
+                ),
+                grid_coords
+            );
+        }
+    }
+
     #[test]
     fn test_ldtk_pixel_coords_to_translation_pivoted() {
         assert_eq!(
@@ -691,4 +933,95 @@ mod tests {
         assert_eq!(try_each_optional_permutation(4, 4, test_func), Some(4));
         assert_eq!(try_each_optional_permutation(5, 5, test_func), Some(4));
     }
+
+    #[test]
+    fn test_ldtk_map_get_all_or_default() {
+        let map: HashMap<(Option<&str>, Option<i32>), i32> = HashMap::from([
+            ((Some("Walls"), Some(1)), 10),
+            ((None, Some(2)), 20),
+            ((Some("Floor"), None), 30),
+        ]);
+
+        let resolved = ldtk_map_get_all_or_default("Walls", &[1, 2, 3, 1, 2], &0, &map);
+
+        assert_eq!(resolved.len(), 3);
+        assert_eq!(resolved[&1], &10);
+        assert_eq!(resolved[&2], &20);
+        assert_eq!(resolved[&3], &0);
+    }
+
+    #[test]
+    fn test_ldtk_map_get_key() {
+        let map: HashMap<(Option<&str>, Option<i32>), i32> = HashMap::from([
+            ((Some("Walls"), Some(1)), 10),
+            ((None, Some(1)), 11),
+            ((None, Some(2)), 20),
+        ]);
+
+        assert_eq!(
+            ldtk_map_get_key("Walls", 1, &map),
+            Some((Some("Walls"), Some(1)))
+        );
+        assert_eq!(ldtk_map_get_key("Floor", 1, &map), Some((None, Some(1))));
+        assert_eq!(ldtk_map_get_key("Walls", 2, &map), Some((None, Some(2))));
+        assert_eq!(ldtk_map_get_key("Walls", 3, &map), None);
+    }
+
+    #[test]
+    fn test_grid_coords_conversion_between_layers() {
+        let coarse = LayerMetadata {
+            grid_size: 16,
+            ..Default::default()
+        };
+        let fine = LayerMetadata {
+            grid_size: 8,
+            ..Default::default()
+        };
+        let offset_fine = LayerMetadata {
+            px_total_offset_x: 4,
+            px_total_offset_y: -4,
+            ..fine.clone()
+        };
+
+        assert_eq!(
+            layer_grid_coords_to_translation(GridCoords::new(1, 2), &coarse),
+            Vec2::new(24., 40.)
+        );
+        assert_eq!(
+            layer_grid_coords_to_translation(GridCoords::new(1, 2), &offset_fine),
+            Vec2::new(16., 24.)
+        );
+
+        assert_eq!(
+            translation_to_layer_grid_coords(Vec2::new(-1., 15.), &coarse),
+            GridCoords::new(-1, 0)
+        );
+
+        assert_eq!(
+            convert_grid_coords_between_layers(GridCoords::new(3, 5), &fine, &coarse),
+            GridCoords::new(1, 2)
+        );
+        assert_eq!(
+            convert_grid_coords_between_layers(GridCoords::new(1, 2), &coarse, &fine),
+            GridCoords::new(3, 5)
+        );
+
+        assert_eq!(
+            grid_coords_covered_on_layer(GridCoords::new(1, 2), &coarse, &fine).collect::<Vec<_>>(),
+            vec![
+                GridCoords::new(2, 4),
+                GridCoords::new(3, 4),
+                GridCoords::new(2, 5),
+                GridCoords::new(3, 5),
+            ]
+        );
+        assert_eq!(
+            grid_coords_covered_on_layer(GridCoords::new(0, 0), &coarse, &offset_fine).count(),
+            9
+        );
+        assert_eq!(
+            grid_coords_covered_on_layer(GridCoords::new(3, 5), &fine, &coarse).collect::<Vec<_>>(),
+            vec![GridCoords::new(1, 2)]
+        );
+    }
 }
@@ -0,0 +1,536 @@
+//! Validation of LDtk projects outside of a running game, for checking game content in CI.
+//!
+//! [`validate_project_file`] reads a project the same way the [`LdtkProject`] loader does, and
+//! reports problems that would otherwise only show up at runtime:
+//! - Tilesets and level backgrounds whose image files are missing.
+//! - External level files that are missing or can't be parsed.
+//! - Levels with null layer instances.
+//! - LDtk entities that have no registered bundle, given a [`RegistryDump`].
+//! - Field values that violate the constraints of their field definitions.
+//!
+//! The same checks are available from the command line with the `ldtk-validate` binary, which
+//! requires the "cli" feature:
+//! ```sh
+//! cargo run --features cli --bin ldtk-validate -- assets/my_project.ldtk --registry registry.txt
+//! ```
+//!
+//! [`LdtkProject`]: crate::assets::LdtkProject
+
+use crate::{
+    app::LdtkEntityMap,
+    ldtk::{FieldDefinition, FieldInstance, FieldValue, LdtkJson, Level, Type},
+    utils::try_each_optional_permutation,
+};
+use bevy::prelude::*;
+use regex::Regex;
+use std::{
+    collections::{HashMap, HashSet},
+    fs,
+    path::{Path, PathBuf},
+};
+use thiserror::Error;
+
+/// A problem found by [`validate_project`].
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum ValidationIssue {
+    /// The project file itself couldn't be read or parsed.
+    #[error("unable to read project {}: {reason}", .path.display())]
+    UnreadableProject { path: PathBuf, reason: String },
+    /// An image file referenced by a tileset or level background is missing.
+    #[error("{owner} refers to missing file {}", .path.display())]
+    MissingFile { owner: String, path: PathBuf },
+    /// An external level file is missing or can't be parsed.
+    #[error("external level {level} at {} is unreadable: {reason}", .path.display())]
+    UnreadableExternalLevel {
+        level: String,
+        path: PathBuf,
+        reason: String,
+    },
+    /// A level has null layer instances, so it can't be spawned.
+    #[error("level {level} has null layer instances")]
+    NullLayerInstances { level: String },
+    /// An LDtk entity has no registered bundle, so it only spawns with the default components.
+    #[error("entity {entity} ({iid}) in level {level} has no registered bundle")]
+    UnregisteredEntity {
+        level: String,
+        entity: String,
+        iid: String,
+    },
+    /// A field value violates the constraints of its field definition.
+    #[error("field {field} of {owner} in level {level} {problem}")]
+    FieldConstraint {
+        level: String,
+        owner: String,
+        field: String,
+        problem: String,
+    },
+}
+
+/// The LDtk entity registrations of an app, as registered with
+/// [`LdtkEntityAppExt::register_ldtk_entity`].
+///
+/// Dumps are text with one registration per line, in the form `<layer identifier> <entity
+/// identifier>`.
+/// Registrations that apply to any layer or any entity use `*` instead of the identifier.
+///
+/// [`LdtkEntityAppExt::register_ldtk_entity`]: crate::app::LdtkEntityAppExt::register_ldtk_entity
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
+pub struct RegistryDump {
+    registrations: HashSet<(Option<String>, Option<String>)>,
+}
+
+impl RegistryDump {
+    /// Creates a dump of the LDtk entity registrations of the app.
+    ///
+    /// Call this after registering all LDtk entities.
+    pub fn from_app(app: &App) -> RegistryDump {
+        let registrations = app
+            .world
+            .get_non_send_resource::<LdtkEntityMap>()
+            .map(|entity_map| entity_map.keys().cloned().collect())
+            .unwrap_or_default();
+
+        RegistryDump { registrations }
+    }
+
+    /// Parses a dump created with [`RegistryDump::to_dump_string`].
+    pub fn parse(dump: &str) -> RegistryDump {
+        let identifier = |identifier: &str| (identifier != "*").then(|| identifier.to_string());
+
+        let registrations = dump
+            .lines()
+            .filter_map(|line| line.trim().split_once(char::is_whitespace))
+            .map(|(layer, entity)| (identifier(layer.trim()), identifier(entity.trim())))
+            .collect();
+
+        RegistryDump { registrations }
+    }
+
+    /// Writes this dump as text, with one registration per line.
+    pub fn to_dump_string(&self) -> String {
+        let identifier = |identifier: &Option<String>| identifier.as_deref().unwrap_or("*");
+
+        let mut lines: Vec<_> = self
+            .registrations
+            .iter()
+            .map(|(layer, entity)| format!("{} {}", identifier(layer), identifier(entity)))
+            .collect();
+        lines.sort();
+
+        lines.join("\n")
+    }
+
+    /// Returns whether an entity with the given identifier, on the given layer, has a registered
+    /// bundle.
+    pub fn is_registered(&self, layer_identifier: &str, entity_identifier: &str) -> bool {
+        try_each_optional_permutation(
+            layer_identifier.to_string(),
+            entity_identifier.to_string(),
+            |layer, entity| self.registrations.get(&(layer, entity)),
+        )
+        .is_some()
+    }
+}
+
+/// Reads the project at the given path and validates it with [`validate_project`].
+pub fn validate_project_file(
+    project_path: &Path,
+    registry: Option<&RegistryDump>,
+) -> Vec<ValidationIssue> {
+    let data = fs::read(project_path)
+        .map_err(|e| e.to_string())
+        .and_then(|bytes| serde_json::from_slice::<LdtkJson>(&bytes).map_err(|e| e.to_string()));
+
+    match data {
+        Ok(data) => validate_project(&data, project_path, registry),
+        Err(reason) => vec![ValidationIssue::UnreadableProject {
+            path: project_path.to_path_buf(),
+            reason,
+        }],
+    }
+}
+
+/// Validates project data, resolving the paths it refers to relative to `project_path`.
+///
+/// Entities are only checked for registered bundles if a `registry` is given.
+pub fn validate_project(
+    data: &LdtkJson,
+    project_path: &Path,
+    registry: Option<&RegistryDump>,
+) -> Vec<ValidationIssue> {
+    let project_dir = project_path.parent().unwrap_or(Path::new(""));
+    let mut issues = Vec::new();
+
+    for tileset in &data.defs.tilesets {
+        if let Some(rel_path) = &tileset.rel_path {
+            let path = project_dir.join(rel_path);
+
+            if !path.exists() {
+                issues.push(ValidationIssue::MissingFile {
+                    owner: format!("tileset {}", tileset.identifier),
+                    path,
+                });
+            }
+        }
+    }
+
+    let level_field_defs: HashMap<i32, &FieldDefinition> = data
+        .defs
+        .level_fields
+        .iter()
+        .map(|field_def| (field_def.uid, field_def))
+        .collect();
+
+    let entity_field_defs: HashMap<i32, &FieldDefinition> = data
+        .defs
+        .entities
+        .iter()
+        .flat_map(|entity_def| entity_def.field_defs.iter())
+        .map(|field_def| (field_def.uid, field_def))
+        .collect();
+
+    let levels = data
+        .levels
+        .iter()
+        .chain(data.worlds.iter().flat_map(|world| world.levels.iter()));
+
+    for level in levels {
+        let external_level;
+
+        let level = match (data.external_levels, &level.external_rel_path) {
+            (true, Some(rel_path)) => {
+                let path = project_dir.join(rel_path);
+
+                let read = fs::read(&path)
+                    .map_err(|e| e.to_string())
+                    .and_then(|bytes| {
+                        serde_json::from_slice::<Level>(&bytes).map_err(|e| e.to_string())
+                    });
+
+                match read {
+                    Ok(level) => {
+                        external_level = level;
+                        &external_level
+                    }
+                    Err(reason) => {
+                        issues.push(ValidationIssue::UnreadableExternalLevel {
+                            level: level.identifier.clone(),
+                            path,
+                            reason,
+                        });
+                        continue;
+                    }
+                }
+            }
+            _ => level,
+        };
+
+        if let Some(rel_path) = &level.bg_rel_path {
+            let path = project_dir.join(rel_path);
+
+            if !path.exists() {
+                issues.push(ValidationIssue::MissingFile {
+                    owner: format!("background of level {}", level.identifier),
+                    path,
+                });
+            }
+        }
+
+        validate_fields(
+            &level.field_instances,
+            &level_field_defs,
+            &level.identifier,
+            "the level",
+            &mut issues,
+        );
+
+        let Some(layer_instances) = &level.layer_instances else {
+            issues.push(ValidationIssue::NullLayerInstances {
+                level: level.identifier.clone(),
+            });
+            continue;
+        };
+
+        for layer_instance in layer_instances {
+            if layer_instance.layer_instance_type != Type::Entities {
+                continue;
+            }
+
+            for entity_instance in &layer_instance.entity_instances {
+                if let Some(registry) = registry {
+                    if !registry
+                        .is_registered(&layer_instance.identifier, &entity_instance.identifier)
+                    {
+                        issues.push(ValidationIssue::UnregisteredEntity {
+                            level: level.identifier.clone(),
+                            entity: entity_instance.identifier.clone(),
+                            iid: entity_instance.iid.clone(),
+                        });
+                    }
+                }
+
+                validate_fields(
+                    &entity_instance.field_instances,
+                    &entity_field_defs,
+                    &level.identifier,
+                    &format!(
+                        "entity {} ({})",
+                        entity_instance.identifier, entity_instance.iid
+                    ),
+                    &mut issues,
+                );
+            }
+        }
+    }
+
+    issues
+}
+
+fn validate_fields(
+    field_instances: &[FieldInstance],
+    field_defs: &HashMap<i32, &FieldDefinition>,
+    level: &str,
+    owner: &str,
+    issues: &mut Vec<ValidationIssue>,
+) {
+    for field_instance in field_instances {
+        let Some(field_def) = field_defs.get(&field_instance.def_uid) else {
+            continue;
+        };
+
+        for problem in field_problems(&field_instance.value, field_def) {
+            issues.push(ValidationIssue::FieldConstraint {
+                level: level.to_string(),
+                owner: owner.to_string(),
+                field: field_instance.identifier.clone(),
+                problem,
+            });
+        }
+    }
+}
+
+/// Parses an LDtk field regex, which is written like `/pattern/flags`.
+fn ldtk_regex(regex: &str) -> Option<Regex> {
+    let (pattern, flags) = regex.strip_prefix('/')?.rsplit_once('/')?;
+
+    let pattern = if flags.contains('i') {
+        format!("(?i){pattern}")
+    } else {
+        pattern.to_string()
+    };
+
+    Regex::new(&pattern).ok()
+}
+
+/// Returns descriptions of the ways the value violates the constraints of its definition.
+fn field_problems(value: &FieldValue, field_def: &FieldDefinition) -> Vec<String> {
+    let mut problems = Vec::new();
+
+    let (numbers, strings, nulls, len): (Vec<f32>, Vec<&String>, usize, Option<usize>) = match value
+    {
+        FieldValue::Int(i) => (
+            i.iter().map(|i| *i as f32).collect(),
+            vec![],
+            i.is_none() as usize,
+            None,
+        ),
+        FieldValue::Float(f) => (
+            f.iter().copied().collect(),
+            vec![],
+            f.is_none() as usize,
+            None,
+        ),
+        FieldValue::String(s) | FieldValue::FilePath(s) | FieldValue::Enum(s) => {
+            (vec![], s.iter().collect(), s.is_none() as usize, None)
+        }
+        FieldValue::Tile(t) => (vec![], vec![], t.is_none() as usize, None),
+        FieldValue::EntityRef(r) => (vec![], vec![], r.is_none() as usize, None),
+        FieldValue::Point(p) => (vec![], vec![], p.is_none() as usize, None),
+        FieldValue::Ints(is) => (
+            is.iter().flatten().map(|i| *i as f32).collect(),
+            vec![],
+            is.iter().filter(|i| i.is_none()).count(),
+            Some(is.len()),
+        ),
+        FieldValue::Floats(fs) => (
+            fs.iter().flatten().copied().collect(),
+            vec![],
+            fs.iter().filter(|f| f.is_none()).count(),
+            Some(fs.len()),
+        ),
+        FieldValue::Strings(ss) | FieldValue::FilePaths(ss) | FieldValue::Enums(ss) => (
+            vec![],
+            ss.iter().flatten().collect(),
+            ss.iter().filter(|s| s.is_none()).count(),
+            Some(ss.len()),
+        ),
+        FieldValue::Tiles(ts) => (
+            vec![],
+            vec![],
+            ts.iter().filter(|t| t.is_none()).count(),
+            Some(ts.len()),
+        ),
+        FieldValue::EntityRefs(rs) => (
+            vec![],
+            vec![],
+            rs.iter().filter(|r| r.is_none()).count(),
+            Some(rs.len()),
+        ),
+        FieldValue::Points(ps) => (
+            vec![],
+            vec![],
+            ps.iter().filter(|p| p.is_none()).count(),
+            Some(ps.len()),
+        ),
+        FieldValue::Bools(bs) => (vec![], vec![], 0, Some(bs.len())),
+        FieldValue::Colors(cs) => (vec![], vec![], 0, Some(cs.len())),
+        FieldValue::Bool(_) | FieldValue::Color(_) => (vec![], vec![], 0, None),
+    };
+
+    if nulls > 0 && !field_def.can_be_null {
+        problems.push("is null, but can't be".to_string());
+    }
+
+    for number in numbers {
+        if field_def.min.map_or(false, |min| number < min) {
+            problems.push(format!("is {number}, below the minimum"));
+        }
+
+        if field_def.max.map_or(false, |max| number > max) {
+            problems.push(format!("is {number}, above the maximum"));
+        }
+    }
+
+    if let Some(regex) = field_def.regex.as_deref().and_then(ldtk_regex) {
+        for string in strings {
+            if !regex.is_match(string) {
+                problems.push(format!(
+                    "is {string:?}, which doesn't match {}",
+                    regex.as_str()
+                ));
+            }
+        }
+    }
+
+    if let Some(len) = len {
+        if field_def
+            .array_min_length
+            .map_or(false, |min| (len as i32) < min)
+        {
+            problems.push(format!("has {len} elements, fewer than the minimum"));
+        }
+
+        if field_def
+            .array_max_length
+            .map_or(false, |max| (len as i32) > max)
+        {
+            problems.push(format!("has {len} elements, more than the maximum"));
+        }
+    }
+
+    problems
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ldtk::{
+        ldtk_fields::field_instance_from_value, Definitions, EntityDefinition, EntityInstance,
+        LayerInstance,
+    };
+
+    #[test]
+    fn registry_dumps_round_trip_and_match_like_the_entity_map() {
+        let dump = RegistryDump::parse("* Player\nEnemies *\n\n");
+
+        assert_eq!(RegistryDump::parse(&dump.to_dump_string()), dump);
+        assert!(dump.is_registered("Entities", "Player"));
+        assert!(dump.is_registered("Enemies", "Goblin"));
+        assert!(!dump.is_registered("Entities", "Goblin"));
+    }
+
+    #[test]
+    fn invalid_content_is_reported() {
+        let field_def = FieldDefinition {
+            identifier: "health".to_string(),
+            uid: 1,
+            min: Some(1.),
+            can_be_null: false,
+            ..Default::default()
+        };
+
+        let entity = |iid: &str, identifier: &str, health: Option<i32>| EntityInstance {
+            iid: iid.to_string(),
+            identifier: identifier.to_string(),
+            field_instances: vec![FieldInstance {
+                def_uid: 1,
+                ..field_instance_from_value("health", FieldValue::Int(health))
+            }],
+            ..Default::default()
+        };
+
+        let data = LdtkJson {
+            defs: Definitions {
+                entities: vec![EntityDefinition {
+                    field_defs: vec![field_def],
+                    ..Default::default()
+                }],
+                ..Default::default()
+            },
+            levels: vec![
+                Level {
+                    identifier: "Level_0".to_string(),
+                    layer_instances: Some(vec![LayerInstance {
+                        identifier: "Entities".to_string(),
+                        layer_instance_type: Type::Entities,
+                        entity_instances: vec![
+                            entity("a", "Player", Some(3)),
+                            entity("b", "Goblin", Some(0)),
+                            entity("c", "Player", None),
+                        ],
+                        ..Default::default()
+                    }]),
+                    ..Default::default()
+                },
+                Level {
+                    identifier: "Level_1".to_string(),
+                    layer_instances: None,
+                    ..Default::default()
+                },
+            ],
+            ..Default::default()
+        };
+
+        let issues = validate_project(
+            &data,
+            Path::new("project.ldtk"),
+            Some(&RegistryDump::parse("* Player")),
+        );
+
+        assert_eq!(
+            issues,
+            vec![
+                ValidationIssue::UnregisteredEntity {
+                    level: "Level_0".to_string(),
+                    entity: "Goblin".to_string(),
+                    iid: "b".to_string(),
+                },
+                ValidationIssue::FieldConstraint {
+                    level: "Level_0".to_string(),
+                    owner: "entity Goblin (b)".to_string(),
+                    field: "health".to_string(),
+                    problem: "is 0, below the minimum".to_string(),
+                },
+                ValidationIssue::FieldConstraint {
+                    level: "Level_0".to_string(),
+                    owner: "entity Player (c)".to_string(),
+                    field: "health".to_string(),
+                    problem: "is null, but can't be".to_string(),
+                },
+                ValidationIssue::NullLayerInstances {
+                    level: "Level_1".to_string(),
+                },
+            ]
+        );
+    }
+}
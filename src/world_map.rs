@@ -0,0 +1,229 @@
+//! Lightweight overviews of every level in a project, for pause-screen world maps and debugging
+//! world layout.
+//!
+//! Spawn an [`LdtkWorldMapBundle`] to get a proxy sprite for every level in the project, laid out
+//! with the same world translations that [`LevelSpawnBehavior::UseWorldTranslation`] spawns
+//! levels with.
+//! No layers, tiles, or entities are spawned, so even large projects can be shown at once.
+//! ```
+//! # use bevy::prelude::*;
+//! # use bevy_ecs_ldtk::{prelude::*, world_map::*};
+//! fn open_world_map(mut commands: Commands, asset_server: Res<AssetServer>) {
+//!     commands.spawn(LdtkWorldMapBundle {
+//!         ldtk_handle: asset_server.load("my_project.ldtk").into(),
+//!         world_map: WorldMap {
+//!             proxy: WorldMapProxy::Background,
+//!             ..default()
+//!         },
+//!         ..default()
+//!     });
+//! }
+//!
+//! fn mark_current_level(
+//!     level_selection: Res<LevelSelection>,
+//!     mut proxies: Query<(&WorldMapLevel, &mut Sprite)>,
+//! ) {
+//!     for (world_map_level, mut sprite) in proxies.iter_mut() {
+//!         if *level_selection == LevelSelection::Iid(world_map_level.level_iid.clone()) {
+//!             sprite.color = Color::GOLD;
+//!         }
+//!     }
+//! }
+//! ```
+//!
+//! The proxies are respawned whenever the [`WorldMap`] changes or the project is reloaded.
+//!
+//! [`LevelSpawnBehavior::UseWorldTranslation`]: crate::prelude::LevelSpawnBehavior::UseWorldTranslation
+
+use crate::{
+    assets::{LdtkProject, LdtkProjectHandle, LevelMetadataAccessor},
+    components::LevelIid,
+    ldtk::{raw_level_accessor::RawLevelAccessor, Level},
+    utils::ldtk_pixel_coords_to_translation,
+};
+use bevy::{prelude::*, sprite::Anchor};
+use std::collections::HashSet;
+
+/// How the proxy of each level is drawn on a [`WorldMap`].
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Default, Hash)]
+pub enum WorldMapProxy {
+    /// A quad of the level's background color.
+    #[default]
+    Color,
+    /// The level's background image, stretched over the level's bounds.
+    ///
+    /// Levels without a background image fall back to [`WorldMapProxy::Color`].
+    /// Background images are only loaded if the project's loader settings allow it.
+    Background,
+}
+
+/// [`Component`] that spawns a proxy of every level in its entity's [`LdtkProjectHandle`] as
+/// children.
+///
+/// [`Component`]: https://docs.rs/bevy/latest/bevy/ecs/component/trait.Component.html
+#[derive(Clone, PartialEq, Debug, Component)]
+pub struct WorldMap {
+    pub proxy: WorldMapProxy,
+    /// Alpha multiplied into the color of every proxy, from 0 to 1.
+    pub alpha: f32,
+    /// Style of the level identifiers drawn in the center of every proxy, if any.
+    ///
+    /// *Requires the "text" feature*
+    #[cfg(feature = "text")]
+    pub label_style: Option<TextStyle>,
+}
+
+impl Default for WorldMap {
+    fn default() -> Self {
+        WorldMap {
+            proxy: WorldMapProxy::default(),
+            alpha: 1.,
+            #[cfg(feature = "text")]
+            label_style: None,
+        }
+    }
+}
+
+/// [`Component`] on the proxy of a level spawned by a [`WorldMap`].
+///
+/// [`Component`]: https://docs.rs/bevy/latest/bevy/ecs/component/trait.Component.html
+#[derive(Clone, Eq, PartialEq, Debug, Default, Component, Reflect)]
+#[reflect(Component)]
+pub struct WorldMapLevel {
+    pub level_iid: LevelIid,
+    pub identifier: String,
+}
+
+impl WorldMapLevel {
+    /// The bounds of the level in world space, relative to the [`WorldMap`].
+    ///
+    /// These are the bounds the level would have if it were spawned with
+    /// [`LevelSpawnBehavior::UseWorldTranslation`].
+    ///
+    /// [`LevelSpawnBehavior::UseWorldTranslation`]: crate::prelude::LevelSpawnBehavior::UseWorldTranslation
+    pub fn bounds(level: &Level) -> Rect {
+        let min = ldtk_pixel_coords_to_translation(
+            IVec2::new(level.world_x, level.world_y + level.px_hei),
+            0,
+        );
+        let size = IVec2::new(level.px_wid, level.px_hei).as_vec2();
+
+        Rect::from_corners(min, min + size)
+    }
+}
+
+/// [`Bundle`] for spawning a [`WorldMap`] of a project.
+///
+/// [`Bundle`]: https://docs.rs/bevy/latest/bevy/ecs/bundle/trait.Bundle.html
+#[derive(Clone, Default, Bundle)]
+pub struct LdtkWorldMapBundle {
+    pub ldtk_handle: LdtkProjectHandle,
+    pub world_map: WorldMap,
+    pub transform: Transform,
+    pub global_transform: GlobalTransform,
+    pub visibility: Visibility,
+    pub computed_visibility: ComputedVisibility,
+}
+
+/// Spawns the level proxies of [`WorldMap`]s, respawning them when the map or project changes.
+pub fn spawn_world_map_proxies(
+    mut commands: Commands,
+    mut project_events: EventReader<AssetEvent<LdtkProject>>,
+    ldtk_project_assets: Res<Assets<LdtkProject>>,
+    world_map_query: Query<(Entity, Ref<WorldMap>, &LdtkProjectHandle, Option<&Children>)>,
+    proxy_query: Query<(), With<WorldMapLevel>>,
+) {
+    let changed_projects: HashSet<_> = project_events
+        .iter()
+        .filter_map(|event| match event {
+            AssetEvent::Created { handle } | AssetEvent::Modified { handle } => Some(handle),
+            AssetEvent::Removed { .. } => None,
+        })
+        .collect();
+
+    for (world_map_entity, world_map, ldtk_handle, children) in world_map_query.iter() {
+        if !world_map.is_changed() && !changed_projects.contains(&ldtk_handle.handle) {
+            continue;
+        }
+
+        let Some(project) = ldtk_project_assets.get(ldtk_handle) else {
+            continue;
+        };
+
+        for child in children.into_iter().flatten() {
+            if proxy_query.contains(*child) {
+                commands.entity(*child).despawn_recursive();
+            }
+        }
+
+        commands.entity(world_map_entity).with_children(|parent| {
+            for level in project.iter_raw_levels() {
+                let bounds = WorldMapLevel::bounds(level);
+
+                let background = (world_map.proxy == WorldMapProxy::Background)
+                    .then(|| project.get_level_metadata_by_iid(&level.iid))
+                    .flatten()
+                    .and_then(|level_metadata| level_metadata.bg_image().clone());
+
+                let color = if background.is_some() {
+                    Color::WHITE
+                } else {
+                    level.bg_color
+                };
+
+                #[cfg_attr(not(feature = "text"), allow(unused_mut, unused_variables))]
+                let mut proxy = parent.spawn((
+                    SpriteBundle {
+                        sprite: Sprite {
+                            color: color.with_a(color.a() * world_map.alpha),
+                            custom_size: Some(bounds.size()),
+                            anchor: Anchor::BottomLeft,
+                            ..default()
+                        },
+                        texture: background.unwrap_or_default(),
+                        transform: Transform::from_translation(bounds.min.extend(0.)),
+                        ..default()
+                    },
+                    WorldMapLevel {
+                        level_iid: LevelIid::new(level.iid.clone()),
+                        identifier: level.identifier.clone(),
+                    },
+                    Name::new(level.identifier.clone()),
+                ));
+
+                #[cfg(feature = "text")]
+                if let Some(label_style) = &world_map.label_style {
+                    proxy.with_children(|proxy| {
+                        proxy.spawn(Text2dBundle {
+                            text: Text::from_section(level.identifier.clone(), label_style.clone()),
+                            // In front of the proxy, at its center
+                            transform: Transform::from_translation((bounds.size() / 2.).extend(1.)),
+                            ..default()
+                        });
+                    });
+                }
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bounds_match_world_translation() {
+        let level = Level {
+            world_x: 64,
+            world_y: 32,
+            px_wid: 256,
+            px_hei: 128,
+            ..Default::default()
+        };
+
+        assert_eq!(
+            WorldMapLevel::bounds(&level),
+            Rect::new(64., -160., 320., -32.)
+        );
+    }
+}